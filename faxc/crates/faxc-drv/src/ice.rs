@@ -0,0 +1,165 @@
+//! Internal compiler error reporting
+//!
+//! A panic anywhere in the pipeline is a bug in the compiler, not in the
+//! program being compiled. The driver runs compilation under [`catch_ice`],
+//! which turns such a panic into an [`Ice`] naming the phase that was
+//! running, so `faxc` can ask for a bug report and exit with
+//! [`ICE_EXIT_CODE`] instead of printing a raw Rust panic.
+
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::cell::{Cell, RefCell};
+use std::fmt::Write;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+/// Process exit code after an internal compiler error
+pub const ICE_EXIT_CODE: i32 = 2;
+
+/// Where users are asked to report internal compiler errors
+const BUG_REPORT_URL: &str = "https://github.com/Luvion1/Fax/issues/new?template=bug_report.md";
+
+/// Environment variable naming a phase that should panic when entered, for
+/// testing ICE reporting
+const FORCE_ICE_VAR: &str = "FAXC_FORCE_ICE";
+
+thread_local! {
+    /// Phase the compiler is currently running on this thread
+    static PHASE: Cell<&'static str> = const { Cell::new("driver") };
+
+    /// Whether a panic on this thread will be caught by `catch_ice`
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+
+    /// Location and backtrace of the last caught panic
+    static PANIC: RefCell<Option<(Option<String>, Backtrace)>> = const { RefCell::new(None) };
+}
+
+/// An internal compiler error: a panic caught while compiling
+#[derive(Debug)]
+pub struct Ice {
+    /// Phase that was running, one of `timing::PHASES` or `"driver"`
+    pub phase: &'static str,
+    /// The panic message
+    pub message: String,
+    /// Source location of the panic in the compiler, if known
+    pub location: Option<String>,
+    backtrace: Option<Backtrace>,
+}
+
+impl Ice {
+    /// The message printed for the ICE, ending with the backtrace when
+    /// `RUST_BACKTRACE` enabled capturing one
+    pub fn report(&self) -> String {
+        let mut out = format!(
+            "error: internal compiler error (ICE) in phase `{}`: {}\n",
+            self.phase, self.message
+        );
+        if let Some(location) = &self.location {
+            let _ = writeln!(out, "note: the compiler panicked at {}", location);
+        }
+        out.push_str("note: the compiler unexpectedly panicked; this is a bug\n");
+        let _ = writeln!(out, "note: please file a bug report at {}", BUG_REPORT_URL);
+        match &self.backtrace {
+            Some(backtrace) => {
+                let _ = write!(out, "\nstack backtrace:\n{}", backtrace);
+            },
+            None => out.push_str("note: run with `RUST_BACKTRACE=1` to include a backtrace\n"),
+        }
+        out
+    }
+}
+
+/// Record that the compiler is entering `phase`, so an ICE can name it
+///
+/// If the `FAXC_FORCE_ICE` environment variable names `phase`, this
+/// panics, simulating a bug in that phase.
+pub fn enter_phase(phase: &'static str) {
+    PHASE.with(|p| p.set(phase));
+    if std::env::var(FORCE_ICE_VAR).is_ok_and(|forced| forced == phase) {
+        panic!("ICE forced by {}", FORCE_ICE_VAR);
+    }
+}
+
+/// Run `f`, turning a panic into an [`Ice`]
+///
+/// While `f` runs, panics on this thread are not printed by the default
+/// hook; their details end up in the returned `Ice` instead.
+pub fn catch_ice<T>(f: impl FnOnce() -> T) -> Result<T, Ice> {
+    install_hook();
+    PHASE.with(|p| p.set("driver"));
+    let outer = CATCHING.with(|c| c.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.with(|c| c.set(outer));
+
+    result.map_err(|payload| {
+        let message = match payload.downcast_ref::<&str>() {
+            Some(message) => message.to_string(),
+            None => match payload.downcast_ref::<String>() {
+                Some(message) => message.clone(),
+                None => "unknown panic payload".to_string(),
+            },
+        };
+        let (location, backtrace) = match PANIC.with(|p| p.borrow_mut().take()) {
+            Some((location, backtrace)) => {
+                let captured = backtrace.status() == BacktraceStatus::Captured;
+                (location, captured.then_some(backtrace))
+            },
+            None => (None, None),
+        };
+        Ice {
+            phase: PHASE.with(Cell::get),
+            message,
+            location,
+            backtrace,
+        }
+    })
+}
+
+/// Install a panic hook that records caught panics instead of printing
+/// them, deferring to the previous hook for all other panics
+fn install_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !CATCHING.with(Cell::get) {
+                previous(info);
+                return;
+            }
+            let location = info.location().map(ToString::to_string);
+            let backtrace = Backtrace::capture();
+            PANIC.with(|p| *p.borrow_mut() = Some((location, backtrace)));
+        }));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panic_in_phase_becomes_ice() {
+        let ice = catch_ice(|| {
+            enter_phase("sem");
+            panic!("unexpected item");
+        })
+        .unwrap_err();
+
+        assert_eq!(ice.phase, "sem");
+        assert_eq!(ice.message, "unexpected item");
+        assert!(ice
+            .location
+            .as_deref()
+            .is_some_and(|l| l.contains("ice.rs")));
+
+        let report = ice.report();
+        assert!(report
+            .starts_with("error: internal compiler error (ICE) in phase `sem`: unexpected item\n"));
+        assert!(report.contains("please file a bug report"), "{}", report);
+        assert_eq!(ICE_EXIT_CODE, 2);
+    }
+
+    #[test]
+    fn test_no_panic_passes_result_through() {
+        assert_eq!(catch_ice(|| 42).unwrap(), 42);
+    }
+}
@@ -2,17 +2,27 @@
 //!
 //! Driver utama yang mengkoordinasikan seluruh tahapan kompilasi.
 
-use faxc_gen::{CodeGenError, LlvmBackend};
+pub mod ice;
+pub mod timing;
+
+use faxc_gen::{CodeGenError, LlvmBackend, Target};
 use faxc_lex::Lexer;
 use faxc_lir::lower_mir_to_lir;
 use faxc_lir::opt::optimize_function as optimize_lir;
-use faxc_mir::lower_hir_function;
+use faxc_lir::SystemVAbi;
 use faxc_mir::opt::optimize_function as optimize_mir;
-use faxc_par::Parser;
+use faxc_mir::{lower_hir_closures, lower_hir_function};
+use faxc_par::{Parser, TokenWithSpan};
+use faxc_sem::lint::LintRegistry;
 use faxc_sem::{Item as HirItem, SemanticAnalyzer, TypeContext};
-use faxc_util::{DefIdGenerator, Handler};
+pub use faxc_util::FileId;
+use faxc_util::{DefIdGenerator, DiagnosticBuilder, Handler};
 use std::env;
-use std::path::PathBuf;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Instant;
+use timing::{PassTimings, TimePassesFormat};
 
 /// Configuration untuk compiler
 #[derive(Debug, Clone)]
@@ -21,7 +31,18 @@ pub struct Config {
     pub output_file: Option<PathBuf>,
     pub target: String,
     pub emit: EmitType,
+    /// Stop after semantic analysis, only reporting diagnostics
+    pub check: bool,
     pub verbose: bool,
+    pub dump_scopes: bool,
+    /// Print each file's AST as a GraphViz graph after parsing
+    pub print_ast_dot: bool,
+    /// Print each file's HIR after semantic analysis, with desugarings such
+    /// as `for` loops spelled out (`--pretty=expanded`)
+    pub pretty_expanded: bool,
+    pub time_passes: Option<TimePassesFormat>,
+    pub color: ColorChoice,
+    pub error_limit: Option<usize>,
     pub incremental: bool,
     pub help: bool,
     pub version: bool,
@@ -33,11 +54,137 @@ pub enum EmitType {
     Ast,
     Hir,
     Mir,
+    /// Each function's MIR control-flow graph, as GraphViz
+    MirDot,
     Lir,
     LlvmIr,
     Asm,
     Object,
     Exe,
+    /// Makefile rules listing the source files the compilation reads
+    DepInfo,
+}
+
+impl FromStr for EmitType {
+    type Err = String;
+
+    /// Parse an `--emit` value, or one of the `--emit-<type>`, `-S` and
+    /// `-c` shorthands
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let emit = match s {
+            "-S" => return Ok(EmitType::Asm),
+            "-c" => return Ok(EmitType::Object),
+            _ => s.strip_prefix("--emit-").unwrap_or(s),
+        };
+        match emit {
+            "tokens" => Ok(EmitType::Tokens),
+            "ast" => Ok(EmitType::Ast),
+            "hir" => Ok(EmitType::Hir),
+            "mir" => Ok(EmitType::Mir),
+            "mir-dot" => Ok(EmitType::MirDot),
+            "lir" => Ok(EmitType::Lir),
+            "llvm-ir" => Ok(EmitType::LlvmIr),
+            "asm" => Ok(EmitType::Asm),
+            "object" => Ok(EmitType::Object),
+            "exe" => Ok(EmitType::Exe),
+            "dep-info" => Ok(EmitType::DepInfo),
+            _ => Err(format!("Unknown emit type: {}", s)),
+        }
+    }
+}
+
+/// When to color diagnostics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Color when stderr is a terminal
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether diagnostics written to stderr should be colored
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Auto => std::io::stderr().is_terminal(),
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(format!("Unknown color choice: {}", s)),
+        }
+    }
+}
+
+/// Optimization level
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OptLevel {
+    #[default]
+    O0,
+    O1,
+    O2,
+    O3,
+    /// Optimize for size
+    Os,
+}
+
+impl FromStr for OptLevel {
+    type Err = String;
+
+    /// Parse a level such as `2` or `s`, with or without the `-O` prefix
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix("-O").unwrap_or(s) {
+            "0" => Ok(OptLevel::O0),
+            "1" => Ok(OptLevel::O1),
+            "2" => Ok(OptLevel::O2),
+            "3" => Ok(OptLevel::O3),
+            "s" => Ok(OptLevel::Os),
+            _ => Err(format!("Unknown optimization level: {}", s)),
+        }
+    }
+}
+
+impl Config {
+    /// Reject option combinations no compilation could satisfy
+    pub fn validate(&self) -> Result<(), CompileError> {
+        if self.input_files.is_empty() {
+            return Err(CompileError::NoInputFiles);
+        }
+
+        if let Some(output) = &self.output_file {
+            if matches!(
+                self.emit,
+                EmitType::Tokens
+                    | EmitType::Ast
+                    | EmitType::Hir
+                    | EmitType::Mir
+                    | EmitType::MirDot
+                    | EmitType::Lir
+            ) {
+                return Err(CompileError::InvalidArguments(format!(
+                    "{:?} output is not written to a file, but -o {} was given; \
+                     only llvm-ir, asm, object, exe and dep-info can be",
+                    self.emit,
+                    output.display()
+                )));
+            }
+        }
+
+        Target::parse(&self.target)
+            .map(|_| ())
+            .map_err(|e| CompileError::InvalidArguments(e.to_string()))
+    }
 }
 
 impl Default for Config {
@@ -47,7 +194,14 @@ impl Default for Config {
             output_file: None,
             target: default_target(),
             emit: EmitType::Exe,
+            check: false,
             verbose: false,
+            dump_scopes: false,
+            print_ast_dot: false,
+            pretty_expanded: false,
+            time_passes: None,
+            color: ColorChoice::Auto,
+            error_limit: None,
             incremental: false,
             help: false,
             version: false,
@@ -72,6 +226,32 @@ pub fn parse_args() -> Result<Config, String> {
             return Ok(config);
         } else if arg == "--verbose" || arg == "-v" {
             config.verbose = true;
+        } else if arg == "--check" {
+            config.check = true;
+        } else if arg == "--dump-scopes" {
+            config.dump_scopes = true;
+        } else if arg == "--print-ast-dot" {
+            config.print_ast_dot = true;
+        } else if let Some(mode) = arg.strip_prefix("--pretty=") {
+            match mode {
+                "expanded" => config.pretty_expanded = true,
+                _ => return Err(format!("Unknown pretty-print mode: {}", mode)),
+            }
+        } else if arg == "--time-passes" {
+            config.time_passes = Some(TimePassesFormat::Text);
+        } else if let Some(format) = arg.strip_prefix("--time-passes=") {
+            config.time_passes = Some(match format {
+                "text" => TimePassesFormat::Text,
+                "json" => TimePassesFormat::Json,
+                _ => return Err(format!("Unknown time-passes format: {}", format)),
+            });
+        } else if let Some(when) = arg.strip_prefix("--color=") {
+            config.color = when.parse()?;
+        } else if let Some(limit) = arg.strip_prefix("--error-limit=") {
+            config.error_limit = match limit.parse() {
+                Ok(0) | Err(_) => return Err(format!("Invalid error limit: {}", limit)),
+                Ok(n) => Some(n),
+            };
         } else if arg == "--output" || arg == "-o" {
             if i + 1 >= args.len() {
                 return Err("Missing argument for -o".to_string());
@@ -89,18 +269,11 @@ pub fn parse_args() -> Result<Config, String> {
                 return Err("Missing argument for --emit".to_string());
             }
             i += 1;
-            config.emit = match args[i].as_str() {
-                "tokens" => EmitType::Tokens,
-                "ast" => EmitType::Ast,
-                "hir" => EmitType::Hir,
-                "mir" => EmitType::Mir,
-                "lir" => EmitType::Lir,
-                "llvm-ir" => EmitType::LlvmIr,
-                "asm" => EmitType::Asm,
-                "object" => EmitType::Object,
-                "exe" => EmitType::Exe,
-                _ => return Err(format!("Unknown emit type: {}", args[i])),
-            };
+            config.emit = args[i].parse()?;
+        } else if let Some(emit) = arg.strip_prefix("--emit=") {
+            config.emit = emit.parse()?;
+        } else if arg.starts_with("--emit-") || arg == "-S" || arg == "-c" {
+            config.emit = arg.parse()?;
         } else if arg.starts_with('-') {
             return Err(format!("Unknown option: {}", arg));
         } else {
@@ -124,7 +297,22 @@ pub fn print_help() {
     println!("  -v, --verbose        Enable verbose output");
     println!("  -o, --output <FILE>  Specify output file");
     println!("  --target <TARGET>    Target triple (default: x86_64-unknown-linux-gnu)");
-    println!("  --emit <TYPE>        Output type: tokens, ast, hir, mir, lir, asm, llvm-ir, exe");
+    println!("  --emit <TYPE>        Output type: tokens, ast, hir, mir, lir, asm, llvm-ir, exe,");
+    println!("                       dep-info (Makefile rules naming the files read)");
+    println!("  --emit-mir-dot       Print each function's MIR control-flow graph for GraphViz");
+    println!("  --check              Report errors without generating any output");
+    println!("  --dump-scopes        Print the resolved scope tree after semantic analysis");
+    println!("  --print-ast-dot      Print the AST as a GraphViz graph (render with `dot`)");
+    println!("  --pretty=expanded    Print the HIR after desugaring `for` loops and the like");
+    println!("  --time-passes[=json] Print the time spent in each compiler phase");
+    println!("  --error-limit=<N>    Stop reporting errors after N of them");
+    println!("  --color=<WHEN>       Color diagnostics: auto, always, never (default: auto)");
+    println!();
+    println!("Exit codes:");
+    println!("  0  Success");
+    println!("  1  Compilation failed");
+    println!("  2  Internal compiler error");
+    println!("  3  Invalid command line");
     println!();
     println!("Examples:");
     println!("  faxc hello.fax              Compile hello.fax to executable");
@@ -143,12 +331,18 @@ pub struct Session {
     pub sources: SourceMap,
     pub diagnostics: Handler,
     pub def_id_gen: DefIdGenerator,
+    pub timings: PassTimings,
+    /// Lints run over each file's AST after parsing
+    pub lints: LintRegistry,
 }
 
 impl Session {
     pub fn new(config: Config) -> Result<Self, CompileError> {
         let mut sources = SourceMap::new();
         let diagnostics = Handler::new();
+        if let Some(limit) = config.error_limit {
+            diagnostics.set_error_limit(limit);
+        }
         let def_id_gen = DefIdGenerator::new();
 
         for path in &config.input_files {
@@ -162,10 +356,151 @@ impl Session {
             sources,
             diagnostics,
             def_id_gen,
+            timings: PassTimings::new(),
+            lints: LintRegistry::with_builtin_lints(),
         })
     }
 
+    /// Lex and parse one source file, keeping its tokens for `--emit tokens`
+    fn parse_file(
+        &mut self,
+        file_id: FileId,
+        all_tokens: &mut Vec<(FileId, Vec<faxc_lex::Token>)>,
+    ) -> Vec<faxc_par::Item> {
+        let source = &self.sources.files[file_id.index()];
+        if self.config.verbose {
+            eprintln!("[verbose] Lexing: {}", source.path.display());
+        }
+        ice::enter_phase("lex");
+        let start = Instant::now();
+        let mut lexer = Lexer::new(&source.content, &mut self.diagnostics).with_file_id(file_id);
+        let tokens: Vec<_> = std::iter::from_fn(|| {
+            let token = lexer.next_token();
+            let span = lexer.token_span();
+            Some(TokenWithSpan { token, span })
+        })
+        .take_while(|t| t.token != faxc_lex::Token::Eof)
+        .collect();
+        self.timings
+            .record("lex", Some(source.path.clone()), start.elapsed());
+
+        if self.config.emit == EmitType::Tokens {
+            all_tokens.push((file_id, tokens.iter().map(|t| t.token.clone()).collect()));
+        }
+
+        if self.config.verbose {
+            eprintln!("[verbose] Parsing: {}", source.path.display());
+        }
+        ice::enter_phase("parse");
+        let start = Instant::now();
+        let mut parser = Parser::from_tokens(tokens, &mut self.diagnostics, &source.content)
+            .with_file_id(file_id);
+        let ast = parser.parse();
+        self.timings
+            .record("parse", Some(source.path.clone()), start.elapsed());
+        ast
+    }
+
+    /// Load the file of every `mod name;` in `items` and graft its items
+    /// under the module
+    ///
+    /// The file is `name.fax` or `name/mod.fax` in `dir`; modules nested in
+    /// `name` are searched for in `dir/name`.
+    fn load_external_modules(
+        &mut self,
+        items: &mut [faxc_par::Item],
+        dir: &Path,
+        all_tokens: &mut Vec<(FileId, Vec<faxc_lex::Token>)>,
+    ) {
+        for item in items {
+            let faxc_par::Item::Mod(m) = item else {
+                continue;
+            };
+            let module_dir = dir.join(m.name.as_str());
+
+            if m.items.is_none() {
+                let candidates = [
+                    dir.join(format!("{}.fax", m.name.as_str())),
+                    module_dir.join("mod.fax"),
+                ];
+                let Some(path) = candidates.into_iter().find(|path| path.is_file()) else {
+                    DiagnosticBuilder::error(format!(
+                        "file not found for module {}",
+                        m.name.as_str()
+                    ))
+                    .span(m.span)
+                    .emit(&self.diagnostics);
+                    continue;
+                };
+                let content = match std::fs::read_to_string(&path) {
+                    Ok(content) => content,
+                    Err(e) => {
+                        DiagnosticBuilder::error(format!(
+                            "couldn't read {}: {}",
+                            path.display(),
+                            e
+                        ))
+                        .span(m.span)
+                        .emit(&self.diagnostics);
+                        continue;
+                    },
+                };
+                let file_id = self.sources.add(path, content);
+                m.items = Some(self.parse_file(file_id, all_tokens));
+            }
+
+            if let Some(items) = &mut m.items {
+                self.load_external_modules(items, &module_dir, all_tokens);
+            }
+        }
+    }
+
+    /// Render every diagnostic reported so far in source order, naming and
+    /// quoting the file each one points into and coloring them as `--color`
+    /// asks
+    pub fn render_diagnostics(&self) -> String {
+        let color = self.config.color.enabled();
+        let mut out = String::new();
+        for diag in self.diagnostics.sorted_diagnostics() {
+            let path = self.sources.path(diag.span.file_id);
+            let file_name = path.as_ref().map(|path| path.display().to_string());
+            let source = self.sources.content(diag.span.file_id);
+            out.push_str(&diag.render_with_source(file_name.as_deref(), source, color));
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Where `--emit=dep-info` writes its rules: the `-o` path, or the first
+    /// input file with a `.d` extension
+    fn dep_info_path(&self) -> PathBuf {
+        match &self.config.output_file {
+            Some(path) => path.clone(),
+            None => self.config.input_files[0].with_extension("d"),
+        }
+    }
+
+    /// Makefile rules making `target` depend on every source file read so
+    /// far, root files and module files alike
+    ///
+    /// Each source also gets an empty rule of its own, so deleting a file
+    /// does not break the build of whatever includes these rules.
+    pub fn dep_info(&self, target: &Path) -> String {
+        let sources: Vec<String> = self
+            .sources
+            .iter()
+            .map(|(_, file)| escape_make_path(&file.path))
+            .collect();
+        let mut out = format!("{}: {}\n", escape_make_path(target), sources.join(" "));
+        for source in &sources {
+            out.push_str(&format!("\n{}:\n", source));
+        }
+        out
+    }
+
     pub fn compile(&mut self) -> Result<CompilationResults, CompileError> {
+        self.config.validate()?;
+
         if self.config.verbose {
             eprintln!("[verbose] Starting compilation...");
             eprintln!("[verbose] Input files: {:?}", self.config.input_files);
@@ -177,25 +512,43 @@ impl Session {
         let mut all_tokens = Vec::new();
         let mut all_asts = Vec::new();
 
-        for (file_id, source) in self.sources.iter() {
+        let root_files: Vec<FileId> = self.sources.iter().map(|(file_id, _)| file_id).collect();
+        for file_id in root_files {
+            let mut ast = self.parse_file(file_id, &mut all_tokens);
+            let dir = self
+                .sources
+                .path(file_id)
+                .and_then(|path| path.parent().map(Path::to_path_buf))
+                .unwrap_or_default();
+            self.load_external_modules(&mut ast, &dir, &mut all_tokens);
+            all_asts.push((file_id, ast));
+        }
+
+        if self.config.emit == EmitType::DepInfo {
+            let path = self.dep_info_path();
+            std::fs::write(&path, self.dep_info(&path))
+                .map_err(|e| CompileError::IoError(path.clone(), e))?;
             if self.config.verbose {
-                eprintln!("[verbose] Lexing: {}", source.path.display());
+                eprintln!("[verbose] Wrote dependency info to {}", path.display());
             }
-            let mut lexer = Lexer::new(&source.content, &mut self.diagnostics);
-            let tokens: Vec<_> = std::iter::from_fn(|| Some(lexer.next_token()))
-                .take_while(|t| *t != faxc_lex::Token::Eof)
-                .collect();
+            return Ok(CompilationResults {
+                tokens: vec![],
+                asts: vec![],
+                hirs: vec![],
+                mirs: vec![],
+                lirs: vec![],
+                objects: vec![],
+            });
+        }
 
-            if self.config.emit == EmitType::Tokens {
-                all_tokens.push((file_id, tokens.clone()));
-            }
+        for (_, ast) in &all_asts {
+            self.lints.run(ast, &mut self.diagnostics);
+        }
 
-            if self.config.verbose {
-                eprintln!("[verbose] Parsing: {}", source.path.display());
+        if self.config.print_ast_dot {
+            for (_, ast) in &all_asts {
+                print!("{}", faxc_par::ast_to_dot(ast));
             }
-            let mut parser = Parser::new(tokens, &mut self.diagnostics);
-            let ast = parser.parse();
-            all_asts.push((file_id, ast));
         }
 
         if self.config.emit == EmitType::Ast {
@@ -212,6 +565,7 @@ impl Session {
         if self.config.verbose {
             eprintln!("[verbose] Phase: Semantic Analysis");
         }
+        ice::enter_phase("sem");
         let mut type_context = TypeContext::default();
         let mut all_hirs = Vec::new();
         for (file_id, ast) in &all_asts {
@@ -224,9 +578,15 @@ impl Session {
                     .unwrap_or_else(|| "<unknown>".to_string());
                 eprintln!("[verbose] Analyzing: {}", source_name);
             }
+            let start = Instant::now();
             let mut analyzer =
                 SemanticAnalyzer::new(&mut type_context, &self.def_id_gen, &mut self.diagnostics);
             let hir = analyzer.analyze_items(ast.clone());
+            self.timings
+                .record("sem", self.sources.path(*file_id), start.elapsed());
+            if self.config.dump_scopes {
+                print!("{}", analyzer.scope_tree.dump());
+            }
             all_hirs.push((*file_id, hir));
         }
 
@@ -234,6 +594,23 @@ impl Session {
             return Err(CompileError::CompilationFailed);
         }
 
+        if self.config.pretty_expanded {
+            for (_, hir) in &all_hirs {
+                print!("{}", pretty_expanded(hir));
+            }
+        }
+
+        if self.config.check {
+            return Ok(CompilationResults {
+                tokens: vec![],
+                asts: vec![],
+                hirs: vec![],
+                mirs: vec![],
+                lirs: vec![],
+                objects: vec![],
+            });
+        }
+
         if self.config.emit == EmitType::Hir {
             return Ok(CompilationResults {
                 tokens: vec![],
@@ -245,24 +622,41 @@ impl Session {
             });
         }
 
+        ice::enter_phase("mir");
         let mut all_mirs = Vec::new();
         for (file_id, hir) in &all_hirs {
+            let start = Instant::now();
             for item in hir {
                 if let HirItem::Function(func) = item {
                     let mir = lower_hir_function(func);
                     all_mirs.push((*file_id, mir));
+                    for closure in lower_hir_closures(func) {
+                        all_mirs.push((*file_id, closure));
+                    }
                 }
             }
+            self.timings
+                .record("mir", self.sources.path(*file_id), start.elapsed());
         }
 
         if self.config.verbose {
             eprintln!("[verbose] Phase: MIR Optimization");
         }
-        for (_, mir) in &mut all_mirs {
+        ice::enter_phase("mir-opt");
+        for (file_id, mir) in &mut all_mirs {
+            let start = Instant::now();
             optimize_mir(mir);
+            self.timings
+                .record("mir-opt", self.sources.path(*file_id), start.elapsed());
+        }
+
+        if self.config.emit == EmitType::MirDot {
+            for (_, mir) in &all_mirs {
+                print!("{}", mir.to_dot());
+            }
         }
 
-        if self.config.emit == EmitType::Mir {
+        if matches!(self.config.emit, EmitType::Mir | EmitType::MirDot) {
             return Ok(CompilationResults {
                 tokens: vec![],
                 asts: vec![],
@@ -273,17 +667,25 @@ impl Session {
             });
         }
 
+        ice::enter_phase("lir");
         let mut all_lirs = Vec::new();
         for (file_id, mir) in &all_mirs {
+            let start = Instant::now();
             let lir = lower_mir_to_lir(mir);
+            self.timings
+                .record("lir", self.sources.path(*file_id), start.elapsed());
             all_lirs.push((*file_id, lir));
         }
 
         if self.config.verbose {
             eprintln!("[verbose] Phase: LIR Optimization");
         }
-        for (_, lir) in &mut all_lirs {
+        for (file_id, lir) in &mut all_lirs {
+            let start = Instant::now();
             optimize_lir(lir);
+            SystemVAbi::save_live_across_calls(lir);
+            self.timings
+                .record("lir", self.sources.path(*file_id), start.elapsed());
         }
 
         if self.config.emit == EmitType::Lir {
@@ -297,13 +699,17 @@ impl Session {
             });
         }
 
+        ice::enter_phase("codegen");
+        let codegen_start = Instant::now();
         let context = inkwell::context::Context::create();
-        let mut llvm_backend = LlvmBackend::new(
+        let target = Target::parse(&self.config.target).map_err(CompileError::CodeGenError)?;
+        let mut llvm_backend = LlvmBackend::for_target(
             &context,
             "fax_module",
-            self.config.target.clone(),
+            &target,
             inkwell::OptimizationLevel::None,
-        );
+        )
+        .map_err(CompileError::CodeGenError)?;
 
         for (_, lir) in &all_lirs {
             llvm_backend
@@ -311,9 +717,15 @@ impl Session {
                 .map_err(|e| CompileError::CodeGenError(e))?;
         }
 
+        if self.config.emit == EmitType::Exe {
+            llvm_backend
+                .emit_entry_point()
+                .map_err(|e| CompileError::CodeGenError(e))?;
+        }
+
         let llvm_ir = llvm_backend.emit_llvm_ir();
         let mut objects = Vec::new();
-        objects.push((FileId(0), llvm_ir.clone()));
+        objects.push((FileId::DUMMY, llvm_ir.clone()));
 
         let output_path = self.config.output_file.clone();
 
@@ -346,26 +758,9 @@ impl Session {
                     let ir = llvm_backend.emit_llvm_ir();
 
                     let ir_file = std::env::temp_dir().join("fax_compile.ll");
+                    std::fs::write(&ir_file, &ir)?;
 
-                    // Inject GC initialization at the start of main
-                    let ir_with_gc_init = inject_gc_init(&ir);
-                    std::fs::write(&ir_file, &ir_with_gc_init)?;
-
-                    // Find runtime library path - check multiple locations
-                    let possible_paths = vec![
-                        std::env::current_exe()
-                            .ok()
-                            .and_then(|p| p.parent().map(|p| p.join("libfaxc_runtime.so"))),
-                        Some(std::path::PathBuf::from("target/debug/libfaxc_runtime.so")),
-                        Some(std::path::PathBuf::from(
-                            "faxc/target/debug/libfaxc_runtime.so",
-                        )),
-                        Some(std::path::PathBuf::from(
-                            "/root/Fax/target/debug/libfaxc_runtime.so",
-                        )),
-                    ];
-
-                    let runtime_lib = possible_paths.into_iter().flatten().find(|p| p.exists());
+                    let runtime_lib = find_runtime_library();
 
                     let mut cmd = std::process::Command::new("clang");
                     cmd.arg("-o").arg(path).arg("-x").arg("ir").arg(&ir_file);
@@ -373,7 +768,7 @@ impl Session {
                     // Link runtime if exists
                     if let Some(ref lib) = runtime_lib {
                         if self.config.verbose {
-                            eprintln!("[verbose] Linking with FGC runtime: {}", lib.display());
+                            eprintln!("[verbose] Linking with Fax runtime: {}", lib.display());
                         }
                         // Use -L and -l for shared library
                         if lib.extension().map_or(false, |e| e == "so") {
@@ -388,9 +783,7 @@ impl Session {
                             cmd.arg(lib);
                         }
                     } else {
-                        if self.config.verbose {
-                            eprintln!("[verbose] Runtime not found, using system malloc");
-                        }
+                        eprintln!("Warning: Fax runtime library not found, linking may fail");
                     }
 
                     let result = cmd.output();
@@ -417,6 +810,8 @@ impl Session {
                 _ => {},
             }
         }
+        self.timings
+            .record("codegen", None, codegen_start.elapsed());
 
         Ok(CompilationResults {
             tokens: vec![],
@@ -438,15 +833,23 @@ impl SourceMap {
         Self { files: Vec::new() }
     }
     pub fn add(&mut self, path: PathBuf, content: String) -> FileId {
-        let id = FileId(self.files.len() as u32);
+        let id = FileId::new(self.files.len());
         self.files.push(SourceFile { path, content });
         id
     }
+    /// Path of the file with the given id
+    pub fn path(&self, id: FileId) -> Option<PathBuf> {
+        self.files.get(id.0 as usize).map(|f| f.path.clone())
+    }
+    /// Text of the file with the given id
+    pub fn content(&self, id: FileId) -> Option<&str> {
+        self.files.get(id.0 as usize).map(|f| f.content.as_str())
+    }
     pub fn iter(&self) -> impl Iterator<Item = (FileId, &SourceFile)> {
         self.files
             .iter()
             .enumerate()
-            .map(|(i, f)| (FileId(i as u32), f))
+            .map(|(i, f)| (FileId::new(i), f))
     }
 }
 
@@ -455,9 +858,6 @@ pub struct SourceFile {
     pub content: String,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct FileId(pub u32);
-
 pub struct CompilationResults {
     pub tokens: Vec<(FileId, Vec<faxc_lex::Token>)>,
     pub asts: Vec<(FileId, Vec<faxc_par::Item>)>,
@@ -474,6 +874,33 @@ pub enum CompileError {
     NoInputFiles,
     CompilationFailed,
     CodeGenError(CodeGenError),
+    /// Options that contradict each other or name something unsupported
+    InvalidArguments(String),
+    /// The compiler panicked; the report has already been printed
+    Ice(ice::Ice),
+}
+
+/// Exit code of a successful run
+pub const EXIT_SUCCESS: i32 = 0;
+/// Exit code when the program could not be compiled
+pub const EXIT_FAILURE: i32 = 1;
+/// Exit code for a malformed or contradictory command line
+pub const EXIT_USAGE: i32 = 3;
+
+impl CompileError {
+    /// Process exit code for this error: 1 when compilation fails, 2 after
+    /// an internal compiler error and 3 for command-line errors
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CompileError::IoError(..)
+            | CompileError::CompilationFailed
+            | CompileError::CodeGenError(_) => EXIT_FAILURE,
+            CompileError::Ice(_) => ice::ICE_EXIT_CODE,
+            CompileError::ParseError(_)
+            | CompileError::NoInputFiles
+            | CompileError::InvalidArguments(_) => EXIT_USAGE,
+        }
+    }
 }
 
 impl std::fmt::Display for CompileError {
@@ -484,6 +911,10 @@ impl std::fmt::Display for CompileError {
             CompileError::NoInputFiles => write!(f, "No input files provided"),
             CompileError::CompilationFailed => write!(f, "Compilation Failed"),
             CompileError::CodeGenError(e) => write!(f, "Code Generation Error: {}", e),
+            CompileError::InvalidArguments(msg) => write!(f, "Invalid arguments: {}", msg),
+            CompileError::Ice(ice) => {
+                write!(f, "Internal compiler error in phase `{}`", ice.phase)
+            },
         }
     }
 }
@@ -496,6 +927,22 @@ impl From<std::io::Error> for CompileError {
     }
 }
 
+/// Run the compiler on the command line, returning the process exit code
+///
+/// Errors other than failed compilation and ICEs, whose diagnostics are
+/// already printed, are reported on stderr.
+pub fn run() -> i32 {
+    match main() {
+        Ok(()) => EXIT_SUCCESS,
+        Err(err) => {
+            if !matches!(err, CompileError::CompilationFailed | CompileError::Ice(_)) {
+                eprintln!("error: {}", err);
+            }
+            err.exit_code()
+        },
+    }
+}
+
 pub fn main() -> Result<(), CompileError> {
     let config = parse_args().map_err(|e| CompileError::ParseError(e))?;
 
@@ -514,10 +961,36 @@ pub fn main() -> Result<(), CompileError> {
     }
 
     let mut session = Session::new(config)?;
-    session.compile()?;
+    let result = match ice::catch_ice(|| session.compile()) {
+        Ok(result) => result,
+        Err(ice) => {
+            eprint!("{}", session.render_diagnostics());
+            eprint!("{}", ice.report());
+            return Err(CompileError::Ice(ice));
+        },
+    };
+    eprint!("{}", session.render_diagnostics());
+    if let Some(format) = session.config.time_passes {
+        eprint!("{}", session.timings.render(format));
+    }
+    result?;
     Ok(())
 }
 
+/// `path` as a Makefile rule name, with spaces escaped
+/// Render HIR items for `--pretty=expanded`
+///
+/// Semantic analysis desugars as it builds HIR, so this shows the program
+/// as later phases see it: a `for` loop appears as a `Loop` around a
+/// `Match` on an `iter_next` call.
+pub fn pretty_expanded(items: &[HirItem]) -> String {
+    items.iter().map(|item| format!("{:#?}\n", item)).collect()
+}
+
+fn escape_make_path(path: &Path) -> String {
+    path.display().to_string().replace(' ', "\\ ")
+}
+
 fn default_target() -> String {
     let config = inkwell::targets::InitializationConfig::default();
     inkwell::targets::Target::initialize_native(&config).ok();
@@ -525,25 +998,305 @@ fn default_target() -> String {
     triple.as_str().to_string_lossy().into_owned()
 }
 
-fn inject_gc_init(ir: &str) -> String {
-    if ir.contains("define i64 @main(") {
-        let gc_init = "; Fax GC initialization\n\
-define void @__fax_gc_init() {\n\
-entry:\n\
-    %0 = call i1 @fax_gc_init()\n\
-    ret void\n\
-}\n\
-\n";
-        let gc_init_call = "; Call GC initialization\n\
-    call void @__fax_gc_init()\n\
-";
-        let main_pattern = "define i64 @main(";
-        if let Some(pos) = ir.find(main_pattern) {
-            let before = &ir[..pos];
-            let after = &ir[pos..];
-            let with_call = after.replacen("entry:", &format!("entry:\n{}", gc_init_call), 1);
-            return format!("{}{}{}", before, gc_init, with_call);
-        }
-    }
-    ir.to_string()
+/// Locate the `faxc_runtime` shared library
+///
+/// `FAXC_RUNTIME_DIR` takes precedence; otherwise the directory of the
+/// running `faxc` binary is searched, including its `deps/` subdirectory
+/// where cargo places the library when building tests.
+fn find_runtime_library() -> Option<PathBuf> {
+    const RUNTIME_LIB: &str = "libfaxc_runtime.so";
+
+    let mut dirs = Vec::new();
+    if let Some(dir) = env::var_os("FAXC_RUNTIME_DIR") {
+        dirs.push(PathBuf::from(dir));
+    }
+    if let Some(exe_dir) = env::current_exe()
+        .ok()
+        .and_then(|p| p.parent().map(|p| p.to_path_buf()))
+    {
+        let deps_dir = exe_dir.join("deps");
+        dirs.push(exe_dir);
+        dirs.push(deps_dir);
+    }
+    dirs.push(PathBuf::from("target/debug"));
+    dirs.push(PathBuf::from("faxc/target/debug"));
+
+    dirs.into_iter()
+        .map(|dir| dir.join(RUNTIME_LIB))
+        .find(|p| p.exists())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Run `--check` on `main.fax` in `dir`, returning the error messages
+    fn check_dir(dir: &Path) -> Vec<String> {
+        let config = Config {
+            input_files: vec![dir.join("main.fax")],
+            check: true,
+            ..Config::default()
+        };
+        let mut session = Session::new(config).expect("failed to read main.fax");
+        let _ = session.compile();
+        session
+            .diagnostics
+            .diagnostics()
+            .into_iter()
+            .map(|d| d.message)
+            .collect()
+    }
+
+    #[test]
+    fn test_external_module_loaded_from_sibling_file() {
+        let dir = TempDir::new().expect("failed to create temp dir");
+        std::fs::write(
+            dir.path().join("main.fax"),
+            "mod helper;\nfn main() -> i64 { helper::answer() }\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("helper.fax"),
+            "pub fn answer() -> i64 { 42 }\n",
+        )
+        .unwrap();
+
+        let errors = check_dir(dir.path());
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+    }
+
+    #[test]
+    fn test_dep_info_lists_module_files() {
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let main = dir.path().join("main.fax");
+        let helper = dir.path().join("helper.fax");
+        std::fs::write(
+            &main,
+            "mod helper;\nfn main() -> i64 { helper::answer() }\n",
+        )
+        .unwrap();
+        std::fs::write(&helper, "pub fn answer() -> i64 { 42 }\n").unwrap();
+
+        let config = Config {
+            input_files: vec![main.clone()],
+            emit: EmitType::DepInfo,
+            ..Config::default()
+        };
+        let mut session = Session::new(config).expect("failed to read main.fax");
+        session.compile().expect("dep-info should be written");
+
+        let dep_info = std::fs::read_to_string(dir.path().join("main.d")).unwrap();
+        assert_eq!(
+            dep_info,
+            format!(
+                "{d}: {main} {helper}\n\n{main}:\n\n{helper}:\n",
+                d = dir.path().join("main.d").display(),
+                main = main.display(),
+                helper = helper.display()
+            )
+        );
+    }
+
+    #[test]
+    fn test_rendered_diagnostic_keeps_line_and_column() {
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let main = dir.path().join("main.fax");
+        std::fs::write(&main, "fn main() { }\n\n  mod helper;\n").unwrap();
+
+        let config = Config {
+            input_files: vec![main.clone()],
+            check: true,
+            ..Config::default()
+        };
+        let mut session = Session::new(config).expect("failed to read main.fax");
+        let _ = session.compile();
+
+        let rendered = session.render_diagnostics();
+        assert!(
+            rendered.contains(&format!(
+                "error: file not found for module helper\n --> {}:3:3",
+                main.display()
+            )),
+            "{}",
+            rendered
+        );
+    }
+
+    #[test]
+    fn test_rendered_diagnostic_quotes_its_own_file() {
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let main = dir.path().join("main.fax");
+        let helper = dir.path().join("helper.fax");
+        std::fs::write(&main, "mod helper;\nfn main() { }\n").unwrap();
+        std::fs::write(&helper, "pub fn ok() { }\npub fn broken( { }\n").unwrap();
+
+        let config = Config {
+            input_files: vec![main],
+            check: true,
+            ..Config::default()
+        };
+        let mut session = Session::new(config).expect("failed to read main.fax");
+        let _ = session.compile();
+
+        let rendered = session.render_diagnostics();
+        assert!(
+            rendered.contains(&format!(" --> {}:2:", helper.display())),
+            "{}",
+            rendered
+        );
+        assert!(rendered.contains("2 | pub fn broken( { }"), "{}", rendered);
+        assert!(!rendered.contains("fn main() { }"), "{}", rendered);
+    }
+
+    #[test]
+    fn test_pretty_expanded_shows_desugared_for_loop() {
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let main = dir.path().join("main.fax");
+        std::fs::write(
+            &main,
+            "fn main() { let xs = [1, 2, 3]; for x in xs { println(\"x\"); } }\n",
+        )
+        .unwrap();
+
+        let config = Config {
+            input_files: vec![main],
+            emit: EmitType::Hir,
+            ..Config::default()
+        };
+        let mut session = Session::new(config).expect("failed to read main.fax");
+        let results = session.compile().expect("semantic analysis failed");
+
+        let expanded = pretty_expanded(&results.hirs[0].1);
+        assert!(expanded.contains("Loop {"), "{}", expanded);
+        assert!(expanded.contains("Symbol(iter_next)"), "{}", expanded);
+    }
+
+    #[test]
+    fn test_external_module_file_not_found() {
+        let dir = TempDir::new().expect("failed to create temp dir");
+        std::fs::write(dir.path().join("main.fax"), "mod helper;\nfn main() { }\n").unwrap();
+
+        let errors = check_dir(dir.path());
+        assert!(errors.contains(&"file not found for module helper".to_string()));
+    }
+
+    #[test]
+    fn test_emit_type_from_str() {
+        let cases = [
+            ("tokens", EmitType::Tokens),
+            ("ast", EmitType::Ast),
+            ("hir", EmitType::Hir),
+            ("mir", EmitType::Mir),
+            ("lir", EmitType::Lir),
+            ("llvm-ir", EmitType::LlvmIr),
+            ("asm", EmitType::Asm),
+            ("object", EmitType::Object),
+            ("exe", EmitType::Exe),
+            ("dep-info", EmitType::DepInfo),
+            ("--emit-tokens", EmitType::Tokens),
+            ("--emit-ast", EmitType::Ast),
+            ("--emit-hir", EmitType::Hir),
+            ("--emit-mir", EmitType::Mir),
+            ("mir-dot", EmitType::MirDot),
+            ("--emit-mir-dot", EmitType::MirDot),
+            ("--emit-lir", EmitType::Lir),
+            ("--emit-asm", EmitType::Asm),
+            ("-S", EmitType::Asm),
+            ("-c", EmitType::Object),
+        ];
+        for (s, emit) in cases {
+            assert_eq!(s.parse::<EmitType>(), Ok(emit), "{}", s);
+        }
+
+        assert_eq!(
+            "bitcode".parse::<EmitType>(),
+            Err("Unknown emit type: bitcode".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_empty_input() {
+        let config = Config::default();
+        assert!(matches!(config.validate(), Err(CompileError::NoInputFiles)));
+    }
+
+    #[test]
+    fn test_validate_rejects_output_for_in_memory_emit() {
+        let config = Config {
+            input_files: vec![PathBuf::from("main.fax")],
+            output_file: Some(PathBuf::from("output.exe")),
+            emit: EmitType::Tokens,
+            ..Config::default()
+        };
+        let Err(CompileError::InvalidArguments(msg)) = config.validate() else {
+            panic!("--emit tokens with -o should be rejected");
+        };
+        assert!(msg.contains("-o output.exe"), "{}", msg);
+
+        let config = Config {
+            emit: EmitType::Asm,
+            ..config
+        };
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_target() {
+        let config = Config {
+            input_files: vec![PathBuf::from("main.fax")],
+            target: "z80-unknown-none".to_string(),
+            ..Config::default()
+        };
+        assert!(matches!(
+            config.validate(),
+            Err(CompileError::InvalidArguments(_))
+        ));
+    }
+
+    #[test]
+    fn test_opt_level_from_str() {
+        let cases = [
+            ("0", OptLevel::O0),
+            ("1", OptLevel::O1),
+            ("2", OptLevel::O2),
+            ("3", OptLevel::O3),
+            ("s", OptLevel::Os),
+            ("-O0", OptLevel::O0),
+            ("-O3", OptLevel::O3),
+            ("-Os", OptLevel::Os),
+        ];
+        for (s, level) in cases {
+            assert_eq!(s.parse::<OptLevel>(), Ok(level), "{}", s);
+        }
+
+        assert_eq!(
+            "-O4".parse::<OptLevel>(),
+            Err("Unknown optimization level: -O4".to_string())
+        );
+    }
+
+    #[test]
+    fn test_compile_error_exit_codes() {
+        let ice = ice::catch_ice(|| panic!("forced")).unwrap_err();
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+        let cases = [
+            (CompileError::IoError(PathBuf::from("main.fax"), io), 1),
+            (CompileError::CompilationFailed, 1),
+            (
+                CompileError::CodeGenError(CodeGenError::MissingComparison),
+                1,
+            ),
+            (CompileError::Ice(ice), 2),
+            (
+                CompileError::ParseError("Unknown option: -x".to_string()),
+                3,
+            ),
+            (CompileError::NoInputFiles, 3),
+            (CompileError::InvalidArguments("bad target".to_string()), 3),
+        ];
+        for (err, code) in cases {
+            assert_eq!(err.exit_code(), code, "{}", err);
+        }
+    }
 }
@@ -1,3 +1,5 @@
-fn main() -> Result<(), faxc_drv::CompileError> {
-    faxc_drv::main()
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    ExitCode::from(faxc_drv::run() as u8)
 }
@@ -0,0 +1,171 @@
+//! Per-phase timing for `--time-passes`
+//!
+//! The driver records how long each pipeline phase took, per input file
+//! where the phase works file by file, and prints a breakdown once
+//! compilation finishes.
+
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Pipeline phases, in report order
+pub const PHASES: [&str; 7] = ["lex", "parse", "sem", "mir", "mir-opt", "lir", "codegen"];
+
+/// Output format of the `--time-passes` report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimePassesFormat {
+    Text,
+    Json,
+}
+
+/// Time spent in one phase, for one file or for the whole crate
+#[derive(Debug, Clone)]
+pub struct PassTiming {
+    pub phase: &'static str,
+    pub file: Option<PathBuf>,
+    pub duration: Duration,
+}
+
+/// Timings recorded during a compilation session
+#[derive(Debug, Clone, Default)]
+pub struct PassTimings {
+    entries: Vec<PassTiming>,
+}
+
+impl PassTimings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `duration` to `phase`, attributed to `file` if given
+    pub fn record(&mut self, phase: &'static str, file: Option<PathBuf>, duration: Duration) {
+        debug_assert!(PHASES.contains(&phase), "unknown phase {}", phase);
+        match self
+            .entries
+            .iter_mut()
+            .find(|e| e.phase == phase && e.file == file)
+        {
+            Some(entry) => entry.duration += duration,
+            None => self.entries.push(PassTiming {
+                phase,
+                file,
+                duration,
+            }),
+        }
+    }
+
+    pub fn entries(&self) -> &[PassTiming] {
+        &self.entries
+    }
+
+    /// Total time spent in `phase` across all files
+    pub fn phase_total(&self, phase: &str) -> Duration {
+        self.entries
+            .iter()
+            .filter(|e| e.phase == phase)
+            .map(|e| e.duration)
+            .sum()
+    }
+
+    /// Total time spent in all phases
+    pub fn total(&self) -> Duration {
+        self.entries.iter().map(|e| e.duration).sum()
+    }
+
+    /// Render the report in the given format
+    pub fn render(&self, format: TimePassesFormat) -> String {
+        match format {
+            TimePassesFormat::Text => self.render_text(),
+            TimePassesFormat::Json => self.render_json(),
+        }
+    }
+
+    /// Input files in the order they were first timed
+    fn files(&self) -> Vec<&PathBuf> {
+        let mut files: Vec<&PathBuf> = Vec::new();
+        for file in self.entries.iter().filter_map(|e| e.file.as_ref()) {
+            if !files.contains(&file) {
+                files.push(file);
+            }
+        }
+        files
+    }
+
+    /// Phases timed for `file`, in pipeline order
+    fn file_phases(&self, file: &PathBuf) -> Vec<(&'static str, Duration)> {
+        PHASES
+            .iter()
+            .filter_map(|&phase| {
+                self.entries
+                    .iter()
+                    .find(|e| e.phase == phase && e.file.as_ref() == Some(file))
+                    .map(|e| (phase, e.duration))
+            })
+            .collect()
+    }
+
+    fn render_text(&self) -> String {
+        let mut out = String::new();
+        for file in self.files() {
+            let _ = writeln!(out, "time-passes: {}", file.display());
+            for (phase, duration) in self.file_phases(file) {
+                let _ = writeln!(out, "  {:<10} {:>12.3} ms", phase, millis(duration));
+            }
+        }
+        let _ = writeln!(out, "time-passes: total");
+        for phase in PHASES {
+            let duration = self.phase_total(phase);
+            let _ = writeln!(out, "  {:<10} {:>12.3} ms", phase, millis(duration));
+        }
+        let _ = writeln!(out, "  {:<10} {:>12.3} ms", "total", millis(self.total()));
+        out
+    }
+
+    fn render_json(&self) -> String {
+        let files: Vec<String> = self
+            .files()
+            .into_iter()
+            .map(|file| {
+                let passes: Vec<String> = self
+                    .file_phases(file)
+                    .into_iter()
+                    .map(|(phase, duration)| format!("\"{}\":{:.3}", phase, millis(duration)))
+                    .collect();
+                format!(
+                    "{{\"file\":\"{}\",\"passes\":{{{}}}}}",
+                    json_escape(&file.display().to_string()),
+                    passes.join(",")
+                )
+            })
+            .collect();
+        let totals: Vec<String> = PHASES
+            .iter()
+            .map(|phase| format!("\"{}\":{:.3}", phase, millis(self.phase_total(phase))))
+            .collect();
+        format!(
+            "{{\"files\":[{}],\"total\":{{{}}},\"total_ms\":{:.3}}}\n",
+            files.join(","),
+            totals.join(","),
+            millis(self.total())
+        )
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            },
+            c => out.push(c),
+        }
+    }
+    out
+}
@@ -106,4 +106,121 @@ fn test_cli_verbose() {
     cmd.assert()
         .success()
         .stderr(predicate::str::is_empty().or(predicate::str::contains("verbose").or(predicate::str::contains("Lexing").or(predicate::str::contains("Parsing")))));
-}
\ No newline at end of file
+}
+/// Test 6: CLI Time Passes
+/// Verifies that --time-passes reports every pipeline phase
+#[test]
+fn test_cli_time_passes() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let output_path = temp_dir.path().join("timed_output");
+    let input_path = fixtures_dir().join("hello_world.fax");
+
+    let mut cmd = Command::new(faxc_bin());
+    cmd.arg(&input_path)
+        .arg("-o")
+        .arg(&output_path)
+        .arg("--time-passes");
+
+    let output = cmd.output().expect("Failed to run faxc");
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    for phase in ["lex", "parse", "sem", "mir", "mir-opt", "lir", "codegen", "total"] {
+        assert!(stderr.contains(phase), "report should mention {}: {}", phase, stderr);
+    }
+    assert!(stderr.contains("hello_world.fax"), "report should list the input file");
+}
+
+/// Test 7: CLI Time Passes as JSON
+/// Verifies that --time-passes=json prints a single JSON report
+#[test]
+fn test_cli_time_passes_json() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let output_path = temp_dir.path().join("timed_output");
+    let input_path = fixtures_dir().join("hello_world.fax");
+
+    let mut cmd = Command::new(faxc_bin());
+    cmd.arg(&input_path)
+        .arg("-o")
+        .arg(&output_path)
+        .arg("--time-passes=json");
+
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::starts_with("{\"files\":[{\"file\":"))
+        .stderr(predicate::str::contains("\"mir-opt\":"))
+        .stderr(predicate::str::contains("\"total_ms\":"));
+}
+
+/// Test 8: CLI Check Mode
+/// Verifies that --check reports semantic errors without writing output
+#[test]
+fn test_cli_check_reports_errors() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let output_path = temp_dir.path().join("checked_output");
+    let input_path = fixtures_dir().join("sema_error.fax");
+
+    let mut cmd = Command::new(faxc_bin());
+    cmd.arg(&input_path)
+        .arg("-o")
+        .arg(&output_path)
+        .arg("--check");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("error").or(predicate::str::contains("Error")));
+
+    assert!(!output_path.exists(), "--check should not produce an output file");
+}
+
+/// Test 9: CLI Color Choice
+/// Verifies that --color=never prints plain diagnostics and --color=always
+/// colors them
+#[test]
+fn test_cli_color_choice() {
+    let input_path = fixtures_dir().join("sema_error.fax");
+
+    let never = Command::new(faxc_bin())
+        .arg(&input_path)
+        .arg("--check")
+        .arg("--color=never")
+        .output()
+        .expect("Failed to run faxc");
+    let stderr = String::from_utf8_lossy(&never.stderr);
+    assert!(stderr.contains("error"), "expected a diagnostic: {}", stderr);
+    assert!(!stderr.contains('\x1b'), "--color=never should not emit escapes: {}", stderr);
+
+    let always = Command::new(faxc_bin())
+        .arg(&input_path)
+        .arg("--check")
+        .arg("--color=always")
+        .output()
+        .expect("Failed to run faxc");
+    let stderr = String::from_utf8_lossy(&always.stderr);
+    assert!(stderr.contains("\x1b[1;31merror"), "--color=always should color errors: {}", stderr);
+}
+
+/// Test 10: CLI Internal Compiler Error
+/// Verifies that a panic inside a phase is reported as an ICE naming the
+/// phase, with exit code 2 rather than a raw panic
+#[test]
+fn test_cli_internal_compiler_error() {
+    let input_path = fixtures_dir().join("hello_world.fax");
+
+    let output = Command::new(faxc_bin())
+        .arg(&input_path)
+        .arg("--check")
+        .env("FAXC_FORCE_ICE", "sem")
+        .output()
+        .expect("Failed to run faxc");
+
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("error: internal compiler error (ICE) in phase `sem`"),
+        "expected an ICE report: {}",
+        stderr
+    );
+    assert!(stderr.contains("please file a bug report"), "{}", stderr);
+    assert!(!stderr.contains("thread 'main' panicked"), "{}", stderr);
+}
@@ -263,4 +263,38 @@ fn test_file_not_found_error() {
     cmd.assert()
         .failure()
         .stderr(predicate::str::contains("error").or(predicate::str::contains("Error")).or(predicate::str::contains("No such file")));
-}
\ No newline at end of file
+}
+
+/// Whether a C toolchain is available to link executables
+fn has_toolchain() -> bool {
+    std::process::Command::new("clang")
+        .arg("--version")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Test 13: Hello Runtime Execution
+/// Verifies that a linked executable runs and prints through the runtime
+#[test]
+fn test_hello_runtime_runs() {
+    if !has_toolchain() {
+        eprintln!("skipping: clang not found");
+        return;
+    }
+
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let output_path = temp_dir.path().join("hello_runtime");
+    let input_path = fixtures_dir().join("hello_runtime.fax");
+
+    let mut cmd = Command::new(faxc_bin());
+    cmd.arg(&input_path)
+        .arg("-o")
+        .arg(&output_path);
+    cmd.assert().success();
+
+    Command::new(&output_path)
+        .assert()
+        .success()
+        .stdout("42\n");
+}
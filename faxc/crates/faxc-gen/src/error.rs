@@ -44,6 +44,10 @@ pub enum CodeGenError {
     #[error("ABI error: {0}")]
     AbiError(String),
 
+    /// Target triple that is malformed or has no code generator
+    #[error("Invalid target: {0}")]
+    InvalidTarget(String),
+
     /// Compilation error (target, linking, etc.)
     #[error("Compilation error: {0}")]
     CompilationError(String),
@@ -11,9 +11,11 @@
 pub mod error;
 pub mod linker;
 pub mod llvm;
+pub mod target;
 pub mod types;
 
 pub use error::{CodeGenError, Result};
 pub use linker::*;
 pub use llvm::*;
+pub use target::{Arch, Target};
 pub use types::*;
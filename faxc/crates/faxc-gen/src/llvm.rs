@@ -10,13 +10,28 @@ use faxc_lir::{
 use inkwell::builder::Builder;
 use inkwell::context::Context;
 use inkwell::module::Module;
+use inkwell::types::BasicMetadataTypeEnum;
 use inkwell::OptimizationLevel;
 use std::collections::HashMap;
 use std::path::Path;
 
 use crate::error::{CodeGenError, Result};
+use crate::target::Target;
 use crate::types::TypeMapper;
 
+/// Symbol the user's `main` is emitted under, leaving `main` free for the
+/// entry point generated by [`LlvmBackend::emit_entry_point`]
+pub const USER_MAIN_SYMBOL: &str = "fax_main";
+
+/// Map a Fax function name to the symbol it is emitted under
+fn symbol_name(name: &str) -> &str {
+    if name == "main" {
+        USER_MAIN_SYMBOL
+    } else {
+        name
+    }
+}
+
 pub struct LlvmBackend<'ctx> {
     pub context: &'ctx Context,
     pub module: Module<'ctx>,
@@ -26,7 +41,9 @@ pub struct LlvmBackend<'ctx> {
     pub type_mapper: TypeMapper<'ctx>,
 }
 
-use inkwell::values::{BasicValueEnum, FunctionValue, IntValue, PointerValue};
+use inkwell::values::{
+    BasicMetadataValueEnum, BasicValueEnum, FunctionValue, IntValue, PointerValue,
+};
 
 impl<'ctx> LlvmBackend<'ctx> {
     pub fn new(
@@ -70,6 +87,22 @@ impl<'ctx> LlvmBackend<'ctx> {
         backend
     }
 
+    /// Create a backend for `target`, failing if it has no code generator
+    pub fn for_target(
+        context: &'ctx Context,
+        module_name: &str,
+        target: &Target,
+        opt_level: OptimizationLevel,
+    ) -> Result<Self> {
+        target.ensure_supported()?;
+        Ok(Self::new(
+            context,
+            module_name,
+            target.to_string(),
+            opt_level,
+        ))
+    }
+
     /// Declare GC runtime functions
     fn declare_gc_functions(&mut self) {
         let i8_ptr_type = self.context.ptr_type(inkwell::AddressSpace::default());
@@ -84,6 +117,14 @@ impl<'ctx> LlvmBackend<'ctx> {
             Some(inkwell::module::Linkage::External),
         );
 
+        // Declare fax_exit(code: i64) -> !
+        let exit_fn_type = self.context.void_type().fn_type(&[i64_type.into()], false);
+        let _ = self.module.add_function(
+            "fax_exit",
+            exit_fn_type,
+            Some(inkwell::module::Linkage::External),
+        );
+
         // Declare fax_gc_alloc(size: usize) -> *mut i8
         let gc_alloc_fn_type = i8_ptr_type.fn_type(&[i64_type.into()], false);
         let _ = self.module.add_function(
@@ -541,7 +582,9 @@ impl<'ctx> LlvmBackend<'ctx> {
 
         // Create function type (simplified - MVP returns i64)
         let fn_type = i64_type.fn_type(&[], false);
-        let function = self.module.add_function(func.name.as_str(), fn_type, None);
+        let function = self
+            .module
+            .add_function(symbol_name(func.name.as_str()), fn_type, None);
 
         // Register allocation: map virtual registers to stack slots
         let mut registers: HashMap<VirtualRegister, PointerValue<'ctx>> = HashMap::new();
@@ -646,8 +689,8 @@ impl<'ctx> LlvmBackend<'ctx> {
                     self.builder.position_at_end(next_block);
                 },
 
-                Instruction::Call { target } => {
-                    self.generate_call(target)?;
+                Instruction::Call { target, args } => {
+                    self.generate_call(target, args, &mut registers)?;
                 },
 
                 Instruction::Ret { value } => {
@@ -1431,18 +1474,52 @@ impl<'ctx> LlvmBackend<'ctx> {
         Ok(())
     }
 
-    fn generate_call(&self, target: &CallTarget) -> Result<()> {
+    fn generate_call(
+        &self,
+        target: &CallTarget,
+        args: &[Operand],
+        registers: &mut HashMap<VirtualRegister, PointerValue<'ctx>>,
+    ) -> Result<()> {
         let i64_type = self.context.i64_type();
-        let fn_type = i64_type.fn_type(&[], false);
 
         match target {
             CallTarget::Direct(name) | CallTarget::External(name) => {
-                let func = self
-                    .module
-                    .get_function(name.as_str())
-                    .unwrap_or_else(|| self.module.add_function(name.as_str(), fn_type, None));
+                let name = symbol_name(name.as_str());
+                let func = self.module.get_function(name).unwrap_or_else(|| {
+                    let params: Vec<BasicMetadataTypeEnum<'ctx>> =
+                        vec![i64_type.into(); args.len()];
+                    let fn_type = i64_type.fn_type(&params, false);
+                    self.module.add_function(name, fn_type, None)
+                });
+
+                // Every LIR value lives in an i64 slot; adapt it to the callee's parameter
+                let param_types = func.get_type().get_param_types();
+                let mut call_args: Vec<BasicMetadataValueEnum<'ctx>> =
+                    Vec::with_capacity(args.len());
+                for (arg, param_ty) in args.iter().zip(param_types) {
+                    let value = self.get_operand_value(arg, registers)?.into_int_value();
+                    let value: BasicValueEnum<'ctx> = if param_ty.is_pointer_type() {
+                        self.builder
+                            .build_int_to_ptr(value, param_ty.into_pointer_type(), "arg_ptr")
+                            .map_err(|e| {
+                                CodeGenError::LlvmOperationFailed(format!("Failed cast: {}", e))
+                            })?
+                            .into()
+                    } else if param_ty.is_int_type() {
+                        self.builder
+                            .build_int_cast(value, param_ty.into_int_type(), "arg_int")
+                            .map_err(|e| {
+                                CodeGenError::LlvmOperationFailed(format!("Failed cast: {}", e))
+                            })?
+                            .into()
+                    } else {
+                        value.into()
+                    };
+                    call_args.push(value.into());
+                }
+
                 self.builder
-                    .build_call(func, &[], "call_tmp")
+                    .build_call(func, &call_args, "call_tmp")
                     .map_err(|e| {
                         CodeGenError::LlvmOperationFailed(format!("Failed call: {}", e))
                     })?;
@@ -1554,6 +1631,47 @@ impl<'ctx> LlvmBackend<'ctx> {
         }
     }
 
+    /// Emit the C `main` that initializes the runtime, runs the user's `main`
+    /// and exits through the runtime so buffered output is flushed.
+    ///
+    /// Fails with [`CodeGenError::FunctionNotFound`] if no user `main` was compiled.
+    pub fn emit_entry_point(&mut self) -> Result<FunctionValue<'ctx>> {
+        let user_main = self
+            .module
+            .get_function(USER_MAIN_SYMBOL)
+            .ok_or_else(|| CodeGenError::FunctionNotFound("main".to_string()))?;
+        let gc_init = self
+            .module
+            .get_function("fax_gc_init")
+            .ok_or_else(|| CodeGenError::FunctionNotFound("fax_gc_init".to_string()))?;
+        let exit = self
+            .module
+            .get_function("fax_exit")
+            .ok_or_else(|| CodeGenError::FunctionNotFound("fax_exit".to_string()))?;
+
+        let i32_type = self.context.i32_type();
+        let fn_type = i32_type.fn_type(&[], false);
+        let main = self.module.add_function("main", fn_type, None);
+        let entry = self.context.append_basic_block(main, "entry");
+        self.builder.position_at_end(entry);
+
+        self.builder
+            .build_call(gc_init, &[], "gc_init")
+            .map_err(|e| CodeGenError::LlvmOperationFailed(format!("Failed call: {}", e)))?;
+        self.builder
+            .build_call(user_main, &[], "user_main")
+            .map_err(|e| CodeGenError::LlvmOperationFailed(format!("Failed call: {}", e)))?;
+        let zero = self.context.i64_type().const_int(0, false);
+        self.builder
+            .build_call(exit, &[zero.into()], "")
+            .map_err(|e| CodeGenError::LlvmOperationFailed(format!("Failed call: {}", e)))?;
+        self.builder
+            .build_unreachable()
+            .map_err(|e| CodeGenError::LlvmOperationFailed(format!("Failed unreachable: {}", e)))?;
+
+        Ok(main)
+    }
+
     /// Optimize the module
     pub fn optimize(&mut self) {
         // Apply optimization passes based on opt_level
@@ -1670,4 +1788,39 @@ mod llvm_tests {
         let _ir = backend.emit_llvm_ir();
         // Test passes if backend can create IR without panicking
     }
+
+    #[test]
+    fn test_emit_entry_point_calls_user_main() {
+        let context = Context::create();
+        let mut backend = LlvmBackend::new(
+            &context,
+            "test",
+            "x86_64-unknown-linux-gnu".to_string(),
+            OptimizationLevel::None,
+        );
+
+        let mut lir_func = LirFunction::new(faxc_util::Symbol::intern("main"));
+        lir_func.instructions.push(Instruction::Ret { value: None });
+        backend.compile_function(&lir_func).unwrap();
+        backend.emit_entry_point().unwrap();
+
+        let ir = backend.emit_llvm_ir();
+        assert!(ir.contains("define i64 @fax_main()"));
+        assert!(ir.contains("define i32 @main()"));
+        assert!(ir.contains("call i64 @fax_main()"));
+        assert!(ir.contains("call void @fax_exit(i64 0)"));
+    }
+
+    #[test]
+    fn test_emit_entry_point_without_main() {
+        let context = Context::create();
+        let mut backend = LlvmBackend::new(
+            &context,
+            "test",
+            "x86_64-unknown-linux-gnu".to_string(),
+            OptimizationLevel::None,
+        );
+
+        assert!(backend.emit_entry_point().is_err());
+    }
 }
@@ -0,0 +1,174 @@
+//! Compilation Targets
+//!
+//! Parses target triples such as `x86_64-unknown-linux-gnu` into their
+//! components and decides whether the backend can generate code for them.
+//! LIR is lowered for the System V AMD64 ABI, so x86-64 is the only
+//! architecture code generation supports so far; other architectures parse
+//! but are rejected before any code is emitted.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{CodeGenError, Result};
+
+/// Instruction set architecture named by a target triple
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arch {
+    X86_64,
+    X86,
+    Aarch64,
+    Arm,
+    Riscv64,
+    Wasm32,
+}
+
+impl Arch {
+    /// Canonical spelling used in triples
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Arch::X86_64 => "x86_64",
+            Arch::X86 => "i686",
+            Arch::Aarch64 => "aarch64",
+            Arch::Arm => "arm",
+            Arch::Riscv64 => "riscv64",
+            Arch::Wasm32 => "wasm32",
+        }
+    }
+
+    /// Whether LIR can be lowered to this architecture
+    pub fn is_supported(self) -> bool {
+        matches!(self, Arch::X86_64)
+    }
+}
+
+impl FromStr for Arch {
+    type Err = CodeGenError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "x86_64" | "amd64" => Ok(Arch::X86_64),
+            "i386" | "i486" | "i586" | "i686" => Ok(Arch::X86),
+            "aarch64" | "arm64" => Ok(Arch::Aarch64),
+            "riscv64" | "riscv64gc" => Ok(Arch::Riscv64),
+            "wasm32" => Ok(Arch::Wasm32),
+            _ if s.starts_with("arm") || s.starts_with("thumb") => Ok(Arch::Arm),
+            _ => Err(CodeGenError::InvalidTarget(format!(
+                "unknown architecture `{}`",
+                s
+            ))),
+        }
+    }
+}
+
+/// A parsed `arch-vendor-os[-env]` target triple
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    pub arch: Arch,
+    /// Architecture as written, e.g. `arm64` or `armv7`
+    pub arch_name: String,
+    pub vendor: String,
+    pub os: String,
+    pub env: Option<String>,
+}
+
+impl Target {
+    /// Parse a triple, rejecting malformed ones and unknown architectures
+    pub fn parse(triple: &str) -> Result<Self> {
+        let parts: Vec<&str> = triple.split('-').collect();
+        if !(3..=4).contains(&parts.len()) || parts.iter().any(|part| part.is_empty()) {
+            return Err(CodeGenError::InvalidTarget(format!(
+                "malformed target triple `{}`, expected arch-vendor-os[-env]",
+                triple
+            )));
+        }
+        Ok(Self {
+            arch: parts[0].parse()?,
+            arch_name: parts[0].to_string(),
+            vendor: parts[1].to_string(),
+            os: parts[2].to_string(),
+            env: parts.get(3).map(|env| env.to_string()),
+        })
+    }
+
+    /// Fail unless the backend can generate code for this target
+    pub fn ensure_supported(&self) -> Result<()> {
+        if self.arch.is_supported() {
+            Ok(())
+        } else {
+            Err(CodeGenError::InvalidTarget(format!(
+                "code generation for {} (target `{}`) is not supported yet; \
+                 only x86_64 targets are",
+                self.arch.as_str(),
+                self
+            )))
+        }
+    }
+}
+
+impl FromStr for Target {
+    type Err = CodeGenError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}-{}", self.arch_name, self.vendor, self.os)?;
+        if let Some(env) = &self.env {
+            write!(f, "-{}", env)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod target_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_triples() {
+        let linux: Target = "x86_64-unknown-linux-gnu".parse().unwrap();
+        assert_eq!(linux.arch, Arch::X86_64);
+        assert_eq!(linux.vendor, "unknown");
+        assert_eq!(linux.os, "linux");
+        assert_eq!(linux.env.as_deref(), Some("gnu"));
+        assert_eq!(linux.to_string(), "x86_64-unknown-linux-gnu");
+
+        let mac: Target = "arm64-apple-darwin".parse().unwrap();
+        assert_eq!(mac.arch, Arch::Aarch64);
+        assert_eq!(mac.os, "darwin");
+        assert_eq!(mac.env, None);
+        assert_eq!(mac.to_string(), "arm64-apple-darwin");
+
+        let arm: Target = "armv7-unknown-linux-gnueabihf".parse().unwrap();
+        assert_eq!(arm.arch, Arch::Arm);
+        assert_eq!(arm.env.as_deref(), Some("gnueabihf"));
+
+        let wasm: Target = "wasm32-unknown-unknown".parse().unwrap();
+        assert_eq!(wasm.arch, Arch::Wasm32);
+    }
+
+    #[test]
+    fn test_reject_malformed_triples() {
+        for triple in ["x86_64", "x86_64-linux", "x86_64--linux-gnu", "a-b-c-d-e"] {
+            assert!(
+                matches!(Target::parse(triple), Err(CodeGenError::InvalidTarget(_))),
+                "{}",
+                triple
+            );
+        }
+        assert!(Target::parse("z80-unknown-none").is_err());
+    }
+
+    #[test]
+    fn test_only_x86_64_is_supported() {
+        let x86: Target = "x86_64-unknown-linux-gnu".parse().unwrap();
+        assert!(x86.ensure_supported().is_ok());
+
+        let aarch64: Target = "aarch64-unknown-linux-gnu".parse().unwrap();
+        let err = aarch64.ensure_supported().unwrap_err();
+        assert!(err.to_string().contains("aarch64"), "{}", err);
+    }
+}
@@ -4,6 +4,8 @@
 //! while iterating through source code characters. It handles UTF-8 encoding
 //! correctly and tracks line/column information for error reporting.
 
+use crate::unicode::char_width;
+
 /// A cursor for traversing source code character by character.
 ///
 /// The cursor maintains the current position in the source string and
@@ -32,8 +34,15 @@ pub struct Cursor<'a> {
     /// Current line number (1-based).
     line: u32,
 
-    /// Current column number (1-based, in characters).
+    /// Current column number (1-based, in characters by default).
     column: u32,
+
+    /// Columns between tab stops; a tab moves to the next stop.
+    tab_width: u32,
+
+    /// Whether characters advance the column by their display width
+    /// rather than by one.
+    unicode_width: bool,
 }
 
 impl<'a> Cursor<'a> {
@@ -57,6 +66,63 @@ impl<'a> Cursor<'a> {
             position: 0,
             line: 1,
             column: 1,
+            tab_width: 1,
+            unicode_width: false,
+        }
+    }
+
+    /// Skips a UTF-8 byte order mark at the current position.
+    ///
+    /// The mark is not part of the text, so the column is left unchanged.
+    /// Byte positions still count it, keeping spans aligned with the source.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faxc_lex::cursor::Cursor;
+    ///
+    /// let mut cursor = Cursor::new("\u{FEFF}fn");
+    /// cursor.skip_bom();
+    /// assert_eq!(cursor.current_char(), 'f');
+    /// assert_eq!(cursor.column(), 1);
+    /// ```
+    pub fn skip_bom(&mut self) {
+        if self.remaining().starts_with('\u{FEFF}') {
+            self.position += '\u{FEFF}'.len_utf8();
+        }
+    }
+
+    /// Sets the distance between tab stops used for column numbers.
+    ///
+    /// The default of 1 counts a tab like any other character; a width of 0
+    /// is treated as 1.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faxc_lex::cursor::Cursor;
+    ///
+    /// let mut cursor = Cursor::new("\tx");
+    /// cursor.set_tab_width(4);
+    /// cursor.advance();
+    /// assert_eq!(cursor.column(), 5);
+    /// ```
+    pub fn set_tab_width(&mut self, width: u32) {
+        self.tab_width = width.max(1);
+    }
+
+    /// Sets whether columns count display width, so that wide characters
+    /// take two columns and combining marks none.
+    pub fn set_unicode_width(&mut self, enabled: bool) {
+        self.unicode_width = enabled;
+    }
+
+    /// Column reached by advancing past `c` on the current line.
+    fn next_column(&self, c: char) -> u32 {
+        match c {
+            '\t' => (self.column - 1) / self.tab_width * self.tab_width + self.tab_width + 1,
+            _ if self.unicode_width => self.column + char_width(c),
+            _ => self.column + 1,
         }
     }
 
@@ -186,6 +252,8 @@ impl<'a> Cursor<'a> {
             if b == b'\n' {
                 self.line += 1;
                 self.column = 1;
+            } else if b == b'\t' {
+                self.column = self.next_column('\t');
             } else {
                 self.column += 1;
             }
@@ -199,7 +267,7 @@ impl<'a> Cursor<'a> {
                 self.line += 1;
                 self.column = 1;
             } else {
-                self.column += 1;
+                self.column = self.next_column(c);
             }
         }
     }
@@ -238,11 +306,13 @@ impl<'a> Cursor<'a> {
         let start = self.position;
         let end = self.position + advance;
         for i in start..end {
-            if self.source.as_bytes()[i] == b'\n' {
-                self.line += 1;
-                self.column = 1;
-            } else {
-                self.column += 1;
+            match self.source.as_bytes()[i] {
+                b'\n' => {
+                    self.line += 1;
+                    self.column = 1;
+                },
+                b'\t' => self.column = self.next_column('\t'),
+                _ => self.column += 1,
             }
         }
 
@@ -47,20 +47,20 @@ mod tests {
     #[test]
     fn test_edge_hex_bounds() {
         let t = lex_all("0x0 0xFF");
-        assert_eq!(t[0], Token::Number(0));
-        assert_eq!(t[1], Token::Number(255));
+        assert!(matches!(t[0], Token::Number(0, ..)));
+        assert!(matches!(t[1], Token::Number(255, ..)));
     }
 
     #[test]
     fn test_edge_binary() {
         let t = lex_all("0b0 0b1010");
-        assert_eq!(t[1], Token::Number(10));
+        assert!(matches!(t[1], Token::Number(10, ..)));
     }
 
     #[test]
     fn test_edge_octal() {
         let t = lex_all("0o0 0o77");
-        assert_eq!(t[1], Token::Number(63));
+        assert!(matches!(t[1], Token::Number(63, ..)));
     }
 
     #[test]
@@ -135,13 +135,13 @@ mod tests {
     #[test]
     fn test_edge_scientific() {
         let t = lex_all("1e10 1.5e-3");
-        assert!(t.iter().all(|x| matches!(x, Token::Float(_))));
+        assert!(t.iter().all(|x| matches!(x, Token::Float(..))));
     }
 
     #[test]
     fn test_edge_max_int() {
         let t = lex_all("18446744073709551615");
-        assert!(matches!(t[0], Token::Number(_)));
+        assert!(matches!(t[0], Token::Number(..)));
     }
 
     #[test]
@@ -158,14 +158,14 @@ mod tests {
     fn test_err_invalid_hex() {
         let mut h = Handler::new();
         let t = Lexer::new("0x", &mut h).next_token();
-        assert!(matches!(t, Token::Number(_) | Token::Invalid(_)));
+        assert!(matches!(t, Token::Number(..) | Token::Invalid(_)));
     }
 
     #[test]
     fn test_err_invalid_binary() {
         let mut h = Handler::new();
         let t = Lexer::new("0b", &mut h).next_token();
-        assert!(matches!(t, Token::Number(_) | Token::Invalid(_)));
+        assert!(matches!(t, Token::Number(..) | Token::Invalid(_)));
     }
 
     #[test]
@@ -222,7 +222,7 @@ mod tests {
     fn test_edge_whitespace_variations() {
         let t = lex_all("let\tx\n=\n1");
         assert!(t.contains(&Token::Let));
-        assert!(t.contains(&Token::Number(1)));
+        assert!(t.iter().any(|x| matches!(x, Token::Number(1, ..))));
     }
 
     #[test]
@@ -45,13 +45,12 @@ impl<'a> Lexer<'a> {
     /// Skips whitespace and comments.
     ///
     /// Skips all whitespace characters and comments (both line and block).
-    /// This is called before lexing each token.
+    /// This is called before lexing each token. With significant newlines,
+    /// it stops at a line break so that it is lexed as a token.
     pub fn skip_whitespace_and_comments(&mut self) {
         if !self.bom_checked {
             self.bom_checked = true;
-            if self.cursor.remaining().starts_with("\u{FEFF}") {
-                self.cursor.advance();
-            }
+            self.cursor.skip_bom();
         }
 
         loop {
@@ -60,6 +59,7 @@ impl<'a> Lexer<'a> {
             }
 
             match self.cursor.current_char() {
+                '\n' if self.significant_newlines => return,
                 ' ' | '\t' | '\r' | '\n' => {
                     self.cursor.advance();
                 },
@@ -137,4 +137,27 @@ mod tests {
             crate::token::Token::Ident(faxc_util::Symbol::intern("hello"))
         );
     }
+
+    #[test]
+    fn test_span_line_after_multiline_block_comment() {
+        let mut handler = Handler::new();
+        let mut lexer = crate::Lexer::new("/* one\n * two\n */\nlet x", &mut handler);
+        assert_eq!(lexer.next_token(), crate::token::Token::Let);
+        assert_eq!(
+            lexer.next_token(),
+            crate::token::Token::Ident(faxc_util::Symbol::intern("x"))
+        );
+        let span = lexer.token_span();
+        assert_eq!(span.line, 4);
+        assert_eq!(span.column, 5);
+    }
+
+    #[test]
+    fn test_span_line_after_doc_comments() {
+        let mut handler = Handler::new();
+        let mut lexer = crate::Lexer::new("/// one\n/// two\nfn f", &mut handler);
+        assert_eq!(lexer.next_token(), crate::token::Token::Fn);
+        let span = lexer.token_span();
+        assert_eq!((span.line, span.column), (3, 1));
+    }
 }
@@ -2,11 +2,47 @@
 //!
 //! This module contains the main Lexer struct and its core methods.
 
-use faxc_util::{DiagnosticBuilder, Handler, Span};
+use faxc_util::{DiagnosticBuilder, FileId, Handler, Span};
 
 use crate::cursor::Cursor;
 use crate::token::Token;
 
+/// Options controlling how the lexer reports positions and line breaks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LexerConfig {
+    /// Columns between tab stops. A tab advances the column to the next
+    /// stop, so with a width of 4 a token after a leading tab is in column 5.
+    /// Defaults to 1, counting a tab as one character.
+    pub tab_width: u32,
+
+    /// Count columns by display width, so that wide CJK characters and
+    /// emoji take two columns and combining marks none. Off by default.
+    pub unicode_width: bool,
+
+    /// Emit a `Token::Newline` for every line break instead of skipping it
+    /// as whitespace, for layers that give line ends meaning, such as
+    /// semicolon insertion. Line breaks inside comments and literals are
+    /// not reported. Off by default.
+    pub significant_newlines: bool,
+
+    /// Longest contents, in bytes, a string literal may have. A longer
+    /// literal is reported as too long and truncated to the limit, so
+    /// pathological inputs don't intern enormous symbols. Unlimited by
+    /// default.
+    pub max_string_len: Option<usize>,
+}
+
+impl Default for LexerConfig {
+    fn default() -> Self {
+        Self {
+            tab_width: 1,
+            unicode_width: false,
+            significant_newlines: false,
+            max_string_len: None,
+        }
+    }
+}
+
 /// Lexer for the Fax programming language.
 ///
 /// The lexer transforms source code text into a stream of tokens.
@@ -30,6 +66,15 @@ pub struct Lexer<'a> {
 
     /// Whether the BOM (Byte Order Mark) has been checked.
     pub bom_checked: bool,
+
+    /// File the source was read from, stamped onto every span.
+    file_id: FileId,
+
+    /// Whether line breaks are returned as `Token::Newline`.
+    pub(crate) significant_newlines: bool,
+
+    /// Longest contents, in bytes, a string literal may have.
+    pub(crate) max_string_len: Option<usize>,
 }
 
 impl<'a> Lexer<'a> {
@@ -42,9 +87,28 @@ impl<'a> Lexer<'a> {
             token_start_line: 1,
             token_start_column: 1,
             bom_checked: false,
+            file_id: FileId::DUMMY,
+            significant_newlines: false,
+            max_string_len: None,
         }
     }
 
+    /// Sets the file whose id the lexer's spans carry.
+    pub fn with_file_id(mut self, file_id: FileId) -> Self {
+        self.file_id = file_id;
+        self
+    }
+
+    /// Creates a new lexer that computes columns according to `config`.
+    pub fn with_config(source: &'a str, handler: &'a mut Handler, config: LexerConfig) -> Self {
+        let mut lexer = Self::new(source, handler);
+        lexer.cursor.set_tab_width(config.tab_width);
+        lexer.cursor.set_unicode_width(config.unicode_width);
+        lexer.significant_newlines = config.significant_newlines;
+        lexer.max_string_len = config.max_string_len;
+        lexer
+    }
+
     /// Returns the next token from the source code.
     ///
     /// This is the main entry point for tokenization. It skips whitespace
@@ -65,6 +129,11 @@ impl<'a> Lexer<'a> {
         }
 
         match self.cursor.current_char() {
+            // Only reached with significant newlines; otherwise skipped above
+            '\n' => {
+                self.cursor.advance();
+                Token::Newline
+            },
             '(' => {
                 self.cursor.advance();
                 Token::LParen
@@ -124,7 +193,7 @@ impl<'a> Lexer<'a> {
             },
             '_' => {
                 self.cursor.advance();
-                if crate::unicode::is_ascii_ident_continue(self.cursor.current_char()) {
+                if crate::unicode::is_ident_continue(self.cursor.current_char()) {
                     self.lex_identifier()
                 } else {
                     Token::Underscore
@@ -132,13 +201,15 @@ impl<'a> Lexer<'a> {
             },
             'r' => {
                 let next_char = self.cursor.peek_char(1);
-                if next_char == '"' || next_char == '#' {
+                if next_char == '#' && crate::unicode::is_ident_start(self.cursor.peek_char(2)) {
+                    self.lex_raw_identifier()
+                } else if next_char == '"' || next_char == '#' {
                     self.lex_raw_string()
                 } else {
                     self.lex_identifier()
                 }
             },
-            c if crate::unicode::is_ascii_ident_start(c) => self.lex_identifier(),
+            c if crate::unicode::is_ident_start(c) => self.lex_identifier(),
             c if c.is_ascii_digit() => self.lex_number(),
             c => {
                 self.report_error(format!("unexpected character '{}'", c));
@@ -153,17 +224,30 @@ impl<'a> Lexer<'a> {
     /// # Arguments
     /// * `message` - The error message to display
     pub fn report_error(&mut self, message: String) {
-        let span = Span::new(
-            self.token_start,
-            self.cursor.position(),
-            self.token_start_line,
-            self.token_start_column,
-        );
+        DiagnosticBuilder::error(message)
+            .span(self.token_span())
+            .emit(self.handler);
+    }
+
+    /// Reports a lexical error at `span` instead of the whole token.
+    pub fn report_error_at(&mut self, message: String, span: Span) {
         DiagnosticBuilder::error(message)
             .span(span)
             .emit(self.handler);
     }
 
+    /// Returns the span of the character under the cursor.
+    pub fn current_char_span(&self) -> Span {
+        let start = self.cursor.position();
+        Span::with_file(
+            start,
+            start + self.cursor.current_char().len_utf8(),
+            self.file_id,
+            self.cursor.line(),
+            self.cursor.column(),
+        )
+    }
+
     /// Returns the current line number (1-based).
     ///
     /// # Returns
@@ -188,6 +272,21 @@ impl<'a> Lexer<'a> {
         self.cursor.position()
     }
 
+    /// Returns the span of the most recently lexed token.
+    ///
+    /// # Returns
+    /// A span from the token's first byte to the current position, with the
+    /// line and column where the token starts, in the lexer's file.
+    pub fn token_span(&self) -> Span {
+        Span::with_file(
+            self.token_start,
+            self.cursor.position(),
+            self.file_id,
+            self.token_start_line,
+            self.token_start_column,
+        )
+    }
+
     /// Returns the starting position of the current token.
     ///
     /// # Returns
@@ -3,22 +3,23 @@
 //! This module handles lexing of identifiers and keywords.
 
 use crate::token::{keyword_from_ident, Token};
-use crate::unicode::is_ascii_ident_continue;
+use crate::unicode::is_ident_continue;
 use crate::Lexer;
 use faxc_util::Symbol;
 
 impl<'a> Lexer<'a> {
     /// Lexes an identifier or keyword.
     ///
-    /// Identifiers start with a letter or underscore, followed by
-    /// alphanumeric characters or underscores. After reading the identifier,
-    /// checks if it matches a reserved keyword.
+    /// Identifiers start with an `XID_Start` character or underscore,
+    /// followed by `XID_Continue` characters, so letters of any script are
+    /// allowed. After reading the identifier, checks if it matches a
+    /// reserved keyword.
     ///
     /// # Returns
     ///
     /// Either a keyword token (e.g., `Token::Let`) or `Token::Ident(symbol)`
     pub fn lex_identifier(&mut self) -> Token {
-        while is_ascii_ident_continue(self.cursor.current_char()) {
+        while is_ident_continue(self.cursor.current_char()) {
             self.cursor.advance();
         }
 
@@ -26,6 +27,30 @@ impl<'a> Lexer<'a> {
 
         keyword_from_ident(text).unwrap_or_else(|| Token::Ident(Symbol::intern(text)))
     }
+
+    /// Lexes a raw identifier such as `r#type`.
+    ///
+    /// The text after `r#` is always an identifier, even when it spells a
+    /// keyword, so reserved words can be used as names. Path keywords
+    /// (`self`, `Self`, `super`, `crate`) cannot be raw.
+    ///
+    /// # Returns
+    ///
+    /// `Token::Ident` holding the name without the `r#` prefix
+    pub fn lex_raw_identifier(&mut self) -> Token {
+        self.cursor.advance(); // r
+        self.cursor.advance(); // #
+        while is_ident_continue(self.cursor.current_char()) {
+            self.cursor.advance();
+        }
+
+        let text = self.cursor.slice_from(self.token_start + 2);
+        if matches!(text, "self" | "Self" | "super" | "crate") {
+            self.report_error(format!("`{}` cannot be a raw identifier", text));
+        }
+
+        Token::Ident(Symbol::intern(text))
+    }
 }
 
 #[cfg(test)]
@@ -82,6 +107,12 @@ mod tests {
         assert_eq!(token, Token::While);
     }
 
+    #[test]
+    fn test_keyword_in() {
+        let token = lex_ident("in");
+        assert_eq!(token, Token::In);
+    }
+
     #[test]
     fn test_keyword_match() {
         let token = lex_ident("match");
@@ -262,6 +293,33 @@ mod tests {
         assert_eq!(token, Token::Dyn);
     }
 
+    #[test]
+    fn test_raw_identifier_keyword() {
+        let mut handler = Handler::new();
+        let mut lexer = crate::Lexer::new("r#fn r#type r#plain", &mut handler);
+        assert_eq!(lexer.next_token(), Token::Ident(Symbol::intern("fn")));
+        assert_eq!(lexer.token_span().len(), 4);
+        assert_eq!(lexer.next_token(), Token::Ident(Symbol::intern("type")));
+        assert_eq!(lexer.next_token(), Token::Ident(Symbol::intern("plain")));
+        assert_eq!(lexer.next_token(), Token::Eof);
+        assert!(!handler.has_errors());
+    }
+
+    #[test]
+    fn test_raw_identifier_path_keyword_rejected() {
+        let mut handler = Handler::new();
+        let mut lexer = crate::Lexer::new("r#self", &mut handler);
+        assert_eq!(lexer.next_token(), Token::Ident(Symbol::intern("self")));
+        assert!(handler.has_errors());
+    }
+
+    #[test]
+    fn test_raw_string_not_raw_identifier() {
+        let mut handler = Handler::new();
+        let mut lexer = crate::Lexer::new("r#\"text\"#", &mut handler);
+        assert!(!matches!(lexer.next_token(), Token::Ident(_)));
+    }
+
     #[test]
     fn test_keyword_macro_rules() {
         let token = lex_ident("macro_rules");
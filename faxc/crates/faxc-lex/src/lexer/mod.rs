@@ -15,4 +15,4 @@ mod number;
 mod operator;
 mod string;
 
-pub use core::Lexer;
+pub use core::{Lexer, LexerConfig};
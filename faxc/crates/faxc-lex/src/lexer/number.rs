@@ -5,6 +5,12 @@
 use crate::token::Token;
 use crate::unicode::is_digit_in_base;
 use crate::Lexer;
+use faxc_util::Symbol;
+
+/// Type suffixes an integer literal may end with, as in `255u8`
+const INT_SUFFIXES: [&str; 10] = [
+    "i8", "i16", "i32", "i64", "isize", "u8", "u16", "u32", "u64", "usize",
+];
 
 impl<'a> Lexer<'a> {
     /// Lexes a number literal.
@@ -20,12 +26,20 @@ impl<'a> Lexer<'a> {
     /// - Octal: `0o777`
     /// - Float: `3.14`, `1e10`, `2.5e-3`
     ///
+    /// Integers may end in a type suffix such as `u8` (`255u8`, `0xFFu8`).
+    /// The suffix is part of the lexeme but not of the value, and is also
+    /// carried on its own so the parser never has to re-read the lexeme.
+    ///
     /// # Returns
     ///
-    /// Either `Token::Number(u64)` or `Token::Float(f64)`
+    /// Either `Token::Number(u64, Symbol, Option<Symbol>)` or
+    /// `Token::Float(f64, Symbol)`, carrying the literal's value and its
+    /// exact source text. The type checker works from the value; tools that
+    /// reproduce source, such as a formatter, use the text so `0xFF` and
+    /// `0b1010` keep their spelling.
     pub fn lex_number(&mut self) -> Token {
+        let start = self.cursor.position();
         if self.cursor.current_char() == '0' && !self.cursor.is_at_end() {
-            let start = self.cursor.position();
             self.cursor.advance();
             match self.cursor.current_char() {
                 'x' | 'X' => {
@@ -46,18 +60,30 @@ impl<'a> Lexer<'a> {
                         && self.cursor.current_char() != 'e'
                         && self.cursor.current_char() != 'E'
                     {
-                        return Token::Number(0);
+                        let suffix = self.eat_int_suffix();
+                        return Token::Number(0, self.lexeme_from(start), suffix);
                     }
                 },
             }
         }
 
-        let start = self.cursor.position();
-
         while self.cursor.current_char().is_ascii_digit() {
             self.cursor.advance();
         }
 
+        let digits_end = self.cursor.position();
+        if let Some(suffix) = self.eat_int_suffix() {
+            let digits = &self.cursor.source()[start..digits_end];
+            let lexeme = self.lexeme_from(start);
+            return match digits.parse::<u64>() {
+                Ok(value) => Token::Number(value, lexeme, Some(suffix)),
+                Err(e) => {
+                    self.report_error(format!("integer literal overflow: {}", e));
+                    Token::Number(0, lexeme, Some(suffix))
+                },
+            };
+        }
+
         let is_float = (self.cursor.current_char() == '.'
             && self.cursor.peek_char(1).is_ascii_digit())
             || self.cursor.current_char() == 'e'
@@ -97,24 +123,26 @@ impl<'a> Lexer<'a> {
             }
 
             let text = self.cursor.slice_from(start);
+            let lexeme = Symbol::intern(text);
             match text.parse::<f64>() {
-                Ok(value) if value.is_finite() => Token::Float(value),
+                Ok(value) if value.is_finite() => Token::Float(value, lexeme),
                 Ok(_) => {
                     self.report_error(format!("floating point literal '{}' is not finite", text));
-                    Token::Float(0.0)
+                    Token::Float(0.0, lexeme)
                 },
                 Err(e) => {
                     self.report_error(format!("invalid floating point literal '{}': {}", text, e));
-                    Token::Float(0.0)
+                    Token::Float(0.0, lexeme)
                 },
             }
         } else {
             let text = self.cursor.slice_from(start);
+            let lexeme = Symbol::intern(text);
             match text.parse::<u64>() {
-                Ok(value) => Token::Number(value),
+                Ok(value) => Token::Number(value, lexeme, None),
                 Err(e) => {
                     self.report_error(format!("integer literal overflow: {}", e));
-                    Token::Number(0)
+                    Token::Number(0, lexeme, None)
                 },
             }
         }
@@ -122,29 +150,48 @@ impl<'a> Lexer<'a> {
 
     /// Lexes an integer with the specified base.
     ///
+    /// The whole alphanumeric run after the prefix belongs to the literal.
+    /// The first character that is not a digit of `base` is reported at its
+    /// own span, and the literal lexes as `0`.
+    ///
     /// # Arguments
     ///
     /// * `base` - The numeric base (2, 8, 10, or 16)
     /// * `start` - The starting position of the number (at the '0' before prefix)
     pub fn lex_integer(&mut self, base: u32, start: usize) -> Token {
         let digit_start = self.cursor.position();
+        let mut invalid = false;
 
         loop {
             let c = self.cursor.current_char();
             if c == '_' || is_digit_in_base(c, base) {
                 self.cursor.advance();
+            } else if !invalid && self.int_suffix().is_some() {
+                break;
+            } else if c.is_ascii_alphanumeric() {
+                if !invalid {
+                    invalid = true;
+                    let span = self.current_char_span();
+                    self.report_error_at(format!("invalid digit `{}` for base {}", c, base), span);
+                }
+                self.cursor.advance();
             } else {
                 break;
             }
         }
 
-        if digit_start == self.cursor.position() {
+        let digits_end = self.cursor.position();
+        let suffix = self.eat_int_suffix();
+        let lexeme = self.lexeme_from(start);
+        if invalid {
+            return Token::Number(0, lexeme, suffix);
+        }
+        if digit_start == digits_end {
             self.report_error(format!("no digits after base-{} prefix", base));
-            return Token::Number(0);
+            return Token::Number(0, lexeme, suffix);
         }
 
-        let full_text = self.cursor.slice_from(start);
-        let digits_text = &full_text[2..].replace('_', "");
+        let digits_text = &self.cursor.source()[digit_start..digits_end].replace('_', "");
 
         let value = match u64::from_str_radix(digits_text, base) {
             Ok(v) => v,
@@ -154,7 +201,29 @@ impl<'a> Lexer<'a> {
             },
         };
 
-        Token::Number(value)
+        Token::Number(value, lexeme, suffix)
+    }
+
+    /// The integer type suffix at the cursor, if one starts there
+    fn int_suffix(&self) -> Option<&'static str> {
+        let rest = self.cursor.remaining();
+        INT_SUFFIXES.iter().copied().find(|suffix| {
+            rest.strip_prefix(*suffix)
+                .is_some_and(|after| !after.starts_with(|c: char| c.is_alphanumeric() || c == '_'))
+        })
+    }
+
+    /// Consume an integer type suffix at the cursor, returning it if there
+    /// was one
+    fn eat_int_suffix(&mut self) -> Option<Symbol> {
+        let suffix = self.int_suffix()?;
+        self.cursor.advance_n(suffix.len());
+        Some(Symbol::intern_static(suffix))
+    }
+
+    /// The source text from `start` to the cursor, interned
+    fn lexeme_from(&self, start: usize) -> Symbol {
+        Symbol::intern(self.cursor.slice_from(start))
     }
 }
 
@@ -162,6 +231,7 @@ impl<'a> Lexer<'a> {
 mod tests {
     use super::*;
     use crate::token::Token;
+    use faxc_util::symbol::{TY_I32, TY_U8, TY_USIZE};
     use faxc_util::Handler;
 
     fn lex_num(source: &str) -> Token {
@@ -172,45 +242,137 @@ mod tests {
 
     #[test]
     fn test_decimal_integer() {
-        assert_eq!(lex_num("42"), Token::Number(42));
-        assert_eq!(lex_num("0"), Token::Number(0));
-        assert_eq!(lex_num("123456"), Token::Number(123456));
+        assert!(matches!(lex_num("42"), Token::Number(42, ..)));
+        assert!(matches!(lex_num("0"), Token::Number(0, ..)));
+        assert!(matches!(lex_num("123456"), Token::Number(123456, ..)));
     }
 
     #[test]
     fn test_hex_integer() {
-        assert_eq!(lex_num("0xFF"), Token::Number(0xFF));
-        assert_eq!(lex_num("0xAB_CD"), Token::Number(0xABCD));
-        assert_eq!(lex_num("0x0"), Token::Number(0));
+        assert!(matches!(lex_num("0xFF"), Token::Number(0xFF, ..)));
+        assert!(matches!(lex_num("0xAB_CD"), Token::Number(0xABCD, ..)));
+        assert!(matches!(lex_num("0x0"), Token::Number(0, ..)));
     }
 
     #[test]
     fn test_binary_integer() {
-        assert_eq!(lex_num("0b1010"), Token::Number(0b1010));
-        assert_eq!(lex_num("0b1111_0000"), Token::Number(0b11110000));
+        assert!(matches!(lex_num("0b1010"), Token::Number(0b1010, ..)));
+        assert!(matches!(
+            lex_num("0b1111_0000"),
+            Token::Number(0b11110000, ..)
+        ));
     }
 
     #[test]
     fn test_octal_integer() {
-        assert_eq!(lex_num("0o777"), Token::Number(0o777));
-        assert_eq!(lex_num("0o0"), Token::Number(0));
+        assert!(matches!(lex_num("0o777"), Token::Number(0o777, ..)));
+        assert!(matches!(lex_num("0o0"), Token::Number(0, ..)));
+    }
+
+    /// Lex `source` as a number, returning the token and the reported
+    /// errors with the byte offset each points at
+    fn lex_num_errors(source: &str) -> (Token, Vec<(String, usize)>) {
+        let mut handler = Handler::new();
+        let mut lexer = crate::Lexer::new(source, &mut handler);
+        let token = lexer.lex_number();
+        assert!(
+            lexer.cursor.is_at_end(),
+            "`{}` was not lexed to the end",
+            source
+        );
+        let errors = handler
+            .diagnostics()
+            .into_iter()
+            .map(|d| (d.message, d.span.start))
+            .collect();
+        (token, errors)
+    }
+
+    #[test]
+    fn test_invalid_digit_for_base() {
+        let (token, errors) = lex_num_errors("0b102");
+        assert!(matches!(token, Token::Number(0, ..)));
+        assert_eq!(errors, [("invalid digit `2` for base 2".to_string(), 4)]);
+
+        let (_, errors) = lex_num_errors("0o9");
+        assert_eq!(errors, [("invalid digit `9` for base 8".to_string(), 2)]);
+
+        let (_, errors) = lex_num_errors("0o89");
+        assert_eq!(errors, [("invalid digit `8` for base 8".to_string(), 2)]);
+
+        let (_, errors) = lex_num_errors("0xG");
+        assert_eq!(errors, [("invalid digit `G` for base 16".to_string(), 2)]);
+    }
+
+    #[test]
+    fn test_valid_digits_in_each_base() {
+        for (source, value) in [
+            ("0b1_01", 0b101),
+            ("0o17", 0o17),
+            ("0xfA", 0xFA),
+            ("19", 19),
+        ] {
+            let (token, errors) = lex_num_errors(source);
+            assert_eq!(token, Token::Number(value, Symbol::intern(source), None));
+            assert!(errors.is_empty(), "{}: {:?}", source, errors);
+        }
+    }
+
+    #[test]
+    fn test_lexeme_preserves_spelling() {
+        let (Token::Number(hex, hex_text, _), Token::Number(dec, dec_text, _)) =
+            (lex_num("0xFF"), lex_num("255"))
+        else {
+            panic!("expected integer tokens");
+        };
+        assert_eq!(hex, dec);
+        assert_ne!(hex_text, dec_text);
+        assert_eq!(hex_text.as_str(), "0xFF");
+        assert_eq!(dec_text.as_str(), "255");
+
+        assert!(matches!(lex_num("0.5"), Token::Float(_, text) if text.as_str() == "0.5"));
+    }
+
+    #[test]
+    fn test_int_suffix() {
+        for (source, value, suffix) in [
+            ("255u8", 255, TY_U8),
+            ("0xFFu8", 0xFF, TY_U8),
+            ("0i32", 0, TY_I32),
+            ("7usize", 7, TY_USIZE),
+        ] {
+            let (token, errors) = lex_num_errors(source);
+            assert_eq!(
+                token,
+                Token::Number(value, Symbol::intern(source), Some(suffix))
+            );
+            assert!(errors.is_empty(), "{}: {:?}", source, errors);
+        }
+
+        // Not a suffix: the literal stops before the identifier
+        let mut handler = Handler::new();
+        let mut lexer = crate::Lexer::new("3u7", &mut handler);
+        assert_eq!(
+            lexer.lex_number(),
+            Token::Number(3, Symbol::intern("3"), None)
+        );
     }
 
     #[test]
     fn test_float() {
         let result = lex_num("3.14");
-        assert!(matches!(result, Token::Float(f) if (f - 3.14).abs() < 0.001));
+        assert!(matches!(result, Token::Float(f, _) if (f - 3.14).abs() < 0.001));
     }
 
     #[test]
     fn test_float_with_exponent() {
         let result = lex_num("1e10");
-        assert!(matches!(result, Token::Float(f) if (f - 1e10).abs() < 1.0));
+        assert!(matches!(result, Token::Float(f, _) if (f - 1e10).abs() < 1.0));
     }
 
     #[test]
     fn test_float_negative_exponent() {
         let result = lex_num("2.5e-3");
-        assert!(matches!(result, Token::Float(f) if (f - 2.5e-3).abs() < 0.0001));
+        assert!(matches!(result, Token::Float(f, _) if (f - 2.5e-3).abs() < 0.0001));
     }
 }
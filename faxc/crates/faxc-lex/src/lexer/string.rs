@@ -19,6 +19,7 @@ impl<'a> Lexer<'a> {
         self.cursor.advance();
 
         let mut content = String::new();
+        let mut too_long = false;
 
         loop {
             if self.cursor.is_at_end() {
@@ -41,14 +42,17 @@ impl<'a> Lexer<'a> {
             if c == '\\' {
                 self.cursor.advance();
                 if let Some(escaped) = self.parse_escape() {
-                    content.push(escaped);
+                    self.push_string_char(&mut content, escaped, &mut too_long);
                 }
             } else {
-                content.push(c);
+                self.push_string_char(&mut content, c, &mut too_long);
                 self.cursor.advance();
             }
         }
 
+        if too_long {
+            self.report_string_too_long();
+        }
         Token::String(Symbol::intern(&content))
     }
 
@@ -82,6 +86,7 @@ impl<'a> Lexer<'a> {
 
         let mut content = String::new();
         let mut found_closing = false;
+        let mut too_long = false;
 
         while !self.cursor.is_at_end() {
             let mut lookahead = String::new();
@@ -97,13 +102,17 @@ impl<'a> Lexer<'a> {
                 break;
             }
 
-            content.push(self.cursor.current_char());
+            let c = self.cursor.current_char();
+            self.push_string_char(&mut content, c, &mut too_long);
             self.cursor.advance();
         }
 
         if !found_closing {
             self.report_error("unterminated raw string literal".to_string());
         }
+        if too_long {
+            self.report_string_too_long();
+        }
 
         Token::RawString(Symbol::intern(&content))
     }
@@ -151,6 +160,24 @@ impl<'a> Lexer<'a> {
         Token::Char(c)
     }
 
+    /// Appends `c` to a string literal's contents while they are within
+    /// the configured maximum length.
+    ///
+    /// The first character that does not fit sets `too_long`; it and every
+    /// character after it are dropped.
+    fn push_string_char(&self, content: &mut String, c: char, too_long: &mut bool) {
+        match self.max_string_len {
+            Some(max) if *too_long || content.len() + c.len_utf8() > max => *too_long = true,
+            _ => content.push(c),
+        }
+    }
+
+    /// Reports a string literal whose contents were truncated.
+    fn report_string_too_long(&mut self) {
+        let max = self.max_string_len.unwrap_or_default();
+        self.report_error(format!("string literal too long (limit is {} bytes)", max));
+    }
+
     /// Parses an escape sequence.
     ///
     /// Handles: `\n`, `\t`, `\r`, `\\`, `\"`, `\'`, `\0`, `\xNN`, `\u{NNNN}`
@@ -43,9 +43,9 @@
 //!
 //! ## Keywords
 //!
-//! Reserved words with special meaning (35 total):
+//! Reserved words with special meaning (36 total):
 //!
-//! **Control Flow**: `fn`, `let`, `if`, `else`, `match`, `while`, `for`, `loop`, `break`, `continue`, `return`
+//! **Control Flow**: `fn`, `let`, `if`, `else`, `match`, `while`, `for`, `in`, `loop`, `break`, `continue`, `return`
 //!
 //! **Type System**: `struct`, `enum`, `trait`, `impl`, `dyn`, `type`, `where`
 //!
@@ -55,7 +55,9 @@
 //!
 //! ## Identifiers
 //!
-//! Names for variables, functions, types, etc. Pattern: `[a-zA-Z_][a-zA-Z0-9_]*`
+//! Names for variables, functions, types, etc. An identifier starts with `_` or
+//! an `XID_Start` character and continues with `XID_Continue` characters, so
+//! `count`, `_tmp` and `変数` are all identifiers.
 //!
 //! ## Literals
 //!
@@ -94,11 +96,12 @@ pub mod unicode;
 
 // Re-export main types for convenience
 pub use cursor::Cursor;
-pub use lexer::Lexer;
+pub use lexer::{Lexer, LexerConfig};
 pub use token::{keyword_from_ident, Token};
 pub use unicode::{
-    codepoint_to_char, hex_digit_to_value, is_ascii_ident_continue, is_ascii_ident_start,
-    is_digit_in_base, is_ident_continue, is_ident_start, parse_hex_byte, parse_hex_codepoint,
+    char_width, codepoint_to_char, hex_digit_to_value, is_ascii_ident_continue,
+    is_ascii_ident_start, is_digit_in_base, is_ident_continue, is_ident_start, parse_hex_byte,
+    parse_hex_codepoint,
 };
 
 #[cfg(test)]
@@ -222,13 +225,13 @@ mod tests {
         let source = "42 0xFF 0b1010 0o777 3.14 1e10 2.5e-3";
         let tokens = lex_all(source);
 
-        assert_eq!(tokens[0], Token::Number(42));
-        assert_eq!(tokens[1], Token::Number(0xFF));
-        assert_eq!(tokens[2], Token::Number(0b1010));
-        assert_eq!(tokens[3], Token::Number(0o777));
-        assert!(matches!(tokens[4], Token::Float(f) if (f - 3.14).abs() < 0.001));
-        assert!(matches!(tokens[5], Token::Float(f) if (f - 1e10).abs() < 1.0));
-        assert!(matches!(tokens[6], Token::Float(f) if (f - 2.5e-3).abs() < 0.0001));
+        assert!(matches!(tokens[0], Token::Number(42, ..)));
+        assert!(matches!(tokens[1], Token::Number(0xFF, ..)));
+        assert!(matches!(tokens[2], Token::Number(0b1010, ..)));
+        assert!(matches!(tokens[3], Token::Number(0o777, ..)));
+        assert!(matches!(tokens[4], Token::Float(f, _) if (f - 3.14).abs() < 0.001));
+        assert!(matches!(tokens[5], Token::Float(f, _) if (f - 1e10).abs() < 1.0));
+        assert!(matches!(tokens[6], Token::Float(f, _) if (f - 2.5e-3).abs() < 0.0001));
     }
 
     #[test]
@@ -270,7 +273,7 @@ mod tests {
 
         // Lexer should continue after error
         let token5 = lexer.next_token();
-        assert_eq!(token5, Token::Number(42));
+        assert!(matches!(token5, Token::Number(42, ..)));
     }
 
     #[test]
@@ -311,6 +314,150 @@ mod tests {
         let _ = lexer.next_token(); // Token::Number
     }
 
+    #[test]
+    fn test_tab_width_column() {
+        let config = LexerConfig {
+            tab_width: 4,
+            ..LexerConfig::default()
+        };
+        let mut handler = Handler::new();
+        let mut lexer = Lexer::with_config("\tlet x\nab\tc", &mut handler, config);
+
+        assert_eq!(lexer.next_token(), Token::Let);
+        assert_eq!(lexer.token_span().column, 5);
+        let _ = lexer.next_token(); // x
+
+        // A tab after text moves to the next stop, not a fixed distance
+        let _ = lexer.next_token(); // ab
+        assert_eq!(lexer.next_token(), Token::Ident(faxc_util::Symbol::intern("c")));
+        assert_eq!(lexer.token_span().column, 5);
+    }
+
+    #[test]
+    fn test_default_tab_is_one_column() {
+        let mut handler = Handler::new();
+        let mut lexer = Lexer::new("\tlet", &mut handler);
+        assert_eq!(lexer.next_token(), Token::Let);
+        assert_eq!(lexer.token_span().column, 2);
+    }
+
+    #[test]
+    fn test_unicode_width_column() {
+        let config = LexerConfig {
+            unicode_width: true,
+            ..LexerConfig::default()
+        };
+        let mut handler = Handler::new();
+        let mut lexer = Lexer::with_config("\"中文\" x", &mut handler, config);
+        let _ = lexer.next_token(); // "中文"
+        let _ = lexer.next_token(); // x
+        assert_eq!(lexer.token_span().column, 8);
+    }
+
+    /// Collects tokens up to end of input
+    fn lex_all_from(lexer: &mut Lexer) -> Vec<Token> {
+        std::iter::from_fn(|| Some(lexer.next_token()).filter(|t| *t != Token::Eof)).collect()
+    }
+
+    #[test]
+    fn test_significant_newlines() {
+        let config = LexerConfig {
+            significant_newlines: true,
+            ..LexerConfig::default()
+        };
+        let mut handler = Handler::new();
+        let source = "let x = 1 // one\nx /* two\nlines */ + 2\r\n";
+        let mut lexer = Lexer::with_config(source, &mut handler, config);
+        let x = Token::Ident(faxc_util::Symbol::intern("x"));
+
+        let tokens = lex_all_from(&mut lexer);
+        assert_eq!(
+            tokens,
+            [
+                Token::Let,
+                x.clone(),
+                Token::Eq,
+                Token::Number(1, faxc_util::Symbol::intern("1"), None),
+                Token::Newline,
+                x,
+                Token::Plus,
+                Token::Number(2, faxc_util::Symbol::intern("2"), None),
+                Token::Newline,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_string_literal_length_limit() {
+        let config = LexerConfig {
+            max_string_len: Some(4),
+            ..LexerConfig::default()
+        };
+        let mut handler = Handler::new();
+        let mut lexer = Lexer::with_config("\"abcdefgh\" \"ok\" x", &mut handler, config);
+
+        let tokens = lex_all_from(&mut lexer);
+        assert_eq!(
+            tokens,
+            [
+                Token::String(faxc_util::Symbol::intern("abcd")),
+                Token::String(faxc_util::Symbol::intern("ok")),
+                Token::Ident(faxc_util::Symbol::intern("x")),
+            ]
+        );
+        let diagnostics = handler.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.starts_with("string literal too long"));
+        assert_eq!((diagnostics[0].span.start, diagnostics[0].span.end), (0, 10));
+    }
+
+    #[test]
+    fn test_newlines_skipped_by_default() {
+        let mut handler = Handler::new();
+        let mut lexer = Lexer::new("let x = 1\nx + 2\n", &mut handler);
+        assert!(!lex_all_from(&mut lexer).contains(&Token::Newline));
+    }
+
+    #[test]
+    fn test_spans_carry_file_id() {
+        let file_id = faxc_util::FileId(3);
+        let mut handler = Handler::new();
+        let mut lexer = Lexer::new("x `", &mut handler).with_file_id(file_id);
+
+        let _ = lexer.next_token(); // x
+        assert_eq!(lexer.token_span().file_id, file_id);
+        let _ = lexer.next_token(); // `
+
+        let diagnostics = handler.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].span.file_id, file_id);
+    }
+
+    #[test]
+    fn test_bom_prefixed_source() {
+        let source = "\u{FEFF}let x = 1;";
+        let mut handler = Handler::new();
+        let mut lexer = Lexer::new(source, &mut handler);
+
+        assert_eq!(lexer.next_token(), Token::Let);
+        let span = lexer.token_span();
+        assert_eq!(span.start, 3);
+        assert_eq!(span.column, 1);
+
+        let rest: Vec<Token> = lexer.collect();
+        assert_eq!(rest.len(), 4);
+        assert!(!handler.has_errors());
+    }
+
+    #[test]
+    fn test_unicode_identifiers() {
+        let tokens = lex_all("let αβγ = 変数 + x\u{301};");
+        assert_eq!(tokens[1], Token::Ident(faxc_util::Symbol::intern("αβγ")));
+        assert_eq!(tokens[3], Token::Ident(faxc_util::Symbol::intern("変数")));
+        // A combining mark continues the identifier it follows
+        assert_eq!(tokens[5], Token::Ident(faxc_util::Symbol::intern("x\u{301}")));
+    }
+
     #[test]
     fn test_empty_source() {
         let tokens = lex_all("");
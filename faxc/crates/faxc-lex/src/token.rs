@@ -0,0 +1,398 @@
+//! Token type definitions.
+//!
+//! This module defines [`Token`], the unit the lexer produces and the parser
+//! consumes, and [`keyword_from_ident`], which maps reserved words to their
+//! keyword tokens.
+
+use faxc_util::Symbol;
+use std::fmt;
+
+/// A lexical token of the Fax language.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    // ------------------------------------------------------------------
+    // Keywords
+    // ------------------------------------------------------------------
+    /// `fn`
+    Fn,
+    /// `let`
+    Let,
+    /// `if`
+    If,
+    /// `else`
+    Else,
+    /// `match`
+    Match,
+    /// `while`
+    While,
+    /// `for`
+    For,
+    /// `in`
+    In,
+    /// `loop`
+    Loop,
+    /// `break`
+    Break,
+    /// `continue`
+    Continue,
+    /// `return`
+    Return,
+    /// `struct`
+    Struct,
+    /// `enum`
+    Enum,
+    /// `trait`
+    Trait,
+    /// `impl`
+    Impl,
+    /// `dyn`
+    Dyn,
+    /// `type`
+    Type,
+    /// `where`
+    Where,
+    /// `mod`
+    Mod,
+    /// `use`
+    Use,
+    /// `as`
+    As,
+    /// `super`
+    Super,
+    /// `crate`
+    Crate,
+    /// `pub`
+    Pub,
+    /// `async`
+    Async,
+    /// `await`
+    Await,
+    /// `const`
+    Const,
+    /// `static`
+    Static,
+    /// `unsafe`
+    Unsafe,
+    /// `ref`
+    Ref,
+    /// `mut`
+    Mut,
+    /// `self`
+    Self_,
+    /// `Self`
+    SelfUpper,
+    /// `true`
+    True,
+    /// `false`
+    False,
+    /// `macro_rules`
+    MacroRules,
+
+    // ------------------------------------------------------------------
+    // Identifiers and literals
+    // ------------------------------------------------------------------
+    /// Identifier, interned
+    Ident(Symbol),
+    /// Integer literal: its value, its exact source text (so `0xFF` and
+    /// `255` have the same value but different spellings), and its type
+    /// suffix, if any (the `u8` of `255u8`)
+    Number(u64, Symbol, Option<Symbol>),
+    /// Floating-point literal: its value and its exact source text
+    Float(f64, Symbol),
+    /// String literal, with escapes processed
+    String(Symbol),
+    /// Raw string literal `r"..."` / `r#"..."#`, contents verbatim
+    RawString(Symbol),
+    /// Character literal
+    Char(char),
+
+    // ------------------------------------------------------------------
+    // Operators
+    // ------------------------------------------------------------------
+    /// `+`
+    Plus,
+    /// `-`
+    Minus,
+    /// `*`
+    Star,
+    /// `/`
+    Slash,
+    /// `%`
+    Percent,
+    /// `^`
+    Caret,
+    /// `!`
+    Bang,
+    /// `~`
+    Tilde,
+    /// `&`
+    Ampersand,
+    /// `|`
+    Pipe,
+    /// `&&`
+    AndAnd,
+    /// `||`
+    OrOr,
+    /// `<<`
+    Shl,
+    /// `>>`
+    Shr,
+    /// `=`
+    Eq,
+    /// `==`
+    EqEq,
+    /// `!=`
+    NotEq,
+    /// `<`
+    Lt,
+    /// `>`
+    Gt,
+    /// `<=`
+    LtEq,
+    /// `>=`
+    GtEq,
+    /// `+=`
+    PlusEq,
+    /// `-=`
+    MinusEq,
+    /// `*=`
+    StarEq,
+    /// `/=`
+    SlashEq,
+    /// `%=`
+    PercentEq,
+    /// `^=`
+    CaretEq,
+    /// `&=`
+    AmpersandEq,
+    /// `|=`
+    PipeEq,
+    /// `<<=`
+    ShlEq,
+    /// `>>=`
+    ShrEq,
+
+    // ------------------------------------------------------------------
+    // Delimiters and punctuation
+    // ------------------------------------------------------------------
+    /// `(`
+    LParen,
+    /// `)`
+    RParen,
+    /// `{`
+    LBrace,
+    /// `}`
+    RBrace,
+    /// `[`
+    LBracket,
+    /// `]`
+    RBracket,
+    /// `,`
+    Comma,
+    /// `;`
+    Semicolon,
+    /// `:`
+    Colon,
+    /// `::`
+    ColonColon,
+    /// `.`
+    Dot,
+    /// `..`
+    DotDot,
+    /// `...`
+    DotDotDot,
+    /// `..=`
+    DotDotEq,
+    /// `->`
+    Arrow,
+    /// `=>`
+    FatArrow,
+    /// `_`
+    Underscore,
+    /// `$`
+    Dollar,
+    /// `@`
+    At,
+
+    // ------------------------------------------------------------------
+    // Special
+    // ------------------------------------------------------------------
+    /// Line break, only produced when the lexer is configured to treat
+    /// newlines as significant
+    Newline,
+    /// End of input
+    Eof,
+    /// Unrecognized input, with the text that could not be lexed
+    Invalid(String),
+}
+
+/// The keyword token spelled `ident`, if `ident` is a reserved word
+pub fn keyword_from_ident(ident: &str) -> Option<Token> {
+    Some(match ident {
+        "fn" => Token::Fn,
+        "let" => Token::Let,
+        "if" => Token::If,
+        "else" => Token::Else,
+        "match" => Token::Match,
+        "while" => Token::While,
+        "for" => Token::For,
+        "in" => Token::In,
+        "loop" => Token::Loop,
+        "break" => Token::Break,
+        "continue" => Token::Continue,
+        "return" => Token::Return,
+        "struct" => Token::Struct,
+        "enum" => Token::Enum,
+        "trait" => Token::Trait,
+        "impl" => Token::Impl,
+        "dyn" => Token::Dyn,
+        "type" => Token::Type,
+        "where" => Token::Where,
+        "mod" => Token::Mod,
+        "use" => Token::Use,
+        "as" => Token::As,
+        "super" => Token::Super,
+        "crate" => Token::Crate,
+        "pub" => Token::Pub,
+        "async" => Token::Async,
+        "await" => Token::Await,
+        "const" => Token::Const,
+        "static" => Token::Static,
+        "unsafe" => Token::Unsafe,
+        "ref" => Token::Ref,
+        "mut" => Token::Mut,
+        "self" => Token::Self_,
+        "Self" => Token::SelfUpper,
+        "true" => Token::True,
+        "false" => Token::False,
+        "macro_rules" => Token::MacroRules,
+        _ => return None,
+    })
+}
+
+impl fmt::Display for Token {
+    /// Formats the token as it is spelled in source, for diagnostics
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Token::Fn => "fn",
+            Token::Let => "let",
+            Token::If => "if",
+            Token::Else => "else",
+            Token::Match => "match",
+            Token::While => "while",
+            Token::For => "for",
+            Token::In => "in",
+            Token::Loop => "loop",
+            Token::Break => "break",
+            Token::Continue => "continue",
+            Token::Return => "return",
+            Token::Struct => "struct",
+            Token::Enum => "enum",
+            Token::Trait => "trait",
+            Token::Impl => "impl",
+            Token::Dyn => "dyn",
+            Token::Type => "type",
+            Token::Where => "where",
+            Token::Mod => "mod",
+            Token::Use => "use",
+            Token::As => "as",
+            Token::Super => "super",
+            Token::Crate => "crate",
+            Token::Pub => "pub",
+            Token::Async => "async",
+            Token::Await => "await",
+            Token::Const => "const",
+            Token::Static => "static",
+            Token::Unsafe => "unsafe",
+            Token::Ref => "ref",
+            Token::Mut => "mut",
+            Token::Self_ => "self",
+            Token::SelfUpper => "Self",
+            Token::True => "true",
+            Token::False => "false",
+            Token::MacroRules => "macro_rules",
+            Token::Ident(name) => return write!(f, "{}", name),
+            Token::Number(_, text, _) | Token::Float(_, text) => return write!(f, "{}", text),
+            Token::String(s) => return write!(f, "{:?}", s.as_str()),
+            Token::RawString(s) => return write!(f, "r\"{}\"", s),
+            Token::Char(c) => return write!(f, "{:?}", c),
+            Token::Plus => "+",
+            Token::Minus => "-",
+            Token::Star => "*",
+            Token::Slash => "/",
+            Token::Percent => "%",
+            Token::Caret => "^",
+            Token::Bang => "!",
+            Token::Tilde => "~",
+            Token::Ampersand => "&",
+            Token::Pipe => "|",
+            Token::AndAnd => "&&",
+            Token::OrOr => "||",
+            Token::Shl => "<<",
+            Token::Shr => ">>",
+            Token::Eq => "=",
+            Token::EqEq => "==",
+            Token::NotEq => "!=",
+            Token::Lt => "<",
+            Token::Gt => ">",
+            Token::LtEq => "<=",
+            Token::GtEq => ">=",
+            Token::PlusEq => "+=",
+            Token::MinusEq => "-=",
+            Token::StarEq => "*=",
+            Token::SlashEq => "/=",
+            Token::PercentEq => "%=",
+            Token::CaretEq => "^=",
+            Token::AmpersandEq => "&=",
+            Token::PipeEq => "|=",
+            Token::ShlEq => "<<=",
+            Token::ShrEq => ">>=",
+            Token::LParen => "(",
+            Token::RParen => ")",
+            Token::LBrace => "{",
+            Token::RBrace => "}",
+            Token::LBracket => "[",
+            Token::RBracket => "]",
+            Token::Comma => ",",
+            Token::Semicolon => ";",
+            Token::Colon => ":",
+            Token::ColonColon => "::",
+            Token::Dot => ".",
+            Token::DotDot => "..",
+            Token::DotDotDot => "...",
+            Token::DotDotEq => "..=",
+            Token::Arrow => "->",
+            Token::FatArrow => "=>",
+            Token::Underscore => "_",
+            Token::Dollar => "$",
+            Token::At => "@",
+            Token::Newline => "newline",
+            Token::Eof => "end of file",
+            Token::Invalid(text) => text,
+        };
+        f.write_str(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyword_from_ident() {
+        assert_eq!(keyword_from_ident("while"), Some(Token::While));
+        assert_eq!(keyword_from_ident("Self"), Some(Token::SelfUpper));
+        assert_eq!(keyword_from_ident("whilst"), None);
+    }
+
+    #[test]
+    fn test_display_is_source_spelling() {
+        assert_eq!(Token::ShrEq.to_string(), ">>=");
+        assert_eq!(Token::Self_.to_string(), "self");
+        assert_eq!(Token::Ident(Symbol::intern("count")).to_string(), "count");
+        assert_eq!(
+            Token::Number(255, Symbol::intern("0xFF"), None).to_string(),
+            "0xFF"
+        );
+    }
+}
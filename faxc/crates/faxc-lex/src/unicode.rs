@@ -6,10 +6,9 @@
 /// Checks if a character is valid as the start of an identifier.
 ///
 /// Valid identifier start characters:
-/// - ASCII letters: a-z, A-Z
 /// - Underscore: _
-/// - Unicode letters (categories L*)
-/// - Unicode marks (categories M*)
+/// - Characters with the Unicode `XID_Start` property, which includes
+///   ASCII letters and letters of other scripts
 ///
 /// # Arguments
 ///
@@ -27,12 +26,13 @@
 /// assert!(!is_ident_start('+'));
 /// ```
 pub fn is_ident_start(c: char) -> bool {
-    c == '_' || c.is_alphabetic()
+    c == '_' || unicode_ident::is_xid_start(c)
 }
 
 /// Checks if a character is valid as a continuation of an identifier.
 ///
-/// Valid identifier continuation characters:
+/// Valid identifier continuation characters are those with the Unicode
+/// `XID_Continue` property:
 /// - All valid start characters
 /// - ASCII digits: 0-9
 /// - Unicode decimal numbers (category Nd)
@@ -55,7 +55,7 @@ pub fn is_ident_start(c: char) -> bool {
 /// assert!(!is_ident_continue(' '));
 /// ```
 pub fn is_ident_continue(c: char) -> bool {
-    c == '_' || c.is_alphanumeric()
+    unicode_ident::is_xid_continue(c)
 }
 
 /// Checks if a character is a valid ASCII identifier start.
@@ -293,6 +293,50 @@ pub fn codepoint_to_char(codepoint: u32) -> Option<char> {
     }
 }
 
+/// Returns the number of terminal columns a character occupies.
+///
+/// East Asian wide and fullwidth characters and most emoji take two
+/// columns, combining marks and zero-width characters none, and everything
+/// else one. This covers the common blocks rather than the full Unicode
+/// width tables.
+///
+/// # Example
+///
+/// ```
+/// use faxc_lex::unicode::char_width;
+///
+/// assert_eq!(char_width('a'), 1);
+/// assert_eq!(char_width('変'), 2);
+/// assert_eq!(char_width('\u{0301}'), 0);  // Combining acute accent
+/// ```
+pub fn char_width(c: char) -> u32 {
+    match c as u32 {
+        0x0300..=0x036F
+        | 0x1AB0..=0x1AFF
+        | 0x1DC0..=0x1DFF
+        | 0x200B..=0x200F
+        | 0x20D0..=0x20FF
+        | 0xFE00..=0xFE0F
+        | 0xFE20..=0xFE2F => 0,
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x2FFFD
+        | 0x30000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -491,4 +535,14 @@ mod tests {
         assert_eq!(codepoint_to_char(0xD800), None);  // Surrogate
         assert_eq!(codepoint_to_char(0x110000), None);  // Too large
     }
+
+    #[test]
+    fn test_char_width() {
+        assert_eq!(char_width('x'), 1);
+        assert_eq!(char_width('é'), 1);
+        assert_eq!(char_width('中'), 2);
+        assert_eq!(char_width('😀'), 2);
+        assert_eq!(char_width('\u{0301}'), 0);
+        assert_eq!(char_width('\u{200B}'), 0);
+    }
 }
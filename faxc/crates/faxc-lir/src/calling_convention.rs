@@ -3,7 +3,11 @@
 //! MIR-LIR-CODEGEN-DEV-001: Subtask 2
 //! Implements the System V AMD64 calling convention used on Linux, macOS, BSD.
 
-use crate::lir::{Operand, PhysicalRegister, RegisterWidth};
+use indexmap::{IndexMap, IndexSet};
+
+use crate::lir::{
+    Address, CallTarget, Function, Instruction, Operand, PhysicalRegister, RegisterWidth,
+};
 
 /// System V AMD64 ABI calling convention handler
 pub struct SystemVAbi;
@@ -37,6 +41,19 @@ impl SystemVAbi {
     /// Return register for floating point types
     pub const FP_RET_REG: PhysicalRegister = PhysicalRegister::XMM0;
 
+    /// General purpose registers a callee may overwrite
+    pub const CALLER_SAVED_REGS: [PhysicalRegister; 9] = [
+        PhysicalRegister::RAX,
+        PhysicalRegister::RCX,
+        PhysicalRegister::RDX,
+        PhysicalRegister::RSI,
+        PhysicalRegister::RDI,
+        PhysicalRegister::R8,
+        PhysicalRegister::R9,
+        PhysicalRegister::R10,
+        PhysicalRegister::R11,
+    ];
+
     /// Get the argument register for a given argument index (0-5)
     pub fn get_arg_register(index: usize) -> Option<PhysicalRegister> {
         if index < 6 {
@@ -84,10 +101,23 @@ impl SystemVAbi {
         ]
     }
 
+    /// Required alignment of `rsp` at a `call`
+    pub const STACK_ALIGN: u32 = 16;
+
+    /// Bytes to reserve below the saved `rbp` for a frame of `frame_size`
+    ///
+    /// The `call` into the function pushed the return address onto an
+    /// aligned stack and the prologue pushes `rbp`, which brings `rsp` back
+    /// to alignment; the frame is rounded up so it stays there.
+    pub fn aligned_frame_size(frame_size: u32) -> u32 {
+        frame_size.next_multiple_of(Self::STACK_ALIGN)
+    }
+
     /// Generate prologue instructions for a function
     pub fn generate_prologue(frame_size: u32, uses_fp: bool) -> Vec<crate::lir::Instruction> {
         use crate::lir::Instruction;
         let mut prologue = Vec::new();
+        let frame_size = Self::aligned_frame_size(frame_size);
 
         // Push base pointer
         prologue.push(Instruction::Push {
@@ -121,6 +151,7 @@ impl SystemVAbi {
     pub fn generate_epilogue(frame_size: u32, uses_fp: bool) -> Vec<crate::lir::Instruction> {
         use crate::lir::Instruction;
         let mut epilogue = Vec::new();
+        let frame_size = Self::aligned_frame_size(frame_size);
 
         // Restore callee-saved registers if used
         if uses_fp {
@@ -143,6 +174,231 @@ impl SystemVAbi {
 
         epilogue
     }
+
+    /// Preserve caller-saved registers that hold a value across a call
+    ///
+    /// Every caller-saved register still read after a `Call`, other than
+    /// the `RAX` the call returns in, is pushed right before it and popped
+    /// right after. An odd number of pushes is padded by another word so
+    /// `rsp` is still aligned at the `call`. Only physical registers are
+    /// considered; virtual registers have no home to clobber yet.
+    pub fn save_live_across_calls(func: &mut Function) {
+        let live_out = physical_liveness(&func.instructions);
+        let mut instructions = Vec::with_capacity(func.instructions.len());
+        for (inst, live) in func.instructions.drain(..).zip(live_out) {
+            if !matches!(inst, Instruction::Call { .. }) {
+                instructions.push(inst);
+                continue;
+            }
+            let saved: Vec<_> = Self::CALLER_SAVED_REGS
+                .iter()
+                .filter(|reg| **reg != Self::RET_REG && live.contains(*reg))
+                .copied()
+                .collect();
+            let padded = saved.len() % 2 == 1;
+            for reg in &saved {
+                instructions.push(Instruction::Push {
+                    src: Operand::PhysReg(*reg),
+                });
+            }
+            if padded {
+                instructions.push(Instruction::Sub {
+                    dest: Operand::PhysReg(PhysicalRegister::RSP),
+                    src: Operand::Imm(8),
+                });
+            }
+            instructions.push(inst);
+            if padded {
+                instructions.push(Instruction::Add {
+                    dest: Operand::PhysReg(PhysicalRegister::RSP),
+                    src: Operand::Imm(8),
+                });
+            }
+            for reg in saved.iter().rev() {
+                instructions.push(Instruction::Pop {
+                    dest: Operand::PhysReg(*reg),
+                });
+            }
+        }
+        func.instructions = instructions;
+    }
+}
+
+/// Physical registers live after each instruction
+fn physical_liveness(instructions: &[Instruction]) -> Vec<IndexSet<PhysicalRegister>> {
+    let labels: IndexMap<&str, usize> = instructions
+        .iter()
+        .enumerate()
+        .filter_map(|(i, inst)| match inst {
+            Instruction::Label { name } => Some((name.as_str(), i)),
+            _ => None,
+        })
+        .collect();
+    let successors = |i: usize| -> Vec<usize> {
+        let next = (i + 1 < instructions.len()).then_some(i + 1);
+        match &instructions[i] {
+            Instruction::Jmp { target } => {
+                labels.get(target.as_str()).copied().into_iter().collect()
+            },
+            Instruction::Jcc { target, .. } => labels
+                .get(target.as_str())
+                .copied()
+                .into_iter()
+                .chain(next)
+                .collect(),
+            Instruction::Ret { .. } => Vec::new(),
+            _ => next.into_iter().collect(),
+        }
+    };
+
+    let effects: Vec<_> = instructions.iter().map(register_effects).collect();
+    let mut live_in = vec![IndexSet::new(); instructions.len()];
+    let mut live_out = vec![IndexSet::new(); instructions.len()];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for i in (0..instructions.len()).rev() {
+            let out: IndexSet<PhysicalRegister> = successors(i)
+                .into_iter()
+                .flat_map(|succ| live_in[succ].iter().copied())
+                .collect();
+            let (uses, defs) = &effects[i];
+            let mut inn: IndexSet<_> = out.difference(defs).copied().collect();
+            inn.extend(uses.iter().copied());
+            if inn != live_in[i] || out != live_out[i] {
+                live_in[i] = inn;
+                live_out[i] = out;
+                changed = true;
+            }
+        }
+    }
+    live_out
+}
+
+/// Physical registers an instruction reads and writes
+fn register_effects(
+    inst: &Instruction,
+) -> (IndexSet<PhysicalRegister>, IndexSet<PhysicalRegister>) {
+    let mut uses = IndexSet::new();
+    let mut defs = IndexSet::new();
+    match inst {
+        Instruction::Mov { dest, src }
+        | Instruction::Movsx { dest, src, .. }
+        | Instruction::Movzx { dest, src } => {
+            read(src, &mut uses);
+            write(dest, &mut uses, &mut defs);
+        },
+        Instruction::Lea { dest, addr } | Instruction::Load { dest, addr, .. } => {
+            address_regs(addr, &mut uses);
+            write(dest, &mut uses, &mut defs);
+        },
+        Instruction::Store { addr, src, .. } => {
+            address_regs(addr, &mut uses);
+            read(src, &mut uses);
+        },
+        Instruction::Push { src } => read(src, &mut uses),
+        Instruction::Pop { dest } => write(dest, &mut uses, &mut defs),
+        Instruction::Xchg { dest, src }
+        | Instruction::Cmov { dest, src, .. }
+        | Instruction::Add { dest, src }
+        | Instruction::Sub { dest, src }
+        | Instruction::Mul { dest, src, .. }
+        | Instruction::Idiv { dest, src }
+        | Instruction::IdivUnsigned { dest, src }
+        | Instruction::And { dest, src }
+        | Instruction::Or { dest, src }
+        | Instruction::Xor { dest, src }
+        | Instruction::Shl { dest, count: src }
+        | Instruction::Shr { dest, count: src }
+        | Instruction::Sar { dest, count: src }
+        | Instruction::Rol { dest, count: src }
+        | Instruction::Ror { dest, count: src } => {
+            read(dest, &mut uses);
+            read(src, &mut uses);
+            write(dest, &mut uses, &mut defs);
+            if let Instruction::Xchg { .. } = inst {
+                write(src, &mut uses, &mut defs);
+            }
+        },
+        Instruction::Imul { dest, src1, src2 } => {
+            read(src1, &mut uses);
+            match src2 {
+                Some(src2) => read(src2, &mut uses),
+                None => read(dest, &mut uses),
+            }
+            write(dest, &mut uses, &mut defs);
+        },
+        Instruction::Inc { dest }
+        | Instruction::Dec { dest }
+        | Instruction::Neg { dest }
+        | Instruction::Not { dest } => {
+            read(dest, &mut uses);
+            write(dest, &mut uses, &mut defs);
+        },
+        Instruction::Div { divisor } | Instruction::IdivSigned { divisor } => {
+            read(divisor, &mut uses);
+            uses.extend([PhysicalRegister::RAX, PhysicalRegister::RDX]);
+            defs.extend([PhysicalRegister::RAX, PhysicalRegister::RDX]);
+        },
+        Instruction::Cmp { src1, src2 } | Instruction::Test { src1, src2 } => {
+            read(src1, &mut uses);
+            read(src2, &mut uses);
+        },
+        Instruction::Call { target, args } => {
+            if let CallTarget::Indirect(callee) = target {
+                read(callee, &mut uses);
+            }
+            for arg in args {
+                read(arg, &mut uses);
+            }
+            defs.insert(SystemVAbi::RET_REG);
+        },
+        Instruction::Ret { value: Some(value) } => read(value, &mut uses),
+        Instruction::Alloca { dest, size } => {
+            read(size, &mut uses);
+            write(dest, &mut uses, &mut defs);
+        },
+        _ => {},
+    }
+    (uses, defs)
+}
+
+fn read(op: &Operand, uses: &mut IndexSet<PhysicalRegister>) {
+    match op {
+        Operand::PhysReg(reg) => {
+            uses.insert(*reg);
+        },
+        Operand::Mem(addr) => address_regs(addr, uses),
+        _ => {},
+    }
+}
+
+/// Writing to memory still reads the registers that form its address
+fn write(
+    op: &Operand,
+    uses: &mut IndexSet<PhysicalRegister>,
+    defs: &mut IndexSet<PhysicalRegister>,
+) {
+    match op {
+        Operand::PhysReg(reg) => {
+            defs.insert(*reg);
+        },
+        Operand::Mem(addr) => address_regs(addr, uses),
+        _ => {},
+    }
+}
+
+fn address_regs(addr: &Address, uses: &mut IndexSet<PhysicalRegister>) {
+    match addr {
+        Address::Base { base } | Address::BaseOffset { base, .. } => {
+            uses.insert(*base);
+        },
+        Address::Indexed { base, index, .. } => {
+            uses.insert(*base);
+            uses.insert(*index);
+        },
+        _ => {},
+    }
 }
 
 /// Argument classification for System V AMD64
@@ -221,6 +477,124 @@ mod abi_tests {
         assert!(SystemVAbi::is_stack_arg(8, true));
     }
 
+    #[test]
+    fn test_value_live_across_call_is_saved_and_restored() {
+        use faxc_util::Symbol;
+
+        // rcx = 5; call f; rax = rax + rcx; ret rax
+        let mut func = Function::new(Symbol::intern("caller"));
+        func.instructions = vec![
+            Instruction::Mov {
+                dest: Operand::PhysReg(PhysicalRegister::RCX),
+                src: Operand::Imm(5),
+            },
+            Instruction::Mov {
+                dest: Operand::PhysReg(PhysicalRegister::RDX),
+                src: Operand::Imm(7),
+            },
+            Instruction::Call {
+                target: CallTarget::Direct(Symbol::intern("f")),
+                args: Vec::new(),
+            },
+            Instruction::Add {
+                dest: Operand::PhysReg(PhysicalRegister::RAX),
+                src: Operand::PhysReg(PhysicalRegister::RCX),
+            },
+            Instruction::Ret {
+                value: Some(Operand::PhysReg(PhysicalRegister::RAX)),
+            },
+        ];
+        SystemVAbi::save_live_across_calls(&mut func);
+
+        let call = func
+            .instructions
+            .iter()
+            .position(|inst| matches!(inst, Instruction::Call { .. }))
+            .unwrap();
+        // RCX is read after the call and saved around it; RDX is dead and RAX
+        // is the return value, so neither is. The lone push is padded to
+        // keep the stack aligned.
+        assert_eq!(call, 4);
+        assert!(matches!(
+            &func.instructions[call - 2],
+            Instruction::Push {
+                src: Operand::PhysReg(PhysicalRegister::RCX)
+            }
+        ));
+        assert!(matches!(
+            &func.instructions[call - 1],
+            Instruction::Sub {
+                dest: Operand::PhysReg(PhysicalRegister::RSP),
+                src: Operand::Imm(8)
+            }
+        ));
+        assert!(matches!(
+            &func.instructions[call + 1],
+            Instruction::Add {
+                dest: Operand::PhysReg(PhysicalRegister::RSP),
+                src: Operand::Imm(8)
+            }
+        ));
+        assert!(matches!(
+            &func.instructions[call + 2],
+            Instruction::Pop {
+                dest: Operand::PhysReg(PhysicalRegister::RCX)
+            }
+        ));
+        assert_eq!(func.instructions.len(), 9);
+
+        // rsp was aligned on entry, so everything moving it before the call
+        // must add up to a multiple of 16
+        let moved: i64 = func.instructions[..call]
+            .iter()
+            .map(|inst| match inst {
+                Instruction::Push { .. } => 8,
+                Instruction::Pop { .. } => -8,
+                Instruction::Sub {
+                    dest: Operand::PhysReg(PhysicalRegister::RSP),
+                    src: Operand::Imm(n),
+                } => *n,
+                Instruction::Add {
+                    dest: Operand::PhysReg(PhysicalRegister::RSP),
+                    src: Operand::Imm(n),
+                } => -*n,
+                _ => 0,
+            })
+            .sum();
+        assert_eq!(moved % SystemVAbi::STACK_ALIGN as i64, 0);
+    }
+
+    #[test]
+    fn test_prologue_keeps_stack_aligned_for_calls() {
+        let prologue = SystemVAbi::generate_prologue(24, false);
+        let reserved = prologue
+            .iter()
+            .find_map(|inst| match inst {
+                Instruction::Sub {
+                    dest: Operand::PhysReg(PhysicalRegister::RSP),
+                    src: Operand::Imm(n),
+                } => Some(*n),
+                _ => None,
+            })
+            .expect("prologue should reserve the frame");
+        assert!(reserved >= 24);
+
+        // The caller's stack was aligned before its `call` pushed the
+        // return address; the prologue then pushes rbp
+        let return_address = 8;
+        let saved_rbp = 8;
+        assert_eq!((return_address + saved_rbp + reserved) % 16, 0);
+
+        let epilogue = SystemVAbi::generate_epilogue(24, false);
+        assert!(epilogue.iter().any(|inst| matches!(
+            inst,
+            Instruction::Add {
+                dest: Operand::PhysReg(PhysicalRegister::RSP),
+                src: Operand::Imm(n),
+            } if *n == reserved
+        )));
+    }
+
     #[test]
     fn test_callee_saved_regs() {
         let saved = SystemVAbi::get_callee_saved_regs();
@@ -5,14 +5,14 @@
 //! virtual register management, and System V AMD64 ABI support.
 
 pub mod calling_convention;
+#[cfg(test)]
+mod edge_cases;
 pub mod lir;
 pub mod lower;
 pub mod opt;
 pub mod stack_frame;
 #[cfg(test)]
 mod tests;
-// #[cfg(test)]
-// mod edge_cases;
 
 pub use calling_convention::*;
 pub use lir::*;
@@ -292,6 +292,7 @@ pub enum Instruction {
     },
     Call {
         target: CallTarget,
+        args: Vec<Operand>,
     },
     Ret {
         value: Option<Operand>,
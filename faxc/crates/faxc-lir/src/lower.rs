@@ -2,13 +2,35 @@
 //!
 //! MIR-LIR-CODEGEN-DEV-001: Subtask 2
 //! Converts MIR constructs to LIR with x86-64 instructions.
+//!
+//! The CFG is flattened into one instruction list: every MIR block becomes a
+//! `.LbbN` label followed by its statements and an explicit jump, so no block
+//! relies on falling through into the next one.
+//!
+//! LIR has no PHI, so each `Statement::Phi` becomes copies on the edges
+//! into its block: at the end of a predecessor that only jumps there, or in
+//! a block of its own placed on the edge when the predecessor branches
+//! elsewhere too. The copies on one edge happen at once, so they are
+//! sequenced to never overwrite a register another copy still reads.
+//!
+//! Locals live in virtual registers unless they hold an aggregate, are
+//! projected into, or have their address taken. Those get a stack slot, and
+//! every access to them is an explicit `Load`, `Store` or `Lea`.
 
 use crate::lir::*;
 use faxc_mir as mir;
+use faxc_sem::Type;
 use faxc_util::Symbol;
-use std::collections::HashMap;
+use indexmap::{IndexMap, IndexSet};
+
+/// Bytes per field in a stack slot; every scalar is one machine word
+const WORD: i32 = 8;
+
+/// Scratch register holding the base of a computed address
+const ADDR_BASE: PhysicalRegister = PhysicalRegister::R11;
 
-use faxc_util::Idx;
+/// Scratch register holding the index of a computed address
+const ADDR_INDEX: PhysicalRegister = PhysicalRegister::R10;
 
 /// Condition type for MIR compatibility
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -22,19 +44,43 @@ pub enum MirCondition {
 }
 
 pub fn lower_mir_to_lir(mir_fn: &mir::Function) -> Function {
-    let mut lowerer = LirLowerer::new(mir_fn.name.clone());
-    for i in 0..mir_fn.blocks.len() {
-        let block = &mir_fn.blocks[mir::BlockId::from_usize(i)];
+    let mut lowerer = LirLowerer::new(mir_fn.name);
+    lowerer.function.param_count = mir_fn.arg_count;
+    lowerer.returns_value = !matches!(mir_fn.return_ty, Type::Unit | Type::Never);
+    lowerer.assign_stack_slots(mir_fn);
+    lowerer.collect_phis(mir_fn);
+    lowerer.count_reads(mir_fn);
+
+    // The entry block goes first so execution starts at the first instruction
+    let blocks = mir_fn.blocks.as_slice();
+    let entry = blocks.iter().filter(|b| b.id == mir_fn.entry_block);
+    let rest = blocks.iter().filter(|b| b.id != mir_fn.entry_block);
+    for block in entry.chain(rest) {
         lowerer.lower_block(block);
     }
     lowerer.finish()
 }
 
+/// Label of the first instruction of a MIR block
+fn block_label(id: mir::BlockId) -> Label {
+    format!(".Lbb{}", id.0)
+}
+
 pub struct LirLowerer {
     pub function: Function,
     pub register_counter: u32,
     pub label_counter: u32,
-    pub mir_to_lir_reg: HashMap<mir::LocalId, VirtualRegister>,
+    pub mir_to_lir_reg: IndexMap<mir::LocalId, VirtualRegister>,
+    /// Frame offset of the stack slot of each memory-resident local
+    pub stack_slots: IndexMap<mir::LocalId, i32>,
+    /// Whether `Return` hands back the value of the return place
+    pub returns_value: bool,
+    /// PHI nodes of each block, as destination and incoming values
+    pub phis: IndexMap<mir::BlockId, Vec<(mir::LocalId, Vec<(mir::BlockId, mir::Operand)>)>>,
+    /// Block whose instructions are being emitted
+    pub current_block: mir::BlockId,
+    /// How many places in the function mention each local
+    pub reads: IndexMap<mir::LocalId, usize>,
 }
 
 impl LirLowerer {
@@ -51,7 +97,12 @@ impl LirLowerer {
             },
             register_counter: 0,
             label_counter: 0,
-            mir_to_lir_reg: HashMap::new(),
+            mir_to_lir_reg: IndexMap::new(),
+            stack_slots: IndexMap::new(),
+            returns_value: false,
+            phis: IndexMap::new(),
+            current_block: mir::BlockId(0),
+            reads: IndexMap::new(),
         }
     }
 
@@ -62,119 +113,364 @@ impl LirLowerer {
         reg
     }
 
+    fn emit(&mut self, inst: Instruction) {
+        self.function.instructions.push(inst);
+    }
+
+    /// Give a stack slot to every local that has to live in memory
+    ///
+    /// Slots are one word per field, sized by the local's type or by the
+    /// highest field accessed, whichever is larger. Locals whose storage is
+    /// never live at the same time, going by their `StorageLive` and
+    /// `StorageDead` markers, share a slot; a local without markers keeps
+    /// one to itself.
+    pub fn assign_stack_slots(&mut self, mir_fn: &mir::Function) {
+        let mut words: IndexMap<mir::LocalId, i32> = IndexMap::new();
+        let mut need = |local: mir::LocalId, fields: i32| {
+            let slot = words.entry(local).or_insert(1);
+            *slot = (*slot).max(fields);
+        };
+
+        for block in mir_fn.blocks.as_slice() {
+            for stmt in &block.statements {
+                let (place, rvalue) = match stmt {
+                    mir::Statement::Assign(place, rvalue) => (place, rvalue),
+                    mir::Statement::Phi(_, incoming) => {
+                        for (_, op) in incoming {
+                            if let Some((local, fields)) = operand_place(op).and_then(memory_access)
+                            {
+                                need(local, fields);
+                            }
+                        }
+                        continue;
+                    },
+                    _ => continue,
+                };
+                for p in std::iter::once(place).chain(rvalue_places(rvalue)) {
+                    if let Some((local, fields)) = memory_access(p) {
+                        need(local, fields);
+                    }
+                }
+                match (place, rvalue) {
+                    (mir::Place::Local(local), mir::Rvalue::Aggregate(_, ops)) => {
+                        need(*local, ops.len() as i32)
+                    },
+                    (_, mir::Rvalue::Ref(p, _) | mir::Rvalue::AddressOf(p, _)) => {
+                        if let Some(local) = base_local(p) {
+                            need(local, 1);
+                        }
+                    },
+                    _ => {},
+                }
+            }
+            for p in terminator_places(&block.terminator) {
+                if let Some((local, fields)) = memory_access(p) {
+                    need(local, fields);
+                }
+            }
+        }
+
+        let conflicts = storage_conflicts(mir_fn);
+        // Offset, size and occupants of each slot handed out so far
+        let mut slots: Vec<(i32, i32, Vec<mir::LocalId>)> = Vec::new();
+        let mut locals: Vec<_> = words.into_iter().collect();
+        locals.sort_by_key(|(local, _)| local.0);
+        for (local, fields) in locals {
+            let ty_words = mir_fn.locals.get(local).map_or(1, |l| type_words(&l.ty));
+            let size = fields.max(ty_words) * WORD;
+
+            let shared = conflicts.get(&local).and_then(|live_with| {
+                slots.iter_mut().find(|(_, slot_size, occupants)| {
+                    *slot_size >= size
+                        && occupants.iter().all(|other| {
+                            conflicts.contains_key(other) && !live_with.contains(other)
+                        })
+                })
+            });
+            let offset = match shared {
+                Some((offset, _, occupants)) => {
+                    occupants.push(local);
+                    *offset
+                },
+                None => {
+                    self.function.frame_size += size as u32;
+                    let offset = -(self.function.frame_size as i32);
+                    slots.push((offset, size, vec![local]));
+                    offset
+                },
+            };
+            self.stack_slots.insert(local, offset);
+        }
+    }
+
+    /// Record the PHI nodes of every block, to be lowered on incoming edges
+    pub fn collect_phis(&mut self, mir_fn: &mir::Function) {
+        for block in mir_fn.blocks.as_slice() {
+            let phis: Vec<_> = block
+                .statements
+                .iter()
+                .filter_map(|stmt| match stmt {
+                    mir::Statement::Phi(local, incoming) => Some((*local, incoming.clone())),
+                    _ => None,
+                })
+                .collect();
+            if !phis.is_empty() {
+                self.phis.insert(block.id, phis);
+            }
+        }
+    }
+
+    /// Count the mentions of every local outside plain assignments to it
+    pub fn count_reads(&mut self, mir_fn: &mir::Function) {
+        let mut places = Vec::new();
+        for block in mir_fn.blocks.as_slice() {
+            for stmt in &block.statements {
+                match stmt {
+                    mir::Statement::Assign(place, rvalue) => {
+                        if let mir::Place::Projection(..) = place {
+                            places.push(place);
+                        }
+                        places.extend(rvalue_places(rvalue));
+                    },
+                    mir::Statement::Phi(_, incoming) => {
+                        places.extend(incoming.iter().filter_map(|(_, op)| operand_place(op)))
+                    },
+                    _ => {},
+                }
+            }
+            places.extend(terminator_places(&block.terminator));
+        }
+
+        let mut locals = Vec::new();
+        for place in places {
+            mentioned_locals(place, &mut locals);
+        }
+        for local in locals {
+            *self.reads.entry(local).or_insert(0) += 1;
+        }
+    }
+
     pub fn lower_block(&mut self, block: &mir::BasicBlock) {
-        let label = format!(".Lbb{}", block.id.0);
+        self.current_block = block.id;
+        let label = block_label(block.id);
         self.function
-            .instructions
-            .push(Instruction::Label { name: label });
+            .labels
+            .push((self.function.instructions.len(), label.clone()));
+        self.emit(Instruction::Label { name: label });
 
-        for stmt in &block.statements {
+        // A comparison only the branch reads sets the flags the branch
+        // tests directly, without going through a boolean
+        let fused =
+            branch_comparison(block).filter(|(local, ..)| self.reads.get(local) == Some(&1));
+        let statements = match fused {
+            Some(_) => &block.statements[..block.statements.len() - 1],
+            None => &block.statements[..],
+        };
+        for stmt in statements {
             if let mir::Statement::Assign(place, rvalue) = stmt {
-                let dest = self.get_place_reg(place);
-                self.lower_rvalue(dest, rvalue);
+                self.lower_assign(place, rvalue);
+            }
+        }
+        self.lower_terminator(
+            &block.terminator,
+            fused.map(|(_, cond, left, right)| (cond, left, right)),
+        );
+    }
+
+    fn lower_assign(&mut self, place: &mir::Place, rvalue: &mir::Rvalue) {
+        if let mir::Rvalue::Aggregate(_, ops) = rvalue {
+            if self.is_in_memory(place) {
+                for (i, op) in ops.iter().enumerate() {
+                    let src = self.lower_operand(op);
+                    let addr = self.place_address(place);
+                    self.emit(Instruction::Store {
+                        addr: offset_address(addr, i as i32 * WORD),
+                        src,
+                        width: RegisterWidth::W64,
+                    });
+                }
+                return;
             }
         }
-        self.lower_terminator(&block.terminator);
+
+        if self.is_in_memory(place) {
+            let value = self.new_reg();
+            self.lower_rvalue(value, rvalue);
+            let addr = self.place_address(place);
+            self.emit(Instruction::Store {
+                addr,
+                src: Operand::Reg(value),
+                width: RegisterWidth::W64,
+            });
+        } else {
+            let dest = self.get_place_reg(place);
+            self.lower_rvalue(dest, rvalue);
+        }
     }
 
     fn lower_rvalue(&mut self, dest: VirtualRegister, rvalue: &mir::Rvalue) {
         match rvalue {
-            mir::Rvalue::Use(operand) => {
+            mir::Rvalue::Use(operand) | mir::Rvalue::Cast(_, operand, _) => {
                 let src = self.lower_operand(operand);
-                self.function.instructions.push(Instruction::Mov {
+                self.emit(Instruction::Mov {
                     dest: Operand::Reg(dest),
                     src,
                 });
             },
-            mir::Rvalue::BinaryOp(op, left, right) => {
+            mir::Rvalue::BinaryOp(op, left, right)
+            | mir::Rvalue::CheckedBinaryOp(op, left, right) => {
+                if let Some(cond) = comparison_condition(*op) {
+                    self.emit_compare(left, right);
+                    self.materialize_condition(dest, cond);
+                    return;
+                }
+
                 let src1_reg = self.lower_operand_to_reg(left);
                 let src2 = self.lower_operand(right);
                 let bin_op = convert_binop(*op);
+                // Work in a temporary when `dest` is also the right operand,
+                // which the first move would clobber
+                let target = if src2 == Operand::Reg(dest) {
+                    self.new_reg()
+                } else {
+                    dest
+                };
                 // First move src1 to dest
-                self.function.instructions.push(Instruction::Mov {
-                    dest: Operand::Reg(dest),
+                self.emit(Instruction::Mov {
+                    dest: Operand::Reg(target),
                     src: Operand::Reg(src1_reg),
                 });
                 // Then apply the operation
-                match bin_op {
-                    BinOp::Add => {
-                        self.function.instructions.push(Instruction::Add {
-                            dest: Operand::Reg(dest),
-                            src: src2,
-                        });
+                let dest_op = Operand::Reg(target);
+                self.emit(match bin_op {
+                    BinOp::Add => Instruction::Add {
+                        dest: dest_op,
+                        src: src2,
                     },
-                    BinOp::Sub => {
-                        self.function.instructions.push(Instruction::Sub {
-                            dest: Operand::Reg(dest),
-                            src: src2,
-                        });
+                    BinOp::Sub => Instruction::Sub {
+                        dest: dest_op,
+                        src: src2,
                     },
-                    BinOp::Mul => {
-                        self.function.instructions.push(Instruction::Mul {
-                            dest: Operand::Reg(dest),
-                            src: src2,
-                            signed: true,
-                        });
+                    BinOp::Mul => Instruction::Mul {
+                        dest: dest_op,
+                        src: src2,
+                        signed: true,
                     },
-                    BinOp::Div => {
-                        self.function.instructions.push(Instruction::Idiv {
-                            dest: Operand::Reg(dest),
-                            src: src2,
-                        });
+                    BinOp::Div => Instruction::Idiv {
+                        dest: dest_op,
+                        src: src2,
                     },
-                    BinOp::Rem => {
-                        // Rem requires special handling with div
-                        self.function
-                            .instructions
-                            .push(Instruction::IdivSigned { divisor: src2 });
+                    // Rem requires special handling with div
+                    BinOp::Rem => Instruction::IdivSigned { divisor: src2 },
+                    BinOp::And => Instruction::And {
+                        dest: dest_op,
+                        src: src2,
                     },
-                    BinOp::And => {
-                        self.function.instructions.push(Instruction::And {
-                            dest: Operand::Reg(dest),
-                            src: src2,
-                        });
+                    BinOp::Or => Instruction::Or {
+                        dest: dest_op,
+                        src: src2,
                     },
-                    BinOp::Or => {
-                        self.function.instructions.push(Instruction::Or {
-                            dest: Operand::Reg(dest),
-                            src: src2,
-                        });
+                    BinOp::Xor => Instruction::Xor {
+                        dest: dest_op,
+                        src: src2,
                     },
-                    BinOp::Xor => {
-                        self.function.instructions.push(Instruction::Xor {
-                            dest: Operand::Reg(dest),
-                            src: src2,
-                        });
+                    BinOp::Shl => Instruction::Shl {
+                        dest: dest_op,
+                        count: src2,
                     },
-                    BinOp::Shl => {
-                        self.function.instructions.push(Instruction::Shl {
-                            dest: Operand::Reg(dest),
-                            count: src2,
-                        });
-                    },
-                    BinOp::Shr => {
-                        self.function.instructions.push(Instruction::Shr {
-                            dest: Operand::Reg(dest),
-                            count: src2,
-                        });
+                    BinOp::Shr => Instruction::Shr {
+                        dest: dest_op,
+                        count: src2,
                     },
-                    BinOp::Sar => {
-                        self.function.instructions.push(Instruction::Sar {
-                            dest: Operand::Reg(dest),
-                            count: src2,
-                        });
+                    BinOp::Sar => Instruction::Sar {
+                        dest: dest_op,
+                        count: src2,
                     },
+                });
+                if target != dest {
+                    self.emit(Instruction::Mov {
+                        dest: Operand::Reg(dest),
+                        src: Operand::Reg(target),
+                    });
                 }
             },
-            _ => {},
+            mir::Rvalue::UnaryOp(op, operand) => {
+                let src = self.lower_operand(operand);
+                self.emit(Instruction::Mov {
+                    dest: Operand::Reg(dest),
+                    src,
+                });
+                let dest = Operand::Reg(dest);
+                self.emit(match op {
+                    mir::UnOp::Neg => Instruction::Neg { dest },
+                    mir::UnOp::Not => Instruction::Not { dest },
+                });
+            },
+            mir::Rvalue::Ref(place, _) | mir::Rvalue::AddressOf(place, _) => {
+                let addr = self.place_address(place);
+                self.emit(Instruction::Lea {
+                    dest: Operand::Reg(dest),
+                    addr,
+                });
+            },
+            // The discriminant is the first word of the value
+            mir::Rvalue::Discriminant(place) => {
+                let src = self.read_place(place);
+                self.emit(Instruction::Mov {
+                    dest: Operand::Reg(dest),
+                    src,
+                });
+            },
+            mir::Rvalue::NullaryOp(op, ty) => {
+                let value = match op {
+                    mir::NullOp::SizeOf => type_words(ty) * WORD,
+                    mir::NullOp::AlignOf => WORD,
+                };
+                self.emit(Instruction::Mov {
+                    dest: Operand::Reg(dest),
+                    src: Operand::Imm(value as i64),
+                });
+            },
+            // Only reached for aggregates bound to a register; slots are
+            // assigned to every local an aggregate is written to
+            mir::Rvalue::Aggregate(_, _) => {},
         }
     }
 
+    /// Set the flags by comparing `left` with `right`
+    fn emit_compare(&mut self, left: &mir::Operand, right: &mir::Operand) {
+        let src1 = self.lower_operand_to_reg(left);
+        let src2 = self.lower_operand(right);
+        self.emit(Instruction::Cmp {
+            src1: Operand::Reg(src1),
+            src2,
+        });
+    }
+
+    /// Set `dest` to 1 if the flags satisfy `cond`, else 0
+    fn materialize_condition(&mut self, dest: VirtualRegister, cond: Condition) {
+        let one = self.new_reg();
+        self.emit(Instruction::Mov {
+            dest: Operand::Reg(one),
+            src: Operand::Imm(1),
+        });
+        self.emit(Instruction::Mov {
+            dest: Operand::Reg(dest),
+            src: Operand::Imm(0),
+        });
+        self.emit(Instruction::Cmov {
+            cond,
+            dest: Operand::Reg(dest),
+            src: Operand::Reg(one),
+        });
+    }
+
     fn lower_operand(&mut self, operand: &mir::Operand) -> Operand {
         match operand {
-            mir::Operand::Copy(p) | mir::Operand::Move(p) => Operand::Reg(self.get_place_reg(p)),
+            mir::Operand::Copy(p) | mir::Operand::Move(p) => self.read_place(p),
             mir::Operand::Constant(c) => match &c.kind {
                 mir::ConstantKind::Int(n) => Operand::Imm(*n),
+                mir::ConstantKind::Bool(b) => Operand::Imm(*b as i64),
                 _ => Operand::Imm(0),
             },
         }
@@ -185,7 +481,7 @@ impl LirLowerer {
             Operand::Reg(r) => r,
             Operand::Imm(i) => {
                 let reg = self.new_reg();
-                self.function.instructions.push(Instruction::Mov {
+                self.emit(Instruction::Mov {
                     dest: Operand::Reg(reg),
                     src: Operand::Imm(i),
                 });
@@ -195,6 +491,131 @@ impl LirLowerer {
         }
     }
 
+    /// Whether `place` names memory rather than a virtual register
+    fn is_in_memory(&self, place: &mir::Place) -> bool {
+        match place {
+            mir::Place::Local(id) => self.stack_slots.contains_key(id),
+            mir::Place::Projection(_, _) => true,
+        }
+    }
+
+    /// Value of `place`, loading it when it lives in memory
+    fn read_place(&mut self, place: &mir::Place) -> Operand {
+        if !self.is_in_memory(place) {
+            return Operand::Reg(self.get_place_reg(place));
+        }
+        let addr = self.place_address(place);
+        let dest = self.new_reg();
+        self.emit(Instruction::Load {
+            dest: Operand::Reg(dest),
+            addr,
+            width: RegisterWidth::W64,
+        });
+        Operand::Reg(dest)
+    }
+
+    /// Store `src` into `place`
+    fn write_place(&mut self, place: &mir::Place, src: Operand) {
+        if !self.is_in_memory(place) {
+            let dest = self.get_place_reg(place);
+            self.emit(Instruction::Mov {
+                dest: Operand::Reg(dest),
+                src,
+            });
+            return;
+        }
+        let addr = self.place_address(place);
+        self.emit(Instruction::Store {
+            addr,
+            src,
+            width: RegisterWidth::W64,
+        });
+    }
+
+    /// Address of a memory-resident place
+    ///
+    /// Pointers read through `Deref` and array bases indexed at run time
+    /// are moved into the scratch registers, since addresses can only be
+    /// formed from physical registers.
+    fn place_address(&mut self, place: &mir::Place) -> Address {
+        match place {
+            mir::Place::Local(id) => {
+                // `assign_stack_slots` covers every local accessed in memory;
+                // anything it missed gets a one-word slot here
+                let offset = match self.stack_slots.get(id) {
+                    Some(&offset) => offset,
+                    None => {
+                        self.function.frame_size += WORD as u32;
+                        let offset = -(self.function.frame_size as i32);
+                        self.stack_slots.insert(*id, offset);
+                        offset
+                    },
+                };
+                Address::StackRelative { offset }
+            },
+            mir::Place::Projection(base, projection) => match projection {
+                mir::Projection::Deref => {
+                    let pointer = self.read_place(base);
+                    self.address_in_scratch(pointer)
+                },
+                mir::Projection::Field(index) => {
+                    let addr = self.place_address(base);
+                    offset_address(addr, *index as i32 * WORD)
+                },
+                mir::Projection::ConstantIndex {
+                    offset,
+                    from_end: false,
+                    ..
+                } => {
+                    let addr = self.place_address(base);
+                    offset_address(addr, *offset as i32 * WORD)
+                },
+                mir::Projection::Index(index) => {
+                    let index = self.read_place(&mir::Place::Local(*index));
+                    let addr = self.place_address(base);
+                    let offset = match addr {
+                        Address::Base { base: ADDR_BASE } => 0,
+                        Address::BaseOffset {
+                            base: ADDR_BASE,
+                            offset,
+                        } => offset,
+                        addr => {
+                            self.emit(Instruction::Lea {
+                                dest: Operand::PhysReg(ADDR_BASE),
+                                addr,
+                            });
+                            0
+                        },
+                    };
+                    self.emit(Instruction::Mov {
+                        dest: Operand::PhysReg(ADDR_INDEX),
+                        src: index,
+                    });
+                    Address::Indexed {
+                        base: ADDR_BASE,
+                        index: ADDR_INDEX,
+                        scale: WORD as u8,
+                        offset,
+                    }
+                },
+                // Offsets from the end need the length at run time, which
+                // slices do not carry yet; address the start of the base
+                mir::Projection::ConstantIndex { .. } | mir::Projection::Subslice { .. } => {
+                    self.place_address(base)
+                },
+            },
+        }
+    }
+
+    /// Move a pointer into the address base register and address through it
+    fn address_in_scratch(&mut self, pointer: Operand) -> Address {
+        self.emit(Instruction::Mov {
+            dest: Operand::PhysReg(ADDR_BASE),
+            src: pointer,
+        });
+        Address::Base { base: ADDR_BASE }
+    }
+
     fn get_place_reg(&mut self, place: &mir::Place) -> VirtualRegister {
         match place {
             mir::Place::Local(id) => {
@@ -210,96 +631,212 @@ impl LirLowerer {
         }
     }
 
-    fn lower_terminator(&mut self, terminator: &mir::Terminator) {
+    /// Lower a block's terminator
+    ///
+    /// `fused` is the comparison an `If` branches on when the block left it
+    /// to the branch instead of storing it in the condition local.
+    fn lower_terminator(
+        &mut self,
+        terminator: &mir::Terminator,
+        fused: Option<(Condition, &mir::Operand, &mir::Operand)>,
+    ) {
         match terminator {
             mir::Terminator::Return => {
-                self.function
-                    .instructions
-                    .push(Instruction::Ret { value: None });
+                let value = if self.returns_value {
+                    Some(self.read_place(&mir::Place::Local(mir::LocalId(0))))
+                } else {
+                    None
+                };
+                self.emit(Instruction::Ret { value });
             },
             mir::Terminator::Goto { target } => {
-                self.function.instructions.push(Instruction::Jmp {
-                    target: format!(".Lbb{}", target.0),
+                self.emit_phi_copies(*target);
+                self.emit(Instruction::Jmp {
+                    target: block_label(*target),
                 });
             },
             mir::Terminator::Call {
-                func: _,
+                func,
                 args,
-                destination: _,
-                target: _,
+                destination,
+                target,
                 cleanup: _,
             } => {
                 let mut call_args = Vec::new();
                 for arg in args {
-                    let arg_reg = match arg {
-                        mir::Operand::Copy(p) | mir::Operand::Move(p) => self.get_place_reg(p),
-                        mir::Operand::Constant(c) => {
-                            let reg = self.new_reg();
-                            let imm = match &c.kind {
-                                mir::ConstantKind::Int(i) => *i,
-                                mir::ConstantKind::Bool(b) => {
-                                    if *b {
-                                        1
-                                    } else {
-                                        0
-                                    }
-                                },
-                                _ => 0,
-                            };
-                            self.function.instructions.push(Instruction::Mov {
-                                dest: Operand::Reg(reg),
-                                src: Operand::Imm(imm),
-                            });
-                            reg
-                        },
-                    };
+                    let arg_reg = self.lower_operand_to_reg(arg);
                     call_args.push(Operand::Reg(arg_reg));
                 }
 
-                self.function.instructions.push(Instruction::Call {
-                    target: CallTarget::Direct(faxc_util::Symbol::intern("print")),
+                let callee = match func {
+                    mir::Operand::Constant(mir::Constant {
+                        kind: mir::ConstantKind::Fn(name),
+                        ..
+                    }) => CallTarget::Direct(*name),
+                    mir::Operand::Copy(p) | mir::Operand::Move(p) => {
+                        CallTarget::Indirect(self.read_place(p))
+                    },
+                    _ => CallTarget::Direct(faxc_util::Symbol::intern("print")),
+                };
+                self.emit(Instruction::Call {
+                    target: callee,
+                    args: call_args,
                 });
+                self.write_place(destination, Operand::PhysReg(PhysicalRegister::RAX));
+                match target {
+                    Some(target) => {
+                        self.emit_phi_copies(*target);
+                        self.emit(Instruction::Jmp {
+                            target: block_label(*target),
+                        });
+                    },
+                    // A call without a return block diverges
+                    None => self.emit(Instruction::Ret { value: None }),
+                }
             },
             mir::Terminator::If {
                 cond,
                 then_block,
                 else_block,
             } => {
-                let cond_reg = match cond {
-                    mir::Operand::Copy(p) | mir::Operand::Move(p) => self.get_place_reg(p),
-                    mir::Operand::Constant(c) => {
-                        let reg = self.new_reg();
-                        let imm = match c.kind {
-                            mir::ConstantKind::Bool(b) => {
-                                if b {
-                                    1
-                                } else {
-                                    0
-                                }
-                            },
-                            mir::ConstantKind::Int(i) => i,
-                            _ => 0,
-                        };
-                        self.function.instructions.push(Instruction::Mov {
-                            dest: Operand::Reg(reg),
-                            src: Operand::Imm(imm),
+                let mut edges = Vec::new();
+                let jump_if = match fused {
+                    Some((jump_if, left, right)) => {
+                        self.emit_compare(left, right);
+                        jump_if
+                    },
+                    None => {
+                        let cond_reg = self.lower_operand_to_reg(cond);
+                        self.emit(Instruction::Cmp {
+                            src1: Operand::Reg(cond_reg),
+                            src2: Operand::Imm(0),
                         });
-                        reg
+                        Condition::Ne
                     },
                 };
-                self.function.instructions.push(Instruction::Cmp {
-                    src1: Operand::Reg(cond_reg),
-                    src2: Operand::Imm(0),
+                let target = self.edge_label(*then_block, &mut edges);
+                self.emit(Instruction::Jcc {
+                    cond: jump_if,
+                    target,
                 });
-                self.function.instructions.push(Instruction::Jcc {
-                    cond: Condition::Ne,
-                    target: format!(".Lbb{}", then_block.0),
-                });
-                self.function.instructions.push(Instruction::Jmp {
-                    target: format!(".Lbb{}", else_block.0),
+                let target = self.edge_label(*else_block, &mut edges);
+                self.emit(Instruction::Jmp { target });
+                self.emit_edge_blocks(edges);
+            },
+            mir::Terminator::SwitchInt {
+                discr,
+                targets,
+                otherwise,
+                ..
+            } => {
+                let mut edges = Vec::new();
+                let discr = self.lower_operand_to_reg(discr);
+                for (value, target) in targets {
+                    self.emit(Instruction::Cmp {
+                        src1: Operand::Reg(discr),
+                        src2: Operand::Imm(*value as i64),
+                    });
+                    let target = self.edge_label(*target, &mut edges);
+                    self.emit(Instruction::Jcc {
+                        cond: Condition::Eq,
+                        target,
+                    });
+                }
+                let target = self.edge_label(*otherwise, &mut edges);
+                self.emit(Instruction::Jmp { target });
+                self.emit_edge_blocks(edges);
+            },
+            mir::Terminator::Abort => {
+                self.emit(Instruction::Call {
+                    target: CallTarget::External(Symbol::intern("abort")),
+                    args: Vec::new(),
                 });
+                self.emit(Instruction::Ret { value: None });
             },
-            _ => {},
+            // Never executed; the `ret` keeps the block from falling
+            // through into whichever block is laid out after it
+            mir::Terminator::Unreachable | mir::Terminator::Resume => {
+                self.emit(Instruction::Ret { value: None });
+            },
+        }
+    }
+
+    /// Values the PHI nodes of `target` take on the edge from the current
+    /// block
+    fn phi_copies(&self, target: mir::BlockId) -> Vec<(mir::LocalId, mir::Operand)> {
+        let Some(phis) = self.phis.get(&target) else {
+            return Vec::new();
+        };
+        phis.iter()
+            .filter_map(|(dest, incoming)| {
+                incoming
+                    .iter()
+                    .find(|(pred, _)| *pred == self.current_block)
+                    .map(|(_, op)| (*dest, op.clone()))
+            })
+            .collect()
+    }
+
+    /// Emit the PHI copies for the edge from the current block to `target`
+    fn emit_phi_copies(&mut self, target: mir::BlockId) {
+        let copies = self.phi_copies(target);
+        if copies.is_empty() {
+            return;
+        }
+
+        // Read every incoming value before any destination is written
+        let mut moves = Vec::new();
+        let mut stores = Vec::new();
+        for (dest, op) in copies {
+            let src = self.lower_operand(&op);
+            let place = mir::Place::Local(dest);
+            if self.is_in_memory(&place) {
+                stores.push((place, src));
+            } else {
+                moves.push((self.get_place_reg(&place), src));
+            }
+        }
+        // Stores only read registers, so do them before the moves clobber any
+        for (place, src) in stores {
+            self.write_place(&place, src);
+        }
+        for (dest, src) in sequence_parallel_copies(moves, || self.new_reg()) {
+            self.emit(Instruction::Mov {
+                dest: Operand::Reg(dest),
+                src,
+            });
+        }
+    }
+
+    /// Label to branch to for the edge to `target`
+    ///
+    /// A branching block cannot hold the copies for one of its edges, so an
+    /// edge with PHI copies gets its own block, emitted by
+    /// `emit_edge_blocks` once the branch is done.
+    fn edge_label(
+        &mut self,
+        target: mir::BlockId,
+        edges: &mut Vec<(Label, mir::BlockId)>,
+    ) -> Label {
+        if self.phi_copies(target).is_empty() {
+            return block_label(target);
+        }
+        let label = format!(".Ledge{}", self.label_counter);
+        self.label_counter += 1;
+        edges.push((label.clone(), target));
+        label
+    }
+
+    fn emit_edge_blocks(&mut self, edges: Vec<(Label, mir::BlockId)>) {
+        for (label, target) in edges {
+            self.function
+                .labels
+                .push((self.function.instructions.len(), label.clone()));
+            self.emit(Instruction::Label { name: label });
+            self.emit_phi_copies(target);
+            self.emit(Instruction::Jmp {
+                target: block_label(target),
+            });
         }
     }
 
@@ -308,6 +845,278 @@ impl LirLowerer {
     }
 }
 
+/// Order a set of simultaneous register copies so none reads a register
+/// an earlier copy already overwrote
+///
+/// A copy is emitted once no pending copy still reads its destination.
+/// When only cycles remain, such as a swap, one destination is saved to a
+/// fresh register from `temp` and its readers are pointed at the copy.
+fn sequence_parallel_copies(
+    mut pending: Vec<(VirtualRegister, Operand)>,
+    mut temp: impl FnMut() -> VirtualRegister,
+) -> Vec<(VirtualRegister, Operand)> {
+    pending.retain(|(dest, src)| *src != Operand::Reg(*dest));
+    let mut sequenced = Vec::new();
+    while !pending.is_empty() {
+        let ready = pending
+            .iter()
+            .position(|(dest, _)| !pending.iter().any(|(_, src)| *src == Operand::Reg(*dest)));
+        match ready {
+            Some(i) => sequenced.push(pending.remove(i)),
+            None => {
+                let blocked = pending[0].0;
+                let saved = temp();
+                sequenced.push((saved, Operand::Reg(blocked)));
+                for (_, src) in pending.iter_mut() {
+                    if *src == Operand::Reg(blocked) {
+                        *src = Operand::Reg(saved);
+                    }
+                }
+            },
+        }
+    }
+    sequenced
+}
+
+/// Local whose storage `place` is rooted in, unless it is reached through
+/// a pointer
+fn base_local(place: &mir::Place) -> Option<mir::LocalId> {
+    match place {
+        mir::Place::Local(id) => Some(*id),
+        mir::Place::Projection(_, mir::Projection::Deref) => None,
+        mir::Place::Projection(base, _) => base_local(base),
+    }
+}
+
+/// Locals with storage markers, each mapped to the locals whose storage may
+/// be live at the same time as its own
+///
+/// Storage liveness flows forward from `StorageLive` to `StorageDead`; a
+/// local is live at a join if it is live coming from any predecessor.
+fn storage_conflicts(mir_fn: &mir::Function) -> IndexMap<mir::LocalId, IndexSet<mir::LocalId>> {
+    let transfer = |block: &mir::BasicBlock, live: &mut IndexSet<mir::LocalId>| {
+        for stmt in &block.statements {
+            match stmt {
+                mir::Statement::StorageLive(local) => {
+                    live.insert(*local);
+                },
+                mir::Statement::StorageDead(local) => {
+                    live.swap_remove(local);
+                },
+                _ => {},
+            }
+        }
+    };
+
+    let mut conflicts: IndexMap<mir::LocalId, IndexSet<mir::LocalId>> = IndexMap::new();
+    for block in mir_fn.blocks.as_slice() {
+        for stmt in &block.statements {
+            if let mir::Statement::StorageLive(local) = stmt {
+                conflicts.entry(*local).or_default();
+            }
+        }
+    }
+    if conflicts.is_empty() {
+        return conflicts;
+    }
+
+    let cfg = mir::ControlFlowGraph::new(mir_fn);
+    let mut exit: IndexMap<mir::BlockId, IndexSet<mir::LocalId>> = IndexMap::new();
+    let entry_of = |exit: &IndexMap<mir::BlockId, IndexSet<mir::LocalId>>, block: mir::BlockId| {
+        let mut live = IndexSet::new();
+        for pred in cfg.predecessors.get(&block).into_iter().flatten() {
+            live.extend(exit.get(pred).into_iter().flatten().copied());
+        }
+        live
+    };
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for block in mir_fn.blocks.as_slice() {
+            let mut live = entry_of(&exit, block.id);
+            transfer(block, &mut live);
+            if exit.get(&block.id) != Some(&live) {
+                exit.insert(block.id, live);
+                changed = true;
+            }
+        }
+    }
+
+    let mut record = |local: mir::LocalId, live: &IndexSet<mir::LocalId>| {
+        for &other in live {
+            if other != local {
+                conflicts.entry(local).or_default().insert(other);
+                conflicts.entry(other).or_default().insert(local);
+            }
+        }
+    };
+    for block in mir_fn.blocks.as_slice() {
+        let mut live = entry_of(&exit, block.id);
+        for &local in &live {
+            record(local, &live);
+        }
+        for stmt in &block.statements {
+            match stmt {
+                mir::Statement::StorageLive(local) => {
+                    record(*local, &live);
+                    live.insert(*local);
+                },
+                mir::Statement::StorageDead(local) => {
+                    live.swap_remove(local);
+                },
+                _ => {},
+            }
+        }
+    }
+    conflicts
+}
+
+/// Every local whose value is needed to evaluate `place`
+fn mentioned_locals(place: &mir::Place, out: &mut Vec<mir::LocalId>) {
+    match place {
+        mir::Place::Local(id) => out.push(*id),
+        mir::Place::Projection(base, projection) => {
+            if let mir::Projection::Index(index) = projection {
+                out.push(*index);
+            }
+            mentioned_locals(base, out);
+        },
+    }
+}
+
+/// Comparison the last statement of `block` stores into the local its `If`
+/// branches on
+fn branch_comparison(
+    block: &mir::BasicBlock,
+) -> Option<(mir::LocalId, Condition, &mir::Operand, &mir::Operand)> {
+    let mir::Terminator::If { cond, .. } = &block.terminator else {
+        return None;
+    };
+    let Some(mir::Statement::Assign(
+        mir::Place::Local(dest),
+        mir::Rvalue::BinaryOp(op, left, right),
+    )) = block.statements.last()
+    else {
+        return None;
+    };
+    let cond_place = operand_place(cond)?;
+    let jump_if = comparison_condition(*op)?;
+    (*cond_place == mir::Place::Local(*dest)).then_some((*dest, jump_if, left, right))
+}
+
+/// Local that `place` reads from memory, and the number of words of it
+/// that must exist for the access to stay in bounds
+fn memory_access(place: &mir::Place) -> Option<(mir::LocalId, i32)> {
+    let mir::Place::Projection(base, projection) = place else {
+        return None;
+    };
+    let local = base_local(place)?;
+    let fields = match (projection, &**base) {
+        (mir::Projection::Field(index), mir::Place::Local(_)) => *index as i32 + 1,
+        _ => 1,
+    };
+    Some((local, fields))
+}
+
+/// Place an operand reads, if it is not a constant
+fn operand_place(op: &mir::Operand) -> Option<&mir::Place> {
+    match op {
+        mir::Operand::Copy(p) | mir::Operand::Move(p) => Some(p),
+        mir::Operand::Constant(_) => None,
+    }
+}
+
+/// Places read by an rvalue
+fn rvalue_places(rvalue: &mir::Rvalue) -> Vec<&mir::Place> {
+    match rvalue {
+        mir::Rvalue::Use(op) | mir::Rvalue::Cast(_, op, _) => {
+            operand_place(op).into_iter().collect()
+        },
+        mir::Rvalue::UnaryOp(_, op) => operand_place(op).into_iter().collect(),
+        mir::Rvalue::BinaryOp(_, l, r) | mir::Rvalue::CheckedBinaryOp(_, l, r) => operand_place(l)
+            .into_iter()
+            .chain(operand_place(r))
+            .collect(),
+        mir::Rvalue::Ref(p, _) | mir::Rvalue::AddressOf(p, _) | mir::Rvalue::Discriminant(p) => {
+            vec![p]
+        },
+        mir::Rvalue::Aggregate(_, ops) => ops.iter().filter_map(operand_place).collect(),
+        mir::Rvalue::NullaryOp(_, _) => Vec::new(),
+    }
+}
+
+/// Places read or written by a terminator
+fn terminator_places(terminator: &mir::Terminator) -> Vec<&mir::Place> {
+    match terminator {
+        mir::Terminator::If { cond, .. } => operand_place(cond).into_iter().collect(),
+        mir::Terminator::SwitchInt { discr, .. } => operand_place(discr).into_iter().collect(),
+        mir::Terminator::Call {
+            func,
+            args,
+            destination,
+            ..
+        } => std::iter::once(func)
+            .chain(args)
+            .filter_map(operand_place)
+            .chain(std::iter::once(destination))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Machine words a value of `ty` occupies in memory
+fn type_words(ty: &Type) -> i32 {
+    match ty {
+        Type::Tuple(tys) => tys.len().max(1) as i32,
+        Type::Array(_, len) => (*len).max(1) as i32,
+        _ => 1,
+    }
+}
+
+/// `addr` moved `offset` bytes further
+fn offset_address(addr: Address, offset: i32) -> Address {
+    if offset == 0 {
+        return addr;
+    }
+    match addr {
+        Address::Base { base } => Address::BaseOffset { base, offset },
+        Address::BaseOffset { base, offset: o } => Address::BaseOffset {
+            base,
+            offset: o + offset,
+        },
+        Address::Indexed {
+            base,
+            index,
+            scale,
+            offset: o,
+        } => Address::Indexed {
+            base,
+            index,
+            scale,
+            offset: o + offset,
+        },
+        Address::StackRelative { offset: o } => Address::StackRelative { offset: o + offset },
+        Address::RipRelative { offset: o, symbol } => Address::RipRelative {
+            offset: o + offset,
+            symbol,
+        },
+        addr => addr,
+    }
+}
+
+/// Flag condition under which a comparison operator holds
+fn comparison_condition(op: mir::BinOp) -> Option<Condition> {
+    match op {
+        mir::BinOp::Eq => Some(Condition::Eq),
+        mir::BinOp::Ne => Some(Condition::Ne),
+        mir::BinOp::Lt => Some(Condition::L),
+        mir::BinOp::Le => Some(Condition::Le),
+        mir::BinOp::Gt => Some(Condition::G),
+        mir::BinOp::Ge => Some(Condition::Ge),
+        _ => None,
+    }
+}
+
 fn convert_binop(op: mir::BinOp) -> BinOp {
     match op {
         mir::BinOp::Add => BinOp::Add,
@@ -361,4 +1170,298 @@ mod tests {
         // Should have at least one instruction (Mov or Ret)
         assert!(!lir_fn.instructions.is_empty());
     }
+
+    #[test]
+    fn test_mir_to_lir_call_passes_args() {
+        let mut builder = Builder::new(Symbol::intern("main"), Type::Unit);
+        let entry = builder.new_block();
+        let next = builder.new_block();
+        builder.set_current_block(entry);
+
+        let dest = builder.add_local(Type::Unit, None);
+        builder.terminator(mir::Terminator::Call {
+            func: mir::Operand::Constant(mir::Constant {
+                ty: Type::Unit,
+                kind: mir::ConstantKind::Fn(Symbol::intern("println")),
+            }),
+            args: vec![mir::Operand::Constant(mir::Constant {
+                ty: Type::Int,
+                kind: mir::ConstantKind::Int(42),
+            })],
+            destination: mir::Place::Local(dest),
+            target: Some(next),
+            cleanup: None,
+        });
+        builder.set_current_block(next);
+        builder.terminator(mir::Terminator::Return);
+
+        let lir_fn = lower_mir_to_lir(&builder.build());
+        let call = lir_fn.instructions.iter().find_map(|inst| match inst {
+            Instruction::Call { target, args } => Some((target, args)),
+            _ => None,
+        });
+        let (target, args) = call.expect("call should be lowered");
+        assert!(matches!(target, CallTarget::Direct(name) if name.as_str() == "println"));
+        assert_eq!(args.len(), 1);
+    }
+
+    #[test]
+    fn test_mir_to_lir_flattens_cfg_with_explicit_memory() {
+        let int = |n| {
+            mir::Operand::Constant(mir::Constant {
+                ty: Type::Int,
+                kind: mir::ConstantKind::Int(n),
+            })
+        };
+        let mut builder = Builder::new(Symbol::intern("pick"), Type::Int);
+        let entry = builder.new_block();
+        let then_block = builder.new_block();
+        let else_block = builder.new_block();
+        let join = builder.new_block();
+        let ret = mir::Place::Local(mir::LocalId(0));
+
+        // let pair = (1, 2); if cond { _0 = pair.1 } else { _0 = 0 }
+        builder.set_current_block(entry);
+        let pair = builder.add_local(Type::Tuple(vec![Type::Int, Type::Int]), None);
+        let cond = builder.add_local(Type::Bool, None);
+        builder.assign(
+            mir::Place::Local(pair),
+            mir::Rvalue::Aggregate(mir::AggregateKind::Tuple, vec![int(1), int(2)]),
+        );
+        builder.terminator(mir::Terminator::If {
+            cond: mir::Operand::Copy(mir::Place::Local(cond)),
+            then_block,
+            else_block,
+        });
+
+        builder.set_current_block(then_block);
+        let second =
+            mir::Place::Projection(Box::new(mir::Place::Local(pair)), mir::Projection::Field(1));
+        builder.assign(ret.clone(), mir::Rvalue::Use(mir::Operand::Copy(second)));
+        builder.terminator(mir::Terminator::Goto { target: join });
+
+        builder.set_current_block(else_block);
+        builder.assign(ret, mir::Rvalue::Use(int(0)));
+        builder.terminator(mir::Terminator::Goto { target: join });
+
+        builder.set_current_block(join);
+        builder.terminator(mir::Terminator::Return);
+
+        let mir_fn = builder.build();
+        let lir_fn = lower_mir_to_lir(&mir_fn);
+
+        // One label per block, recorded at the position of its instruction
+        assert_eq!(lir_fn.labels.len(), mir_fn.block_count());
+        for block in [entry, then_block, else_block, join] {
+            let name = format!(".Lbb{}", block.0);
+            let (index, _) = lir_fn
+                .labels
+                .iter()
+                .find(|(_, label)| *label == name)
+                .expect("every block should get a label");
+            assert!(
+                matches!(&lir_fn.instructions[*index], Instruction::Label { name: n } if *n == name)
+            );
+        }
+
+        // The tuple lives in a stack slot, written and read explicitly
+        assert!(lir_fn.frame_size >= 16);
+        let stores = lir_fn
+            .instructions
+            .iter()
+            .filter(|inst| matches!(inst, Instruction::Store { .. }))
+            .count();
+        assert_eq!(stores, 2);
+        assert!(lir_fn
+            .instructions
+            .iter()
+            .any(|inst| matches!(inst, Instruction::Load { .. })));
+
+        assert!(matches!(
+            lir_fn.instructions.last(),
+            Some(Instruction::Ret { value: Some(_) })
+        ));
+    }
+
+    #[test]
+    fn test_phi_becomes_copies_in_each_predecessor() {
+        let int = |n| {
+            mir::Operand::Constant(mir::Constant {
+                ty: Type::Int,
+                kind: mir::ConstantKind::Int(n),
+            })
+        };
+        let mut builder = Builder::new(Symbol::intern("diamond"), Type::Int);
+        let entry = builder.new_block();
+        let left = builder.new_block();
+        let right = builder.new_block();
+        let join = builder.new_block();
+
+        builder.set_current_block(entry);
+        let cond = builder.add_local(Type::Bool, None);
+        builder.terminator(mir::Terminator::If {
+            cond: mir::Operand::Copy(mir::Place::Local(cond)),
+            then_block: left,
+            else_block: right,
+        });
+        for block in [left, right] {
+            builder.set_current_block(block);
+            builder.terminator(mir::Terminator::Goto { target: join });
+        }
+        builder.set_current_block(join);
+        builder.terminator(mir::Terminator::Return);
+
+        // _0 = phi [left: 1, right: 2]
+        let mut mir_fn = builder.build();
+        mir_fn.blocks[join].statements.push(mir::Statement::Phi(
+            mir::LocalId(0),
+            vec![(left, int(1)), (right, int(2))],
+        ));
+        let lir_fn = lower_mir_to_lir(&mir_fn);
+
+        // The copy sits between the predecessor's label and its jump to the join
+        let copy_in = |block: BlockId| {
+            let label = format!(".Lbb{}", block.0);
+            let start = lir_fn
+                .instructions
+                .iter()
+                .position(|inst| matches!(inst, Instruction::Label { name } if *name == label))
+                .expect("predecessor should be lowered");
+            let join_label = format!(".Lbb{}", join.0);
+            lir_fn.instructions[start..]
+                .iter()
+                .take_while(
+                    |inst| !matches!(inst, Instruction::Jmp { target } if *target == join_label),
+                )
+                .find_map(|inst| match inst {
+                    Instruction::Mov {
+                        dest: Operand::Reg(reg),
+                        src: Operand::Imm(n),
+                    } => Some((*reg, *n)),
+                    _ => None,
+                })
+                .expect("predecessor should copy the incoming value")
+        };
+        let (left_reg, left_value) = copy_in(left);
+        let (right_reg, right_value) = copy_in(right);
+        assert_eq!(left_value, 1);
+        assert_eq!(right_value, 2);
+        assert_eq!(left_reg, right_reg);
+    }
+
+    #[test]
+    fn test_branch_on_comparison_fuses_into_cmp_jcc() {
+        let mut builder = Builder::new(Symbol::intern("less"), Type::Int);
+        let entry = builder.new_block();
+        let then_block = builder.new_block();
+        let else_block = builder.new_block();
+
+        // if a < b { .. } else { .. }
+        builder.set_current_block(entry);
+        let a = builder.add_local(Type::Int, None);
+        let b = builder.add_local(Type::Int, None);
+        let less = builder.add_local(Type::Bool, None);
+        builder.assign(
+            mir::Place::Local(less),
+            mir::Rvalue::BinaryOp(
+                mir::BinOp::Lt,
+                Box::new(mir::Operand::Copy(mir::Place::Local(a))),
+                Box::new(mir::Operand::Copy(mir::Place::Local(b))),
+            ),
+        );
+        builder.terminator(mir::Terminator::If {
+            cond: mir::Operand::Move(mir::Place::Local(less)),
+            then_block,
+            else_block,
+        });
+        for block in [then_block, else_block] {
+            builder.set_current_block(block);
+            builder.terminator(mir::Terminator::Return);
+        }
+
+        let lir_fn = lower_mir_to_lir(&builder.build());
+        let cmp = lir_fn
+            .instructions
+            .iter()
+            .position(|inst| matches!(inst, Instruction::Cmp { .. }))
+            .expect("the comparison should set the flags");
+        assert!(matches!(
+            &lir_fn.instructions[cmp],
+            Instruction::Cmp {
+                src1: Operand::Reg(_),
+                src2: Operand::Reg(_),
+            }
+        ));
+        assert!(matches!(
+            &lir_fn.instructions[cmp + 1],
+            Instruction::Jcc {
+                cond: Condition::L,
+                ..
+            }
+        ));
+        // No boolean is materialized for the branch to test
+        assert!(!lir_fn
+            .instructions
+            .iter()
+            .any(|inst| matches!(inst, Instruction::Cmov { .. })));
+    }
+
+    #[test]
+    fn test_disjoint_storage_shares_stack_slot() {
+        let int = |n| {
+            mir::Operand::Constant(mir::Constant {
+                ty: Type::Int,
+                kind: mir::ConstantKind::Int(n),
+            })
+        };
+        let mut builder = Builder::new(Symbol::intern("scopes"), Type::Int);
+        let entry = builder.new_block();
+        builder.set_current_block(entry);
+        let pair = Type::Tuple(vec![Type::Int, Type::Int]);
+        let tuples: Vec<_> = (0..3)
+            .map(|_| builder.add_local(pair.clone(), None))
+            .collect();
+        builder.terminator(mir::Terminator::Return);
+        let mut mir_fn = builder.build();
+
+        // { let t = (1, 2); _0 = t.0; } three times over
+        let statements = &mut mir_fn.blocks[entry].statements;
+        for &t in &tuples {
+            let first =
+                mir::Place::Projection(Box::new(mir::Place::Local(t)), mir::Projection::Field(0));
+            statements.push(mir::Statement::StorageLive(t));
+            statements.push(mir::Statement::Assign(
+                mir::Place::Local(t),
+                mir::Rvalue::Aggregate(mir::AggregateKind::Tuple, vec![int(1), int(2)]),
+            ));
+            statements.push(mir::Statement::Assign(
+                mir::Place::Local(mir::LocalId(0)),
+                mir::Rvalue::Use(mir::Operand::Copy(first)),
+            ));
+            statements.push(mir::Statement::StorageDead(t));
+        }
+        let lir_fn = lower_mir_to_lir(&mir_fn);
+
+        // One two-word slot serves all three tuples
+        assert_eq!(lir_fn.frame_size, 16);
+    }
+
+    #[test]
+    fn test_parallel_copy_swap_goes_through_temp() {
+        let a = VirtualRegister::new(0);
+        let b = VirtualRegister::new(1);
+        let t = VirtualRegister::new(2);
+
+        // (a, b) = (b, a)
+        let moves =
+            sequence_parallel_copies(vec![(a, Operand::Reg(b)), (b, Operand::Reg(a))], || t);
+        assert_eq!(
+            moves,
+            vec![
+                (t, Operand::Reg(a)),
+                (a, Operand::Reg(b)),
+                (b, Operand::Reg(t)),
+            ]
+        );
+    }
 }
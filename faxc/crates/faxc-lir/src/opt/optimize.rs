@@ -13,8 +13,20 @@ pub fn optimize_function(func: &mut Function) {
         changed |= constant_fold(func);
         changed |= copy_propagation(func);
         changed |= dead_code(func);
+        changed |= redundant_moves_and_jumps(func);
         iterations += 1;
     }
+
+    // The passes above shift instructions without updating label positions
+    func.labels = func
+        .instructions
+        .iter()
+        .enumerate()
+        .filter_map(|(i, inst)| match inst {
+            Instruction::Label { name } => Some((i, name.clone())),
+            _ => None,
+        })
+        .collect();
 }
 
 fn constant_fold(func: &mut Function) -> bool {
@@ -378,6 +390,63 @@ fn try_optimize_pair(inst0: &Instruction, inst1: &Instruction) -> Option<Vec<Ins
     None
 }
 
+/// Drop instructions with no effect: `Nop`s and self-moves, a register
+/// move overwritten by the very next move before being read, and a `jmp`
+/// to a label that immediately follows it
+fn redundant_moves_and_jumps(func: &mut Function) -> bool {
+    let insts = &func.instructions;
+    let mut kept = Vec::with_capacity(insts.len());
+
+    for (i, inst) in insts.iter().enumerate() {
+        let redundant = match inst {
+            Instruction::Nop => true,
+            Instruction::Mov { dest, src } if dest == src => true,
+            Instruction::Mov { dest, .. } => match insts.get(i + 1) {
+                Some(Instruction::Mov {
+                    dest: next_dest,
+                    src: next_src,
+                }) => {
+                    next_dest == dest
+                        && matches!(dest, Operand::Reg(_) | Operand::PhysReg(_))
+                        && !reads_register(next_src, dest)
+                },
+                _ => false,
+            },
+            Instruction::Jmp { target } => insts[i + 1..]
+                .iter()
+                .take_while(|next| matches!(next, Instruction::Label { .. } | Instruction::Nop))
+                .any(|next| matches!(next, Instruction::Label { name } if name == target)),
+            _ => false,
+        };
+        if !redundant {
+            kept.push(inst.clone());
+        }
+    }
+
+    let changed = kept.len() != insts.len();
+    if changed {
+        func.instructions = kept;
+    }
+    changed
+}
+
+/// Whether reading `op` reads the register `reg`, directly or as part of
+/// a memory address
+fn reads_register(op: &Operand, reg: &Operand) -> bool {
+    if op == reg {
+        return true;
+    }
+    let (Operand::Mem(addr), Operand::PhysReg(reg)) = (op, reg) else {
+        return false;
+    };
+    match addr {
+        Address::Base { base } | Address::BaseOffset { base, .. } => base == reg,
+        Address::Indexed { base, index, .. } => base == reg || index == reg,
+        Address::StackRelative { .. } => *reg == PhysicalRegister::RBP,
+        _ => false,
+    }
+}
+
 fn dead_code(func: &mut Function) -> bool {
     let mut used = vec![false; func.registers.len()];
 
@@ -433,7 +502,11 @@ fn collect_uses(inst: &Instruction, used: &mut Vec<bool>) {
             mark_use(src1, used);
             mark_use(src2, used);
         },
-        Instruction::Call { .. } => {},
+        Instruction::Call { args, .. } => {
+            for arg in args {
+                mark_use(arg, used);
+            }
+        },
         _ => {},
     }
 }
@@ -195,3 +195,68 @@ fn test_param_assignment() {
     let assign6 = ParamAssignment::from_systemv(6, 6, false);
     assert!(matches!(assign6.location, ParamLocation::Stack(_)));
 }
+
+#[test]
+fn test_peephole_drops_self_moves_overwritten_moves_and_fallthrough_jumps() {
+    let rax = Operand::PhysReg(PhysicalRegister::RAX);
+    let rcx = Operand::PhysReg(PhysicalRegister::RCX);
+    let mut func = Function::new(Symbol::intern("f"));
+    func.instructions = vec![
+        Instruction::Mov {
+            dest: rax.clone(),
+            src: rax.clone(),
+        },
+        Instruction::Mov {
+            dest: rcx.clone(),
+            src: Operand::Imm(1),
+        },
+        Instruction::Mov {
+            dest: rcx.clone(),
+            src: Operand::Imm(2),
+        },
+        Instruction::Jmp {
+            target: ".L1".to_string(),
+        },
+        Instruction::Label {
+            name: ".L1".to_string(),
+        },
+        Instruction::Ret {
+            value: Some(rcx.clone()),
+        },
+    ];
+    func.labels = vec![(4, ".L1".to_string())];
+
+    optimize_function(&mut func);
+
+    assert_eq!(func.instructions.len(), 3, "{:?}", func.instructions);
+    assert!(matches!(
+        &func.instructions[0],
+        Instruction::Mov { dest, src: Operand::Imm(2) } if *dest == rcx
+    ));
+    assert!(matches!(&func.instructions[1], Instruction::Label { name } if name == ".L1"));
+    assert!(matches!(func.instructions[2], Instruction::Ret { .. }));
+    assert_eq!(func.labels, vec![(1, ".L1".to_string())]);
+}
+
+#[test]
+fn test_peephole_keeps_move_read_by_the_next_one() {
+    let rax = Operand::PhysReg(PhysicalRegister::RAX);
+    let mut func = Function::new(Symbol::intern("f"));
+    func.instructions = vec![
+        Instruction::Mov {
+            dest: rax.clone(),
+            src: Operand::Imm(8),
+        },
+        Instruction::Mov {
+            dest: rax.clone(),
+            src: Operand::Mem(Address::Base {
+                base: PhysicalRegister::RAX,
+            }),
+        },
+        Instruction::Ret { value: Some(rax) },
+    ];
+
+    optimize_function(&mut func);
+
+    assert_eq!(func.instructions.len(), 3, "{:?}", func.instructions);
+}
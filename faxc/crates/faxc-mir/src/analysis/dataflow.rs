@@ -88,6 +88,14 @@ fn compute_block_in(block: &BasicBlock, out: &HashSet<LocalId>) -> HashSet<Local
                 }
                 rvalue_uses(rvalue, &mut uses);
             },
+            // Incoming values are read on the edges into the block; counting
+            // them here keeps them live out of every predecessor
+            Statement::Phi(id, incoming) => {
+                for (_, op) in incoming {
+                    operand_uses(op, &mut uses);
+                }
+                defines.insert(*id);
+            },
             Statement::Nop => {},
             Statement::StorageLive(id) | Statement::StorageDead(id) => {
                 defines.insert(*id);
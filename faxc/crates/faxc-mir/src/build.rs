@@ -0,0 +1,108 @@
+//! MIR Builder
+//!
+//! Incremental construction of a MIR [`Function`]. Lowering adds locals and
+//! basic blocks, then appends statements to and terminates the current
+//! block.
+
+use crate::mir::*;
+use faxc_sem::hir;
+use faxc_sem::Type;
+use faxc_util::{DefId, Span, Symbol};
+use indexmap::IndexMap;
+
+/// Builder for a single MIR function
+///
+/// Local 0 is the return place, so locals added afterwards start at 1. A
+/// new block ends in `Unreachable` until it is given a terminator.
+pub struct Builder {
+    func: Function,
+    current_block: Option<BlockId>,
+    /// Local holding each variable declared so far
+    vars: IndexMap<DefId, LocalId>,
+}
+
+impl Builder {
+    /// Start a function called `name` returning `return_ty`
+    pub fn new(name: Symbol, return_ty: Type) -> Self {
+        let mut func = Function::new(name, return_ty.clone(), 0);
+        func.locals.push(Local {
+            ty: return_ty,
+            span: Span::DUMMY,
+            name: None,
+        });
+        Self {
+            func,
+            current_block: None,
+            vars: IndexMap::new(),
+        }
+    }
+
+    /// Add a local of type `ty`
+    ///
+    /// If `pattern` binds a name, the local is named after it and becomes
+    /// the place that variable lives in.
+    pub fn add_local(&mut self, ty: Type, pattern: Option<hir::Pattern>) -> LocalId {
+        let binding = match pattern {
+            Some(hir::Pattern::Binding { name, def_id, .. }) => Some((name, def_id)),
+            _ => None,
+        };
+        let local = self.func.locals.push(Local {
+            ty,
+            span: Span::DUMMY,
+            name: binding.map(|(name, _)| name),
+        });
+        if let Some((_, def_id)) = binding {
+            self.vars.insert(def_id, local);
+        }
+        local
+    }
+
+    /// The local declared for the variable `def_id`, if any
+    pub fn var_local(&self, def_id: DefId) -> Option<LocalId> {
+        self.vars.get(&def_id).copied()
+    }
+
+    /// Add an empty basic block
+    pub fn new_block(&mut self) -> BlockId {
+        let id = BlockId(self.func.blocks.len() as u32);
+        self.func.blocks.push(BasicBlock {
+            id,
+            statements: Vec::new(),
+            terminator: Terminator::Unreachable,
+        })
+    }
+
+    /// Direct subsequent statements and the next terminator to `block`
+    pub fn set_current_block(&mut self, block: BlockId) {
+        self.current_block = Some(block);
+    }
+
+    /// The block statements are currently appended to
+    ///
+    /// # Panics
+    ///
+    /// Panics if no block has been selected with [`Builder::set_current_block`].
+    pub fn current_block(&self) -> BlockId {
+        self.current_block
+            .expect("MIR builder has no current block")
+    }
+
+    /// Append `place = rvalue` to the current block
+    pub fn assign(&mut self, place: Place, rvalue: Rvalue) {
+        let block = self.current_block();
+        self.func.blocks[block]
+            .statements
+            .push(Statement::Assign(place, rvalue));
+    }
+
+    /// Set the terminator of the current block
+    pub fn terminator(&mut self, terminator: Terminator) {
+        let block = self.current_block();
+        self.func.blocks[block].terminator = terminator;
+    }
+
+    /// Finish the function
+    pub fn build(self) -> Function {
+        self.func
+    }
+}
@@ -4,7 +4,7 @@
 mod tests {
     use crate::{Function, Local, LocalId, BlockId, BasicBlock, Statement, Terminator};
     use crate::{Place, Rvalue, Operand, Constant, ConstantKind};
-    use crate::{BinOp, UnOp, Mutability, CastKind, AggregateKind};
+    use crate::{BinOp, UnOp, CastKind, AggregateKind};
     use faxc_sem::Type;
     use faxc_util::{Symbol, IndexVec, Span};
 
@@ -13,8 +13,8 @@ mod tests {
     /// EDGE CASE: Empty function
     #[test]
     fn test_edge_empty_function() {
-        let mut locals: IndexVec<LocalId, Local> = IndexVec::new();
-        let mut blocks: IndexVec<BlockId, BasicBlock> = IndexVec::new();
+        let locals: IndexVec<LocalId, Local> = IndexVec::new();
+        let blocks: IndexVec<BlockId, BasicBlock> = IndexVec::new();
         
         let entry = BlockId(0);
         
@@ -24,6 +24,8 @@ mod tests {
             blocks,
             entry_block: entry,
             return_ty: Type::Unit,
+            arg_count: 0,
+            arg_locals: Vec::new(),
         };
         
         assert_eq!(func.name, Symbol::intern("empty"));
@@ -254,10 +256,10 @@ mod tests {
     fn test_edge_unary_op() {
         let rv = Rvalue::UnaryOp(
             UnOp::Neg,
-            Operand::Constant(Constant {
+            Box::new(Operand::Constant(Constant {
                 ty: Type::Int,
                 kind: ConstantKind::Int(5),
-            }),
+            })),
         );
         assert!(matches!(rv, Rvalue::UnaryOp(_, _)));
     }
@@ -8,6 +8,10 @@ pub mod build;
 pub mod lower;
 pub mod opt;
 pub mod analysis;
+#[cfg(test)]
+mod tests;
+#[cfg(test)]
+mod edge_cases;
 
 pub use mir::*;
 pub use build::*;
@@ -6,18 +6,264 @@ use crate::build::Builder;
 use crate::mir::*;
 use faxc_sem::hir;
 use faxc_sem::Type;
+use faxc_util::{DefId, Span, Symbol};
+
+/// Shorthands for the temporaries and operands that lowering creates
+impl Builder {
+    /// A fresh unnamed local of type `ty`
+    pub fn temp(&mut self, ty: Type) -> Place {
+        Place::Local(self.add_local(ty, None))
+    }
+
+    /// Assign `rvalue` to a fresh temporary of type `ty` and return it
+    pub fn push_assign(&mut self, rvalue: Rvalue, ty: Type) -> Place {
+        let place = self.temp(ty);
+        self.assign(place.clone(), rvalue);
+        place
+    }
+
+    /// Lower `expr` and use its value as an operand
+    pub fn operand(&mut self, expr: &hir::Expr) -> Operand {
+        let place = lower_expr(self, expr);
+        place_to_operand(place, &expr.ty())
+    }
+}
 
 pub fn lower_hir_function(hir_fn: &hir::FnItem) -> Function {
     let mut builder = Builder::new(hir_fn.name.clone(), hir_fn.ret_type.clone());
+    let arg_locals: Vec<_> = hir_fn
+        .params
+        .iter()
+        .map(|param| builder.add_local(param.ty.clone(), Some(param.pat.clone())))
+        .collect();
 
     let entry = builder.new_block();
     builder.set_current_block(entry);
+    lower_body(&mut builder, &hir_fn.body.value, &hir_fn.ret_type);
+
+    let mut func = builder.build();
+    func.arg_count = arg_locals.len();
+    func.arg_locals = arg_locals;
+    func
+}
+
+/// Lower the closures in `hir_fn`'s body, including nested ones, to the
+/// functions they call
+///
+/// A closure's function is named after its `DefId`. Its first argument is
+/// the environment built where the closure is created, followed by the
+/// closure's own parameters.
+pub fn lower_hir_closures(hir_fn: &hir::FnItem) -> Vec<Function> {
+    let mut closures = Vec::new();
+    collect_closures(&hir_fn.body.value, &mut closures);
+    closures.into_iter().filter_map(lower_closure).collect()
+}
+
+/// Lower `value` as the body of the function being built, starting in the
+/// current block and returning its result through local 0
+fn lower_body(builder: &mut Builder, value: &hir::Expr, ret_ty: &Type) {
+    let result = lower_expr(builder, value);
+
+    if value.diverges() {
+        // Every path has already returned; the current block is dead code
+        builder.terminator(Terminator::Unreachable);
+    } else {
+        let ret_place = Place::Local(LocalId(0));
+        if result != ret_place {
+            let result = place_to_operand(result, ret_ty);
+            builder.assign(ret_place, Rvalue::Use(result));
+        }
+        builder.terminator(Terminator::Return);
+    }
+}
 
-    lower_expr(&mut builder, &hir_fn.body.value);
+/// Name of the function generated for the closure `def_id`
+fn closure_fn_name(def_id: DefId) -> Symbol {
+    Symbol::intern(&format!("closure#{}", def_id.0))
+}
 
-    builder.terminator(Terminator::Return);
+/// Type of a closure's environment: a tuple of its captures, holding a
+/// reference to each variable captured by reference
+fn closure_env_ty(captures: &[hir::Capture]) -> Type {
+    Type::Tuple(
+        captures
+            .iter()
+            .map(|capture| match capture.mode {
+                hir::CaptureMode::ByRef => Type::Ref(Box::new(capture.ty.clone()), false),
+                hir::CaptureMode::ByValue => capture.ty.clone(),
+            })
+            .collect(),
+    )
+}
 
-    builder.build()
+/// Build the environment of a closure where it is created
+///
+/// The environment is an aggregate tagged with the closure's `DefId`, so
+/// the closure value also identifies the function that calls it.
+fn lower_closure_env(builder: &mut Builder, def_id: DefId, captures: &[hir::Capture]) -> Place {
+    let env_ty = closure_env_ty(captures);
+    let operands = captures
+        .iter()
+        .map(|capture| {
+            let var = lower_expr(
+                builder,
+                &hir::Expr::Var {
+                    def_id: capture.def_id,
+                    ty: capture.ty.clone(),
+                    span: Span::DUMMY,
+                },
+            );
+            match capture.mode {
+                hir::CaptureMode::ByRef => {
+                    let ref_ty = Type::Ref(Box::new(capture.ty.clone()), false);
+                    let place =
+                        builder.push_assign(Rvalue::Ref(var, Mutability::Immutable), ref_ty);
+                    Operand::Move(place)
+                },
+                hir::CaptureMode::ByValue => place_to_operand(var, &capture.ty),
+            }
+        })
+        .collect();
+    builder.push_assign(
+        Rvalue::Aggregate(AggregateKind::Closure(def_id), operands),
+        env_ty,
+    )
+}
+
+/// Generate the function a closure expression calls
+fn lower_closure(closure: &hir::Expr) -> Option<Function> {
+    let hir::Expr::Closure {
+        def_id,
+        params,
+        body,
+        captures,
+        ty,
+        ..
+    } = closure
+    else {
+        return None;
+    };
+    let (param_tys, ret_ty) = match ty {
+        Type::Fn(param_tys, ret_ty) => (param_tys.clone(), (**ret_ty).clone()),
+        _ => (Vec::new(), body.ty()),
+    };
+
+    let mut builder = Builder::new(closure_fn_name(*def_id), ret_ty.clone());
+    let env = builder.add_local(closure_env_ty(captures), None);
+    let mut arg_locals = vec![env];
+    for (pat, ty) in params.iter().zip(param_tys) {
+        arg_locals.push(builder.add_local(ty, Some(pat.clone())));
+    }
+
+    let entry = builder.new_block();
+    builder.set_current_block(entry);
+
+    // Each captured variable gets a local in the closure, read out of the
+    // environment on entry
+    for (i, capture) in captures.iter().enumerate() {
+        let mut field = Place::Projection(Box::new(Place::Local(env)), Projection::Field(i as u32));
+        if capture.mode == hir::CaptureMode::ByRef {
+            field = Place::Projection(Box::new(field), Projection::Deref);
+        }
+        let binding = hir::Pattern::Binding {
+            name: capture.name,
+            def_id: capture.def_id,
+            ty: capture.ty.clone(),
+            mutability: false,
+        };
+        let local = builder.add_local(capture.ty.clone(), Some(binding));
+        builder.assign(
+            Place::Local(local),
+            Rvalue::Use(place_to_operand(field, &capture.ty)),
+        );
+    }
+
+    lower_body(&mut builder, body, &ret_ty);
+
+    let mut func = builder.build();
+    func.arg_count = arg_locals.len();
+    func.arg_locals = arg_locals;
+    Some(func)
+}
+
+/// Collect the closure expressions in `expr`, outermost first
+fn collect_closures<'h>(expr: &'h hir::Expr, out: &mut Vec<&'h hir::Expr>) {
+    let mut visit = |e: &'h hir::Expr| collect_closures(e, out);
+    match expr {
+        hir::Expr::Literal { .. }
+        | hir::Expr::Var { .. }
+        | hir::Expr::Intrinsic { .. }
+        | hir::Expr::Continue { .. } => {},
+        hir::Expr::Binary { left, right, .. } => {
+            visit(left);
+            visit(right);
+        },
+        hir::Expr::Unary { expr, .. }
+        | hir::Expr::Field { object: expr, .. }
+        | hir::Expr::Async { body: expr, .. }
+        | hir::Expr::Loop { body: expr, .. }
+        | hir::Expr::Await { expr, .. }
+        | hir::Expr::Cast { expr, .. } => visit(expr),
+        hir::Expr::Call { func, args, .. } => {
+            visit(func);
+            args.iter().for_each(visit);
+        },
+        hir::Expr::MethodCall { receiver, args, .. } => {
+            visit(receiver);
+            args.iter().for_each(visit);
+        },
+        hir::Expr::Index { object, index, .. } => {
+            visit(object);
+            visit(index);
+        },
+        hir::Expr::Block { stmts, expr, .. } => {
+            for stmt in stmts {
+                match stmt {
+                    hir::Stmt::Let {
+                        init, else_block, ..
+                    } => {
+                        init.iter().for_each(&mut visit);
+                        else_block.iter().for_each(|e| visit(e));
+                    },
+                    hir::Stmt::Expr(e) => visit(e),
+                }
+            }
+            expr.iter().for_each(|e| visit(e));
+        },
+        hir::Expr::If {
+            cond,
+            then_expr,
+            else_expr,
+            ..
+        } => {
+            visit(cond);
+            visit(then_expr);
+            else_expr.iter().for_each(|e| visit(e));
+        },
+        hir::Expr::Match {
+            scrutinee, arms, ..
+        } => {
+            visit(scrutinee);
+            for arm in arms {
+                arm.guard.iter().for_each(&mut visit);
+                visit(&arm.body);
+            }
+        },
+        hir::Expr::Assign { place, value, .. } => {
+            visit(place);
+            visit(value);
+        },
+        hir::Expr::Return { value, .. } | hir::Expr::Break { value, .. } => {
+            value.iter().for_each(|e| visit(e));
+        },
+        hir::Expr::Closure { body, .. } => {
+            out.push(expr);
+            collect_closures(body, out);
+        },
+        hir::Expr::Tuple { elems, .. }
+        | hir::Expr::Array { elems, .. }
+        | hir::Expr::Struct { fields: elems, .. } => elems.iter().for_each(visit),
+    }
 }
 
 pub fn lower_expr(builder: &mut Builder, expr: &hir::Expr) -> Place {
@@ -29,6 +275,10 @@ pub fn lower_expr(builder: &mut Builder, expr: &hir::Expr) -> Place {
         } => {
             for stmt in stmts {
                 lower_stmt(builder, stmt);
+                // Nothing after a diverging statement can run
+                if matches!(stmt, hir::Stmt::Expr(e) if e.diverges()) {
+                    return Place::Local(LocalId(0));
+                }
             }
             if let Some(expr) = trailing_expr {
                 lower_expr(builder, expr)
@@ -37,28 +287,8 @@ pub fn lower_expr(builder: &mut Builder, expr: &hir::Expr) -> Place {
             }
         },
 
-        hir::Expr::Literal { lit, ty } => {
-            let constant = match lit {
-                hir::Literal::Int(n) => ConstantKind::Int(*n),
-                hir::Literal::Float(f) => ConstantKind::Float(*f),
-                hir::Literal::String(s) => ConstantKind::String(*s),
-                hir::Literal::Bool(b) => ConstantKind::Bool(*b),
-                hir::Literal::Char(c) => ConstantKind::Int(*c as i64),
-                hir::Literal::Unit => ConstantKind::Unit,
-            };
-
-            let temp = builder.add_local(ty.clone(), None);
-            let place = Place::Local(temp);
-
-            builder.assign(
-                place.clone(),
-                Rvalue::Use(Operand::Constant(Constant {
-                    ty: ty.clone(),
-                    kind: constant,
-                })),
-            );
-
-            place
+        hir::Expr::Literal { lit, ty, .. } => {
+            builder.push_assign(Rvalue::Use(literal_operand(lit, ty)), ty.clone())
         },
 
         hir::Expr::Binary {
@@ -66,34 +296,50 @@ pub fn lower_expr(builder: &mut Builder, expr: &hir::Expr) -> Place {
             left,
             right,
             ty,
+            ..
         } => {
-            let left_place = lower_expr(builder, left);
-            let right_place = lower_expr(builder, right);
+            let left_op = builder.operand(left);
+            let right_op = builder.operand(right);
 
-            let left_op = place_to_operand(left_place);
-            let right_op = place_to_operand(right_place);
-
-            let temp = builder.add_local(ty.clone(), None);
-            let place = Place::Local(temp);
+            // `String + String` is concatenation, provided by the runtime
+            if matches!(op, hir::BinOp::Add) && *ty == Type::String {
+                let func = Operand::Constant(Constant {
+                    ty: Type::Fn(vec![Type::String, Type::String], Box::new(Type::String)),
+                    kind: ConstantKind::Fn(Symbol::intern("str_concat")),
+                });
+                return emit_call(builder, func, vec![left_op, right_op], ty);
+            }
 
-            builder.assign(
-                place.clone(),
+            builder.push_assign(
                 Rvalue::BinaryOp(convert_binop(*op), Box::new(left_op), Box::new(right_op)),
-            );
-
-            place
+                ty.clone(),
+            )
         },
 
-        hir::Expr::Var { def_id: _, ty: _ } => Place::Local(LocalId(0)),
+        hir::Expr::Var { def_id, ty, .. } => match builder.var_local(*def_id) {
+            Some(local) => Place::Local(local),
+            // Anything that is not a local variable is a function item
+            None => {
+                let temp = builder.add_local(ty.clone(), None);
+                builder.assign(
+                    Place::Local(temp),
+                    Rvalue::Use(Operand::Constant(Constant {
+                        ty: ty.clone(),
+                        kind: ConstantKind::FnDef(*def_id),
+                    })),
+                );
+                Place::Local(temp)
+            },
+        },
 
         hir::Expr::If {
             cond,
             then_expr,
             else_expr,
             ty,
+            ..
         } => {
-            let cond_place = lower_expr(builder, cond);
-            let cond_op = place_to_operand(cond_place);
+            let cond_op = builder.operand(cond);
 
             let then_block = builder.new_block();
             let else_block = builder.new_block();
@@ -116,45 +362,442 @@ pub fn lower_expr(builder: &mut Builder, expr: &hir::Expr) -> Place {
             builder.terminator(Terminator::Goto { target: join_block });
 
             builder.set_current_block(join_block);
-            let res_temp = builder.add_local(ty.clone(), None);
-            Place::Local(res_temp)
+            builder.temp(ty.clone())
         },
 
-        hir::Expr::Call { func: _, args, ty } => {
-            eprintln!("DEBUG: Handling call expression with ty={:?}", ty);
+        hir::Expr::Call { func, args, ty, .. } => {
+            // A closure called where it is written receives its environment
+            // as the first argument
             let mut arg_operands = Vec::new();
-            for arg in args {
-                let place = lower_expr(builder, arg);
-                arg_operands.push(place_to_operand(place));
+            if let hir::Expr::Closure {
+                def_id, captures, ..
+            } = func.as_ref()
+            {
+                let env = lower_closure_env(builder, *def_id, captures);
+                arg_operands.push(Operand::Move(env));
             }
+            arg_operands.extend(args.iter().map(|arg| builder.operand(arg)));
 
-            let result_temp = builder.add_local(ty.clone(), None);
-
-            builder.terminator(Terminator::Call {
-                func: Operand::Constant(Constant {
+            let func = match func.as_ref() {
+                hir::Expr::Intrinsic { name, ty, .. } => Operand::Constant(Constant {
+                    ty: ty.clone(),
+                    kind: ConstantKind::Fn(*name),
+                }),
+                hir::Expr::Closure { def_id, .. } => Operand::Constant(Constant {
+                    ty: func.ty(),
+                    kind: ConstantKind::FnDef(*def_id),
+                }),
+                _ => Operand::Constant(Constant {
                     ty: Type::Unit,
                     kind: ConstantKind::Int(0),
                 }),
-                args: arg_operands,
-                destination: Place::Local(result_temp),
-                target: None,
-                cleanup: None,
+            };
+
+            emit_call(builder, func, arg_operands, ty)
+        },
+
+        hir::Expr::MethodCall {
+            receiver,
+            method,
+            args,
+            ty,
+            ..
+        } => {
+            // The receiver is passed as the method's first (`self`) argument
+            let mut arg_operands = vec![builder.operand(receiver)];
+            arg_operands.extend(args.iter().map(|arg| builder.operand(arg)));
+
+            let param_tys = std::iter::once(receiver.ty())
+                .chain(args.iter().map(hir::Expr::ty))
+                .collect();
+            let func = Operand::Constant(Constant {
+                ty: Type::Fn(param_tys, Box::new(ty.clone())),
+                kind: ConstantKind::FnDef(*method),
             });
 
-            Place::Local(result_temp)
+            emit_call(builder, func, arg_operands, ty)
+        },
+
+        hir::Expr::Match {
+            scrutinee,
+            arms,
+            ty,
+            ..
+        } => lower_match(builder, scrutinee, arms, ty),
+
+        hir::Expr::Cast {
+            expr: inner, ty, ..
+        } => {
+            let src_ty = inner.ty();
+            let src = builder.operand(inner);
+            builder.push_assign(
+                Rvalue::Cast(cast_kind(&src_ty, ty), src, ty.clone()),
+                ty.clone(),
+            )
+        },
+
+        hir::Expr::Return { value, .. } => {
+            if let Some(value) = value {
+                let value = builder.operand(value);
+                builder.assign(Place::Local(LocalId(0)), Rvalue::Use(value));
+            }
+            builder.terminator(Terminator::Return);
+
+            // Code following the return is unreachable; give it a fresh block
+            let dead = builder.new_block();
+            builder.set_current_block(dead);
+            Place::Local(LocalId(0))
+        },
+
+        hir::Expr::Unary {
+            op: hir::UnOp::Deref,
+            expr: inner,
+            ..
+        } => {
+            // As a place, `*r` reads or writes the referenced value
+            let base = lower_expr(builder, inner);
+            Place::Projection(Box::new(base), Projection::Deref)
+        },
+
+        hir::Expr::Field { object, index, .. } => {
+            let mut base = lower_expr(builder, object);
+            // Field access through a reference reads the referenced value
+            if matches!(object.ty(), Type::Ref(_, _)) {
+                base = Place::Projection(Box::new(base), Projection::Deref);
+            }
+            Place::Projection(Box::new(base), Projection::Field(*index))
         },
 
+        hir::Expr::Index { object, index, .. } => {
+            let base = lower_expr(builder, object);
+
+            // Tuples are indexed by constant position
+            if let (
+                Type::Tuple(_),
+                hir::Expr::Literal {
+                    lit: hir::Literal::Int(n),
+                    ..
+                },
+            ) = (object.ty(), index.as_ref())
+            {
+                return Place::Projection(Box::new(base), Projection::Field(*n as u32));
+            }
+
+            // `Projection::Index` takes the index in a local
+            let index_local = match lower_expr(builder, index) {
+                Place::Local(local) => local,
+                place => {
+                    let temp = builder.add_local(index.ty(), None);
+                    builder.assign(
+                        Place::Local(temp),
+                        Rvalue::Use(place_to_operand(place, &index.ty())),
+                    );
+                    temp
+                },
+            };
+            Place::Projection(Box::new(base), Projection::Index(index_local))
+        },
+
+        hir::Expr::Assign { place, value, .. } => {
+            let value = builder.operand(value);
+            let dest = lower_expr(builder, place);
+            builder.assign(dest, Rvalue::Use(value));
+            Place::Local(LocalId(0))
+        },
+
+        hir::Expr::Tuple { elems, ty, .. } => {
+            lower_aggregate(builder, AggregateKind::Tuple, elems, ty)
+        },
+
+        hir::Expr::Array { elems, ty, .. } => {
+            let elem_ty = match ty {
+                Type::Array(elem_ty, _) => (**elem_ty).clone(),
+                _ => Type::Unit,
+            };
+            lower_aggregate(builder, AggregateKind::Array(elem_ty), elems, ty)
+        },
+
+        hir::Expr::Struct {
+            def_id, fields, ty, ..
+        } => lower_aggregate(builder, AggregateKind::Struct(*def_id), fields, ty),
+
+        // The closure's value is its environment; the body is lowered
+        // separately by `lower_hir_closures`
+        hir::Expr::Closure {
+            def_id, captures, ..
+        } => lower_closure_env(builder, *def_id, captures),
+
         _ => Place::Local(LocalId(0)),
     }
 }
 
+/// Evaluate `elems` in order and build an aggregate value from them
+fn lower_aggregate(
+    builder: &mut Builder,
+    kind: AggregateKind,
+    elems: &[hir::Expr],
+    ty: &Type,
+) -> Place {
+    let operands = elems.iter().map(|elem| builder.operand(elem)).collect();
+    builder.push_assign(Rvalue::Aggregate(kind, operands), ty.clone())
+}
+
+/// Emit a call terminator and continue lowering in a fresh block
+fn emit_call(builder: &mut Builder, func: Operand, args: Vec<Operand>, ty: &Type) -> Place {
+    let destination = builder.temp(ty.clone());
+    let next = builder.new_block();
+
+    builder.terminator(Terminator::Call {
+        func,
+        args,
+        destination: destination.clone(),
+        target: Some(next),
+        cleanup: None,
+    });
+    builder.set_current_block(next);
+
+    destination
+}
+
+/// Lower a match by switching on the scrutinee's discriminant.
+///
+/// Each discriminant value jumps to the first arm whose pattern can match
+/// it. When no arm is left to try, match checking has proven the earlier
+/// arms exhaustive, so `otherwise` is an unreachable block rather than a
+/// redundant arm. Literal and nested sub-patterns are tested in the arm's
+/// own block; a failed test or guard switches again over the arms after it.
+fn lower_match(
+    builder: &mut Builder,
+    scrutinee: &hir::Expr,
+    arms: &[hir::Arm],
+    ty: &Type,
+) -> Place {
+    let scrut_place = lower_expr(builder, scrutinee);
+
+    // Only variant arms are dispatched on the discriminant
+    let has_variant_arm = arms
+        .iter()
+        .any(|arm| matches!(arm.pat, hir::Pattern::Variant { .. }));
+    let discr = has_variant_arm.then(|| {
+        let discr = builder.add_local(Type::Int, None);
+        builder.assign(
+            Place::Local(discr),
+            Rvalue::Discriminant(scrut_place.clone()),
+        );
+        discr
+    });
+
+    let result = builder.add_local(ty.clone(), None);
+    let join_block = builder.new_block();
+
+    let arm_blocks: Vec<BlockId> = arms.iter().map(|_| builder.new_block()).collect();
+    lower_match_dispatch(builder, discr, arms, &arm_blocks, 0);
+
+    for (i, (arm, &block)) in arms.iter().zip(&arm_blocks).enumerate() {
+        builder.set_current_block(block);
+
+        // The dispatch switch has already checked a variant arm's discriminant
+        let has_tests = match &arm.pat {
+            hir::Pattern::Variant { fields, .. } => fields.iter().any(pattern_has_tests),
+            pat => pattern_has_tests(pat),
+        };
+        let next_arm = (has_tests || arm.guard.is_some()).then(|| builder.new_block());
+
+        if let Some(next_arm) = next_arm {
+            match &arm.pat {
+                hir::Pattern::Variant { fields, .. } => {
+                    lower_field_tests(builder, fields, &scrut_place, next_arm)
+                },
+                pat => lower_pattern_test(builder, pat, scrut_place.clone(), next_arm),
+            }
+        }
+        bind_pattern(builder, &arm.pat, scrut_place.clone());
+
+        if let (Some(guard), Some(next_arm)) = (&arm.guard, next_arm) {
+            let cond = builder.operand(guard);
+            let body_block = builder.new_block();
+            builder.terminator(Terminator::If {
+                cond,
+                then_block: body_block,
+                else_block: next_arm,
+            });
+            builder.set_current_block(body_block);
+        }
+
+        let value = builder.operand(&arm.body);
+        builder.assign(Place::Local(result), Rvalue::Use(value));
+        builder.terminator(Terminator::Goto { target: join_block });
+
+        if let Some(next_arm) = next_arm {
+            builder.set_current_block(next_arm);
+            lower_match_dispatch(builder, discr, arms, &arm_blocks, i + 1);
+        }
+    }
+
+    builder.set_current_block(join_block);
+    Place::Local(result)
+}
+
+/// Terminate the current block with a jump to the first of `arms[from..]`
+/// that can match, switching on `discr` over the variant arms
+///
+/// The first arm that is not a variant becomes `otherwise`, since only its
+/// own tests can rule it out; variant arms after it can never be reached
+/// first and get no target of their own.
+fn lower_match_dispatch(
+    builder: &mut Builder,
+    discr: Option<LocalId>,
+    arms: &[hir::Arm],
+    arm_blocks: &[BlockId],
+    from: usize,
+) {
+    let mut targets: Vec<(u128, BlockId)> = Vec::new();
+    let mut otherwise = None;
+    for (arm, &block) in arms.iter().zip(arm_blocks).skip(from) {
+        match &arm.pat {
+            hir::Pattern::Variant { index, .. } => {
+                let value = *index as u128;
+                // Later arms for an already-covered variant are only reached
+                // through a failed test or guard
+                if !targets.iter().any(|(v, _)| *v == value) {
+                    targets.push((value, block));
+                }
+            },
+            _ => {
+                otherwise = Some(block);
+                break;
+            },
+        }
+    }
+
+    let (otherwise, unreachable_block) = match otherwise {
+        Some(block) => (block, None),
+        None => {
+            let block = builder.new_block();
+            (block, Some(block))
+        },
+    };
+
+    match discr {
+        Some(discr) if !targets.is_empty() => builder.terminator(Terminator::SwitchInt {
+            discr: Operand::Copy(Place::Local(discr)),
+            switch_ty: Type::Int,
+            targets,
+            otherwise,
+        }),
+        _ => builder.terminator(Terminator::Goto { target: otherwise }),
+    }
+
+    if let Some(block) = unreachable_block {
+        builder.set_current_block(block);
+        builder.terminator(Terminator::Unreachable);
+    }
+}
+
+/// Whether matching `pat` needs a runtime test, rather than only binding
+fn pattern_has_tests(pat: &hir::Pattern) -> bool {
+    match pat {
+        hir::Pattern::Literal { .. } | hir::Pattern::Variant { .. } => true,
+        hir::Pattern::Tuple { pats } => pats.iter().any(pattern_has_tests),
+        hir::Pattern::Ref { pat, .. } => pattern_has_tests(pat),
+        _ => false,
+    }
+}
+
+/// Test the value in `place` against `pat`, continuing in a fresh block when
+/// it matches and jumping to `fail` as soon as a literal or variant differs
+fn lower_pattern_test(builder: &mut Builder, pat: &hir::Pattern, place: Place, fail: BlockId) {
+    match pat {
+        hir::Pattern::Literal { lit, ty } => {
+            let eq = Rvalue::BinaryOp(
+                BinOp::Eq,
+                Box::new(Operand::Copy(place)),
+                Box::new(literal_operand(lit, ty)),
+            );
+            let cond = builder.push_assign(eq, Type::Bool);
+            let matched = builder.new_block();
+            builder.terminator(Terminator::If {
+                cond: Operand::Copy(cond),
+                then_block: matched,
+                else_block: fail,
+            });
+            builder.set_current_block(matched);
+        },
+        hir::Pattern::Variant { index, fields, .. } => {
+            let discr = builder.push_assign(Rvalue::Discriminant(place.clone()), Type::Int);
+            let matched = builder.new_block();
+            builder.terminator(Terminator::SwitchInt {
+                discr: Operand::Copy(discr),
+                switch_ty: Type::Int,
+                targets: vec![(*index as u128, matched)],
+                otherwise: fail,
+            });
+            builder.set_current_block(matched);
+            lower_field_tests(builder, fields, &place, fail);
+        },
+        hir::Pattern::Tuple { pats } => lower_field_tests(builder, pats, &place, fail),
+        hir::Pattern::Ref { pat, .. } => {
+            let target = Place::Projection(Box::new(place), Projection::Deref);
+            lower_pattern_test(builder, pat, target, fail);
+        },
+        _ => {},
+    }
+}
+
+/// Test each field of the value in `place` against its pattern in turn
+fn lower_field_tests(builder: &mut Builder, pats: &[hir::Pattern], place: &Place, fail: BlockId) {
+    for (i, pat) in pats.iter().enumerate() {
+        let field_place = Place::Projection(Box::new(place.clone()), Projection::Field(i as u32));
+        lower_pattern_test(builder, pat, field_place, fail);
+    }
+}
+
+/// Bind the variables introduced by a pattern to projections of `place`
+fn bind_pattern(builder: &mut Builder, pat: &hir::Pattern, place: Place) {
+    match pat {
+        hir::Pattern::Binding { ty, .. } => {
+            let local = builder.add_local(ty.clone(), Some(pat.clone()));
+            builder.assign(
+                Place::Local(local),
+                Rvalue::Use(place_to_operand(place, ty)),
+            );
+        },
+        hir::Pattern::Variant { fields: pats, .. } | hir::Pattern::Tuple { pats } => {
+            for (i, field) in pats.iter().enumerate() {
+                let field_place =
+                    Place::Projection(Box::new(place.clone()), Projection::Field(i as u32));
+                bind_pattern(builder, field, field_place);
+            }
+        },
+        _ => {},
+    }
+}
+
 pub fn lower_stmt(builder: &mut Builder, stmt: &hir::Stmt) {
     match stmt {
-        hir::Stmt::Let { pat, ty, init } => {
+        hir::Stmt::Let {
+            pat,
+            init: Some(init),
+            else_block: Some(else_block),
+            ..
+        } => lower_let_else(builder, pat, init, else_block),
+        hir::Stmt::Let {
+            pat: pat @ hir::Pattern::Binding { .. },
+            ty,
+            init,
+            ..
+        } => {
             let local = builder.add_local(ty.clone(), Some(pat.clone()));
             if let Some(init_expr) = init {
-                let src_place = lower_expr(builder, init_expr);
-                builder.assign(Place::Local(local), Rvalue::Use(Operand::Move(src_place)));
+                let src = builder.operand(init_expr);
+                builder.assign(Place::Local(local), Rvalue::Use(src));
+            }
+        },
+        hir::Stmt::Let { pat, init, .. } => {
+            // Destructuring binds each variable to a projection of the value
+            if let Some(init_expr) = init {
+                let init_place = lower_expr(builder, init_expr);
+                bind_pattern(builder, pat, init_place);
             }
         },
         hir::Stmt::Expr(expr) => {
@@ -163,8 +806,66 @@ pub fn lower_stmt(builder: &mut Builder, stmt: &hir::Stmt) {
     }
 }
 
-fn place_to_operand(place: Place) -> Operand {
-    Operand::Copy(place)
+/// Lower `let PAT = init else { ... };`
+///
+/// The pattern is tested like a match arm: when it matches, it binds and
+/// continues, anything else runs the diverging else block.
+fn lower_let_else(
+    builder: &mut Builder,
+    pat: &hir::Pattern,
+    init: &hir::Expr,
+    else_block: &hir::Expr,
+) {
+    let init_place = lower_expr(builder, init);
+
+    let else_bb = builder.new_block();
+    lower_pattern_test(builder, pat, init_place.clone(), else_bb);
+    let bind_block = builder.current_block();
+
+    builder.set_current_block(else_bb);
+    lower_expr(builder, else_block);
+    builder.terminator(Terminator::Unreachable);
+
+    builder.set_current_block(bind_block);
+    bind_pattern(builder, pat, init_place);
+}
+
+/// The constant operand for a literal of type `ty`
+fn literal_operand(lit: &hir::Literal, ty: &Type) -> Operand {
+    let kind = match lit {
+        hir::Literal::Int(n) => ConstantKind::Int(*n),
+        hir::Literal::Float(f) => ConstantKind::Float(*f),
+        hir::Literal::String(s) => ConstantKind::String(*s),
+        hir::Literal::Bool(b) => ConstantKind::Bool(*b),
+        hir::Literal::Char(c) => ConstantKind::Int(*c as i64),
+        hir::Literal::Unit => ConstantKind::Unit,
+    };
+    Operand::Constant(Constant {
+        ty: ty.clone(),
+        kind,
+    })
+}
+
+/// Use the value in `place`, of type `ty`, copying it only if `ty` is `Copy`
+fn place_to_operand(place: Place, ty: &Type) -> Operand {
+    if ty.is_copy() {
+        Operand::Copy(place)
+    } else {
+        Operand::Move(place)
+    }
+}
+
+/// Select the MIR cast kind for a cast already validated by semantic analysis
+fn cast_kind(from: &Type, to: &Type) -> CastKind {
+    match (from, to) {
+        (Type::Ptr(_, _) | Type::Ref(_, _), Type::Ptr(_, _)) => CastKind::PtrToPtr,
+        (Type::Ptr(_, _), _) => CastKind::PtrToInt,
+        (_, Type::Ptr(_, _)) => CastKind::IntToPtr,
+        (from, to) if from.is_float() && to.is_float() => CastKind::FloatToFloat,
+        (from, _) if from.is_float() => CastKind::FloatToInt,
+        (_, to) if to.is_float() => CastKind::IntToFloat,
+        _ => CastKind::IntToInt,
+    }
 }
 
 fn convert_binop(op: hir::BinOp) -> BinOp {
@@ -184,3 +885,1057 @@ fn convert_binop(op: hir::BinOp) -> BinOp {
         hir::BinOp::Or => BinOp::BitOr,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Helper to wrap an expression as the body of a function
+    fn fn_with_body(value: hir::Expr) -> hir::FnItem {
+        fn_with_params(Vec::new(), value)
+    }
+
+    /// Helper to wrap an expression in a function taking `params`, given
+    /// as the `DefId`, name and type of each
+    fn fn_with_params(params: Vec<(DefId, &str, Type)>, value: hir::Expr) -> hir::FnItem {
+        let params: Vec<_> = params
+            .into_iter()
+            .map(|(def_id, name, ty)| hir::Param {
+                pat: hir::Pattern::Binding {
+                    name: Symbol::intern(name),
+                    def_id,
+                    ty: ty.clone(),
+                    mutability: false,
+                },
+                ty,
+            })
+            .collect();
+        hir::FnItem {
+            def_id: DefId(0),
+            name: Symbol::intern("test_fn"),
+            generics: hir::GenericParams::default(),
+            ret_type: value.ty(),
+            body: hir::Body {
+                params: params.iter().map(|param| param.pat.clone()).collect(),
+                value,
+            },
+            params,
+            async_kw: false,
+        }
+    }
+
+    /// Helper to collect every cast rvalue in a function
+    fn casts(func: &Function) -> Vec<(CastKind, Type)> {
+        func.blocks
+            .as_slice()
+            .iter()
+            .flat_map(|block| block.statements.iter())
+            .filter_map(|stmt| match stmt {
+                Statement::Assign(_, Rvalue::Cast(kind, _, ty)) => Some((*kind, ty.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_lower_int_to_float_cast() {
+        let cast = hir::Expr::Cast {
+            expr: Box::new(hir::Expr::Literal {
+                lit: hir::Literal::Int(1),
+                ty: Type::Int,
+                span: Span::DUMMY,
+            }),
+            ty: Type::Float,
+            span: Span::DUMMY,
+        };
+
+        let func = lower_hir_function(&fn_with_body(cast));
+
+        assert_eq!(casts(&func), vec![(CastKind::IntToFloat, Type::Float)]);
+    }
+
+    #[test]
+    fn test_lower_ptr_to_ptr_cast() {
+        let byte_ptr = Type::Ptr(Box::new(Type::UInt8), false);
+        let cast = hir::Expr::Cast {
+            expr: Box::new(hir::Expr::Var {
+                def_id: DefId(1),
+                ty: Type::Ptr(Box::new(Type::Int32), true),
+                span: Span::DUMMY,
+            }),
+            ty: byte_ptr.clone(),
+            span: Span::DUMMY,
+        };
+
+        let func = lower_hir_function(&fn_with_body(cast));
+
+        assert_eq!(casts(&func), vec![(CastKind::PtrToPtr, byte_ptr)]);
+    }
+
+    #[test]
+    fn test_lower_enum_match_to_switch_int() {
+        let enum_ty = Type::Adt(DefId(1));
+        let int_lit = |n| hir::Expr::Literal {
+            lit: hir::Literal::Int(n),
+            ty: Type::Int,
+            span: Span::DUMMY,
+        };
+        let arm = |pat, body| hir::Arm {
+            pat,
+            guard: None,
+            body,
+        };
+
+        let matched = hir::Expr::Match {
+            scrutinee: Box::new(hir::Expr::Var {
+                def_id: DefId(4),
+                ty: enum_ty,
+                span: Span::DUMMY,
+            }),
+            arms: vec![
+                arm(
+                    hir::Pattern::Variant {
+                        def_id: DefId(2),
+                        index: 0,
+                        fields: Vec::new(),
+                    },
+                    int_lit(1),
+                ),
+                arm(
+                    hir::Pattern::Variant {
+                        def_id: DefId(3),
+                        index: 1,
+                        fields: vec![hir::Pattern::Binding {
+                            name: Symbol::intern("n"),
+                            def_id: DefId(5),
+                            ty: Type::Int,
+                            mutability: false,
+                        }],
+                    },
+                    int_lit(2),
+                ),
+                arm(hir::Pattern::Wildcard, int_lit(0)),
+            ],
+            ty: Type::Int,
+            span: Span::DUMMY,
+        };
+
+        let func = lower_hir_function(&fn_with_body(matched));
+        let blocks = func.blocks.as_slice();
+
+        let has_discriminant = blocks.iter().any(|block| {
+            block
+                .statements
+                .iter()
+                .any(|stmt| matches!(stmt, Statement::Assign(_, Rvalue::Discriminant(_))))
+        });
+        assert!(has_discriminant);
+
+        let switch = blocks.iter().find_map(|block| match &block.terminator {
+            Terminator::SwitchInt {
+                targets, otherwise, ..
+            } => Some((targets.clone(), *otherwise)),
+            _ => None,
+        });
+        let (targets, otherwise) = switch.expect("match should lower to SwitchInt");
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[0].0, 0);
+        assert_eq!(targets[1].0, 1);
+        assert!(targets.iter().all(|(_, target)| *target != otherwise));
+
+        // The payload binding reads field 0 of the scrutinee in the second arm
+        let payload = &blocks[targets[1].1 .0 as usize];
+        assert!(payload.statements.iter().any(|stmt| matches!(
+            stmt,
+            Statement::Assign(
+                _,
+                Rvalue::Use(Operand::Copy(Place::Projection(_, Projection::Field(0))))
+            )
+        )));
+    }
+
+    #[test]
+    fn test_lower_exhaustive_match_otherwise_unreachable() {
+        let variant = |index| hir::Pattern::Variant {
+            def_id: DefId(2 + index),
+            index,
+            fields: Vec::new(),
+        };
+        let arm = |pat, n| hir::Arm {
+            pat,
+            guard: None,
+            body: hir::Expr::Literal {
+                lit: hir::Literal::Int(n),
+                ty: Type::Int,
+                span: Span::DUMMY,
+            },
+        };
+
+        let matched = hir::Expr::Match {
+            scrutinee: Box::new(hir::Expr::Var {
+                def_id: DefId(4),
+                ty: Type::Adt(DefId(1)),
+                span: Span::DUMMY,
+            }),
+            arms: vec![arm(variant(0), 1), arm(variant(1), 2)],
+            ty: Type::Int,
+            span: Span::DUMMY,
+        };
+
+        let func = lower_hir_function(&fn_with_body(matched));
+        let blocks = func.blocks.as_slice();
+
+        let (targets, otherwise) = blocks
+            .iter()
+            .find_map(|block| match &block.terminator {
+                Terminator::SwitchInt {
+                    targets, otherwise, ..
+                } => Some((targets.clone(), *otherwise)),
+                _ => None,
+            })
+            .expect("match should lower to SwitchInt");
+        assert_eq!(targets.len(), 2);
+        assert_eq!(
+            blocks[otherwise.0 as usize].terminator,
+            Terminator::Unreachable
+        );
+    }
+
+    /// Helper to collect the place and constant of every `==` test
+    fn eq_tests(func: &Function) -> Vec<(Place, ConstantKind)> {
+        func.blocks
+            .as_slice()
+            .iter()
+            .flat_map(|block| block.statements.iter())
+            .filter_map(|stmt| match stmt {
+                Statement::Assign(_, Rvalue::BinaryOp(BinOp::Eq, lhs, rhs)) => {
+                    match (lhs.as_ref(), rhs.as_ref()) {
+                        (Operand::Copy(place), Operand::Constant(constant)) => {
+                            Some((place.clone(), constant.kind.clone()))
+                        },
+                        _ => None,
+                    }
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_lower_int_literal_match_to_eq_tests() {
+        // fn f(x: i32) -> i32 { match x { 0 => 10, 1 => 11, _ => 12 } }
+        let int_lit = |n| hir::Expr::Literal {
+            lit: hir::Literal::Int(n),
+            ty: Type::Int,
+            span: Span::DUMMY,
+        };
+        let arm = |pat, n| hir::Arm {
+            pat,
+            guard: None,
+            body: int_lit(n),
+        };
+        let lit_pat = |n| hir::Pattern::Literal {
+            lit: hir::Literal::Int(n),
+            ty: Type::Int,
+        };
+
+        let matched = hir::Expr::Match {
+            scrutinee: Box::new(hir::Expr::Var {
+                def_id: DefId(1),
+                ty: Type::Int,
+                span: Span::DUMMY,
+            }),
+            arms: vec![
+                arm(lit_pat(0), 10),
+                arm(lit_pat(1), 11),
+                arm(hir::Pattern::Wildcard, 12),
+            ],
+            ty: Type::Int,
+            span: Span::DUMMY,
+        };
+
+        let hir_fn = fn_with_params(vec![(DefId(1), "x", Type::Int)], matched);
+        let func = lower_hir_function(&hir_fn);
+        let x = func.arg_locals[0];
+        let blocks = func.blocks.as_slice();
+
+        // An integer scrutinee has no discriminant to switch on
+        assert!(!blocks.iter().any(|block| block
+            .statements
+            .iter()
+            .any(|stmt| matches!(stmt, Statement::Assign(_, Rvalue::Discriminant(_))))));
+        assert_eq!(
+            eq_tests(&func),
+            vec![
+                (Place::Local(x), ConstantKind::Int(0)),
+                (Place::Local(x), ConstantKind::Int(1)),
+            ]
+        );
+
+        // Each failed test falls through to the next arm, ending at `_`
+        let ifs: Vec<_> = blocks
+            .iter()
+            .filter_map(|block| match block.terminator {
+                Terminator::If { else_block, .. } => Some(else_block),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(ifs.len(), 2);
+        let Terminator::Goto { target: second_arm } = blocks[ifs[0].0 as usize].terminator else {
+            panic!("a failed first test should jump to the second arm");
+        };
+        assert!(matches!(
+            blocks[second_arm.0 as usize].terminator,
+            Terminator::If { .. }
+        ));
+        let Terminator::Goto { target: wildcard } = blocks[ifs[1].0 as usize].terminator else {
+            panic!("a failed second test should jump to the wildcard arm");
+        };
+        assert!(blocks[wildcard.0 as usize]
+            .statements
+            .iter()
+            .any(|stmt| matches!(
+                stmt,
+                Statement::Assign(
+                    _,
+                    Rvalue::Use(Operand::Constant(Constant {
+                        kind: ConstantKind::Int(12),
+                        ..
+                    }))
+                )
+            )));
+    }
+
+    #[test]
+    fn test_lower_nested_literal_pattern_falls_through() {
+        // match opt { Some(0) => 1, Some(n) => n, None => 0 }
+        let option_ty = Type::Adt(DefId(1));
+        let int_lit = |n| hir::Expr::Literal {
+            lit: hir::Literal::Int(n),
+            ty: Type::Int,
+            span: Span::DUMMY,
+        };
+        let some = |field| hir::Pattern::Variant {
+            def_id: DefId(2),
+            index: 1,
+            fields: vec![field],
+        };
+        let arm = |pat, body| hir::Arm {
+            pat,
+            guard: None,
+            body,
+        };
+
+        let matched = hir::Expr::Match {
+            scrutinee: Box::new(hir::Expr::Var {
+                def_id: DefId(4),
+                ty: option_ty.clone(),
+                span: Span::DUMMY,
+            }),
+            arms: vec![
+                arm(
+                    some(hir::Pattern::Literal {
+                        lit: hir::Literal::Int(0),
+                        ty: Type::Int,
+                    }),
+                    int_lit(1),
+                ),
+                arm(
+                    some(hir::Pattern::Binding {
+                        name: Symbol::intern("n"),
+                        def_id: DefId(5),
+                        ty: Type::Int,
+                        mutability: false,
+                    }),
+                    hir::Expr::Var {
+                        def_id: DefId(5),
+                        ty: Type::Int,
+                        span: Span::DUMMY,
+                    },
+                ),
+                arm(
+                    hir::Pattern::Variant {
+                        def_id: DefId(3),
+                        index: 0,
+                        fields: Vec::new(),
+                    },
+                    int_lit(0),
+                ),
+            ],
+            ty: Type::Int,
+            span: Span::DUMMY,
+        };
+
+        let hir_fn = fn_with_params(vec![(DefId(4), "opt", option_ty)], matched);
+        let func = lower_hir_function(&hir_fn);
+        let opt = Place::Local(func.arg_locals[0]);
+        let blocks = func.blocks.as_slice();
+
+        let switches: Vec<_> = blocks
+            .iter()
+            .filter_map(|block| match &block.terminator {
+                Terminator::SwitchInt { targets, .. } => Some(targets.clone()),
+                _ => None,
+            })
+            .collect();
+        let [first, second] = switches.as_slice() else {
+            panic!("expected the initial switch and one after the failed test");
+        };
+        assert_eq!(
+            first.iter().map(|(v, _)| *v).collect::<Vec<_>>(),
+            vec![1, 0]
+        );
+
+        // `Some(0)` tests the payload before its body runs
+        let payload = Place::Projection(Box::new(opt), Projection::Field(0));
+        assert_eq!(
+            eq_tests(&func),
+            vec![(payload.clone(), ConstantKind::Int(0))]
+        );
+        let Terminator::If { else_block, .. } = blocks[first[0].1 .0 as usize].terminator else {
+            panic!("the Some(0) arm should test its payload");
+        };
+
+        // A non-zero payload switches again and reaches `Some(n)`, which binds it
+        assert!(matches!(
+            blocks[else_block.0 as usize].terminator,
+            Terminator::SwitchInt { .. }
+        ));
+        assert_eq!(second[0].0, 1);
+        assert_ne!(second[0].1, first[0].1);
+        assert_eq!(second[1], first[1]);
+        assert!(blocks[second[0].1 .0 as usize]
+            .statements
+            .iter()
+            .any(|stmt| matches!(
+                stmt,
+                Statement::Assign(_, Rvalue::Use(Operand::Copy(place))) if *place == payload
+            )));
+    }
+
+    #[test]
+    fn test_lower_let_else_to_switch_int() {
+        let let_else = hir::Stmt::Let {
+            pat: hir::Pattern::Variant {
+                def_id: DefId(2),
+                index: 1,
+                fields: vec![hir::Pattern::Binding {
+                    name: Symbol::intern("x"),
+                    def_id: DefId(5),
+                    ty: Type::Int,
+                    mutability: false,
+                }],
+            },
+            ty: Type::Adt(DefId(1)),
+            init: Some(hir::Expr::Var {
+                def_id: DefId(3),
+                ty: Type::Adt(DefId(1)),
+                span: Span::DUMMY,
+            }),
+            else_block: Some(Box::new(hir::Expr::Return {
+                value: None,
+                span: Span::DUMMY,
+            })),
+            span: Span::DUMMY,
+        };
+        let body = hir::Expr::Block {
+            stmts: vec![let_else],
+            expr: None,
+            ty: Type::Unit,
+            span: Span::DUMMY,
+        };
+
+        let func = lower_hir_function(&fn_with_body(body));
+        let blocks = func.blocks.as_slice();
+
+        let (targets, otherwise) = blocks
+            .iter()
+            .find_map(|block| match &block.terminator {
+                Terminator::SwitchInt {
+                    targets, otherwise, ..
+                } => Some((targets.clone(), *otherwise)),
+                _ => None,
+            })
+            .expect("let-else should lower to SwitchInt");
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].0, 1);
+        // The else block leaves the function through its `return`
+        assert_eq!(blocks[otherwise.0 as usize].terminator, Terminator::Return);
+    }
+
+    #[test]
+    fn test_lower_string_concat_to_call() {
+        let string_lit = |s| hir::Expr::Literal {
+            lit: hir::Literal::String(Symbol::intern(s)),
+            ty: Type::String,
+            span: Span::DUMMY,
+        };
+        let concat = hir::Expr::Binary {
+            op: hir::BinOp::Add,
+            left: Box::new(string_lit("a")),
+            right: Box::new(string_lit("b")),
+            ty: Type::String,
+            span: Span::DUMMY,
+        };
+
+        let func = lower_hir_function(&fn_with_body(concat));
+
+        let call = func
+            .blocks
+            .as_slice()
+            .iter()
+            .find_map(|block| match &block.terminator {
+                Terminator::Call {
+                    func, args, target, ..
+                } => Some((func.clone(), args.len(), *target)),
+                _ => None,
+            });
+        let (callee, arg_count, target) = call.expect("concat should lower to a call");
+        match callee {
+            Operand::Constant(Constant {
+                kind: ConstantKind::Fn(name),
+                ..
+            }) => assert_eq!(name.as_str(), "str_concat"),
+            other => panic!("expected function constant, got {:?}", other),
+        }
+        assert_eq!(arg_count, 2);
+        assert!(target.is_some());
+
+        let has_binary_op = func.blocks.as_slice().iter().any(|block| {
+            block
+                .statements
+                .iter()
+                .any(|stmt| matches!(stmt, Statement::Assign(_, Rvalue::BinaryOp(..))))
+        });
+        assert!(!has_binary_op);
+    }
+
+    #[test]
+    fn test_lower_intrinsic_call() {
+        let println_ty = Type::Fn(vec![Type::String], Box::new(Type::Unit));
+        let call = hir::Expr::Call {
+            func: Box::new(hir::Expr::Intrinsic {
+                name: Symbol::intern("println"),
+                ty: println_ty,
+                span: Span::DUMMY,
+            }),
+            args: vec![hir::Expr::Literal {
+                lit: hir::Literal::String(Symbol::intern("hi")),
+                ty: Type::String,
+                span: Span::DUMMY,
+            }],
+            ty: Type::Unit,
+            span: Span::DUMMY,
+        };
+
+        let func = lower_hir_function(&fn_with_body(call));
+
+        let callee = func
+            .blocks
+            .as_slice()
+            .iter()
+            .find_map(|block| match &block.terminator {
+                Terminator::Call {
+                    func:
+                        Operand::Constant(Constant {
+                            kind: ConstantKind::Fn(name),
+                            ..
+                        }),
+                    ..
+                } => Some(*name),
+                _ => None,
+            });
+        assert_eq!(callee, Some(Symbol::intern("println")));
+    }
+
+    #[test]
+    fn test_lower_method_call_passes_receiver_first() {
+        let int_lit = |n| hir::Expr::Literal {
+            lit: hir::Literal::Int(n),
+            ty: Type::Int,
+            span: Span::DUMMY,
+        };
+        let call = hir::Expr::MethodCall {
+            receiver: Box::new(int_lit(7)),
+            method: DefId(5),
+            args: vec![int_lit(1), int_lit(2)],
+            ty: Type::Int,
+            span: Span::DUMMY,
+        };
+
+        let func = lower_hir_function(&fn_with_body(call));
+        let blocks = func.blocks.as_slice();
+
+        let (callee, args) = blocks
+            .iter()
+            .find_map(|block| match &block.terminator {
+                Terminator::Call { func, args, .. } => Some((func.clone(), args.clone())),
+                _ => None,
+            })
+            .expect("method call should lower to a call");
+        assert!(matches!(
+            callee,
+            Operand::Constant(Constant {
+                kind: ConstantKind::FnDef(DefId(5)),
+                ..
+            })
+        ));
+        assert_eq!(args.len(), 3);
+
+        // The first argument is the place holding the receiver's value
+        let Operand::Copy(receiver) = &args[0] else {
+            panic!("expected receiver operand, got {:?}", args[0]);
+        };
+        let receiver_value = blocks
+            .iter()
+            .flat_map(|block| block.statements.iter())
+            .find_map(|stmt| match stmt {
+                Statement::Assign(place, Rvalue::Use(Operand::Constant(c)))
+                    if place == receiver =>
+                {
+                    Some(c.kind.clone())
+                },
+                _ => None,
+            });
+        assert_eq!(receiver_value, Some(ConstantKind::Int(7)));
+    }
+
+    #[test]
+    fn test_lower_operand_moves_non_copy_values() {
+        let var = |ty| hir::Expr::Var {
+            def_id: DefId(1),
+            ty,
+            span: Span::DUMMY,
+        };
+
+        let mut builder = Builder::new(Symbol::intern("test_fn"), Type::Unit);
+        let entry = builder.new_block();
+        builder.set_current_block(entry);
+
+        assert!(matches!(
+            builder.operand(&var(Type::String)),
+            Operand::Move(_)
+        ));
+        assert!(matches!(builder.operand(&var(Type::Int)), Operand::Copy(_)));
+        assert!(matches!(
+            builder.operand(&var(Type::Tuple(vec![Type::Int, Type::String]))),
+            Operand::Move(_)
+        ));
+    }
+
+    /// Helper to lower a single expression in a fresh builder
+    fn lower_single(expr: &hir::Expr) -> Place {
+        let mut builder = Builder::new(Symbol::intern("test_fn"), expr.ty());
+        let entry = builder.new_block();
+        builder.set_current_block(entry);
+        lower_expr(&mut builder, expr)
+    }
+
+    #[test]
+    fn test_lower_field_to_projection() {
+        let point = Type::Adt(DefId(1));
+        let field = hir::Expr::Field {
+            object: Box::new(hir::Expr::Var {
+                def_id: DefId(2),
+                ty: point,
+                span: Span::DUMMY,
+            }),
+            index: 1,
+            ty: Type::Int,
+            span: Span::DUMMY,
+        };
+
+        let place = lower_single(&field);
+
+        assert!(matches!(place, Place::Projection(_, Projection::Field(1))));
+    }
+
+    #[test]
+    fn test_lower_index_assignment_to_projection() {
+        let array = hir::Expr::Var {
+            def_id: DefId(1),
+            ty: Type::Array(Box::new(Type::Int), 4),
+            span: Span::DUMMY,
+        };
+        let index = hir::Expr::Var {
+            def_id: DefId(2),
+            ty: Type::UInt,
+            span: Span::DUMMY,
+        };
+        let assign = hir::Expr::Assign {
+            place: Box::new(hir::Expr::Index {
+                object: Box::new(array),
+                index: Box::new(index),
+                ty: Type::Int,
+                span: Span::DUMMY,
+            }),
+            value: Box::new(hir::Expr::Literal {
+                lit: hir::Literal::Int(9),
+                ty: Type::Int,
+                span: Span::DUMMY,
+            }),
+            span: Span::DUMMY,
+        };
+
+        let hir_fn = fn_with_params(
+            vec![
+                (DefId(1), "a", Type::Array(Box::new(Type::Int), 4)),
+                (DefId(2), "i", Type::UInt),
+            ],
+            assign,
+        );
+        let func = lower_hir_function(&hir_fn);
+        let (a, i) = (func.arg_locals[0], func.arg_locals[1]);
+
+        let through_index = func
+            .blocks
+            .as_slice()
+            .iter()
+            .flat_map(|block| block.statements.iter())
+            .any(|stmt| match stmt {
+                Statement::Assign(Place::Projection(base, Projection::Index(index)), _) => {
+                    **base == Place::Local(a) && *index == i
+                },
+                _ => false,
+            });
+        assert!(through_index, "`a[i] = 9` should assign through `a[i]`");
+    }
+
+    #[test]
+    fn test_lower_var_to_its_let_local() {
+        // { let x = 7; x }
+        let body = hir::Expr::Block {
+            stmts: vec![hir::Stmt::Let {
+                pat: hir::Pattern::Binding {
+                    name: Symbol::intern("x"),
+                    def_id: DefId(1),
+                    ty: Type::Int,
+                    mutability: false,
+                },
+                ty: Type::Int,
+                init: Some(hir::Expr::Literal {
+                    lit: hir::Literal::Int(7),
+                    ty: Type::Int,
+                    span: Span::DUMMY,
+                }),
+                else_block: None,
+                span: Span::DUMMY,
+            }],
+            expr: Some(Box::new(hir::Expr::Var {
+                def_id: DefId(1),
+                ty: Type::Int,
+                span: Span::DUMMY,
+            })),
+            ty: Type::Int,
+            span: Span::DUMMY,
+        };
+
+        let mut builder = Builder::new(Symbol::intern("test_fn"), Type::Int);
+        let entry = builder.new_block();
+        builder.set_current_block(entry);
+        let place = lower_expr(&mut builder, &body);
+        let func = builder.build();
+
+        let x = func
+            .locals
+            .iter_enumerated()
+            .find(|(_, local)| local.name == Some(Symbol::intern("x")))
+            .map(|(id, _)| id)
+            .expect("`x` should have a local");
+        assert_eq!(place, Place::Local(x));
+    }
+
+    #[test]
+    fn test_lower_deref_assignment_to_projection() {
+        let r = hir::Expr::Var {
+            def_id: DefId(1),
+            ty: Type::Ref(Box::new(Type::Int), true),
+            span: Span::DUMMY,
+        };
+        let assign = hir::Expr::Assign {
+            place: Box::new(hir::Expr::Unary {
+                op: hir::UnOp::Deref,
+                expr: Box::new(r),
+                ty: Type::Int,
+                span: Span::DUMMY,
+            }),
+            value: Box::new(hir::Expr::Literal {
+                lit: hir::Literal::Int(5),
+                ty: Type::Int,
+                span: Span::DUMMY,
+            }),
+            span: Span::DUMMY,
+        };
+
+        let hir_fn = fn_with_params(
+            vec![(DefId(1), "r", Type::Ref(Box::new(Type::Int), true))],
+            assign,
+        );
+        let func = lower_hir_function(&hir_fn);
+        let r_local = func.arg_locals[0];
+        assert_eq!(func.locals[r_local].name, Some(Symbol::intern("r")));
+
+        let dest = func
+            .blocks
+            .as_slice()
+            .iter()
+            .flat_map(|block| block.statements.iter())
+            .find_map(|stmt| match stmt {
+                Statement::Assign(place @ Place::Projection(_, Projection::Deref), _) => {
+                    Some(place.clone())
+                },
+                _ => None,
+            })
+            .expect("`*r = 5` should assign through a deref projection");
+        assert_eq!(
+            dest,
+            Place::Projection(Box::new(Place::Local(r_local)), Projection::Deref)
+        );
+    }
+
+    #[test]
+    fn test_lower_early_return() {
+        // fn f() -> i32 { return 1; 2 }
+        let int_lit = |n| hir::Expr::Literal {
+            lit: hir::Literal::Int(n),
+            ty: Type::Int32,
+            span: Span::DUMMY,
+        };
+        let body = hir::Expr::Block {
+            stmts: vec![hir::Stmt::Expr(hir::Expr::Return {
+                value: Some(Box::new(int_lit(1))),
+                span: Span::DUMMY,
+            })],
+            expr: Some(Box::new(int_lit(2))),
+            ty: Type::Int32,
+            span: Span::DUMMY,
+        };
+
+        let func = lower_hir_function(&fn_with_body(body));
+        let blocks = func.blocks.as_slice();
+
+        let returns = blocks
+            .iter()
+            .filter(|block| block.terminator == Terminator::Return)
+            .count();
+        assert_eq!(returns, 1);
+
+        // The trailing `2` is never lowered
+        let constants: Vec<_> = blocks
+            .iter()
+            .flat_map(|block| block.statements.iter())
+            .filter_map(|stmt| match stmt {
+                Statement::Assign(_, Rvalue::Use(Operand::Constant(c))) => Some(c.kind.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(constants, vec![ConstantKind::Int(1)]);
+
+        // The block after the return is terminated as unreachable
+        assert!(blocks
+            .iter()
+            .any(|block| block.terminator == Terminator::Unreachable));
+    }
+
+    /// Helper to find the single aggregate rvalue in a function
+    fn aggregate(func: &Function) -> (AggregateKind, usize) {
+        let aggregates: Vec<_> = func
+            .blocks
+            .as_slice()
+            .iter()
+            .flat_map(|block| block.statements.iter())
+            .filter_map(|stmt| match stmt {
+                Statement::Assign(_, Rvalue::Aggregate(kind, ops)) => {
+                    Some((kind.clone(), ops.len()))
+                },
+                _ => None,
+            })
+            .collect();
+        assert_eq!(aggregates.len(), 1);
+        aggregates.into_iter().next().unwrap()
+    }
+
+    #[test]
+    fn test_lower_tuple_to_aggregate() {
+        let tuple = hir::Expr::Tuple {
+            elems: vec![
+                hir::Expr::Literal {
+                    lit: hir::Literal::Int(1),
+                    ty: Type::Int,
+                    span: Span::DUMMY,
+                },
+                hir::Expr::Literal {
+                    lit: hir::Literal::Bool(true),
+                    ty: Type::Bool,
+                    span: Span::DUMMY,
+                },
+            ],
+            ty: Type::Tuple(vec![Type::Int, Type::Bool]),
+            span: Span::DUMMY,
+        };
+
+        let func = lower_hir_function(&fn_with_body(tuple));
+
+        let (kind, count) = aggregate(&func);
+        assert!(matches!(kind, AggregateKind::Tuple));
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_lower_array_to_aggregate() {
+        let int_lit = |n| hir::Expr::Literal {
+            lit: hir::Literal::Int(n),
+            ty: Type::Int,
+            span: Span::DUMMY,
+        };
+        let array = hir::Expr::Array {
+            elems: vec![int_lit(1), int_lit(2), int_lit(3)],
+            ty: Type::Array(Box::new(Type::Int), 3),
+            span: Span::DUMMY,
+        };
+
+        let func = lower_hir_function(&fn_with_body(array));
+
+        let (kind, count) = aggregate(&func);
+        assert!(matches!(kind, AggregateKind::Array(Type::Int)));
+        assert_eq!(count, 3);
+    }
+
+    /// `|x| x + captured` as closure `DefId(7)`, capturing `captured` by
+    /// reference
+    fn add_captured_closure() -> hir::Expr {
+        let var = |id| hir::Expr::Var {
+            def_id: DefId(id),
+            ty: Type::Int,
+            span: Span::DUMMY,
+        };
+        hir::Expr::Closure {
+            def_id: DefId(7),
+            params: vec![hir::Pattern::Binding {
+                name: Symbol::intern("x"),
+                def_id: DefId(5),
+                ty: Type::Int,
+                mutability: false,
+            }],
+            body: Box::new(hir::Expr::Binary {
+                op: hir::BinOp::Add,
+                left: Box::new(var(2)),
+                right: Box::new(var(1)),
+                ty: Type::Int,
+                span: Span::DUMMY,
+            }),
+            captures: vec![hir::Capture {
+                def_id: DefId(1),
+                name: Symbol::intern("captured"),
+                ty: Type::Int,
+                mode: hir::CaptureMode::ByRef,
+            }],
+            ty: Type::Fn(vec![Type::Int], Box::new(Type::Int)),
+            span: Span::DUMMY,
+        }
+    }
+
+    #[test]
+    fn test_lower_closure_to_env_and_fn() {
+        let hir_fn = fn_with_body(add_captured_closure());
+
+        // The closure value is an environment holding the one capture
+        let func = lower_hir_function(&hir_fn);
+        let (kind, count) = aggregate(&func);
+        assert_eq!(kind, AggregateKind::Closure(DefId(7)));
+        assert_eq!(count, 1);
+
+        // The generated function takes the environment, then `x`
+        let closures = lower_hir_closures(&hir_fn);
+        assert_eq!(closures.len(), 1);
+        let closure_fn = &closures[0];
+        assert_eq!(closure_fn.name.as_str(), "closure#7");
+        assert_eq!(closure_fn.arg_count, 2);
+        assert_eq!(closure_fn.return_ty, Type::Int);
+        assert_eq!(
+            closure_fn.locals[closure_fn.arg_locals[0]].ty,
+            Type::Tuple(vec![Type::Ref(Box::new(Type::Int), false)])
+        );
+        assert_eq!(closure_fn.locals[closure_fn.arg_locals[1]].ty, Type::Int);
+    }
+
+    #[test]
+    fn test_lower_closure_call_passes_env() {
+        let call = hir::Expr::Call {
+            func: Box::new(add_captured_closure()),
+            args: vec![hir::Expr::Literal {
+                lit: hir::Literal::Int(1),
+                ty: Type::Int,
+                span: Span::DUMMY,
+            }],
+            ty: Type::Int,
+            span: Span::DUMMY,
+        };
+
+        let func = lower_hir_function(&fn_with_body(call));
+
+        let (callee, args) = func
+            .blocks
+            .as_slice()
+            .iter()
+            .find_map(|block| match &block.terminator {
+                Terminator::Call { func, args, .. } => Some((func.clone(), args.len())),
+                _ => None,
+            })
+            .expect("closure call should lower to a call");
+        assert!(matches!(
+            callee,
+            Operand::Constant(Constant {
+                kind: ConstantKind::FnDef(DefId(7)),
+                ..
+            })
+        ));
+        assert_eq!(args, 2);
+    }
+
+    #[test]
+    fn test_lower_struct_literal_to_aggregate() {
+        // Pair { a: 1, b: true }, with fields in declaration order
+        let pair = hir::Expr::Struct {
+            def_id: DefId(3),
+            fields: vec![
+                hir::Expr::Literal {
+                    lit: hir::Literal::Int(1),
+                    ty: Type::Int,
+                    span: Span::DUMMY,
+                },
+                hir::Expr::Literal {
+                    lit: hir::Literal::Bool(true),
+                    ty: Type::Bool,
+                    span: Span::DUMMY,
+                },
+            ],
+            ty: Type::Adt(DefId(3)),
+            span: Span::DUMMY,
+        };
+
+        let func = lower_hir_function(&fn_with_body(pair));
+
+        let (kind, count) = aggregate(&func);
+        assert_eq!(kind, AggregateKind::Struct(DefId(3)));
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_cast_kind_selection() {
+        assert_eq!(cast_kind(&Type::Float, &Type::Int32), CastKind::FloatToInt);
+        assert_eq!(
+            cast_kind(&Type::Float32, &Type::Float),
+            CastKind::FloatToFloat
+        );
+        assert_eq!(cast_kind(&Type::Bool, &Type::Int), CastKind::IntToInt);
+        assert_eq!(
+            cast_kind(&Type::Ptr(Box::new(Type::Int), false), &Type::UInt),
+            CastKind::PtrToInt
+        );
+        assert_eq!(
+            cast_kind(&Type::UInt, &Type::Ptr(Box::new(Type::Int), false)),
+            CastKind::IntToPtr
+        );
+    }
+}
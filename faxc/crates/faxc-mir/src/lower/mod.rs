@@ -4,4 +4,4 @@
 
 pub mod hir_to_mir;
 
-pub use hir_to_mir::{lower_expr, lower_hir_function, lower_stmt};
+pub use hir_to_mir::{lower_expr, lower_hir_closures, lower_hir_function, lower_stmt};
@@ -0,0 +1,351 @@
+//! GraphViz export of a function's control-flow graph
+//!
+//! [`Function::to_dot`] renders one box per basic block, listing its
+//! statements and terminator in a compact textual MIR syntax, with an edge
+//! for every successor named by the terminator. Branch edges are labelled
+//! `then`/`else`, switch edges with the case value or `otherwise`, and call
+//! edges with `return`/`unwind`.
+
+use std::fmt::{self, Write};
+
+use super::types::*;
+
+impl Function {
+    /// Render the control-flow graph as a GraphViz `digraph`
+    pub fn to_dot(&self) -> String {
+        let mut out = format!("digraph \"{}\" {{\n", escape(self.name.as_str()));
+        out.push_str("    node [shape=box, fontname=monospace];\n");
+        for block in self.blocks.as_slice() {
+            let mut label = format!("{}:\\l", block.id);
+            for stmt in &block.statements {
+                label.push_str(&escape(&stmt.to_string()));
+                label.push_str("\\l");
+            }
+            label.push_str(&escape(&block.terminator.to_string()));
+            label.push_str("\\l");
+            let _ = writeln!(out, "    {} [label=\"{}\"];", block.id, label);
+        }
+        for block in self.blocks.as_slice() {
+            for (target, edge) in successor_edges(&block.terminator) {
+                match edge {
+                    Some(edge) => {
+                        let _ =
+                            writeln!(out, "    {} -> {} [label=\"{}\"];", block.id, target, edge);
+                    },
+                    None => {
+                        let _ = writeln!(out, "    {} -> {};", block.id, target);
+                    },
+                }
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Successors of a terminator with the label of the edge leading to each
+fn successor_edges(terminator: &Terminator) -> Vec<(BlockId, Option<String>)> {
+    match terminator {
+        Terminator::Goto { target } => vec![(*target, None)],
+        Terminator::If {
+            then_block,
+            else_block,
+            ..
+        } => vec![
+            (*then_block, Some("then".to_string())),
+            (*else_block, Some("else".to_string())),
+        ],
+        Terminator::SwitchInt {
+            targets, otherwise, ..
+        } => targets
+            .iter()
+            .map(|(value, target)| (*target, Some(value.to_string())))
+            .chain(std::iter::once((*otherwise, Some("otherwise".to_string()))))
+            .collect(),
+        Terminator::Call {
+            target, cleanup, ..
+        } => target
+            .map(|target| (target, Some("return".to_string())))
+            .into_iter()
+            .chain(cleanup.map(|cleanup| (cleanup, Some("unwind".to_string()))))
+            .collect(),
+        Terminator::Return | Terminator::Unreachable | Terminator::Resume | Terminator::Abort => {
+            Vec::new()
+        },
+    }
+}
+
+/// Escape text for use inside a double-quoted DOT string
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Write `items` separated by commas
+fn comma_separated<T: fmt::Display>(f: &mut fmt::Formatter<'_>, items: &[T]) -> fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for LocalId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "_{}", self.0)
+    }
+}
+
+impl fmt::Display for BlockId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bb{}", self.0)
+    }
+}
+
+impl fmt::Display for Place {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Place::Local(local) => write!(f, "{}", local),
+            Place::Projection(base, Projection::Field(field)) => write!(f, "{}.{}", base, field),
+            Place::Projection(base, Projection::Index(index)) => {
+                write!(f, "{}[{}]", base, index)
+            },
+            Place::Projection(
+                base,
+                Projection::ConstantIndex {
+                    offset, from_end, ..
+                },
+            ) => write!(
+                f,
+                "{}[{}{}]",
+                base,
+                if *from_end { "-" } else { "" },
+                offset
+            ),
+            Place::Projection(base, Projection::Deref) => write!(f, "(*{})", base),
+            Place::Projection(base, Projection::Subslice { from, to, from_end }) => {
+                write!(
+                    f,
+                    "{}[{}..{}{}]",
+                    base,
+                    from,
+                    if *from_end { "-" } else { "" },
+                    to
+                )
+            },
+        }
+    }
+}
+
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ConstantKind::Int(n) => write!(f, "const {}", n),
+            ConstantKind::Float(x) => write!(f, "const {:?}", x),
+            ConstantKind::String(s) => write!(f, "const {:?}", s.as_str()),
+            ConstantKind::Bool(b) => write!(f, "const {}", b),
+            ConstantKind::Unit => write!(f, "const ()"),
+            ConstantKind::Fn(name) => write!(f, "const {}", name),
+            ConstantKind::FnDef(def_id) => write!(f, "const {:?}", def_id),
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Copy(place) => write!(f, "{}", place),
+            Operand::Move(place) => write!(f, "move {}", place),
+            Operand::Constant(constant) => write!(f, "{}", constant),
+        }
+    }
+}
+
+impl fmt::Display for Rvalue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mutability = |m: &Mutability| match m {
+            Mutability::Mutable => "mut ",
+            Mutability::Immutable => "",
+        };
+        match self {
+            Rvalue::Use(operand) => write!(f, "{}", operand),
+            Rvalue::Ref(place, m) => write!(f, "&{}{}", mutability(m), place),
+            Rvalue::AddressOf(place, Mutability::Mutable) => write!(f, "&raw mut {}", place),
+            Rvalue::AddressOf(place, Mutability::Immutable) => write!(f, "&raw const {}", place),
+            Rvalue::UnaryOp(op, operand) => write!(f, "{:?}({})", op, operand),
+            Rvalue::BinaryOp(op, lhs, rhs) => write!(f, "{:?}({}, {})", op, lhs, rhs),
+            Rvalue::CheckedBinaryOp(op, lhs, rhs) => {
+                write!(f, "Checked{:?}({}, {})", op, lhs, rhs)
+            },
+            Rvalue::NullaryOp(op, ty) => write!(f, "{:?}({})", op, ty),
+            Rvalue::Cast(kind, operand, ty) => write!(f, "{} as {} ({:?})", operand, ty, kind),
+            Rvalue::Discriminant(place) => write!(f, "discriminant({})", place),
+            Rvalue::Aggregate(kind, operands) => {
+                let (open, close) = match kind {
+                    AggregateKind::Tuple => ("(", ")"),
+                    AggregateKind::Array(_) => ("[", "]"),
+                    AggregateKind::Struct(def_id) => {
+                        write!(f, "{:?} ", def_id)?;
+                        ("{ ", " }")
+                    },
+                    AggregateKind::Closure(def_id) => {
+                        write!(f, "closure {:?} ", def_id)?;
+                        ("{ ", " }")
+                    },
+                };
+                write!(f, "{}", open)?;
+                comma_separated(f, operands)?;
+                write!(f, "{}", close)
+            },
+        }
+    }
+}
+
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Statement::Assign(place, rvalue) => write!(f, "{} = {}", place, rvalue),
+            Statement::Phi(local, incoming) => {
+                write!(f, "{} = phi [", local)?;
+                for (i, (block, operand)) in incoming.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", block, operand)?;
+                }
+                write!(f, "]")
+            },
+            Statement::StorageLive(local) => write!(f, "StorageLive({})", local),
+            Statement::StorageDead(local) => write!(f, "StorageDead({})", local),
+            Statement::Nop => write!(f, "nop"),
+        }
+    }
+}
+
+impl fmt::Display for Terminator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Terminator::Goto { target } => write!(f, "goto -> {}", target),
+            Terminator::If {
+                cond,
+                then_block,
+                else_block,
+            } => write!(
+                f,
+                "if {} -> [then: {}, else: {}]",
+                cond, then_block, else_block
+            ),
+            Terminator::SwitchInt {
+                discr,
+                targets,
+                otherwise,
+                ..
+            } => {
+                write!(f, "switchInt({}) -> [", discr)?;
+                for (value, target) in targets {
+                    write!(f, "{}: {}, ", value, target)?;
+                }
+                write!(f, "otherwise: {}]", otherwise)
+            },
+            Terminator::Return => write!(f, "return"),
+            Terminator::Unreachable => write!(f, "unreachable"),
+            Terminator::Call {
+                func,
+                args,
+                destination,
+                target,
+                cleanup,
+            } => {
+                write!(f, "{} = {}(", destination, func)?;
+                comma_separated(f, args)?;
+                write!(f, ")")?;
+                match (target, cleanup) {
+                    (Some(target), Some(cleanup)) => {
+                        write!(f, " -> [return: {}, unwind: {}]", target, cleanup)
+                    },
+                    (Some(target), None) => write!(f, " -> {}", target),
+                    (None, Some(cleanup)) => write!(f, " -> [unwind: {}]", cleanup),
+                    (None, None) => Ok(()),
+                }
+            },
+            Terminator::Resume => write!(f, "resume"),
+            Terminator::Abort => write!(f, "abort"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build::Builder;
+    use faxc_sem::Type;
+    use faxc_util::Symbol;
+
+    #[test]
+    fn test_if_function_dot_has_block_per_node_and_branch_edges() {
+        let mut builder = Builder::new(Symbol::intern("pick"), Type::Int);
+        let entry = builder.new_block();
+        let then_block = builder.new_block();
+        let else_block = builder.new_block();
+
+        builder.set_current_block(entry);
+        let cond = builder.add_local(Type::Bool, None);
+        builder.assign(
+            Place::Local(cond),
+            Rvalue::Use(Operand::Constant(Constant {
+                ty: Type::Bool,
+                kind: ConstantKind::Bool(true),
+            })),
+        );
+        builder.terminator(Terminator::If {
+            cond: Operand::Copy(Place::Local(cond)),
+            then_block,
+            else_block,
+        });
+        for (block, value) in [(then_block, 1), (else_block, 2)] {
+            builder.set_current_block(block);
+            builder.assign(
+                Place::Local(LocalId(0)),
+                Rvalue::Use(Operand::Constant(Constant {
+                    ty: Type::Int,
+                    kind: ConstantKind::Int(value),
+                })),
+            );
+            builder.terminator(Terminator::Return);
+        }
+
+        let dot = builder.build().to_dot();
+        assert!(dot.starts_with("digraph \"pick\" {\n"), "{}", dot);
+        let nodes: Vec<&str> = dot
+            .lines()
+            .filter(|line| line.contains("[label=\"bb"))
+            .collect();
+        assert_eq!(nodes.len(), 3, "{}", dot);
+        assert!(
+            nodes[0].contains("bb0:\\l_1 = const true\\lif _1 -> [then: bb1, else: bb2]\\l"),
+            "{}",
+            dot
+        );
+        assert!(
+            nodes[1].contains("bb1:\\l_0 = const 1\\lreturn\\l"),
+            "{}",
+            dot
+        );
+        assert!(
+            dot.contains("    bb0 -> bb1 [label=\"then\"];\n"),
+            "{}",
+            dot
+        );
+        assert!(
+            dot.contains("    bb0 -> bb2 [label=\"else\"];\n"),
+            "{}",
+            dot
+        );
+        assert!(
+            !dot.contains("bb1 -> ") && !dot.contains("bb2 -> "),
+            "{}",
+            dot
+        );
+    }
+}
@@ -3,6 +3,7 @@
 //! MIR-LIR-CODEGEN-DEV-001: Subtask 1 - MIR Development
 //! Core data structures for Mid-level Intermediate Representation
 
+mod dot;
 pub mod types;
 
 pub use types::*;
@@ -142,6 +142,9 @@ pub struct BasicBlock {
 #[derive(Debug, Clone, PartialEq)]
 pub enum Statement {
     Assign(Place, Rvalue),
+    /// Join-point assignment: the local takes the operand paired with the
+    /// predecessor control arrived from
+    Phi(LocalId, Vec<(BlockId, Operand)>),
     StorageLive(LocalId),
     StorageDead(LocalId),
     Nop,
@@ -204,6 +207,10 @@ pub enum ConstantKind {
     String(Symbol),
     Bool(bool),
     Unit,
+    /// Function referenced by its symbol name
+    Fn(Symbol),
+    /// Function referenced by its definition, such as a resolved method
+    FnDef(DefId),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -2,6 +2,7 @@
 
 use crate::mir::*;
 use faxc_sem::types::Type;
+use indexmap::IndexSet;
 use std::collections::HashMap;
 
 pub fn optimize_function(func: &mut Function) {
@@ -14,6 +15,7 @@ pub fn optimize_function(func: &mut Function) {
 
         simplify(func);
         fold(func);
+        const_prop(func);
         propagate(func);
         reduce(func);
         cse(func);
@@ -229,12 +231,7 @@ fn fold(func: &mut Function) {
         let mut i = 0;
         while i < block.statements.len() {
             if let Statement::Assign(place, rvalue) = &block.statements[i] {
-                let folded = match rvalue {
-                    Rvalue::BinaryOp(op, left, right) => fold_bin(*op, left, right),
-                    Rvalue::UnaryOp(op, operand) => fold_un(*op, operand),
-                    _ => None,
-                };
-                if let Some(r) = folded {
+                if let Some(r) = fold_rvalue(rvalue) {
                     block.statements[i] = Statement::Assign(place.clone(), r);
                 }
             }
@@ -243,6 +240,14 @@ fn fold(func: &mut Function) {
     }
 }
 
+fn fold_rvalue(rvalue: &Rvalue) -> Option<Rvalue> {
+    match rvalue {
+        Rvalue::BinaryOp(op, left, right) => fold_bin(*op, left, right),
+        Rvalue::UnaryOp(op, operand) => fold_un(*op, operand),
+        _ => None,
+    }
+}
+
 fn fold_bin(op: BinOp, left: &Box<Operand>, right: &Box<Operand>) -> Option<Rvalue> {
     match (op, left.as_ref(), right.as_ref()) {
         (BinOp::Add, Operand::Constant(l), Operand::Constant(r)) => {
@@ -270,6 +275,8 @@ fn fold_bin(op: BinOp, left: &Box<Operand>, right: &Box<Operand>) -> Option<Rval
             }
         },
         (BinOp::Div, Operand::Constant(l), Operand::Constant(r)) => {
+            // A zero divisor was already reported by semantic analysis; the
+            // division is left in place so it still traps at run time
             if let (ConstantKind::Int(li), ConstantKind::Int(ri)) = (&l.kind, &r.kind) {
                 if *ri != 0 {
                     return const_int(li.wrapping_div(*ri));
@@ -413,6 +420,72 @@ fn const_float(f: f64) -> Option<Rvalue> {
     })))
 }
 
+/// Propagate locals assigned a constant into their later uses
+///
+/// Within each block, a local assigned `Rvalue::Use(Constant)` is replaced
+/// by that constant in subsequent operands until it is reassigned, borrowed
+/// or its storage ends. Each statement is folded as soon as its operands
+/// are substituted, so chains like `x = 5; y = x + 1; z = y * 2` collapse
+/// in one pass. Nothing is carried across block boundaries.
+pub fn const_prop(func: &mut Function) {
+    for block_idx in 0..func.blocks.len() {
+        let block = &mut func.blocks[BlockId(block_idx as u32)];
+        let mut consts: HashMap<LocalId, Operand> = HashMap::new();
+        // Locals whose address has been taken can change through a pointer,
+        // so their value is unknown for the rest of the block
+        let mut escaped: IndexSet<LocalId> = IndexSet::new();
+
+        for stmt in block.statements.iter_mut() {
+            match stmt {
+                Statement::Assign(place, rvalue) => {
+                    *rvalue = propagate_rvalue(rvalue, &consts);
+                    if let Some(folded) = fold_rvalue(rvalue) {
+                        *rvalue = folded;
+                    }
+
+                    if let Rvalue::Ref(borrowed, _) | Rvalue::AddressOf(borrowed, _) = &*rvalue {
+                        let base = place_base(borrowed);
+                        consts.remove(&base);
+                        escaped.insert(base);
+                    }
+                    consts.remove(&place_base(place));
+                    if let (Place::Local(dest), Rvalue::Use(op @ Operand::Constant(_))) =
+                        (&*place, &*rvalue)
+                    {
+                        if !escaped.contains(dest) {
+                            consts.insert(*dest, op.clone());
+                        }
+                    }
+                },
+                Statement::StorageLive(local)
+                | Statement::StorageDead(local)
+                | Statement::Phi(local, _) => {
+                    consts.remove(local);
+                },
+                Statement::Nop => {},
+            }
+        }
+
+        match &mut block.terminator {
+            Terminator::If { cond, .. } => *cond = propagate_operand(cond, &consts),
+            Terminator::SwitchInt { discr, .. } => *discr = propagate_operand(discr, &consts),
+            Terminator::Call { args, .. } => {
+                for arg in args.iter_mut() {
+                    *arg = propagate_operand(arg, &consts);
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+fn place_base(place: &Place) -> LocalId {
+    match place {
+        Place::Local(id) => *id,
+        Place::Projection(base, _) => place_base(base),
+    }
+}
+
 fn propagate(func: &mut Function) {
     for block_idx in 0..func.blocks.len() {
         let block = &mut func.blocks[BlockId(block_idx as u32)];
@@ -755,8 +828,14 @@ pub fn dead_code(func: &mut Function) -> bool {
     for block_idx in 0..func.blocks.len() {
         let block = &func.blocks[BlockId(block_idx as u32)];
         for stmt in &block.statements {
-            if let Statement::Assign(_, rvalue) = stmt {
-                mark_use(rvalue, &mut used);
+            match stmt {
+                Statement::Assign(_, rvalue) => mark_use(rvalue, &mut used),
+                Statement::Phi(_, incoming) => {
+                    for (_, op) in incoming {
+                        mark_op_use(op, &mut used);
+                    }
+                },
+                _ => {},
             }
             mark_term_use(&block.terminator, &mut used);
         }
@@ -1070,3 +1149,97 @@ fn update_terminator_targets(term: &mut Terminator, mapping: &HashMap<BlockId, B
         _ => {},
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use faxc_util::Symbol;
+
+    fn int(n: i64) -> Operand {
+        Operand::Constant(Constant {
+            ty: Type::Int,
+            kind: ConstantKind::Int(n),
+        })
+    }
+
+    fn copy(local: u32) -> Box<Operand> {
+        Box::new(Operand::Copy(Place::Local(LocalId(local))))
+    }
+
+    fn single_block(statements: Vec<Statement>) -> Function {
+        let mut func = Function::new(Symbol::intern("test"), Type::Int, 0);
+        func.blocks.push(BasicBlock {
+            id: BlockId(0),
+            statements,
+            terminator: Terminator::Return,
+        });
+        func
+    }
+
+    #[test]
+    fn test_const_prop_folds_propagated_constant() {
+        // x = 5; y = x + 1
+        let mut func = single_block(vec![
+            Statement::Assign(Place::Local(LocalId(1)), Rvalue::Use(int(5))),
+            Statement::Assign(
+                Place::Local(LocalId(2)),
+                Rvalue::BinaryOp(BinOp::Add, copy(1), Box::new(int(1))),
+            ),
+        ]);
+
+        const_prop(&mut func);
+
+        assert_eq!(
+            func.blocks[BlockId(0)].statements[1],
+            Statement::Assign(Place::Local(LocalId(2)), Rvalue::Use(int(6)))
+        );
+    }
+
+    #[test]
+    fn test_const_prop_stops_at_reassignment() {
+        // x = 5; x = y; z = x + 1
+        let mut func = single_block(vec![
+            Statement::Assign(Place::Local(LocalId(1)), Rvalue::Use(int(5))),
+            Statement::Assign(Place::Local(LocalId(1)), Rvalue::Use(*copy(2))),
+            Statement::Assign(
+                Place::Local(LocalId(3)),
+                Rvalue::BinaryOp(BinOp::Add, copy(1), Box::new(int(1))),
+            ),
+        ]);
+
+        const_prop(&mut func);
+
+        assert_eq!(
+            func.blocks[BlockId(0)].statements[2],
+            Statement::Assign(
+                Place::Local(LocalId(3)),
+                Rvalue::BinaryOp(BinOp::Add, copy(1), Box::new(int(1)))
+            )
+        );
+    }
+
+    #[test]
+    fn test_const_prop_ignores_borrowed_locals() {
+        // r = &mut x; x = 5; *r = 6; y = x
+        let mut func = single_block(vec![
+            Statement::Assign(
+                Place::Local(LocalId(2)),
+                Rvalue::Ref(Place::Local(LocalId(1)), Mutability::Mutable),
+            ),
+            Statement::Assign(Place::Local(LocalId(1)), Rvalue::Use(int(5))),
+            Statement::Assign(
+                Place::Projection(Box::new(Place::Local(LocalId(2))), Projection::Deref),
+                Rvalue::Use(int(6)),
+            ),
+            Statement::Assign(Place::Local(LocalId(3)), Rvalue::Use(*copy(1))),
+        ]);
+
+        const_prop(&mut func);
+
+        // The write through `r` changed `x`, so `y` must still read it
+        assert_eq!(
+            func.blocks[BlockId(0)].statements[3],
+            Statement::Assign(Place::Local(LocalId(3)), Rvalue::Use(*copy(1)))
+        );
+    }
+}
@@ -5,7 +5,7 @@
 
 use crate::*;
 use faxc_sem::Type;
-use faxc_util::Symbol;
+use faxc_util::{Idx, Symbol};
 
 #[test]
 fn test_function_creation() {
@@ -64,9 +64,52 @@ fn test_builder_assign() {
     assert_eq!(func.blocks[entry].statements.len(), 1);
 }
 
+#[test]
+fn test_builder_push_assign() {
+    let name = Symbol::intern("test");
+    let mut builder = Builder::new(name, Type::Int);
+
+    let entry = builder.new_block();
+    builder.set_current_block(entry);
+
+    let place = builder.push_assign(
+        Rvalue::Use(Operand::Constant(Constant {
+            ty: Type::Bool,
+            kind: ConstantKind::Bool(true),
+        })),
+        Type::Bool,
+    );
+    assert_eq!(place, Place::Local(LocalId(1)));
+
+    let func = builder.build();
+    assert_eq!(func.locals.len(), 2); // return value and the temporary
+    assert_eq!(func.locals[LocalId(1)].ty, Type::Bool);
+    assert_eq!(func.blocks[entry].statements.len(), 1);
+    assert!(matches!(
+        &func.blocks[entry].statements[0],
+        Statement::Assign(Place::Local(LocalId(1)), Rvalue::Use(Operand::Constant(_)))
+    ));
+}
+
+#[test]
+fn test_builder_temp_has_no_statement() {
+    let name = Symbol::intern("test");
+    let mut builder = Builder::new(name, Type::Int);
+
+    let entry = builder.new_block();
+    builder.set_current_block(entry);
+
+    assert_eq!(builder.temp(Type::Int), Place::Local(LocalId(1)));
+    assert_eq!(builder.temp(Type::Int), Place::Local(LocalId(2)));
+
+    let func = builder.build();
+    assert_eq!(func.locals.len(), 3);
+    assert!(func.blocks[entry].statements.is_empty());
+}
+
 #[test]
 fn test_constant_folding_optimization() {
-    use crate::optimize::constant_folding;
+    use crate::optimize::const_prop;
     
     let name = Symbol::intern("test");
     let mut func = Function::new(name, Type::Int, 0);
@@ -94,7 +137,7 @@ fn test_constant_folding_optimization() {
         terminator: Terminator::Return,
     });
     
-    constant_folding(&mut func);
+    const_prop(&mut func);
     
     // After folding, should be a constant 30
     if let Statement::Assign(_, Rvalue::Use(Operand::Constant(c))) = &func.blocks[entry].statements[0] {
@@ -120,15 +163,16 @@ fn test_lower_literal() {
     };
     
     let fn_item = hir::FnItem {
+        def_id: faxc_util::DefId(0),
         name: Symbol::intern("test"),
+        generics: hir::GenericParams::default(),
         params: Vec::new(),
         ret_type: Type::Int,
-        body: Box::new(hir::Block {
-            stmts: Vec::new(),
-            expr: Some(Box::new(lit_expr)),
-            span: Span::DUMMY,
-        }),
-        span: Span::DUMMY,
+        body: hir::Body {
+            params: Vec::new(),
+            value: lit_expr,
+        },
+        async_kw: false,
     };
     
     let mir_func = lower_hir_function(&fn_item);
@@ -15,6 +15,50 @@ fn parse_source(source: &str) -> faxc_par::Ast {
     parser.parse()
 }
 
+/// Generate a program of `functions` functions with `stmts` statements each
+fn generate_program(functions: usize, stmts: usize) -> String {
+    let mut source = String::new();
+    for f in 0..functions {
+        source.push_str(&format!("fn f{}(a: i32, b: i32) -> i32 {{\n", f));
+        for s in 0..stmts {
+            source.push_str(&format!("    let x{} = a * {} + (b - {}) / 2;\n", s, s, f));
+        }
+        source.push_str("    a\n}\n");
+    }
+    source
+}
+
+fn bench_large_program(c: &mut Criterion) {
+    const FUNCTIONS: usize = 1_000;
+    const STMTS: usize = 20;
+    let source = generate_program(FUNCTIONS, STMTS);
+
+    let mut handler = Handler::new();
+    let tokens: Vec<_> = Lexer::new(&source, &mut handler).collect();
+
+    // Tokens per second
+    let mut group = c.benchmark_group("lexer_large");
+    group.throughput(Throughput::Elements(tokens.len() as u64));
+    group.bench_function("generated_program", |b| {
+        b.iter(|| {
+            let mut handler = Handler::new();
+            Lexer::new(black_box(&source), &mut handler).count()
+        })
+    });
+    group.finish();
+
+    // Nodes (functions and their statements) per second
+    let mut group = c.benchmark_group("parser_large");
+    group.throughput(Throughput::Elements((FUNCTIONS * (STMTS + 1)) as u64));
+    group.bench_function("generated_program", |b| {
+        b.iter(|| {
+            let mut handler = Handler::new();
+            Parser::new(black_box(tokens.clone()), &mut handler).parse()
+        })
+    });
+    group.finish();
+}
+
 fn bench_parser_simple(c: &mut Criterion) {
     let mut group = c.benchmark_group("parser_simple");
 
@@ -223,6 +267,7 @@ criterion_group!(
     bench_parser_structs,
     bench_parser_enums,
     bench_parser_control_flow,
-    bench_parser_complex
+    bench_parser_complex,
+    bench_large_program
 );
 criterion_main!(benches);
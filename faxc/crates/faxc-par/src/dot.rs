@@ -0,0 +1,196 @@
+//! GraphViz export of the AST
+//!
+//! [`ast_to_dot`] renders a parsed file as a `digraph` with one node per
+//! AST node, labelled with its variant (and name or value where it has
+//! one), and an edge from every node to each of its children. The output
+//! can be rendered with `dot -Tsvg`.
+
+use std::fmt::Write;
+
+use crate::visit::{self, Visitor};
+use crate::{Block, Expr, Item, Literal, Path, Pattern, Stmt, Type};
+
+/// Render `items` as a GraphViz `digraph`
+pub fn ast_to_dot(items: &[Item]) -> String {
+    let mut builder = DotBuilder {
+        out: String::from("digraph ast {\n    node [shape=box];\n"),
+        next_id: 0,
+        parents: Vec::new(),
+    };
+    for item in items {
+        builder.visit_item(item);
+    }
+    builder.out.push_str("}\n");
+    builder.out
+}
+
+struct DotBuilder {
+    out: String,
+    next_id: usize,
+    /// Nodes whose children are being visited, innermost last
+    parents: Vec<usize>,
+}
+
+impl DotBuilder {
+    /// Add a node under the current parent and visit its children below it
+    fn enter(&mut self, label: String, walk: impl FnOnce(&mut Self)) {
+        let id = self.next_id;
+        self.next_id += 1;
+        let _ = writeln!(self.out, "    n{} [label=\"{}\"];", id, escape(&label));
+        if let Some(parent) = self.parents.last() {
+            let _ = writeln!(self.out, "    n{} -> n{};", parent, id);
+        }
+        self.parents.push(id);
+        walk(self);
+        self.parents.pop();
+    }
+}
+
+impl Visitor for DotBuilder {
+    fn visit_item(&mut self, item: &Item) {
+        self.enter(item_label(item), |b| visit::walk_item(b, item));
+    }
+
+    fn visit_block(&mut self, block: &Block) {
+        self.enter("Block".to_string(), |b| visit::walk_block(b, block));
+    }
+
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        self.enter(stmt_label(stmt), |b| visit::walk_stmt(b, stmt));
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        self.enter(expr_label(expr), |b| visit::walk_expr(b, expr));
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        self.enter(pattern_label(pattern), |b| visit::walk_pattern(b, pattern));
+    }
+
+    fn visit_type(&mut self, ty: &Type) {
+        self.enter(type_label(ty), |b| visit::walk_type(b, ty));
+    }
+}
+
+/// Escape a label for use inside a double-quoted DOT string
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn path_str(path: &Path) -> String {
+    path.segments
+        .iter()
+        .map(|segment| segment.ident.as_str().to_string())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+fn literal_str(lit: &Literal) -> String {
+    match lit {
+        Literal::Int(n) => n.to_string(),
+        Literal::TypedInt(n, suffix) => format!("{}{}", n, suffix),
+        Literal::Float(f) => f.to_string(),
+        Literal::String(s) => format!("{:?}", s.as_str()),
+        Literal::Char(c) => format!("{:?}", c),
+        Literal::Bool(b) => b.to_string(),
+        Literal::Unit => "()".to_string(),
+    }
+}
+
+fn item_label(item: &Item) -> String {
+    match item {
+        Item::Fn(f) => format!("Fn {}", f.name.as_str()),
+        Item::Struct(s) => format!("Struct {}", s.name.as_str()),
+        Item::Enum(e) => format!("Enum {}", e.name.as_str()),
+        Item::Trait(t) => format!("Trait {}", t.name.as_str()),
+        Item::Impl(_) => "Impl".to_string(),
+        Item::Use(u) => format!("Use {}", path_str(&u.path)),
+        Item::Mod(m) => format!("Mod {}", m.name.as_str()),
+        Item::Const(c) => format!("Const {}", c.name.as_str()),
+        Item::Static(s) => format!("Static {}", s.name.as_str()),
+    }
+}
+
+fn stmt_label(stmt: &Stmt) -> String {
+    match stmt {
+        Stmt::Let(_) => "Let",
+        Stmt::Expr(_) => "ExprStmt",
+        Stmt::Return(_) => "Return",
+        Stmt::If(_) => "If",
+        Stmt::While(_) => "While",
+        Stmt::WhileLet(_) => "WhileLet",
+        Stmt::For(_) => "For",
+        Stmt::Break(_) => "Break",
+        Stmt::Continue(_) => "Continue",
+        Stmt::Item(_) => "ItemStmt",
+    }
+    .to_string()
+}
+
+fn expr_label(expr: &Expr) -> String {
+    match expr {
+        Expr::Literal(lit) => format!("Literal {}", literal_str(lit)),
+        Expr::Path(path) => format!("Path {}", path_str(path)),
+        Expr::Binary(b) => format!("Binary {:?}", b.op),
+        Expr::Unary(u) => format!("Unary {:?}", u.op),
+        Expr::Call(_) => "Call".to_string(),
+        Expr::MethodCall(m) => format!("MethodCall {}", m.method.as_str()),
+        Expr::Field(f) => format!("Field {}", f.field.as_str()),
+        Expr::Index(_) => "Index".to_string(),
+        Expr::Block(_) => "BlockExpr".to_string(),
+        Expr::If(_) => "If".to_string(),
+        Expr::IfLet(_) => "IfLet".to_string(),
+        Expr::Match(_) => "Match".to_string(),
+        Expr::Loop(_) => "Loop".to_string(),
+        Expr::Closure(_) => "Closure".to_string(),
+        Expr::Assign(_) => "Assign".to_string(),
+        Expr::CompoundAssign(a) => format!("CompoundAssign {:?}", a.op),
+        Expr::Return(_) => "Return".to_string(),
+        Expr::Break(..) => "Break".to_string(),
+        Expr::Continue(_) => "Continue".to_string(),
+        Expr::Paren(_) => "Paren".to_string(),
+        Expr::Tuple(_) => "Tuple".to_string(),
+        Expr::Array(_) => "Array".to_string(),
+        Expr::ArrayRepeat { .. } => "ArrayRepeat".to_string(),
+        Expr::Range(r) if r.inclusive => "Range ..=".to_string(),
+        Expr::Range(_) => "Range ..".to_string(),
+        Expr::Cast(..) => "Cast".to_string(),
+        Expr::Async(_) => "Async".to_string(),
+        Expr::Await(_) => "Await".to_string(),
+        Expr::StructLiteral(s) => format!("StructLiteral {}", path_str(&s.path)),
+        Expr::EnumVariant(e) => {
+            format!("EnumVariant {}::{}", path_str(&e.path), e.variant.as_str())
+        },
+    }
+}
+
+fn pattern_label(pattern: &Pattern) -> String {
+    match pattern {
+        Pattern::Wildcard => "Wildcard".to_string(),
+        Pattern::Ident(name, _) => format!("Ident {}", name.as_str()),
+        Pattern::Literal(lit) => format!("Literal {}", literal_str(lit)),
+        Pattern::Path(path) => format!("Path {}", path_str(path)),
+        Pattern::Struct(path, _) => format!("StructPattern {}", path_str(path)),
+        Pattern::TupleStruct(path, _) => format!("TupleStructPattern {}", path_str(path)),
+        Pattern::Tuple(_) => "TuplePattern".to_string(),
+        Pattern::Slice(_) => "SlicePattern".to_string(),
+    }
+}
+
+fn type_label(ty: &Type) -> String {
+    match ty {
+        Type::Unit => "Type ()".to_string(),
+        Type::Never => "Type !".to_string(),
+        Type::Path(path) => format!("Type {}", path_str(path)),
+        Type::Generic(..) => "GenericType".to_string(),
+        Type::Reference(_, _) => "ReferenceType".to_string(),
+        Type::Pointer(_, _) => "PointerType".to_string(),
+        Type::Slice(_) => "SliceType".to_string(),
+        Type::Array(..) => "ArrayType".to_string(),
+        Type::Tuple(_) => "TupleType".to_string(),
+        Type::Fn(..) => "FnType".to_string(),
+        Type::TraitObject(_) => "TraitObjectType".to_string(),
+        Type::ImplTrait(_) => "ImplTraitType".to_string(),
+        Type::Inferred => "Type _".to_string(),
+    }
+}
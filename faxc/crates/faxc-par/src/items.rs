@@ -45,7 +45,7 @@ impl<'a> Parser<'a> {
                     self.advance();
                     Visibility::Super
                 },
-                Token::Ident(sym) if sym.as_str() == "in" => {
+                Token::Ident(sym) if sym.is("in") => {
                     self.advance();
                     let path = self.parse_path();
                     Visibility::Restricted(path)
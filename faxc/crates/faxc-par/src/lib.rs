@@ -58,11 +58,21 @@
 #![allow(unused_variables)]
 #![allow(unused_imports)]
 
+mod dot;
 #[cfg(test)]
 mod edge_cases;
+mod stream;
+pub mod visit;
+
+pub use dot::ast_to_dot;
+pub use stream::{LexerTokenStream, TokenStream, VecTokenStream};
+pub use visit::Visitor;
 
 use faxc_lex::Token;
-use faxc_util::{Handler, Span, Symbol};
+use faxc_util::{FileId, Handler, Span, Symbol};
+
+/// Returned by the token navigation helpers once the input is exhausted
+static EOF: Token = Token::Eof;
 
 // ============================================================================
 // AST NODE DEFINITIONS
@@ -92,6 +102,9 @@ pub enum Item {
     /// Module import
     Use(UseItem),
 
+    /// Module declaration
+    Mod(ModItem),
+
     /// Constant definition
     Const(ConstItem),
 
@@ -108,6 +121,9 @@ pub struct FnItem {
     /// Generic parameters (if any)
     pub generics: Vec<GenericParam>,
 
+    /// `self` receiver, if the function is a method
+    pub self_param: Option<SelfKind>,
+
     /// Function parameters
     pub params: Vec<Param>,
 
@@ -156,6 +172,22 @@ pub struct WhereBound {
     pub traits: Vec<Path>,
 }
 
+/// How a method takes its `self` receiver
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelfKind {
+    /// `self`
+    Value,
+
+    /// `mut self`
+    MutValue,
+
+    /// `&self`
+    Ref,
+
+    /// `&mut self`
+    RefMut,
+}
+
 /// Function parameter
 #[derive(Debug, Clone)]
 pub struct Param {
@@ -202,6 +234,9 @@ pub struct Field {
 
     /// Visibility
     pub visibility: Visibility,
+
+    /// Location of the field name
+    pub span: Span,
 }
 
 /// Enum item
@@ -234,6 +269,9 @@ pub struct Variant {
 
     /// Variant data
     pub data: VariantData,
+
+    /// Location of the variant name
+    pub span: Span,
 }
 
 /// Variant data types
@@ -271,8 +309,8 @@ pub struct TraitItem {
 /// Trait member
 #[derive(Debug, Clone)]
 pub enum TraitMember {
-    /// Method signature
-    Method(FnSig),
+    /// Method signature, with the default body if the trait provides one
+    Method(FnSig, Option<Block>),
 
     /// Associated type
     Type(Symbol, Vec<Type>),
@@ -286,6 +324,7 @@ pub enum TraitMember {
 pub struct FnSig {
     pub name: Symbol,
     pub generics: Vec<GenericParam>,
+    pub self_param: Option<SelfKind>,
     pub params: Vec<Param>,
     pub ret_type: Option<Type>,
 }
@@ -307,6 +346,9 @@ pub struct ImplItem {
 
     /// Where clause constraints
     pub where_clause: Option<WhereClause>,
+
+    /// Source location
+    pub span: Span,
 }
 
 /// Implementation member
@@ -335,6 +377,22 @@ pub struct UseItem {
     pub is_glob: bool,
 }
 
+/// Module item
+#[derive(Debug, Clone)]
+pub struct ModItem {
+    /// Module name
+    pub name: Symbol,
+
+    /// Items of an inline module, `None` for `mod name;`
+    pub items: Option<Vec<Item>>,
+
+    /// Visibility
+    pub visibility: Visibility,
+
+    /// Source location
+    pub span: Span,
+}
+
 /// Constant item
 #[derive(Debug, Clone)]
 pub struct ConstItem {
@@ -411,12 +469,15 @@ pub enum Stmt {
     /// Return statement
     Return(Option<Expr>),
 
-    /// If statement
-    If(IfStmt),
+    /// If statement; `else if` chains nest as in an `if` expression
+    If(IfExpr),
 
     /// While loop
     While(WhileStmt),
 
+    /// While-let loop
+    WhileLet(WhileLetStmt),
+
     /// For loop
     For(ForStmt),
 
@@ -442,38 +503,34 @@ pub struct LetStmt {
     /// Initializer expression
     pub init: Option<Expr>,
 
+    /// Diverging block run when a refutable pattern fails to match (let-else)
+    pub else_block: Option<Block>,
+
     /// Mutability
     pub mutable: bool,
 }
 
-/// If statement
+/// While loop
 #[derive(Debug, Clone)]
-pub struct IfStmt {
+pub struct WhileStmt {
     /// Condition
     pub cond: Expr,
 
-    /// Then block
-    pub then_block: Block,
+    /// Loop body
+    pub body: Block,
 
-    /// Else clause
-    pub else_clause: Option<Box<ElseClause>>,
+    /// Label
+    pub label: Option<Symbol>,
 }
 
-/// Else clause
+/// While-let loop: `while let pattern = scrutinee { body }`
 #[derive(Debug, Clone)]
-pub enum ElseClause {
-    /// Else block
-    Block(Block),
-
-    /// Else-if
-    If(IfStmt),
-}
+pub struct WhileLetStmt {
+    /// Pattern matched on each iteration
+    pub pattern: Pattern,
 
-/// While loop
-#[derive(Debug, Clone)]
-pub struct WhileStmt {
-    /// Condition
-    pub cond: Expr,
+    /// Expression being matched
+    pub scrutinee: Expr,
 
     /// Loop body
     pub body: Block,
@@ -548,9 +605,15 @@ pub enum Expr {
     /// If expression
     If(IfExpr),
 
+    /// If-let expression
+    IfLet(IfLetExpr),
+
     /// Match expression
     Match(MatchExpr),
 
+    /// Loop expression
+    Loop(LoopExpr),
+
     /// Closure expression
     Closure(ClosureExpr),
 
@@ -569,12 +632,21 @@ pub enum Expr {
     /// Continue expression
     Continue(Option<Symbol>),
 
+    /// Parenthesized expression: `(a + b)`
+    ///
+    /// Kept so the grouping the user wrote survives parsing; semantic
+    /// analysis treats it as the inner expression.
+    Paren(Box<Expr>),
+
     /// Tuple expression
     Tuple(Vec<Expr>),
 
     /// Array expression
     Array(Vec<Expr>),
 
+    /// Array repeat expression: `[value; count]`
+    ArrayRepeat { value: Box<Expr>, count: Box<Expr> },
+
     /// Range expression
     Range(RangeExpr),
 
@@ -602,6 +674,10 @@ pub enum Literal {
     /// Integer literal
     Int(i64),
 
+    /// Integer literal with a type suffix, such as `255u8`; the symbol is
+    /// the suffix
+    TypedInt(i64, Symbol),
+
     /// Float literal
     Float(f64),
 
@@ -618,6 +694,17 @@ pub enum Literal {
     Unit,
 }
 
+impl Literal {
+    /// Literal for an integer token with value `n`, keeping its type
+    /// `suffix` (the `u8` of `255u8`) if it has one
+    fn from_int(n: i64, suffix: Option<Symbol>) -> Self {
+        match suffix {
+            Some(suffix) => Literal::TypedInt(n, suffix),
+            None => Literal::Int(n),
+        }
+    }
+}
+
 /// Path expression
 #[derive(Debug, Clone, PartialEq)]
 pub struct Path {
@@ -739,6 +826,15 @@ pub struct IfExpr {
     pub else_block: Option<Box<Expr>>,
 }
 
+/// If-let expression: `if let pattern = scrutinee { ... } else { ... }`
+#[derive(Debug, Clone)]
+pub struct IfLetExpr {
+    pub pattern: Pattern,
+    pub scrutinee: Box<Expr>,
+    pub then_block: Block,
+    pub else_block: Option<Box<Expr>>,
+}
+
 /// Match expression
 #[derive(Debug, Clone)]
 pub struct MatchExpr {
@@ -763,6 +859,16 @@ pub struct ClosureExpr {
     pub move_kw: bool,
 }
 
+/// Loop expression: `loop { body }`, whose type comes from its `break`s
+#[derive(Debug, Clone)]
+pub struct LoopExpr {
+    /// Loop body
+    pub body: Block,
+
+    /// Label
+    pub label: Option<Symbol>,
+}
+
 /// Async expression
 #[derive(Debug, Clone)]
 pub struct AsyncExpr {
@@ -862,11 +968,14 @@ impl Expr {
             Expr::Call(c) => Some(c.span),
             Expr::Field(f) => Some(f.span),
             Expr::Block(b) => Some(b.span),
+            Expr::Loop(l) => Some(l.body.span),
+            Expr::Paren(inner) => inner.span(),
             Expr::Literal(_) => None,
             Expr::Path(_) => None,
             Expr::MethodCall(_) => None,
             Expr::Index(_) => None,
             Expr::If(_) => None,
+            Expr::IfLet(_) => None,
             Expr::Match(_) => None,
             Expr::Closure(_) => None,
             Expr::Assign(_) => None,
@@ -876,6 +985,7 @@ impl Expr {
             Expr::Continue(_) => None,
             Expr::Tuple(_) => None,
             Expr::Array(_) => None,
+            Expr::ArrayRepeat { .. } => None,
             Expr::Range(_) => None,
             Expr::Cast(_, _) => None,
             Expr::Async(_) => None,
@@ -954,7 +1064,7 @@ pub enum Type {
     Slice(Box<Type>),
 
     /// Array type
-    Array(Box<Type>, usize),
+    Array(Box<Type>, ArrayLen),
 
     /// Tuple type
     Tuple(Vec<Type>),
@@ -972,6 +1082,27 @@ pub enum Type {
     Inferred,
 }
 
+/// Length of an array type
+#[derive(Debug, Clone)]
+pub enum ArrayLen {
+    /// Integer literal length
+    Lit(usize),
+
+    /// Length expression, folded during semantic analysis
+    Expr(Box<Expr>),
+}
+
+impl PartialEq for ArrayLen {
+    /// An expression length has no value until it is evaluated, so only
+    /// literal lengths compare equal
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ArrayLen::Lit(a), ArrayLen::Lit(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
 /// Mutability
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Mutability {
@@ -999,13 +1130,11 @@ impl TokenWithSpan {
 /// Recursive descent parser with Pratt parsing for expressions
 ///
 /// The parser uses LL(2) lookahead for disambiguation and implements
-/// panic-mode error recovery for robust error handling.
-pub struct Parser<'a> {
+/// panic-mode error recovery for robust error handling. Tokens are read
+/// through a [`TokenStream`]; by default one materialized up front.
+pub struct Parser<'a, S = VecTokenStream> {
     /// Token stream with spans
-    tokens: Vec<TokenWithSpan>,
-
-    /// Current position in token stream
-    position: usize,
+    tokens: S,
 
     /// Error handler
     handler: &'a mut Handler,
@@ -1013,6 +1142,16 @@ pub struct Parser<'a> {
     /// Source code (for span calculation)
     #[allow(dead_code)]
     source: &'a str,
+
+    /// Number of times the parser skipped input to recover from an error
+    recoveries: usize,
+
+    /// File stamped onto every span, overriding the tokens' own
+    file_id: Option<FileId>,
+
+    /// Whether `Path {` is read as something other than a struct literal,
+    /// as in a `match` scrutinee where the brace opens the arms
+    no_struct_literal: bool,
 }
 
 impl<'a> Parser<'a> {
@@ -1049,12 +1188,7 @@ impl<'a> Parser<'a> {
         handler: &'a mut Handler,
         source: &'a str,
     ) -> Self {
-        Self {
-            tokens,
-            position: 0,
-            handler,
-            source,
-        }
+        Parser::from_stream(VecTokenStream::new(tokens), handler, source)
     }
 
     /// Create a new parser from raw tokens (without spans)
@@ -1066,14 +1200,48 @@ impl<'a> Parser<'a> {
             .into_iter()
             .map(|t| TokenWithSpan::new(t, Span::DUMMY))
             .collect();
+        Parser::from_stream(VecTokenStream::new(tokens_with_span), handler, "")
+    }
+}
+
+impl<'a, S: TokenStream> Parser<'a, S> {
+    /// Create a parser reading from any token stream
+    ///
+    /// Use this with a [`LexerTokenStream`] to parse while lexing instead of
+    /// collecting every token first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faxc_util::Handler;
+    /// use faxc_lex::Lexer;
+    /// use faxc_par::{LexerTokenStream, Parser};
+    ///
+    /// let source = "fn main() { }";
+    /// let mut lex_handler = Handler::new();
+    /// let mut handler = Handler::new();
+    /// let stream = LexerTokenStream::new(Lexer::new(source, &mut lex_handler));
+    ///
+    /// let mut parser = Parser::from_stream(stream, &mut handler, source);
+    /// let ast = parser.parse();
+    /// ```
+    pub fn from_stream(tokens: S, handler: &'a mut Handler, source: &'a str) -> Self {
         Self {
-            tokens: tokens_with_span,
-            position: 0,
+            tokens,
             handler,
-            source: "",
+            source,
+            recoveries: 0,
+            file_id: None,
+            no_struct_literal: false,
         }
     }
 
+    /// Attribute every span the parser produces to `file_id`
+    pub fn with_file_id(mut self, file_id: FileId) -> Self {
+        self.file_id = Some(file_id);
+        self
+    }
+
     /// Parse a complete source file
     ///
     /// # Returns
@@ -1102,19 +1270,60 @@ impl<'a> Parser<'a> {
     /// let ast = parser.parse();
     /// ```
     pub fn parse(&mut self) -> Ast {
-        let mut items = Vec::new();
+        std::iter::from_fn(|| self.parse_next_item()).collect()
+    }
 
+    /// Parse the next top-level item, or `None` once the input is exhausted
+    ///
+    /// Items that fail to parse are reported and skipped, so a caller can
+    /// process each item as soon as it is parsed and still see every item
+    /// [`Parser::parse`] would return.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faxc_util::Handler;
+    /// use faxc_lex::{Lexer, Token};
+    /// use faxc_par::Parser;
+    ///
+    /// let source = "fn a() { } fn b() { }";
+    /// let mut handler = Handler::new();
+    /// let mut lexer = Lexer::new(source, &mut handler);
+    ///
+    /// let mut tokens = Vec::new();
+    /// loop {
+    ///     let token = lexer.next_token();
+    ///     if token == Token::Eof { break; }
+    ///     tokens.push(token);
+    /// }
+    ///
+    /// let mut parser = Parser::new(tokens, &mut handler);
+    /// let mut count = 0;
+    /// while let Some(_item) = parser.parse_next_item() {
+    ///     count += 1;
+    /// }
+    /// assert_eq!(count, 2);
+    /// ```
+    pub fn parse_next_item(&mut self) -> Option<Item> {
         while !self.is_at_end() {
             match self.parse_item() {
-                Some(item) => items.push(item),
+                Some(item) => return Some(item),
                 None => {
                     // Error recovery: skip to sync point
                     self.recover_to_sync_point();
                 },
             }
         }
+        None
+    }
 
-        items
+    /// Number of times the parser recovered from a syntax error
+    ///
+    /// Unlike the handler's error count, this counts how often input was
+    /// skipped to resynchronize; a high count means the AST is mostly
+    /// guesswork.
+    pub fn recovery_count(&self) -> usize {
+        self.recoveries
     }
 
     // ========================================================================
@@ -1164,7 +1373,7 @@ impl<'a> Parser<'a> {
                     self.advance();
                     Visibility::Super
                 },
-                Token::Ident(sym) if sym.as_str() == "in" => {
+                Token::In => {
                     self.advance();
                     let path = self.parse_path();
                     Visibility::Restricted(path)
@@ -1189,7 +1398,7 @@ impl<'a> Parser<'a> {
 
         let name = self.parse_ident()?;
         let generics = self.parse_generics();
-        let params = self.parse_params()?;
+        let (self_param, params) = self.parse_fn_params()?;
         let where_clause = self.parse_where_clause();
         let ret_type = self.parse_return_type();
         let body = self.parse_block()?;
@@ -1199,6 +1408,7 @@ impl<'a> Parser<'a> {
         Some(Item::Fn(FnItem {
             name,
             generics,
+            self_param,
             params,
             ret_type,
             body,
@@ -1209,42 +1419,93 @@ impl<'a> Parser<'a> {
         }))
     }
 
+    /// Parse the target type of an `as` cast
+    ///
+    /// A `<` after the type name opens generic arguments when a balanced
+    /// `<...>` that could be a type follows, as in `x as Foo<T>`; otherwise
+    /// it is a comparison: `x as i64 < y`.
+    fn parse_cast_type(&mut self) -> Option<Type> {
+        if let Token::Ident(name) = *self.current_token() {
+            if *self.peek_token() == Token::Lt && !self.looks_like_type_args() {
+                self.advance();
+                return Some(Type::Path(Path {
+                    segments: vec![PathSegment {
+                        ident: name,
+                        args: None,
+                    }],
+                }));
+            }
+        }
+        self.parse_type()
+    }
+
+    /// Check if the `<` after the current token opens generic arguments
+    ///
+    /// True if the matching `>` comes before any token that cannot appear
+    /// in a type.
+    fn looks_like_type_args(&mut self) -> bool {
+        let mut depth = 0usize;
+        let mut pos = 1;
+        while let Some(token) = self.tokens.lookahead(pos) {
+            match token.token {
+                Token::Lt => depth += 1,
+                Token::Gt => depth -= 1,
+                Token::Shr => depth = depth.saturating_sub(2),
+                Token::Ident(_)
+                | Token::ColonColon
+                | Token::Comma
+                | Token::Ampersand
+                | Token::Star
+                | Token::Mut
+                | Token::Const
+                | Token::Dyn
+                | Token::Fn
+                | Token::Arrow
+                | Token::Plus
+                | Token::Bang
+                | Token::Underscore
+                | Token::SelfUpper
+                | Token::LParen
+                | Token::RParen
+                | Token::LBracket
+                | Token::RBracket
+                | Token::Semicolon
+                | Token::Number(..) => {},
+                _ => return false,
+            }
+            if depth == 0 {
+                return true;
+            }
+            pos += 1;
+        }
+        false
+    }
+
     /// Parse generic parameters
     fn parse_generics(&mut self) -> Vec<GenericParam> {
         if !self.match_token(Token::Lt) {
             return Vec::new();
         }
 
-        let mut params = Vec::new();
-
-        while !self.is_at_end() && self.current_token() != Token::Gt {
-            let name = match self.parse_ident() {
-                Some(n) => n,
-                None => break,
-            };
+        self.parse_comma_separated(Token::Gt, |p| {
+            let name = p.parse_ident()?;
 
             let mut bounds = Vec::new();
-            if self.match_token(Token::Colon) {
+            if p.match_token(Token::Colon) {
                 // Parse trait bounds (simplified)
                 loop {
-                    if let Some(ty) = self.parse_type() {
+                    if let Some(ty) = p.parse_type() {
                         bounds.push(ty);
                     }
-                    if !self.match_token(Token::Plus) {
+                    if !p.match_token(Token::Plus) {
                         break;
                     }
                 }
             }
 
-            params.push(GenericParam { name, bounds });
-
-            if !self.match_token(Token::Comma) {
-                break;
-            }
-        }
-
-        self.expect(Token::Gt);
-        params
+            Some(GenericParam { name, bounds })
+        })
+        .unwrap_or_default()
     }
 
     /// Parse where clause
@@ -1283,26 +1544,57 @@ impl<'a> Parser<'a> {
     /// Parse function parameters
     fn parse_params(&mut self) -> Option<Vec<Param>> {
         self.expect(Token::LParen)?;
+        self.parse_param_list()
+    }
 
-        let mut params = Vec::new();
+    /// Parse a function's parameter list, which may start with a `self`
+    /// receiver
+    fn parse_fn_params(&mut self) -> Option<(Option<SelfKind>, Vec<Param>)> {
+        self.expect(Token::LParen)?;
 
-        if !self.match_token(Token::RParen) {
-            loop {
-                let mutable = self.match_token(Token::Mut);
-                let name = self.parse_ident()?;
-                self.expect(Token::Colon)?;
-                let ty = self.parse_type()?;
+        let self_param = self.parse_self_param();
+        if self_param.is_some() && !self.match_token(Token::Comma) {
+            self.expect(Token::RParen)?;
+            return Some((self_param, Vec::new()));
+        }
 
-                params.push(Param { name, ty, mutable });
+        Some((self_param, self.parse_param_list()?))
+    }
 
-                if !self.match_token(Token::Comma) {
-                    break;
-                }
-            }
-            self.expect(Token::RParen)?;
+    /// Parse a `self`, `mut self`, `&self` or `&mut self` receiver
+    fn parse_self_param(&mut self) -> Option<SelfKind> {
+        let kind = match (self.current_token(), self.peek_token()) {
+            (Token::Self_, _) => SelfKind::Value,
+            (Token::Mut, Token::Self_) => SelfKind::MutValue,
+            (Token::Ampersand, Token::Self_) => SelfKind::Ref,
+            (Token::Ampersand, Token::Mut)
+                if self.tokens.peek2().map(|t| &t.token) == Some(&Token::Self_) =>
+            {
+                SelfKind::RefMut
+            },
+            _ => return None,
+        };
+
+        let len = match kind {
+            SelfKind::Value => 1,
+            SelfKind::MutValue | SelfKind::Ref => 2,
+            SelfKind::RefMut => 3,
+        };
+        for _ in 0..len {
+            self.advance();
         }
+        Some(kind)
+    }
 
-        Some(params)
+    /// Parse `name: Type` parameters up to and including the closing `)`
+    fn parse_param_list(&mut self) -> Option<Vec<Param>> {
+        self.parse_comma_separated(Token::RParen, |p| {
+            let mutable = p.match_token(Token::Mut);
+            let name = p.parse_ident()?;
+            p.expect(Token::Colon)?;
+            let ty = p.parse_type()?;
+            Some(Param { name, ty, mutable })
+        })
     }
 
     /// Parse return type
@@ -1325,25 +1617,20 @@ impl<'a> Parser<'a> {
 
         self.expect(Token::LBrace)?;
 
-        let mut fields = Vec::new();
-        while !self.is_at_end() && self.current_token() != Token::RBrace {
-            let field_vis = self.parse_visibility();
-            let field_name = self.parse_ident()?;
-            self.expect(Token::Colon)?;
-            let field_ty = self.parse_type()?;
+        let fields = self.parse_comma_separated(Token::RBrace, |p| {
+            let field_vis = p.parse_visibility();
+            let field_span = p.current_span();
+            let field_name = p.parse_ident()?;
+            p.expect(Token::Colon)?;
+            let field_ty = p.parse_type()?;
 
-            fields.push(Field {
+            Some(Field {
                 name: field_name,
                 ty: field_ty,
                 visibility: field_vis,
-            });
-
-            if !self.match_token(Token::Comma) {
-                break;
-            }
-        }
-
-        self.expect(Token::RBrace)?;
+                span: field_span,
+            })
+        })?;
 
         let span = self.span_from_start(span_start);
 
@@ -1369,56 +1656,38 @@ impl<'a> Parser<'a> {
 
         self.expect(Token::LBrace)?;
 
-        let mut variants = Vec::new();
-        while !self.is_at_end() && self.current_token() != Token::RBrace {
-            let variant_name = self.parse_ident()?;
+        let variants = self.parse_comma_separated(Token::RBrace, |p| {
+            let variant_span = p.current_span();
+            let variant_name = p.parse_ident()?;
 
-            let data = if self.match_token(Token::LParen) {
+            let data = if p.match_token(Token::LParen) {
                 // Tuple variant
-                let mut types = Vec::new();
-                while !self.is_at_end() && self.current_token() != Token::RParen {
-                    if let Some(ty) = self.parse_type() {
-                        types.push(ty);
-                    }
-                    if !self.match_token(Token::Comma) {
-                        break;
-                    }
-                }
-                self.expect(Token::RParen);
-                VariantData::Tuple(types)
-            } else if self.match_token(Token::LBrace) {
+                VariantData::Tuple(p.parse_comma_separated(Token::RParen, Self::parse_type)?)
+            } else if p.match_token(Token::LBrace) {
                 // Struct variant
-                let mut fields = Vec::new();
-                while !self.is_at_end() && self.current_token() != Token::RBrace {
-                    let field_name = self.parse_ident()?;
-                    self.expect(Token::Colon)?;
-                    let field_ty = self.parse_type()?;
-                    fields.push(Field {
+                let fields = p.parse_comma_separated(Token::RBrace, |p| {
+                    let field_span = p.current_span();
+                    let field_name = p.parse_ident()?;
+                    p.expect(Token::Colon)?;
+                    let field_ty = p.parse_type()?;
+                    Some(Field {
                         name: field_name,
                         ty: field_ty,
                         visibility: Visibility::Private,
-                    });
-                    if !self.match_token(Token::Comma) {
-                        break;
-                    }
-                }
-                self.expect(Token::RBrace);
+                        span: field_span,
+                    })
+                })?;
                 VariantData::Struct(fields)
             } else {
                 VariantData::Unit
             };
 
-            variants.push(Variant {
+            Some(Variant {
                 name: variant_name,
                 data,
-            });
-
-            if !self.match_token(Token::Comma) {
-                break;
-            }
-        }
-
-        self.expect(Token::RBrace)?;
+                span: variant_span,
+            })
+        })?;
 
         let span = self.span_from_start(span_start);
 
@@ -1457,11 +1726,16 @@ impl<'a> Parser<'a> {
         self.expect(Token::LBrace)?;
 
         let mut items = Vec::new();
-        while !self.is_at_end() && self.current_token() != Token::RBrace {
-            // Parse trait members (simplified - just method signatures for now)
-            if self.current_token() == Token::Fn {
+        while !self.is_at_end() && *self.current_token() != Token::RBrace {
+            // Parse trait members (simplified - just methods for now)
+            if *self.current_token() == Token::Fn {
                 if let Some(sig) = self.parse_fn_sig() {
-                    items.push(TraitMember::Method(sig));
+                    let body = if *self.current_token() == Token::LBrace {
+                        self.parse_block()
+                    } else {
+                        None
+                    };
+                    items.push(TraitMember::Method(sig, body));
                 }
             } else {
                 self.recover_to_stmt_sync();
@@ -1483,13 +1757,13 @@ impl<'a> Parser<'a> {
         }))
     }
 
-    /// Parse function signature (for traits)
+    /// Parse function signature (for traits), leaving any default body
     fn parse_fn_sig(&mut self) -> Option<FnSig> {
         self.expect(Token::Fn)?;
 
         let name = self.parse_ident()?;
         let generics = self.parse_generics();
-        let params = self.parse_params()?;
+        let (self_param, params) = self.parse_fn_params()?;
         let ret_type = self.parse_return_type();
 
         // Consume semicolon if present
@@ -1498,6 +1772,7 @@ impl<'a> Parser<'a> {
         Some(FnSig {
             name,
             generics,
+            self_param,
             params,
             ret_type,
         })
@@ -1505,57 +1780,26 @@ impl<'a> Parser<'a> {
 
     /// Parse impl item
     fn parse_impl_item(&mut self) -> Option<Item> {
-        let _span_start = self.current_span();
+        let span_start = self.current_span();
 
         self.expect(Token::Impl)?;
 
         let generics = self.parse_generics();
         let where_clause = self.parse_where_clause();
 
-        // Check for trait impl: impl Trait for Type
-        let trait_ref = if self.current_token() != Token::For {
-            let ty = self.parse_type()?;
-            if self.match_token(Token::For) {
-                Some(ty)
-            } else {
-                // Inherent impl
-                let self_ty = ty;
-                self.expect(Token::LBrace)?;
-
-                let mut items = Vec::new();
-                while !self.is_at_end() && self.current_token() != Token::RBrace {
-                    if self.current_token() == Token::Fn {
-                        if let Some(item) = self.parse_item() {
-                            if let Item::Fn(fn_item) = item {
-                                items.push(ImplMember::Method(fn_item));
-                            }
-                        }
-                    } else {
-                        self.recover_to_stmt_sync();
-                    }
-                }
-                self.expect(Token::RBrace)?;
-
-                return Some(Item::Impl(ImplItem {
-                    generics,
-                    trait_ref: None,
-                    self_ty,
-                    items,
-                    where_clause,
-                }));
-            }
+        // Trait impl `impl Trait for Type` or inherent impl `impl Type`
+        let ty = self.parse_type()?;
+        let (trait_ref, self_ty) = if self.match_token(Token::For) {
+            (Some(ty), self.parse_type()?)
         } else {
-            None
+            (None, ty)
         };
 
-        self.expect(Token::For)?;
-        let self_ty = self.parse_type()?;
-
         self.expect(Token::LBrace)?;
 
         let mut items = Vec::new();
-        while !self.is_at_end() && self.current_token() != Token::RBrace {
-            if self.current_token() == Token::Fn {
+        while !self.is_at_end() && *self.current_token() != Token::RBrace {
+            if *self.current_token() == Token::Fn {
                 if let Some(item) = self.parse_item() {
                     if let Item::Fn(fn_item) = item {
                         items.push(ImplMember::Method(fn_item));
@@ -1566,6 +1810,7 @@ impl<'a> Parser<'a> {
             }
         }
         self.expect(Token::RBrace)?;
+        let span = self.span_from_start(span_start);
 
         Some(Item::Impl(ImplItem {
             generics,
@@ -1573,6 +1818,7 @@ impl<'a> Parser<'a> {
             self_ty,
             items,
             where_clause,
+            span,
         }))
     }
 
@@ -1604,19 +1850,20 @@ impl<'a> Parser<'a> {
     }
 
     /// Parse mod item
-    fn parse_mod_item(&mut self, _visibility: Visibility) -> Option<Item> {
+    fn parse_mod_item(&mut self, visibility: Visibility) -> Option<Item> {
+        let span_start = self.current_span();
+
         self.expect(Token::Mod)?;
-        let _name = self.parse_ident()?;
+        let name = self.parse_ident()?;
 
-        if self.match_token(Token::Semicolon) {
+        let items = if self.match_token(Token::Semicolon) {
             // External module
-            return None;
-        }
-
-        if self.match_token(Token::LBrace) {
+            None
+        } else {
             // Inline module - parse items inside
+            self.expect(Token::LBrace)?;
             let mut items = Vec::new();
-            while !self.is_at_end() && self.current_token() != Token::RBrace {
+            while !self.is_at_end() && *self.current_token() != Token::RBrace {
                 if let Some(item) = self.parse_item() {
                     items.push(item);
                 } else {
@@ -1624,10 +1871,17 @@ impl<'a> Parser<'a> {
                 }
             }
             self.expect(Token::RBrace)?;
-        }
+            Some(items)
+        };
 
-        // For now, return None for mod items (they're handled differently)
-        None
+        let span = self.span_from_start(span_start);
+
+        Some(Item::Mod(ModItem {
+            name,
+            items,
+            visibility,
+            span,
+        }))
     }
 
     /// Parse const item
@@ -1704,7 +1958,7 @@ impl<'a> Parser<'a> {
                 // Check for assignment
                 if self.match_token(Token::Eq) {
                     let value = self.parse_expr()?;
-                    self.expect(Token::Semicolon);
+                    self.expect_or_insert(Token::Semicolon);
                     return Some(Stmt::Expr(Expr::Assign(AssignExpr {
                         place: Box::new(expr),
                         value: Box::new(value),
@@ -1714,7 +1968,7 @@ impl<'a> Parser<'a> {
                 // Check for compound assignment
                 if let Some(op) = self.parse_compound_assign_op() {
                     let value = self.parse_expr()?;
-                    self.expect(Token::Semicolon);
+                    self.expect_or_insert(Token::Semicolon);
                     return Some(Stmt::Expr(Expr::CompoundAssign(CompoundAssignExpr {
                         place: Box::new(expr),
                         op,
@@ -1725,11 +1979,11 @@ impl<'a> Parser<'a> {
                 // Regular expression statement
                 if self.match_token(Token::Semicolon) {
                     Some(Stmt::Expr(expr))
-                } else if self.is_at_end() || self.current_token() == Token::RBrace {
+                } else if self.is_at_end() || *self.current_token() == Token::RBrace {
                     // Trailing expression in block
                     Some(Stmt::Expr(expr))
                 } else {
-                    self.expect(Token::Semicolon);
+                    self.expect_one_of(&[Token::Semicolon, Token::RBrace]);
                     Some(Stmt::Expr(expr))
                 }
             },
@@ -1804,54 +2058,55 @@ impl<'a> Parser<'a> {
             None
         };
 
-        self.expect(Token::Semicolon)?;
+        // `let PAT = EXPR else { ... };`
+        let else_block = if init.is_some() && self.match_token(Token::Else) {
+            Some(self.parse_block()?)
+        } else {
+            None
+        };
+
+        self.expect_or_insert(Token::Semicolon);
 
         Some(Stmt::Let(LetStmt {
             pattern,
             ty,
             init,
+            else_block,
             mutable,
         }))
     }
 
     /// Parse if statement
+    ///
+    /// This parses the same `IfExpr` as an `if` expression, so `else if`
+    /// nests an `Expr::If` in `else_block` in both positions. `if let`
+    /// becomes an expression statement.
     fn parse_if_stmt(&mut self) -> Option<Stmt> {
-        let _span_start = self.current_span();
-
-        self.expect(Token::If)?;
-
-        let cond = self.parse_expr()?;
-        let then_block = self.parse_block()?;
-
-        let else_clause = if self.match_token(Token::Else) {
-            if self.match_token(Token::If) {
-                // else if - parse nested if and extract IfStmt
-                if let Some(Stmt::If(if_stmt)) = self.parse_if_stmt() {
-                    Some(Box::new(ElseClause::If(if_stmt)))
-                } else {
-                    None
-                }
-            } else {
-                // else block
-                let block = self.parse_block()?;
-                Some(Box::new(ElseClause::Block(block)))
-            }
-        } else {
-            None
-        };
-
-        Some(Stmt::If(IfStmt {
-            cond,
-            then_block,
-            else_clause,
-        }))
+        match self.parse_if_expr()? {
+            Expr::If(if_expr) => Some(Stmt::If(if_expr)),
+            expr => Some(Stmt::Expr(expr)),
+        }
     }
 
     /// Parse while statement
     fn parse_while_stmt(&mut self) -> Option<Stmt> {
         self.expect(Token::While)?;
 
-        let cond = self.parse_expr()?;
+        if self.match_token(Token::Let) {
+            let pattern = self.parse_pattern()?;
+            self.expect(Token::Eq)?;
+            let scrutinee = self.parse_expr_no_struct()?;
+            let body = self.parse_block()?;
+
+            return Some(Stmt::WhileLet(WhileLetStmt {
+                pattern,
+                scrutinee,
+                body,
+                label: None,
+            }));
+        }
+
+        let cond = self.parse_expr_no_struct()?;
         let body = self.parse_block()?;
 
         Some(Stmt::While(WhileStmt {
@@ -1867,18 +2122,9 @@ impl<'a> Parser<'a> {
 
         let pattern = self.parse_pattern()?;
 
-        // Check for 'in' keyword (handled as identifier in lexer)
-        let is_in = match self.current_token() {
-            Token::Ident(sym) => sym.as_str() == "in",
-            _ => false,
-        };
-        if !is_in {
-            self.error("expected 'in' after pattern in for loop");
-            return None;
-        }
-        self.advance();
+        self.expect(Token::In)?;
 
-        let iter = self.parse_expr()?;
+        let iter = self.parse_expr_no_struct()?;
         let body = self.parse_block()?;
 
         Some(Stmt::For(ForStmt {
@@ -1893,8 +2139,8 @@ impl<'a> Parser<'a> {
     fn parse_return_stmt(&mut self) -> Option<Stmt> {
         self.expect(Token::Return)?;
 
-        let expr = if self.current_token() != Token::Semicolon
-            && self.current_token() != Token::RBrace
+        let expr = if *self.current_token() != Token::Semicolon
+            && *self.current_token() != Token::RBrace
             && !self.is_at_end()
         {
             self.parse_expr()
@@ -1902,7 +2148,7 @@ impl<'a> Parser<'a> {
             None
         };
 
-        self.expect(Token::Semicolon)?;
+        self.expect_or_insert(Token::Semicolon);
 
         Some(Stmt::Return(expr))
     }
@@ -1911,17 +2157,19 @@ impl<'a> Parser<'a> {
     fn parse_break_stmt(&mut self) -> Option<Stmt> {
         self.expect(Token::Break)?;
 
-        let label = if let Token::Ident(_sym) = self.current_token() {
-            // Check if it's a label (not an expression)
-            // For simplicity, we don't support break with value yet
-            None
-        } else {
-            None
-        };
+        // Labels are not lexed yet, so anything before the `;` is the value
+        if *self.current_token() != Token::Semicolon
+            && *self.current_token() != Token::RBrace
+            && !self.is_at_end()
+        {
+            let value = self.parse_expr()?;
+            self.expect_or_insert(Token::Semicolon);
+            return Some(Stmt::Expr(Expr::Break(Some(Box::new(value)), None)));
+        }
 
-        self.expect(Token::Semicolon)?;
+        self.expect_or_insert(Token::Semicolon);
 
-        Some(Stmt::Break(label))
+        Some(Stmt::Break(None))
     }
 
     /// Parse continue statement
@@ -1930,7 +2178,7 @@ impl<'a> Parser<'a> {
 
         let label = None;
 
-        self.expect(Token::Semicolon)?;
+        self.expect_or_insert(Token::Semicolon);
 
         Some(Stmt::Continue(label))
     }
@@ -1970,11 +2218,12 @@ impl<'a> Parser<'a> {
 
             let op_token = self.current_token();
             let op_span = self.current_span();
+            let op = self.token_to_binop(op_token);
 
             // Special handling for range expression
-            if op_token == Token::DotDot || op_token == Token::DotDotEq {
+            if matches!(op_token, Token::DotDot | Token::DotDotEq) {
+                let inclusive = *op_token == Token::DotDotEq;
                 self.advance();
-                let inclusive = op_token == Token::DotDotEq;
 
                 // Parse end expression (optional)
                 let end = if matches!(
@@ -2001,9 +2250,9 @@ impl<'a> Parser<'a> {
             }
 
             // Special handling for cast expression (takes a type, not an expression)
-            if op_token == Token::As {
+            if *op_token == Token::As {
                 self.advance();
-                let cast_type = self.parse_type()?;
+                let cast_type = self.parse_cast_type()?;
                 lhs = Expr::Cast(Box::new(lhs), cast_type);
                 continue;
             }
@@ -2012,7 +2261,7 @@ impl<'a> Parser<'a> {
 
             let rhs = self.parse_expr_with_min_bp(rbp)?;
 
-            let op = self.token_to_binop(op_token)?;
+            let op = op?;
             let span = self.span_from_start(op_span);
 
             lhs = Expr::Binary(BinaryExpr {
@@ -2026,9 +2275,8 @@ impl<'a> Parser<'a> {
         // Handle prefix range: ..end or ..=end (when min_bp is 0)
         if min_bp == 0 {
             if let Token::DotDot | Token::DotDotEq = self.current_token() {
-                let op_token = self.current_token();
+                let inclusive = *self.current_token() == Token::DotDotEq;
                 self.advance();
-                let inclusive = op_token == Token::DotDotEq;
 
                 let end = if matches!(
                     self.current_token(),
@@ -2060,11 +2308,22 @@ impl<'a> Parser<'a> {
     fn parse_prefix(&mut self) -> Option<Expr> {
         let span_start = self.current_span();
 
-        match self.current_token() {
+        match *self.current_token() {
             // Prefix operators
             Token::Minus => {
                 self.advance();
                 let expr = self.parse_prefix()?;
+                // Fold the sign into an integer literal so that `-9223372036854775808`
+                // is a single in-range value rather than a negated overflowing one
+                match expr {
+                    Expr::Literal(Literal::Int(n)) => {
+                        return Some(Expr::Literal(Literal::Int(n.wrapping_neg())));
+                    },
+                    Expr::Literal(Literal::TypedInt(n, suffix)) => {
+                        return Some(Expr::Literal(Literal::TypedInt(n.wrapping_neg(), suffix)));
+                    },
+                    _ => {},
+                }
                 let span = self.span_from_start(span_start);
                 Some(Expr::Unary(UnaryExpr {
                     op: UnOp::Neg,
@@ -2116,11 +2375,11 @@ impl<'a> Parser<'a> {
             },
 
             // Literals
-            Token::Number(n) => {
+            Token::Number(n, _, suffix) => {
                 self.advance();
-                Some(Expr::Literal(Literal::Int(n as i64)))
+                Some(Expr::Literal(Literal::from_int(n as i64, suffix)))
             },
-            Token::Float(n) => {
+            Token::Float(n, _) => {
                 self.advance();
                 Some(Expr::Literal(Literal::Float(n)))
             },
@@ -2141,19 +2400,27 @@ impl<'a> Parser<'a> {
                 Some(Expr::Literal(Literal::Bool(false)))
             },
 
+            // `move` is only a keyword in front of a closure
+            Token::Ident(name)
+                if matches!(self.peek_token(), Token::Pipe | Token::OrOr) && name.is("move") =>
+            {
+                self.advance();
+                self.parse_closure_pipe(true)
+            },
+
             // Identifiers and paths
             Token::Ident(_) | Token::Self_ | Token::SelfUpper | Token::Super | Token::Crate => {
                 self.parse_path_expr()
             },
 
             // Parenthesized expressions, tuples, closures
-            Token::LParen => self.parse_paren_or_tuple_or_closure(),
+            Token::LParen => self.with_struct_literals(Self::parse_paren_or_tuple_or_closure),
 
             // Block, struct literal, or closure
             Token::LBrace => self.parse_block_expr(),
 
             // Array literal
-            Token::LBracket => self.parse_array_expr(),
+            Token::LBracket => self.with_struct_literals(Self::parse_array_expr),
 
             // Control flow expressions
             Token::If => self.parse_if_expr(),
@@ -2168,8 +2435,8 @@ impl<'a> Parser<'a> {
             // Return expression (in expression context)
             Token::Return => {
                 self.advance();
-                let expr = if self.current_token() != Token::Semicolon
-                    && self.current_token() != Token::RBrace
+                let expr = if *self.current_token() != Token::Semicolon
+                    && *self.current_token() != Token::RBrace
                     && !self.is_at_end()
                 {
                     Some(Box::new(self.parse_expr()?))
@@ -2200,7 +2467,7 @@ impl<'a> Parser<'a> {
             },
 
             // Closure with pipe syntax: |x| x + 1
-            Token::Pipe => self.parse_closure_pipe(),
+            Token::Pipe | Token::OrOr => self.parse_closure_pipe(false),
 
             _ => {
                 self.error("expected expression");
@@ -2210,15 +2477,21 @@ impl<'a> Parser<'a> {
     }
 
     /// Parse closure with pipe syntax: |params| body
-    fn parse_closure_pipe(&mut self) -> Option<Expr> {
-        let _span_start = self.current_span();
-
+    ///
+    /// `||` introduces a closure without parameters.
+    fn parse_closure_pipe(&mut self, move_kw: bool) -> Option<Expr> {
         // Parse parameters between pipes
-        let params = self.parse_closure_params()?;
-        self.expect(Token::Pipe)?;
+        let params = if self.match_token(Token::OrOr) {
+            Vec::new()
+        } else {
+            self.expect(Token::Pipe)?;
+            let params = self.parse_closure_params()?;
+            self.expect(Token::Pipe)?;
+            params
+        };
 
         // Parse closure body (can be expression or block)
-        let body = if self.current_token() == Token::LBrace {
+        let body = if *self.current_token() == Token::LBrace {
             let block = self.parse_block()?;
             Expr::Block(block)
         } else {
@@ -2229,7 +2502,7 @@ impl<'a> Parser<'a> {
             params,
             ret_type: None,
             body: Box::new(body),
-            move_kw: false,
+            move_kw,
         }))
     }
 
@@ -2254,13 +2527,13 @@ impl<'a> Parser<'a> {
             // This is Enum::Variant syntax
             if let Some(variant) = self.parse_ident() {
                 // Check for turbofish: Enum::Variant::<T>
-                let generics = if self.current_token() == Token::ColonColon
-                    && self.peek_token() == Token::Lt
+                let generics = if *self.current_token() == Token::ColonColon
+                    && *self.peek_token() == Token::Lt
                 {
                     self.advance(); // consume ::
                     self.advance(); // consume <
                     let mut types = Vec::new();
-                    while !self.is_at_end() && self.current_token() != Token::Gt {
+                    while !self.is_at_end() && *self.current_token() != Token::Gt {
                         if let Some(ty) = self.parse_type() {
                             types.push(ty);
                         }
@@ -2268,7 +2541,7 @@ impl<'a> Parser<'a> {
                             break;
                         }
                     }
-                    self.expect(Token::Gt);
+                    self.expect_gt();
                     Some(types)
                 } else {
                     None
@@ -2276,16 +2549,7 @@ impl<'a> Parser<'a> {
 
                 // Check for tuple variant: Enum::Variant(args)
                 if self.match_token(Token::LParen) {
-                    let mut args = Vec::new();
-                    while !self.is_at_end() && self.current_token() != Token::RParen {
-                        if let Some(arg) = self.parse_expr() {
-                            args.push(arg);
-                        }
-                        if !self.match_token(Token::Comma) {
-                            break;
-                        }
-                    }
-                    self.expect(Token::RParen)?;
+                    let args = self.parse_comma_separated(Token::RParen, Self::parse_expr)?;
 
                     return Some(Expr::EnumVariant(Box::new(EnumVariantExpr {
                         path,
@@ -2296,8 +2560,9 @@ impl<'a> Parser<'a> {
                 }
 
                 // Check for struct variant: Enum::Variant { fields }
-                if self.match_token(Token::LBrace) {
+                if !self.no_struct_literal && self.match_token(Token::LBrace) {
                     let fields = self.parse_struct_fields()?;
+                    self.expect(Token::RBrace)?;
                     return Some(Expr::EnumVariant(Box::new(EnumVariantExpr {
                         path,
                         variant,
@@ -2318,7 +2583,7 @@ impl<'a> Parser<'a> {
 
         // Check for struct literal: Struct { fields }
         // But not if this looks like match arms (contains =>) or a block expression
-        if self.current_token() == Token::LBrace {
+        if *self.current_token() == Token::LBrace && !self.no_struct_literal {
             // Peek ahead to check if content has => (match arms) or looks like block
             if !self.looks_like_match_arms() && !self.looks_like_block() {
                 self.advance(); // consume LBrace
@@ -2333,6 +2598,7 @@ impl<'a> Parser<'a> {
                 } else {
                     None
                 };
+                self.expect(Token::RBrace)?;
 
                 return Some(Expr::StructLiteral(Box::new(StructLiteralExpr {
                     path,
@@ -2348,16 +2614,7 @@ impl<'a> Parser<'a> {
             // Use generics from path (turbofish already parsed by parse_path)
             let generics = path_generics.clone();
 
-            let mut args = Vec::new();
-            while !self.is_at_end() && self.current_token() != Token::RParen {
-                if let Some(arg) = self.parse_expr() {
-                    args.push(arg);
-                }
-                if !self.match_token(Token::Comma) {
-                    break;
-                }
-            }
-            self.expect(Token::RParen)?;
+            let args = self.parse_comma_separated(Token::RParen, Self::parse_expr)?;
 
             let span = self.span_from_start(span_start);
             return Some(Expr::Call(CallExpr {
@@ -2372,13 +2629,13 @@ impl<'a> Parser<'a> {
         if self.match_token(Token::Dot) {
             if let Some(field) = self.parse_ident() {
                 // Check for turbofish: method::<T>()
-                let generics = if self.current_token() == Token::ColonColon
-                    && self.peek_token() == Token::Lt
+                let generics = if *self.current_token() == Token::ColonColon
+                    && *self.peek_token() == Token::Lt
                 {
                     self.advance(); // consume ::
                     self.advance(); // consume <
                     let mut types = Vec::new();
-                    while !self.is_at_end() && self.current_token() != Token::Gt {
+                    while !self.is_at_end() && *self.current_token() != Token::Gt {
                         if let Some(ty) = self.parse_type() {
                             types.push(ty);
                         }
@@ -2386,24 +2643,15 @@ impl<'a> Parser<'a> {
                             break;
                         }
                     }
-                    self.expect(Token::Gt);
+                    self.expect_gt();
                     Some(types)
                 } else {
                     None
                 };
 
                 // Parse call arguments
-                let mut call_args = Vec::new();
                 if self.match_token(Token::LParen) {
-                    while !self.is_at_end() && self.current_token() != Token::RParen {
-                        if let Some(arg) = self.parse_expr() {
-                            call_args.push(arg);
-                        }
-                        if !self.match_token(Token::Comma) {
-                            break;
-                        }
-                    }
-                    self.expect(Token::RParen)?;
+                    let call_args = self.parse_comma_separated(Token::RParen, Self::parse_expr)?;
 
                     // This is a method call
                     return Some(Expr::MethodCall(MethodCallExpr {
@@ -2443,7 +2691,7 @@ impl<'a> Parser<'a> {
             self.expect(Token::Pipe)?;
 
             // Parse closure body (can be expression or block)
-            let body = if self.current_token() == Token::LBrace {
+            let body = if *self.current_token() == Token::LBrace {
                 let block = self.parse_block()?;
                 Expr::Block(block)
             } else {
@@ -2458,32 +2706,24 @@ impl<'a> Parser<'a> {
             }));
         }
 
-        // Parse expressions for tuple or parenthesized expression
-        let mut exprs = Vec::new();
-        loop {
-            if let Some(expr) = self.parse_expr() {
-                exprs.push(expr);
-            }
-            if !self.match_token(Token::Comma) {
-                break;
-            }
+        // A comma after the first expression makes a tuple, so `(a,)` is a
+        // one-element tuple while `(a)` is `a` in parentheses
+        let first = self.parse_expr()?;
+        if !self.match_token(Token::Comma) {
+            self.expect_or_insert(Token::RParen);
+            return Some(Expr::Paren(Box::new(first)));
         }
 
-        self.expect(Token::RParen)?;
-
-        // Single expression in parens vs tuple
-        if exprs.len() == 1 {
-            Some(exprs.into_iter().next().unwrap())
-        } else {
-            Some(Expr::Tuple(exprs))
-        }
+        let mut exprs = vec![first];
+        exprs.extend(self.parse_comma_separated(Token::RParen, Self::parse_expr)?);
+        Some(Expr::Tuple(exprs))
     }
 
     /// Parse closure parameters (between pipes)
     fn parse_closure_params(&mut self) -> Option<Vec<Param>> {
         let mut params = Vec::new();
 
-        while !self.is_at_end() && self.current_token() != Token::Pipe {
+        while !self.is_at_end() && *self.current_token() != Token::Pipe {
             let mutable = self.match_token(Token::Mut);
             let name = self.parse_ident()?;
 
@@ -2503,28 +2743,27 @@ impl<'a> Parser<'a> {
         Some(params)
     }
 
-    /// Parse struct literal fields: `{ field: expr, field2 }` (shorthand)
+    /// Parse struct literal fields: `field: expr, field2` (shorthand)
+    ///
+    /// Called after the opening `{`. Stops before a base struct `..base`
+    /// (struct update syntax) or the closing `}`, which the caller handles.
     ///
     /// Handles:
     /// - Named fields: `field: expr`
     /// - Shorthand: `field` (same as `field: field`)
-    /// - Base struct: `..base` (struct update syntax)
     fn parse_struct_fields(&mut self) -> Option<Vec<StructField>> {
         let mut fields = Vec::new();
 
-        self.expect(Token::LBrace)?;
-
-        while !self.is_at_end() && self.current_token() != Token::RBrace {
-            // Check for base struct: ..base
-            if self.match_token(Token::DotDot) {
-                // Base struct is handled separately in parse_path_expr
+        while !self.is_at_end() && *self.current_token() != Token::RBrace {
+            // Base struct is handled by the caller
+            if *self.current_token() == Token::DotDot {
                 break;
             }
 
             let field_name = self.parse_ident()?;
 
             // Check for shorthand: just the field name (no colon)
-            if self.current_token() != Token::Colon {
+            if *self.current_token() != Token::Colon {
                 // Shorthand: field name is used as both name and value
                 fields.push(StructField {
                     name: field_name,
@@ -2552,7 +2791,6 @@ impl<'a> Parser<'a> {
             }
         }
 
-        self.expect(Token::RBrace)?;
         Some(fields)
     }
 
@@ -2561,7 +2799,7 @@ impl<'a> Parser<'a> {
         let params = self.parse_params()?;
         let ret_type = self.parse_return_type();
 
-        let body = if self.current_token() == Token::LBrace {
+        let body = if *self.current_token() == Token::LBrace {
             let block = self.parse_block()?;
             Expr::Block(block)
         } else {
@@ -2587,15 +2825,16 @@ impl<'a> Parser<'a> {
         let span_start = self.current_span();
 
         self.expect(Token::LBrace)?;
+        let outer = std::mem::replace(&mut self.no_struct_literal, false);
 
         let mut stmts = Vec::new();
         let mut trailing = None;
 
-        while !self.is_at_end() && self.current_token() != Token::RBrace {
+        while !self.is_at_end() && *self.current_token() != Token::RBrace {
             if let Some(stmt) = self.parse_stmt() {
                 // Check if this is an expression statement that could be trailing
                 if let Stmt::Expr(_) = stmt {
-                    if self.current_token() == Token::RBrace || self.is_at_end() {
+                    if *self.current_token() == Token::RBrace || self.is_at_end() {
                         // This is a trailing expression
                         if let Stmt::Expr(expr) = stmt {
                             trailing = Some(Box::new(expr));
@@ -2609,7 +2848,8 @@ impl<'a> Parser<'a> {
             }
         }
 
-        self.expect(Token::RBrace)?;
+        self.expect_or_insert(Token::RBrace);
+        self.no_struct_literal = outer;
 
         let span = self.span_from_start(span_start);
 
@@ -2644,8 +2884,8 @@ impl<'a> Parser<'a> {
                 | Token::SelfUpper
                 | Token::Super
                 | Token::Crate
-                | Token::Number(_)
-                | Token::Float(_)
+                | Token::Number(..)
+                | Token::Float(..)
                 | Token::String(_)
                 | Token::True
                 | Token::False
@@ -2662,11 +2902,19 @@ impl<'a> Parser<'a> {
 
         self.expect(Token::If)?;
 
-        let cond = self.parse_expr()?;
+        let let_pattern = if self.match_token(Token::Let) {
+            let pattern = self.parse_pattern()?;
+            self.expect(Token::Eq)?;
+            Some(pattern)
+        } else {
+            None
+        };
+
+        let cond = self.parse_expr_no_struct()?;
         let then_block = self.parse_block()?;
 
         let else_block = if self.match_token(Token::Else) {
-            if self.match_token(Token::If) {
+            if *self.current_token() == Token::If {
                 // Nested if-else as else block
                 let inner_if = self.parse_if_expr()?;
                 Some(Box::new(inner_if))
@@ -2678,6 +2926,15 @@ impl<'a> Parser<'a> {
             None
         };
 
+        if let Some(pattern) = let_pattern {
+            return Some(Expr::IfLet(IfLetExpr {
+                pattern,
+                scrutinee: Box::new(cond),
+                then_block,
+                else_block,
+            }));
+        }
+
         Some(Expr::If(IfExpr {
             cond: Box::new(cond),
             then_block,
@@ -2691,16 +2948,16 @@ impl<'a> Parser<'a> {
 
         self.expect(Token::Match)?;
 
-        let scrutinee = self.parse_expr()?;
+        let scrutinee = self.parse_expr_no_struct()?;
 
         self.expect(Token::LBrace)?;
 
         let mut arms = Vec::new();
-        while !self.is_at_end() && self.current_token() != Token::RBrace {
+        while !self.is_at_end() && *self.current_token() != Token::RBrace {
             let pattern = self.parse_pattern()?;
 
             let guard = if self.match_token(Token::If) {
-                self.parse_expr()
+                self.parse_expr_no_struct()
             } else {
                 None
             };
@@ -2748,8 +3005,7 @@ impl<'a> Parser<'a> {
         self.expect(Token::Loop)?;
         let body = self.parse_block()?;
 
-        // Loop expression returns never type conceptually
-        Some(Expr::Block(body))
+        Some(Expr::Loop(LoopExpr { body, label: None }))
     }
 
     /// Parse async expression
@@ -2771,17 +3027,28 @@ impl<'a> Parser<'a> {
 
         self.expect(Token::LBracket)?;
 
-        let mut elements = Vec::new();
-        while !self.is_at_end() && self.current_token() != Token::RBracket {
-            if let Some(expr) = self.parse_expr() {
-                elements.push(expr);
-            }
-            if !self.match_token(Token::Comma) {
-                break;
-            }
+        if self.match_token(Token::RBracket) {
+            return Some(Expr::Array(Vec::new()));
+        }
+
+        let first = self.parse_expr()?;
+
+        // `[value; count]`
+        if self.match_token(Token::Semicolon) {
+            let count = self.parse_expr()?;
+            self.expect_or_insert(Token::RBracket);
+            return Some(Expr::ArrayRepeat {
+                value: Box::new(first),
+                count: Box::new(count),
+            });
         }
 
-        self.expect(Token::RBracket)?;
+        let mut elements = vec![first];
+        if self.match_token(Token::Comma) {
+            elements.extend(self.parse_comma_separated(Token::RBracket, Self::parse_expr)?);
+        } else {
+            self.expect_or_insert(Token::RBracket);
+        }
 
         Some(Expr::Array(elements))
     }
@@ -2792,19 +3059,36 @@ impl<'a> Parser<'a> {
 
     /// Parse pattern
     fn parse_pattern(&mut self) -> Option<Pattern> {
-        match self.current_token() {
+        match *self.current_token() {
             Token::Underscore => {
                 self.advance();
                 Some(Pattern::Wildcard)
             },
+            Token::Ident(_) if matches!(self.peek_token(), Token::ColonColon | Token::LParen) => {
+                self.parse_path_pattern()
+            },
             Token::Ident(name) => {
                 self.advance();
                 let _mutable = false; // Could check for 'mut' prefix
                 Some(Pattern::Ident(name, Mutability::Immutable))
             },
-            Token::Number(n) => {
+            Token::Number(n, _, suffix) => {
                 self.advance();
-                Some(Pattern::Literal(Literal::Int(n as i64)))
+                Some(Pattern::Literal(Literal::from_int(n as i64, suffix)))
+            },
+            Token::Minus => match *self.peek_token() {
+                Token::Number(n, _, suffix) => {
+                    self.advance();
+                    self.advance();
+                    Some(Pattern::Literal(Literal::from_int(
+                        (n as i64).wrapping_neg(),
+                        suffix,
+                    )))
+                },
+                _ => {
+                    self.error("expected pattern");
+                    None
+                },
             },
             Token::True => {
                 self.advance();
@@ -2841,27 +3125,7 @@ impl<'a> Parser<'a> {
                 self.expect(Token::RParen)?;
                 Some(Pattern::Tuple(patterns))
             },
-            Token::Self_ | Token::SelfUpper => {
-                // Path pattern (could be enum variant)
-                let path = self.parse_path();
-
-                // Check for tuple struct pattern
-                if self.match_token(Token::LParen) {
-                    let mut patterns = Vec::new();
-                    while !self.is_at_end() && self.current_token() != Token::RParen {
-                        if let Some(pat) = self.parse_pattern() {
-                            patterns.push(pat);
-                        }
-                        if !self.match_token(Token::Comma) {
-                            break;
-                        }
-                    }
-                    self.expect(Token::RParen)?;
-                    Some(Pattern::TupleStruct(path, patterns))
-                } else {
-                    Some(Pattern::Path(path))
-                }
-            },
+            Token::Self_ | Token::SelfUpper => self.parse_path_pattern(),
             _ => {
                 self.error("expected pattern");
                 None
@@ -2869,15 +3133,37 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Parse path pattern (could be enum variant) or tuple struct pattern
+    fn parse_path_pattern(&mut self) -> Option<Pattern> {
+        let path = self.parse_path();
+
+        // Check for tuple struct pattern
+        if self.match_token(Token::LParen) {
+            let mut patterns = Vec::new();
+            while !self.is_at_end() && *self.current_token() != Token::RParen {
+                if let Some(pat) = self.parse_pattern() {
+                    patterns.push(pat);
+                }
+                if !self.match_token(Token::Comma) {
+                    break;
+                }
+            }
+            self.expect(Token::RParen)?;
+            Some(Pattern::TupleStruct(path, patterns))
+        } else {
+            Some(Pattern::Path(path))
+        }
+    }
+
     // ========================================================================
     // TYPE PARSING
     // ========================================================================
 
     /// Parse type expression
     fn parse_type(&mut self) -> Option<Type> {
-        match self.current_token() {
-            Token::Ident(name) => {
-                self.advance();
+        match *self.current_token() {
+            Token::Ident(_) | Token::SelfUpper => {
+                let name = self.parse_ident()?;
                 let path = Path {
                     segments: vec![PathSegment {
                         ident: name,
@@ -2888,7 +3174,7 @@ impl<'a> Parser<'a> {
                 // Check for generic arguments
                 if self.match_token(Token::Lt) {
                     let mut args = Vec::new();
-                    while !self.is_at_end() && self.current_token() != Token::Gt {
+                    while !self.is_at_end() && *self.current_token() != Token::Gt {
                         if let Some(ty) = self.parse_type() {
                             args.push(ty);
                         }
@@ -2896,7 +3182,7 @@ impl<'a> Parser<'a> {
                             break;
                         }
                     }
-                    self.expect(Token::Gt)?;
+                    self.expect_gt()?;
                     return Some(Type::Generic(Box::new(Type::Path(path)), args));
                 }
 
@@ -2926,6 +3212,10 @@ impl<'a> Parser<'a> {
                     Some(Type::Tuple(types))
                 }
             },
+            Token::Bang => {
+                self.advance();
+                Some(Type::Never)
+            },
             Token::Ampersand => {
                 self.advance();
                 let mutable = self.match_token(Token::Mut);
@@ -2945,10 +3235,12 @@ impl<'a> Parser<'a> {
 
                 if self.match_token(Token::Semicolon) {
                     // Array type: [T; N]
-                    // For now, we just parse the type and ignore size
-                    let _size = self.parse_expr();
+                    let len = match self.parse_expr()? {
+                        Expr::Literal(Literal::Int(n)) if n >= 0 => ArrayLen::Lit(n as usize),
+                        size => ArrayLen::Expr(Box::new(size)),
+                    };
                     self.expect(Token::RBracket)?;
-                    Some(Type::Array(Box::new(ty), 0)) // Size would need evaluation
+                    Some(Type::Array(Box::new(ty), len))
                 } else {
                     self.expect(Token::RBracket)?;
                     Some(Type::Slice(Box::new(ty)))
@@ -2956,7 +3248,11 @@ impl<'a> Parser<'a> {
             },
             Token::Star => {
                 self.advance();
+                // `*const T` and `*mut T`; a bare `*T` is treated as const
                 let mutable = self.match_token(Token::Mut);
+                if !mutable {
+                    self.match_token(Token::Const);
+                }
                 let ty = self.parse_type()?;
                 Some(Type::Pointer(
                     Box::new(ty),
@@ -2972,7 +3268,7 @@ impl<'a> Parser<'a> {
                 self.expect(Token::LParen)?;
 
                 let mut param_types = Vec::new();
-                while !self.is_at_end() && self.current_token() != Token::RParen {
+                while !self.is_at_end() && *self.current_token() != Token::RParen {
                     if let Some(ty) = self.parse_type() {
                         param_types.push(ty);
                     }
@@ -3023,7 +3319,7 @@ impl<'a> Parser<'a> {
         let mut segments = Vec::new();
 
         loop {
-            let ident = match self.current_token() {
+            let ident = match *self.current_token() {
                 Token::Ident(sym) => {
                     self.advance();
                     sym
@@ -3049,11 +3345,11 @@ impl<'a> Parser<'a> {
 
             // Check for generic arguments (turbofish: ::<T>)
             let args =
-                if self.current_token() == Token::ColonColon && self.peek_token() == Token::Lt {
+                if *self.current_token() == Token::ColonColon && *self.peek_token() == Token::Lt {
                     self.advance(); // consume ::
                     self.advance(); // consume <
                     let mut types = Vec::new();
-                    while !self.is_at_end() && self.current_token() != Token::Gt {
+                    while !self.is_at_end() && *self.current_token() != Token::Gt {
                         if let Some(ty) = self.parse_type() {
                             types.push(ty);
                         }
@@ -3061,7 +3357,7 @@ impl<'a> Parser<'a> {
                             break;
                         }
                     }
-                    self.expect(Token::Gt);
+                    self.expect_gt();
                     Some(types)
                 } else {
                     None
@@ -3088,7 +3384,7 @@ impl<'a> Parser<'a> {
 
     /// Parse identifier
     fn parse_ident(&mut self) -> Option<Symbol> {
-        let sym = match self.current_token() {
+        let sym = match *self.current_token() {
             Token::Ident(s) => {
                 self.advance();
                 s
@@ -3122,25 +3418,26 @@ impl<'a> Parser<'a> {
     // ========================================================================
 
     /// Get current token
-    fn current_token(&self) -> Token {
-        self.tokens
-            .get(self.position)
-            .map(|t| t.token.clone())
-            .unwrap_or(Token::Eof)
+    ///
+    /// Borrowed rather than cloned: this is called several times per token,
+    /// and the parser only ever needs to inspect it.
+    fn current_token(&self) -> &Token {
+        self.tokens.current().map_or(&EOF, |t| &t.token)
     }
 
     /// Get current token with span
     #[allow(dead_code)]
     fn current_token_with_span(&self) -> Option<&TokenWithSpan> {
-        self.tokens.get(self.position)
+        self.tokens.current()
     }
 
     /// Get current span
     fn current_span(&self) -> Span {
-        self.tokens
-            .get(self.position)
-            .map(|t| t.span)
-            .unwrap_or(Span::DUMMY)
+        let span = self.tokens.current().map(|t| t.span).unwrap_or(Span::DUMMY);
+        match self.file_id {
+            Some(file_id) => span.with_file_id(file_id),
+            None => span,
+        }
     }
 
     /// Create span from start position to current
@@ -3157,36 +3454,28 @@ impl<'a> Parser<'a> {
 
     /// Check if at end of tokens
     fn is_at_end(&self) -> bool {
-        self.position >= self.tokens.len()
+        self.tokens.is_at_end()
     }
 
     /// Peek at next token
-    fn peek_token(&self) -> Token {
-        self.tokens
-            .get(self.position + 1)
-            .map(|t| t.token.clone())
-            .unwrap_or(Token::Eof)
+    fn peek_token(&self) -> &Token {
+        self.tokens.peek().map_or(&EOF, |t| &t.token)
     }
 
     /// Peek at current token without consuming
     #[allow(dead_code)]
-    fn peek_token_raw(&self) -> Token {
-        self.tokens
-            .get(self.position)
-            .map(|t| t.token.clone())
-            .unwrap_or(Token::Eof)
+    fn peek_token_raw(&self) -> &Token {
+        self.tokens.current().map_or(&EOF, |t| &t.token)
     }
 
     /// Advance to next token
     fn advance(&mut self) {
-        if !self.is_at_end() {
-            self.position += 1;
-        }
+        self.tokens.advance();
     }
 
     /// Match and consume token
     fn match_token(&mut self, expected: Token) -> bool {
-        if self.current_token() == expected {
+        if *self.current_token() == expected {
             self.advance();
             true
         } else {
@@ -3196,7 +3485,7 @@ impl<'a> Parser<'a> {
 
     /// Expect specific token
     fn expect(&mut self, expected: Token) -> Option<()> {
-        if self.current_token() == expected {
+        if *self.current_token() == expected {
             self.advance();
             Some(())
         } else {
@@ -3209,6 +3498,114 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Expect `expected`, or report it missing and go on as if it were there
+    ///
+    /// For `;` and closing delimiters, whose absence leaves no doubt about
+    /// what was meant. Nothing is consumed on a mismatch, so the current
+    /// production finishes from the token that was found instead of failing
+    /// and leaving recovery to skip input, which would cascade into further
+    /// errors.
+    fn expect_or_insert(&mut self, expected: Token) {
+        debug_assert!(matches!(
+            expected,
+            Token::Semicolon | Token::RParen | Token::RBracket | Token::RBrace
+        ));
+        let _ = self.expect(expected);
+    }
+
+    /// Expect any one of `expected`, consuming and returning it
+    ///
+    /// For parse points that accept several tokens; the error lists them
+    /// all, as in "expected one of `;`, `}`, found `let`".
+    fn expect_one_of(&mut self, expected: &[Token]) -> Option<Token> {
+        let current = self.current_token().clone();
+        if expected.contains(&current) {
+            self.advance();
+            return Some(current);
+        }
+        let alternatives: Vec<_> = expected.iter().map(|t| format!("`{}`", t)).collect();
+        self.error(format!(
+            "expected one of {}, found `{}`",
+            alternatives.join(", "),
+            current
+        ));
+        None
+    }
+
+    /// Expect the `>` closing a generic argument list
+    ///
+    /// The lexer reads `>>`, `>=` and `>>=` as single tokens, so in
+    /// `Vec<Vec<i32>>` the inner list is closed by the first half of a `>>`.
+    /// Such a token is split: one `>` is consumed and the rest is left at
+    /// the cursor for the enclosing list or the following `=`.
+    fn expect_gt(&mut self) -> Option<()> {
+        let rest = match self.current_token() {
+            Token::Shr => Token::Gt,
+            Token::GtEq => Token::Eq,
+            Token::ShrEq => Token::GtEq,
+            _ => return self.expect(Token::Gt),
+        };
+        let span = self.tokens.current().map_or(Span::DUMMY, |t| t.span);
+        let rest_span = Span {
+            start: span.start + 1,
+            column: span.column + 1,
+            ..span
+        };
+        self.tokens
+            .replace_current(TokenWithSpan::new(rest, rest_span));
+        Some(())
+    }
+
+    /// Parse an expression in which `Path {` is not a struct literal
+    ///
+    /// Used for `if`, `while` and `for` heads and for `match` scrutinees and
+    /// guards, where the brace opens the body or the arms. Struct literals are still allowed inside
+    /// parentheses, brackets and blocks, as in `p if p == (Point { x: 0 })`.
+    fn parse_expr_no_struct(&mut self) -> Option<Expr> {
+        let outer = std::mem::replace(&mut self.no_struct_literal, true);
+        let expr = self.parse_expr();
+        self.no_struct_literal = outer;
+        expr
+    }
+
+    /// Run `f` with struct literals allowed again, for the delimited
+    /// expressions nested in a restricted one
+    fn with_struct_literals<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let outer = std::mem::replace(&mut self.no_struct_literal, false);
+        let result = f(self);
+        self.no_struct_literal = outer;
+        result
+    }
+
+    /// Parse `f`-elements separated by commas up to and including `close`
+    ///
+    /// A single trailing comma before `close` is accepted; an empty element,
+    /// as in `a,,b`, is reported and skipped.
+    fn parse_comma_separated<T>(
+        &mut self,
+        close: Token,
+        mut f: impl FnMut(&mut Self) -> Option<T>,
+    ) -> Option<Vec<T>> {
+        let mut items = Vec::new();
+
+        while !self.is_at_end() && *self.current_token() != close {
+            if *self.current_token() == Token::Comma {
+                self.error("expected element before ','");
+                self.advance();
+                continue;
+            }
+
+            items.push(self.with_struct_literals(&mut f)?);
+
+            if !self.match_token(Token::Comma) {
+                break;
+            }
+        }
+
+        self.expect(close)?;
+        Some(items)
+    }
+
     // ========================================================================
     // OPERATOR PRECEDENCE
     // ========================================================================
@@ -3273,7 +3670,7 @@ impl<'a> Parser<'a> {
     }
 
     /// Convert token to binary operator
-    fn token_to_binop(&self, token: Token) -> Option<BinOp> {
+    fn token_to_binop(&self, token: &Token) -> Option<BinOp> {
         match token {
             Token::Plus => Some(BinOp::Add),
             Token::Minus => Some(BinOp::Sub),
@@ -3308,14 +3705,16 @@ impl<'a> Parser<'a> {
 
     /// Check if current position looks like match arms (contains =>)
     /// Used to distinguish match arms from struct fields
-    fn looks_like_match_arms(&self) -> bool {
+    fn looks_like_match_arms(&mut self) -> bool {
         // Peek ahead up to 10 tokens to check for =>
         let mut depth = 0;
-        let mut pos = self.position;
+        let mut pos = 0;
         let max_depth = 10;
 
-        while depth < max_depth && pos < self.tokens.len() {
-            let token = &self.tokens[pos];
+        while depth < max_depth {
+            let Some(token) = self.tokens.lookahead(pos) else {
+                break;
+            };
             match token.token {
                 Token::FatArrow => return true,
                 Token::LBrace | Token::LParen | Token::LBracket => depth += 1,
@@ -3339,30 +3738,29 @@ impl<'a> Parser<'a> {
 
     /// Check if current position looks like a block (expression context) vs struct fields
     /// Returns true if it looks like a block (starts with expression, not ident:)
-    fn looks_like_block(&self) -> bool {
+    fn looks_like_block(&mut self) -> bool {
         // Peek at the next non-whitespace token after {
-        let mut pos = self.position;
+        let mut pos = 0;
 
         // Skip LBrace
-        if pos < self.tokens.len() && self.tokens[pos].token == Token::LBrace {
+        if *self.current_token() == Token::LBrace {
             pos += 1;
         }
 
         // Check first token inside braces
-        if pos < self.tokens.len() {
-            let token = &self.tokens[pos];
+        if let Some(token) = self.tokens.lookahead(pos) {
             match token.token {
                 // If starts with ident followed by :, it's struct field
                 Token::Ident(_) => {
                     // Check if next token is :
-                    if pos + 1 < self.tokens.len() {
-                        return self.tokens[pos + 1].token != Token::Colon;
+                    if let Some(next) = self.tokens.lookahead(pos + 1) {
+                        return next.token != Token::Colon;
                     }
                     return true;
                 },
                 // If starts with other expression tokens, it's a block
-                Token::Number(_)
-                | Token::Float(_)
+                Token::Number(..)
+                | Token::Float(..)
                 | Token::String(_)
                 | Token::True
                 | Token::False
@@ -3433,7 +3831,7 @@ impl<'a> Parser<'a> {
         use faxc_util::diagnostic::DiagnosticBuilder;
         DiagnosticBuilder::error(message)
             .span(span)
-            .emit(&self.handler);
+            .emit(self.handler);
     }
 
     /// Report an error with expected token info
@@ -3449,7 +3847,19 @@ impl<'a> Parser<'a> {
         use faxc_util::diagnostic::DiagnosticBuilder;
         DiagnosticBuilder::error(message)
             .span(span)
-            .emit(&self.handler);
+            .emit(self.handler);
+    }
+
+    /// Skip the rest of the input once the handler's error limit is reached
+    ///
+    /// Every parsing loop stops at end of input, so this unwinds the parser
+    /// without reporting the cascade of errors that would be dropped anyway.
+    fn bail_on_error_limit(&mut self) -> bool {
+        if !self.handler.error_limit_reached() {
+            return false;
+        }
+        self.tokens.skip_to_end();
+        true
     }
 
     /// Recover to synchronization point
@@ -3460,6 +3870,10 @@ impl<'a> Parser<'a> {
     /// - Block boundaries ({, })
     /// - Top-level item keywords
     fn recover_to_sync_point(&mut self) {
+        if self.bail_on_error_limit() {
+            return;
+        }
+        self.recoveries += 1;
         loop {
             match self.current_token() {
                 Token::Eof => break,
@@ -3485,6 +3899,10 @@ impl<'a> Parser<'a> {
 
     /// Recover to statement synchronization point
     fn recover_to_stmt_sync(&mut self) {
+        if self.bail_on_error_limit() {
+            return;
+        }
+        self.recoveries += 1;
         loop {
             match self.current_token() {
                 Token::Eof | Token::RBrace => break,
@@ -3648,49 +4066,190 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_unary_negation() {
-        let (expr, handler) = parse_expr_source("-x");
+    fn test_parse_turbofish_call() {
+        let (expr, handler) = parse_expr_source("foo::<i32>(baz)");
         assert!(!handler.has_errors());
-        assert!(matches!(expr, Some(Expr::Unary(u)) if u.op == UnOp::Neg));
+
+        let Some(Expr::Call(call)) = expr else {
+            panic!("Expected call, got {:?}", expr);
+        };
+        assert!(matches!(call.generics.as_deref(), Some([Type::Path(_)])));
+        assert_eq!(call.args.len(), 1);
     }
 
     #[test]
-    fn test_parse_unary_not() {
-        let (expr, handler) = parse_expr_source("!flag");
-        assert!(!handler.has_errors());
-        assert!(matches!(expr, Some(Expr::Unary(u)) if u.op == UnOp::Not));
+    fn test_parse_nested_generic_type() {
+        let (ast, handler) = parse_source("fn f(v: Vec<Vec<i32>>) { let w: Vec<Vec<u8>>= v; }");
+        assert!(!handler.has_errors(), "{:?}", handler.diagnostics());
+
+        let Item::Fn(f) = &ast[0] else {
+            panic!("Expected function, got {:?}", ast[0]);
+        };
+        let Type::Generic(outer, args) = &f.params[0].ty else {
+            panic!("Expected generic type, got {:?}", f.params[0].ty);
+        };
+        assert!(matches!(**outer, Type::Path(_)));
+        assert!(matches!(args.as_slice(), [Type::Generic(_, inner)] if inner.len() == 1));
+        assert_eq!(f.body.stmts.len(), 1);
     }
 
     #[test]
-    fn test_parse_unary_bitwise_not() {
-        let (expr, handler) = parse_expr_source("~mask");
-        assert!(!handler.has_errors());
-        assert!(matches!(expr, Some(Expr::Unary(u)) if u.op == UnOp::BitNot));
+    fn test_parse_nested_generic_turbofish() {
+        let (expr, handler) = parse_expr_source("foo::<Vec<i32>>()");
+        assert!(!handler.has_errors(), "{:?}", handler.diagnostics());
+
+        let Some(Expr::Call(call)) = expr else {
+            panic!("Expected call, got {:?}", expr);
+        };
+        assert!(matches!(
+            call.generics.as_deref(),
+            Some([Type::Generic(_, _)])
+        ));
+        assert!(call.args.is_empty());
     }
 
     #[test]
-    fn test_parse_parenthesized() {
-        let (expr, handler) = parse_expr_source("(1 + 2) * 3");
-        assert!(!handler.has_errors());
-
-        if let Some(Expr::Binary(b)) = expr {
-            assert_eq!(b.op, BinOp::Mul);
-            // Left side should be the parenthesized addition
-            assert!(matches!(*b.left, Expr::Binary(ref lb) if lb.op == BinOp::Add));
-        } else {
-            panic!("Expected binary expression");
+    fn test_parse_bare_angle_brackets_are_comparisons() {
+        // Without `::<`, `<` and `>` compare, left to right
+        for source in ["a < b > c", "foo<bar>(baz)"] {
+            let (expr, handler) = parse_expr_source(source);
+            assert!(!handler.has_errors(), "{}", source);
+
+            let Some(Expr::Binary(outer)) = expr else {
+                panic!("Expected comparison for {}, got {:?}", source, expr);
+            };
+            assert_eq!(outer.op, BinOp::Gt);
+            assert!(matches!(*outer.left, Expr::Binary(ref inner) if inner.op == BinOp::Lt));
         }
     }
 
     #[test]
-    fn test_parse_tuple() {
-        let (expr, handler) = parse_expr_source("(1, 2, 3)");
+    fn test_parse_cast_then_comparison() {
+        let (expr, handler) = parse_expr_source("x as i64 < y");
         assert!(!handler.has_errors());
-        assert!(matches!(expr, Some(Expr::Tuple(v)) if v.len() == 3));
+
+        let Some(Expr::Binary(b)) = expr else {
+            panic!("Expected comparison, got {:?}", expr);
+        };
+        assert_eq!(b.op, BinOp::Lt);
+        assert!(matches!(*b.left, Expr::Cast(_, Type::Path(_))));
     }
 
     #[test]
-    fn test_parse_array() {
+    fn test_parse_cast_to_generic_type() {
+        for source in ["x as Foo<T>", "x as Foo<Bar<T>>", "x as Foo<T> < y"] {
+            let (expr, handler) = parse_expr_source(source);
+            assert!(
+                !handler.has_errors(),
+                "{}: {:?}",
+                source,
+                handler.diagnostics()
+            );
+
+            let cast = match expr {
+                Some(Expr::Binary(b)) if b.op == BinOp::Lt => *b.left,
+                Some(expr) => expr,
+                None => panic!("Expected cast for {}", source),
+            };
+            assert!(
+                matches!(cast, Expr::Cast(_, Type::Generic(_, ref args)) if args.len() == 1),
+                "{}: {:?}",
+                source,
+                cast
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_unary_negation() {
+        let (expr, handler) = parse_expr_source("-x");
+        assert!(!handler.has_errors());
+        assert!(matches!(expr, Some(Expr::Unary(u)) if u.op == UnOp::Neg));
+    }
+
+    #[test]
+    fn test_parse_negative_literal_is_folded() {
+        let (expr, handler) = parse_expr_source("-128");
+        assert!(!handler.has_errors());
+        assert!(matches!(expr, Some(Expr::Literal(Literal::Int(-128)))));
+
+        let (expr, _) = parse_expr_source("-9223372036854775808");
+        assert!(matches!(expr, Some(Expr::Literal(Literal::Int(i64::MIN)))));
+
+        // Explicit grouping keeps the negation as an operator
+        let (expr, _) = parse_expr_source("-(128)");
+        assert!(matches!(expr, Some(Expr::Unary(u)) if u.op == UnOp::Neg));
+    }
+
+    #[test]
+    fn test_parse_negative_literal_pattern() {
+        let (expr, handler) = parse_expr_source("match x { -128 => 1, _ => 0 }");
+        assert!(!handler.has_errors());
+        match expr {
+            Some(Expr::Match(m)) => assert!(matches!(
+                m.arms[0].pattern,
+                Pattern::Literal(Literal::Int(-128))
+            )),
+            other => panic!("expected match expression, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_unary_not() {
+        let (expr, handler) = parse_expr_source("!flag");
+        assert!(!handler.has_errors());
+        assert!(matches!(expr, Some(Expr::Unary(u)) if u.op == UnOp::Not));
+    }
+
+    #[test]
+    fn test_parse_unary_bitwise_not() {
+        let (expr, handler) = parse_expr_source("~mask");
+        assert!(!handler.has_errors());
+        assert!(matches!(expr, Some(Expr::Unary(u)) if u.op == UnOp::BitNot));
+    }
+
+    #[test]
+    fn test_parse_parenthesized() {
+        let (expr, handler) = parse_expr_source("(1 + 2) * 3");
+        assert!(!handler.has_errors());
+
+        if let Some(Expr::Binary(b)) = expr {
+            assert_eq!(b.op, BinOp::Mul);
+            // Left side should be the parenthesized addition
+            match &*b.left {
+                Expr::Paren(inner) => {
+                    assert!(matches!(**inner, Expr::Binary(ref lb) if lb.op == BinOp::Add))
+                },
+                other => panic!("Expected parenthesized expression, got {:?}", other),
+            }
+        } else {
+            panic!("Expected binary expression");
+        }
+    }
+
+    #[test]
+    fn test_parse_paren_is_preserved() {
+        let (expr, handler) = parse_expr_source("(1 + 2)");
+        assert!(!handler.has_errors());
+        assert!(matches!(expr, Some(Expr::Paren(inner)) if matches!(*inner, Expr::Binary(_))));
+
+        // The AST export shows the grouping as its own node
+        let (ast, handler) = parse_source("fn main() { let x = (1 + 2); }");
+        assert!(!handler.has_errors());
+        let dot = ast_to_dot(&ast);
+        assert!(dot.contains("[label=\"Paren\"];"), "{}", dot);
+        assert!(dot.contains("[label=\"Binary Add\"];"), "{}", dot);
+    }
+
+    #[test]
+    fn test_parse_tuple() {
+        let (expr, handler) = parse_expr_source("(1, 2, 3)");
+        assert!(!handler.has_errors());
+        assert!(matches!(expr, Some(Expr::Tuple(v)) if v.len() == 3));
+    }
+
+    #[test]
+    fn test_parse_array() {
         let (expr, handler) = parse_expr_source("[1, 2, 3]");
         assert!(!handler.has_errors());
         assert!(matches!(expr, Some(Expr::Array(v)) if v.len() == 3));
@@ -3724,6 +4283,54 @@ mod tests {
         assert!(matches!(expr, Some(Expr::If(_))));
     }
 
+    #[test]
+    fn test_parse_if_let_expression() {
+        let (expr, handler) = parse_expr_source("if let Some(x) = o { x } else { 0 }");
+        assert!(!handler.has_errors());
+        match expr {
+            Some(Expr::IfLet(if_let)) => {
+                assert!(
+                    matches!(&if_let.pattern, Pattern::TupleStruct(_, pats) if pats.len() == 1)
+                );
+                assert!(matches!(*if_let.scrutinee, Expr::Path(_)));
+                assert!(if_let.else_block.is_some());
+            },
+            other => panic!("expected if-let, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_while_let_statement() {
+        let (ast, handler) = parse_source("fn f() { while let Some(x) = next() { x; } }");
+        assert!(!handler.has_errors());
+
+        if let Item::Fn(fn_item) = &ast[0] {
+            assert!(matches!(&fn_item.body.stmts[0], Stmt::WhileLet(w)
+                if matches!(w.pattern, Pattern::TupleStruct(_, _))));
+        } else {
+            panic!("Expected function item");
+        }
+    }
+
+    #[test]
+    fn test_parse_let_else() {
+        let (ast, handler) = parse_source("fn f() { let Some(x) = opt else { return; }; x }");
+        assert!(!handler.has_errors());
+
+        if let Item::Fn(fn_item) = &ast[0] {
+            match &fn_item.body.stmts[0] {
+                Stmt::Let(l) => {
+                    assert!(matches!(l.pattern, Pattern::TupleStruct(_, _)));
+                    assert!(l.init.is_some());
+                    assert!(matches!(&l.else_block, Some(b) if b.stmts.len() == 1));
+                },
+                other => panic!("expected let, got {:?}", other),
+            }
+        } else {
+            panic!("Expected function item");
+        }
+    }
+
     #[test]
     fn test_parse_match_expression() {
         let source = "match x { 0 => \"zero\", _ => \"other\" }";
@@ -3732,6 +4339,69 @@ mod tests {
         assert!(matches!(expr, Some(Expr::Match(m)) if m.arms.len() == 2));
     }
 
+    /// Guard of the first arm of a match expression
+    fn first_guard(source: &str) -> Expr {
+        let (expr, handler) = parse_expr_source(source);
+        assert!(
+            !handler.has_errors(),
+            "{}: {:?}",
+            source,
+            handler.diagnostics()
+        );
+        match expr {
+            Some(Expr::Match(m)) if m.arms.len() == 2 => {
+                m.arms[0].guard.clone().expect("expected a guard")
+            },
+            other => panic!("expected two-arm match, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_match_guards() {
+        let guard = first_guard("match x { n if n > 0 => a, _ => b }");
+        assert!(matches!(guard, Expr::Binary(b) if b.op == BinOp::Gt));
+
+        // A struct literal in a guard must be parenthesized
+        let guard = first_guard("match p { q if q == (Point { x: 0 }) => a, _ => b }");
+        assert!(matches!(guard, Expr::Binary(b)
+            if matches!(&*b.right, Expr::Paren(e) if matches!(**e, Expr::StructLiteral(_)))));
+
+        let guard = first_guard("match n { n if { compute(n) } => a, _ => b }");
+        assert!(matches!(guard, Expr::Block(_)));
+    }
+
+    #[test]
+    fn test_parse_match_variant_patterns() {
+        let source = "match c { Shape::Circle(r) => r, Empty => 0, Square(s) => s }";
+        let (expr, handler) = parse_expr_source(source);
+        assert!(!handler.has_errors());
+        match expr {
+            Some(Expr::Match(m)) => {
+                assert!(matches!(&m.arms[0].pattern, Pattern::TupleStruct(p, pats)
+                    if p.segments.len() == 2 && pats.len() == 1));
+                assert!(matches!(&m.arms[1].pattern, Pattern::Ident(_, _)));
+                assert!(matches!(&m.arms[2].pattern, Pattern::TupleStruct(_, _)));
+            },
+            other => panic!("expected match, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_array_repeat() {
+        let (expr, handler) = parse_expr_source("[0; 5]");
+        assert!(!handler.has_errors());
+        assert!(matches!(
+            expr,
+            Some(Expr::ArrayRepeat { value, count })
+                if matches!(*value, Expr::Literal(Literal::Int(0)))
+                    && matches!(*count, Expr::Literal(Literal::Int(5)))
+        ));
+
+        let (expr, handler) = parse_expr_source("[1, 2, 3]");
+        assert!(!handler.has_errors());
+        assert!(matches!(expr, Some(Expr::Array(v)) if v.len() == 3));
+    }
+
     #[test]
     fn test_parse_block() {
         let (expr, handler) = parse_expr_source("{ let x = 1; x + 1 }");
@@ -3746,6 +4416,17 @@ mod tests {
         assert!(matches!(expr, Some(Expr::Closure(c))));
     }
 
+    #[test]
+    fn test_parse_closure_params_and_move() {
+        let (expr, handler) = parse_expr_source("|x: i32, y| x + y");
+        assert!(!handler.has_errors());
+        assert!(matches!(expr, Some(Expr::Closure(c)) if c.params.len() == 2 && !c.move_kw));
+
+        let (expr, handler) = parse_expr_source("move || 1");
+        assert!(!handler.has_errors());
+        assert!(matches!(expr, Some(Expr::Closure(c)) if c.params.is_empty() && c.move_kw));
+    }
+
     #[test]
     fn test_parse_closure_fn_syntax() {
         let (expr, handler) = parse_expr_source("fn(x: i32) -> i32 { x + 1 }");
@@ -3776,6 +4457,39 @@ mod tests {
         assert!(!handler.has_errors());
     }
 
+    /// Struct literal initializing the single `let` in `source`'s function
+    fn let_struct_literal(source: &str) -> StructLiteralExpr {
+        let (ast, handler) = parse_source(source);
+        assert!(
+            !handler.has_errors(),
+            "{}: {:?}",
+            source,
+            handler.diagnostics()
+        );
+        match &ast[0] {
+            Item::Fn(f) => match &f.body.stmts[..] {
+                [Stmt::Let(LetStmt {
+                    init: Some(Expr::StructLiteral(lit)),
+                    ..
+                })] => (**lit).clone(),
+                other => panic!("expected a struct literal let, got {:?}", other),
+            },
+            other => panic!("expected function, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_let_struct_literal() {
+        let lit = let_struct_literal("fn f() { let p = P { x: 1 }; }");
+        assert_eq!(lit.fields.len(), 1);
+        assert!(!lit.fields[0].is_shorthand);
+        assert!(lit.base.is_none());
+
+        let lit = let_struct_literal("fn f() { let p = P { x: 1, ..q }; }");
+        assert_eq!(lit.fields.len(), 1);
+        assert!(matches!(lit.base, Some(Expr::Path(_))));
+    }
+
     #[test]
     fn test_parse_return_statement() {
         let (ast, handler) = parse_source("fn foo() { return 42; }");
@@ -3795,12 +4509,90 @@ mod tests {
         assert!(!handler.has_errors());
     }
 
+    /// Whether `if_expr` is `if _ { } else if _ { } else { }`, with the
+    /// `else if` nested as an `Expr::If` in `else_block`
+    fn is_else_if_chain(if_expr: &IfExpr) -> bool {
+        match if_expr.else_block.as_deref() {
+            Some(Expr::If(else_if)) => {
+                matches!(else_if.else_block.as_deref(), Some(Expr::Block(_)))
+            },
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn test_parse_else_if_same_in_statement_and_expression() {
+        let source = "if a { } else if b { } else { }";
+
+        let (expr, handler) = parse_expr_source(source);
+        assert!(!handler.has_errors());
+        assert!(matches!(&expr, Some(Expr::If(i)) if is_else_if_chain(i)));
+
+        let (ast, handler) = parse_source(&format!("fn f() {{ {} }}", source));
+        assert!(!handler.has_errors());
+        match &ast[0] {
+            Item::Fn(f) => {
+                assert!(matches!(&f.body.stmts[0], Stmt::If(i) if is_else_if_chain(i)))
+            },
+            other => panic!("expected function, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_parse_while_statement() {
         let (ast, handler) = parse_source("fn foo() { while i < 10 { i = i + 1; } }");
         assert!(!handler.has_errors());
     }
 
+    #[test]
+    fn test_parse_for_in_statement() {
+        let (ast, handler) = parse_source("fn f() { for x in xs { x; } }");
+        assert!(!handler.has_errors());
+
+        if let Item::Fn(fn_item) = &ast[0] {
+            assert!(matches!(&fn_item.body.stmts[0], Stmt::For(f)
+                if matches!(f.pattern, Pattern::Ident(name, _) if name.is("x"))));
+        } else {
+            panic!("Expected function item");
+        }
+    }
+
+    #[test]
+    fn test_in_is_a_keyword() {
+        let (_ast, handler) = parse_source("fn f() { for x xs { } }");
+        assert!(handler
+            .diagnostics()
+            .iter()
+            .any(|d| d.message.starts_with("expected 'in'")));
+
+        // `in` can no longer name a binding
+        let (_ast, handler) = parse_source("fn f() { let in = 1; }");
+        assert!(handler.has_errors());
+    }
+
+    #[test]
+    fn test_missing_semicolon_is_inserted() {
+        let (ast, handler) = parse_source("fn f() { let x = 1 }");
+        assert_eq!(handler.error_count(), 1);
+
+        let Some(Item::Fn(f)) = ast.first() else {
+            panic!("expected function, got {:?}", ast.first());
+        };
+        assert!(matches!(&f.body.stmts[..], [Stmt::Let(l)]
+            if matches!(l.pattern, Pattern::Ident(name, _) if name.is("x"))));
+    }
+
+    #[test]
+    fn test_missing_semicolon_lists_alternatives() {
+        let (_ast, handler) = parse_source("fn f() { g() let x = 1; }");
+        let messages: Vec<_> = handler
+            .diagnostics()
+            .into_iter()
+            .map(|d| d.message)
+            .collect();
+        assert_eq!(messages, ["expected one of `;`, `}`, found `let`"]);
+    }
+
     // ========================================================================
     // ITEM TESTS
     // ========================================================================
@@ -3826,6 +4618,98 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_method_receivers() {
+        let source = "impl Point {
+            fn m(&self, x: i32) {}
+            fn consume(self) {}
+            fn grow(&mut self) {}
+            fn reset(mut self,) {}
+            fn new() {}
+        }";
+        let (ast, handler) = parse_source(source);
+        assert!(!handler.has_errors());
+
+        let Item::Impl(imp) = &ast[0] else {
+            panic!("Expected impl item");
+        };
+        let methods: Vec<&FnItem> = imp
+            .items
+            .iter()
+            .map(|member| match member {
+                ImplMember::Method(f) => f,
+                other => panic!("Expected method, got {:?}", other),
+            })
+            .collect();
+
+        assert_eq!(methods[0].self_param, Some(SelfKind::Ref));
+        assert_eq!(methods[0].params.len(), 1);
+        assert_eq!(methods[0].params[0].name.as_str(), "x");
+        assert_eq!(methods[1].self_param, Some(SelfKind::Value));
+        assert!(methods[1].params.is_empty());
+        assert_eq!(methods[2].self_param, Some(SelfKind::RefMut));
+        assert_eq!(methods[3].self_param, Some(SelfKind::MutValue));
+        assert_eq!(methods[4].self_param, None);
+    }
+
+    #[test]
+    fn test_parse_trait_impl_header() {
+        let (ast, handler) = parse_source("impl Show for Point { fn show(&self) {} }");
+        assert!(!handler.has_errors(), "{:?}", handler.diagnostics());
+
+        let Item::Impl(imp) = &ast[0] else {
+            panic!("Expected impl item");
+        };
+        assert!(matches!(&imp.trait_ref, Some(Type::Path(p)) if p.segments[0].ident.is("Show")));
+        assert!(matches!(&imp.self_ty, Type::Path(p) if p.segments[0].ident.is("Point")));
+        assert_eq!(imp.items.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_self_type() {
+        let (ast, handler) = parse_source("impl Point { fn at(x: i32) -> Self { Self::new(x) } }");
+        assert!(!handler.has_errors(), "{:?}", handler.diagnostics());
+
+        let Item::Impl(imp) = &ast[0] else {
+            panic!("Expected impl item");
+        };
+        let ImplMember::Method(at) = &imp.items[0] else {
+            panic!("Expected method");
+        };
+        assert!(matches!(&at.ret_type, Some(Type::Path(p)) if p.segments[0].ident.is("Self")));
+    }
+
+    #[test]
+    fn test_parse_never_type() {
+        let (ast, handler) = parse_source("fn f(x: !) -> ! { loop { } }");
+        assert!(!handler.has_errors(), "{:?}", handler.diagnostics());
+
+        let Item::Fn(f) = &ast[0] else {
+            panic!("Expected function item");
+        };
+        assert!(matches!(f.params[0].ty, Type::Never));
+        assert!(matches!(f.ret_type, Some(Type::Never)));
+    }
+
+    #[test]
+    fn test_parse_loop_with_break_value() {
+        let (ast, handler) = parse_source("fn f() { let x = loop { break 1; }; }");
+        assert!(!handler.has_errors(), "{:?}", handler.diagnostics());
+
+        let Some(Item::Fn(f)) = ast.first() else {
+            panic!("expected function, got {:?}", ast.first());
+        };
+        let [Stmt::Let(let_stmt)] = &f.body.stmts[..] else {
+            panic!("expected let, got {:?}", f.body.stmts);
+        };
+        let Some(Expr::Loop(loop_expr)) = &let_stmt.init else {
+            panic!("expected loop, got {:?}", let_stmt.init);
+        };
+        assert!(matches!(loop_expr.body.trailing.as_deref(),
+            Some(Expr::Break(Some(value), None))
+                if matches!(**value, Expr::Literal(Literal::Int(1)))));
+    }
+
     #[test]
     fn test_parse_function_async() {
         let (ast, handler) = parse_source("async fn fetch() -> str { \"data\" }");
@@ -3838,6 +4722,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_array_type_lengths() {
+        let (ast, handler) = parse_source("fn f(a: [i32; 3], b: [i32; N]) {}");
+        assert!(!handler.has_errors());
+
+        if let Item::Fn(fn_item) = &ast[0] {
+            assert!(matches!(
+                fn_item.params[0].ty,
+                Type::Array(_, ArrayLen::Lit(3))
+            ));
+            assert!(matches!(
+                fn_item.params[1].ty,
+                Type::Array(_, ArrayLen::Expr(_))
+            ));
+        } else {
+            panic!("Expected function item");
+        }
+    }
+
     #[test]
     fn test_parse_struct() {
         let (ast, handler) = parse_source("struct Point { x: f64, y: f64 }");
@@ -3900,6 +4803,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_mod_items() {
+        let (ast, handler) = parse_source("mod a { use b::f; fn g() { } } mod c;");
+        assert!(!handler.has_errors());
+        assert_eq!(ast.len(), 2);
+
+        match &ast[0] {
+            Item::Mod(m) => {
+                assert_eq!(m.name.as_str(), "a");
+                let items = m.items.as_ref().expect("inline module has items");
+                assert_eq!(items.len(), 2);
+                assert!(matches!(items[0], Item::Use(_)));
+                assert!(matches!(items[1], Item::Fn(_)));
+            },
+            other => panic!("expected mod item, got {:?}", other),
+        }
+        assert!(matches!(&ast[1], Item::Mod(m) if m.items.is_none()));
+    }
+
     #[test]
     fn test_parse_pub_function() {
         let (ast, handler) = parse_source("pub fn main() { }");
@@ -3952,6 +4874,232 @@ mod tests {
         assert!(handler.error_count() >= 1);
     }
 
+    #[test]
+    fn test_error_recovery_stops_at_error_limit() {
+        let source = "fn ( ;\n".repeat(50);
+        let mut handler = Handler::new();
+        handler.set_error_limit(5);
+        let mut lexer = Lexer::new(&source, &mut handler);
+
+        let mut tokens = Vec::new();
+        loop {
+            let token = lexer.next_token();
+            if token == Token::Eof {
+                break;
+            }
+            tokens.push(TokenWithSpan::new(token, Span::DUMMY));
+        }
+
+        let mut parser = Parser::from_tokens(tokens, &mut handler, &source);
+        parser.parse();
+        assert!(parser.is_at_end());
+
+        assert_eq!(handler.error_count(), 5);
+        assert!(handler
+            .diagnostics()
+            .iter()
+            .any(|d| d.message == "aborting due to 5 errors; too many errors emitted"));
+    }
+
+    #[test]
+    fn test_spans_carry_file_id() {
+        let source = "fn ok() { }\nfn broken( { }";
+        let file_id = FileId(2);
+        let mut handler = Handler::new();
+        let mut lexer = Lexer::new(source, &mut handler);
+
+        let mut tokens = Vec::new();
+        loop {
+            let token = lexer.next_token();
+            if token == Token::Eof {
+                break;
+            }
+            tokens.push(TokenWithSpan::new(token, lexer.token_span()));
+        }
+
+        let ast = Parser::from_tokens(tokens, &mut handler, source)
+            .with_file_id(file_id)
+            .parse();
+
+        let Some(Item::Fn(f)) = ast.first() else {
+            panic!("expected function, got {:?}", ast.first());
+        };
+        assert_eq!(f.span.file_id, file_id);
+
+        let diagnostics = handler.diagnostics();
+        assert!(!diagnostics.is_empty());
+        assert!(diagnostics.iter().all(|d| d.span.file_id == file_id));
+        assert_eq!(diagnostics[0].span.line, 2);
+    }
+
+    #[test]
+    fn test_parse_next_item_matches_parse() {
+        let source = "fn a() { }\nfn ( ;\nstruct S { x: i64 }\nfn ( ;\nconst N: i64 = 1;";
+        let tokenize = || {
+            let mut handler = Handler::new();
+            let mut lexer = Lexer::new(source, &mut handler);
+            let mut tokens = Vec::new();
+            loop {
+                let token = lexer.next_token();
+                if token == Token::Eof {
+                    break;
+                }
+                tokens.push(TokenWithSpan::new(token, lexer.token_span()));
+            }
+            tokens
+        };
+
+        let mut handler = Handler::new();
+        let all = Parser::from_tokens(tokenize(), &mut handler, source).parse();
+
+        let mut streamed_handler = Handler::new();
+        let mut parser = Parser::from_tokens(tokenize(), &mut streamed_handler, source);
+        let mut streamed = Vec::new();
+        while let Some(item) = parser.parse_next_item() {
+            streamed.push(format!("{:?}", item));
+        }
+        assert!(parser.parse_next_item().is_none());
+        assert_eq!(parser.recovery_count(), 2);
+
+        let all: Vec<_> = all.iter().map(|item| format!("{:?}", item)).collect();
+        assert_eq!(all.len(), 3);
+        assert_eq!(streamed, all);
+        assert_eq!(streamed_handler.error_count(), handler.error_count());
+    }
+
+    #[test]
+    fn test_recovery_count() {
+        let source = "fn ( ;\nfn ok() { }\nfn ( ;\n";
+        let mut handler = Handler::new();
+        let mut lexer = Lexer::new(source, &mut handler);
+
+        let mut tokens = Vec::new();
+        loop {
+            let token = lexer.next_token();
+            if token == Token::Eof {
+                break;
+            }
+            tokens.push(TokenWithSpan::new(token, Span::DUMMY));
+        }
+
+        let mut parser = Parser::from_tokens(tokens, &mut handler, source);
+        let ast = parser.parse();
+
+        assert_eq!(parser.recovery_count(), 2);
+        assert_eq!(ast.len(), 1);
+    }
+
+    #[test]
+    fn test_trailing_comma_in_lists() {
+        let items = [
+            "fn f(a: i32, b: i32,) { }",
+            "fn f<T, U,>() { }",
+            "struct S { a: i32, b: i32, }",
+            "enum E { A(i32, i32,), B { x: i32, }, C, }",
+        ];
+        for source in items {
+            let (ast, handler) = parse_source(source);
+            assert!(!handler.has_errors(), "{}", source);
+            assert_eq!(ast.len(), 1, "{}", source);
+        }
+
+        let (expr, handler) = parse_expr_source("(1, 2,)");
+        assert!(!handler.has_errors());
+        assert!(matches!(expr, Some(Expr::Tuple(v)) if v.len() == 2));
+
+        // A trailing comma turns a parenthesized expression into a tuple
+        let (expr, _) = parse_expr_source("(1,)");
+        assert!(matches!(expr, Some(Expr::Tuple(v)) if v.len() == 1));
+
+        let (expr, handler) = parse_expr_source("[1, 2, 3,]");
+        assert!(!handler.has_errors());
+        assert!(matches!(expr, Some(Expr::Array(v)) if v.len() == 3));
+
+        let (expr, handler) = parse_expr_source("f(1, 2,)");
+        assert!(!handler.has_errors());
+        assert!(matches!(expr, Some(Expr::Call(c)) if c.args.len() == 2));
+    }
+
+    #[test]
+    fn test_vec_and_lexer_streams_produce_same_ast() {
+        let source = "struct P<T> { x: T, y: T }\n\
+                      fn f(p: P<i32>) -> i32 {\n\
+                          let v = match p.x { 0 => 1, n => n * 2 };\n\
+                          if v > 0 { let q = P { x: v, y: 0 }; q.x } else { -v }\n\
+                      }\n\
+                      fn g() { let r = 0..=3; }";
+
+        let mut lex_handler = Handler::new();
+        let mut lexer = Lexer::new(source, &mut lex_handler);
+        let tokens: Vec<_> = std::iter::from_fn(|| {
+            let token = lexer.next_token();
+            let span = lexer.token_span();
+            Some(TokenWithSpan { token, span })
+        })
+        .take_while(|t| t.token != Token::Eof)
+        .collect();
+        let mut vec_handler = Handler::new();
+        let from_vec = Parser::from_tokens(tokens, &mut vec_handler, source).parse();
+
+        let mut lex_handler = Handler::new();
+        let stream = LexerTokenStream::new(Lexer::new(source, &mut lex_handler));
+        let mut stream_handler = Handler::new();
+        let from_stream = Parser::from_stream(stream, &mut stream_handler, source).parse();
+
+        assert!(!vec_handler.has_errors());
+        assert!(!stream_handler.has_errors());
+        assert_eq!(from_vec.len(), 3);
+        assert_eq!(format!("{:?}", from_vec), format!("{:?}", from_stream));
+    }
+
+    #[test]
+    fn test_parse_large_input_time_bound() {
+        // 100k lines: 5k functions of a header, 18 statements and a closing
+        // brace. Every identifier and integer literal goes through the
+        // parser's per-token checks, suffixed or not.
+        let mut source = String::new();
+        for f in 0..5_000 {
+            source.push_str(&format!("fn f{}(a: i32) -> i32 {{\n", f));
+            for s in 0..18 {
+                source.push_str(&format!("    let x{} = a * {}i32 + {};\n", s, s, f));
+            }
+            source.push_str("}\n");
+        }
+        assert_eq!(source.lines().count(), 100_000);
+
+        let start = std::time::Instant::now();
+        let (ast, handler) = parse_source(&source);
+        let elapsed = start.elapsed();
+
+        assert!(!handler.has_errors());
+        assert_eq!(ast.len(), 5_000);
+        // Generous for debug builds; quadratic behavior overshoots it by far
+        assert!(
+            elapsed.as_secs() < 30,
+            "parsing 100k lines took {:?}",
+            elapsed
+        );
+    }
+
+    #[test]
+    fn test_doubled_comma_in_lists() {
+        let items = [
+            "fn f(a: i32,, b: i32) { }",
+            "fn f<T,, U>() { }",
+            "struct S { a: i32,, b: i32 }",
+            "enum E { A,, B }",
+        ];
+        for source in items {
+            let (_, handler) = parse_source(source);
+            assert!(handler.has_errors(), "{}", source);
+        }
+
+        for source in ["(1,, 2)", "[1,, 2]", "f(1,, 2)"] {
+            let (_, handler) = parse_expr_source(source);
+            assert!(handler.has_errors(), "{}", source);
+        }
+    }
+
     // ========================================================================
     // EDGE CASE TESTS
     // ========================================================================
@@ -4020,4 +5168,43 @@ mod tests {
         assert!(!handler.has_errors());
         assert!(matches!(expr, Some(Expr::Unary(u)) if matches!(u.op, UnOp::Ref(true))));
     }
+
+    #[test]
+    fn test_ast_dot_is_well_formed() {
+        let (ast, handler) = parse_source("fn main() { let x = 1 + 2; }\nstruct S { f: i32 }");
+        assert!(!handler.has_errors());
+        let dot = ast_to_dot(&ast);
+
+        let lines: Vec<&str> = dot.lines().collect();
+        assert_eq!(lines.first(), Some(&"digraph ast {"));
+        assert_eq!(lines.last(), Some(&"}"));
+
+        // Every statement is a node or an edge between declared nodes
+        let mut nodes = std::collections::HashMap::new();
+        for line in &lines[1..lines.len() - 1] {
+            let line = line
+                .trim()
+                .strip_suffix(';')
+                .expect("statements end in `;`");
+            if let Some((id, rest)) = line.split_once(" [label=\"") {
+                let label = rest.strip_suffix("\"]").expect("label should be closed");
+                assert!(!label.replace("\\\"", "").contains('"'), "{}", label);
+                nodes.insert(id.to_string(), label.to_string());
+            } else if let Some((from, to)) = line.split_once(" -> ") {
+                assert!(
+                    nodes.contains_key(from) && nodes.contains_key(to),
+                    "{}",
+                    line
+                );
+            } else {
+                assert_eq!(line, "node [shape=box]");
+            }
+        }
+
+        let labels: Vec<_> = nodes.values().map(String::as_str).collect();
+        assert!(labels.contains(&"Fn main"));
+        assert!(labels.contains(&"Struct S"));
+        assert!(labels.contains(&"Binary Add"));
+        assert!(labels.contains(&"Ident x"));
+    }
 }
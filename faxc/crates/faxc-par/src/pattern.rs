@@ -16,7 +16,7 @@ impl<'a> Parser<'a> {
                 self.advance();
                 Some(Pattern::Ident(name, Mutability::Immutable))
             },
-            Token::Number(n) => {
+            Token::Number(n, ..) => {
                 self.advance();
                 Some(Pattern::Literal(Literal::Int(n as i64)))
             },
@@ -183,7 +183,7 @@ impl<'a> Parser<'a> {
         let pattern = self.parse_pattern()?;
 
         let is_in = match self.current_token() {
-            Token::Ident(sym) => sym.as_str() == "in",
+            Token::Ident(sym) => sym.is("in"),
             _ => false,
         };
         if !is_in {
@@ -0,0 +1,172 @@
+//! Token sources for the parser
+//!
+//! The parser reads tokens through the [`TokenStream`] trait rather than
+//! indexing a vector, so tokens can come from a fully lexed file
+//! ([`VecTokenStream`]) or be pulled from the lexer on demand
+//! ([`LexerTokenStream`]).
+
+use std::collections::VecDeque;
+
+use faxc_lex::{Lexer, Token};
+
+use crate::TokenWithSpan;
+
+/// A cursor over the tokens of one source file
+///
+/// `current`, `peek` and `peek2` cover the parser's fixed LL(2) lookahead
+/// and never need to pull new tokens. Longer scans go through `lookahead`,
+/// which may have to buffer ahead and therefore takes `&mut self`.
+pub trait TokenStream {
+    /// The token at the cursor, or `None` at end of input
+    fn current(&self) -> Option<&TokenWithSpan>;
+
+    /// The token after the current one
+    fn peek(&self) -> Option<&TokenWithSpan>;
+
+    /// The token two positions after the current one
+    fn peek2(&self) -> Option<&TokenWithSpan>;
+
+    /// The token `n` positions after the current one
+    fn lookahead(&mut self, n: usize) -> Option<&TokenWithSpan>;
+
+    /// Move the cursor one token forward; does nothing at end of input
+    fn advance(&mut self);
+
+    /// Replace the token at the cursor, as when the parser splits `>>` to
+    /// close a generic argument list; does nothing at end of input
+    fn replace_current(&mut self, token: TokenWithSpan);
+
+    /// Whether all tokens have been consumed
+    fn is_at_end(&self) -> bool;
+
+    /// Consume the rest of the input
+    fn skip_to_end(&mut self) {
+        while !self.is_at_end() {
+            self.advance();
+        }
+    }
+}
+
+/// Tokens materialized up front, indexed by position
+#[derive(Debug, Clone, Default)]
+pub struct VecTokenStream {
+    tokens: Vec<TokenWithSpan>,
+    position: usize,
+}
+
+impl VecTokenStream {
+    pub fn new(tokens: Vec<TokenWithSpan>) -> Self {
+        Self {
+            tokens,
+            position: 0,
+        }
+    }
+}
+
+impl TokenStream for VecTokenStream {
+    fn current(&self) -> Option<&TokenWithSpan> {
+        self.tokens.get(self.position)
+    }
+
+    fn peek(&self) -> Option<&TokenWithSpan> {
+        self.tokens.get(self.position + 1)
+    }
+
+    fn peek2(&self) -> Option<&TokenWithSpan> {
+        self.tokens.get(self.position + 2)
+    }
+
+    fn lookahead(&mut self, n: usize) -> Option<&TokenWithSpan> {
+        self.tokens.get(self.position + n)
+    }
+
+    fn advance(&mut self) {
+        if !self.is_at_end() {
+            self.position += 1;
+        }
+    }
+
+    fn replace_current(&mut self, token: TokenWithSpan) {
+        if let Some(current) = self.tokens.get_mut(self.position) {
+            *current = token;
+        }
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.position >= self.tokens.len()
+    }
+
+    fn skip_to_end(&mut self) {
+        self.position = self.tokens.len();
+    }
+}
+
+/// Tokens lexed lazily as the parser advances
+///
+/// Keeps the current token and the two after it buffered so the fixed
+/// lookahead stays `&self`; `lookahead` lexes further ahead when a scan
+/// needs it. Lexical errors go to the handler the lexer was created with.
+pub struct LexerTokenStream<'a> {
+    lexer: Lexer<'a>,
+    buffer: VecDeque<TokenWithSpan>,
+    exhausted: bool,
+}
+
+impl<'a> LexerTokenStream<'a> {
+    pub fn new(lexer: Lexer<'a>) -> Self {
+        let mut stream = Self {
+            lexer,
+            buffer: VecDeque::new(),
+            exhausted: false,
+        };
+        stream.fill(3);
+        stream
+    }
+
+    /// Lex until `len` tokens are buffered or the input runs out
+    fn fill(&mut self, len: usize) {
+        while self.buffer.len() < len && !self.exhausted {
+            let token = self.lexer.next_token();
+            if token == Token::Eof {
+                self.exhausted = true;
+            } else {
+                let span = self.lexer.token_span();
+                self.buffer.push_back(TokenWithSpan { token, span });
+            }
+        }
+    }
+}
+
+impl TokenStream for LexerTokenStream<'_> {
+    fn current(&self) -> Option<&TokenWithSpan> {
+        self.buffer.front()
+    }
+
+    fn peek(&self) -> Option<&TokenWithSpan> {
+        self.buffer.get(1)
+    }
+
+    fn peek2(&self) -> Option<&TokenWithSpan> {
+        self.buffer.get(2)
+    }
+
+    fn lookahead(&mut self, n: usize) -> Option<&TokenWithSpan> {
+        self.fill(n + 1);
+        self.buffer.get(n)
+    }
+
+    fn advance(&mut self) {
+        self.buffer.pop_front();
+        self.fill(3);
+    }
+
+    fn replace_current(&mut self, token: TokenWithSpan) {
+        if let Some(current) = self.buffer.front_mut() {
+            *current = token;
+        }
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.buffer.is_empty()
+    }
+}
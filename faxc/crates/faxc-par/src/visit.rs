@@ -0,0 +1,362 @@
+//! AST traversal
+//!
+//! [`Visitor`] has one method per kind of node, each defaulting to the
+//! matching `walk_*` function, which visits the node's children in source
+//! order. An implementation overrides the methods for the nodes it cares
+//! about and calls the `walk_*` function from them to keep descending.
+
+use crate::{
+    ArrayLen, Block, EnumVariantData, Expr, FnItem, GenericParam, IfExpr, ImplMember, Item, Path,
+    Pattern, Stmt, TraitMember, Type, VariantData, WhereClause,
+};
+
+pub trait Visitor: Sized {
+    fn visit_item(&mut self, item: &Item) {
+        walk_item(self, item)
+    }
+
+    fn visit_block(&mut self, block: &Block) {
+        walk_block(self, block)
+    }
+
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        walk_stmt(self, stmt)
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        walk_expr(self, expr)
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        walk_pattern(self, pattern)
+    }
+
+    fn visit_type(&mut self, ty: &Type) {
+        walk_type(self, ty)
+    }
+}
+
+pub fn walk_item<V: Visitor>(v: &mut V, item: &Item) {
+    match item {
+        Item::Fn(f) => walk_fn(v, f),
+        Item::Struct(s) => {
+            walk_generics(v, &s.generics, s.where_clause.as_ref());
+            for field in &s.fields {
+                v.visit_type(&field.ty);
+            }
+        },
+        Item::Enum(e) => {
+            walk_generics(v, &e.generics, e.where_clause.as_ref());
+            for variant in &e.variants {
+                match &variant.data {
+                    VariantData::Unit => {},
+                    VariantData::Tuple(tys) => tys.iter().for_each(|ty| v.visit_type(ty)),
+                    VariantData::Struct(fields) => {
+                        fields.iter().for_each(|field| v.visit_type(&field.ty))
+                    },
+                }
+            }
+        },
+        Item::Trait(t) => {
+            walk_generics(v, &t.generics, None);
+            t.supertraits.iter().for_each(|ty| v.visit_type(ty));
+            for member in &t.items {
+                match member {
+                    TraitMember::Method(sig, body) => {
+                        walk_generics(v, &sig.generics, None);
+                        sig.params.iter().for_each(|param| v.visit_type(&param.ty));
+                        if let Some(ty) = &sig.ret_type {
+                            v.visit_type(ty);
+                        }
+                        if let Some(body) = body {
+                            v.visit_block(body);
+                        }
+                    },
+                    TraitMember::Type(_, bounds) => bounds.iter().for_each(|ty| v.visit_type(ty)),
+                    TraitMember::Const(_, ty, value) => {
+                        v.visit_type(ty);
+                        if let Some(value) = value {
+                            v.visit_expr(value);
+                        }
+                    },
+                }
+            }
+        },
+        Item::Impl(i) => {
+            walk_generics(v, &i.generics, i.where_clause.as_ref());
+            if let Some(trait_ref) = &i.trait_ref {
+                v.visit_type(trait_ref);
+            }
+            v.visit_type(&i.self_ty);
+            for member in &i.items {
+                match member {
+                    ImplMember::Method(f) => walk_fn(v, f),
+                    ImplMember::Type(_, ty) => v.visit_type(ty),
+                    ImplMember::Const(_, ty, value) => {
+                        v.visit_type(ty);
+                        v.visit_expr(value);
+                    },
+                }
+            }
+        },
+        Item::Use(_) => {},
+        Item::Mod(m) => {
+            for item in m.items.iter().flatten() {
+                v.visit_item(item);
+            }
+        },
+        Item::Const(c) => {
+            v.visit_type(&c.ty);
+            v.visit_expr(&c.value);
+        },
+        Item::Static(s) => {
+            v.visit_type(&s.ty);
+            v.visit_expr(&s.value);
+        },
+    }
+}
+
+fn walk_fn<V: Visitor>(v: &mut V, f: &FnItem) {
+    walk_generics(v, &f.generics, f.where_clause.as_ref());
+    for param in &f.params {
+        v.visit_type(&param.ty);
+    }
+    if let Some(ty) = &f.ret_type {
+        v.visit_type(ty);
+    }
+    v.visit_block(&f.body);
+}
+
+fn walk_generics<V: Visitor>(
+    v: &mut V,
+    generics: &[GenericParam],
+    where_clause: Option<&WhereClause>,
+) {
+    for param in generics {
+        param.bounds.iter().for_each(|ty| v.visit_type(ty));
+    }
+    for bound in where_clause.iter().flat_map(|w| &w.bounds) {
+        v.visit_type(&bound.ty);
+    }
+}
+
+pub fn walk_block<V: Visitor>(v: &mut V, block: &Block) {
+    for stmt in &block.stmts {
+        v.visit_stmt(stmt);
+    }
+    if let Some(trailing) = &block.trailing {
+        v.visit_expr(trailing);
+    }
+}
+
+pub fn walk_stmt<V: Visitor>(v: &mut V, stmt: &Stmt) {
+    match stmt {
+        Stmt::Let(l) => {
+            v.visit_pattern(&l.pattern);
+            if let Some(ty) = &l.ty {
+                v.visit_type(ty);
+            }
+            if let Some(init) = &l.init {
+                v.visit_expr(init);
+            }
+            if let Some(else_block) = &l.else_block {
+                v.visit_block(else_block);
+            }
+        },
+        Stmt::Expr(e) => v.visit_expr(e),
+        Stmt::Return(value) => {
+            if let Some(value) = value {
+                v.visit_expr(value);
+            }
+        },
+        Stmt::If(i) => walk_if(v, i),
+        Stmt::While(w) => {
+            v.visit_expr(&w.cond);
+            v.visit_block(&w.body);
+        },
+        Stmt::WhileLet(w) => {
+            v.visit_pattern(&w.pattern);
+            v.visit_expr(&w.scrutinee);
+            v.visit_block(&w.body);
+        },
+        Stmt::For(f) => {
+            v.visit_pattern(&f.pattern);
+            v.visit_expr(&f.iter);
+            v.visit_block(&f.body);
+        },
+        Stmt::Break(_) | Stmt::Continue(_) => {},
+        Stmt::Item(item) => v.visit_item(item),
+    }
+}
+
+fn walk_if<V: Visitor>(v: &mut V, i: &IfExpr) {
+    v.visit_expr(&i.cond);
+    v.visit_block(&i.then_block);
+    if let Some(else_block) = &i.else_block {
+        v.visit_expr(else_block);
+    }
+}
+
+pub fn walk_expr<V: Visitor>(v: &mut V, expr: &Expr) {
+    match expr {
+        Expr::Literal(_) | Expr::Continue(_) => {},
+        Expr::Path(path) => walk_path(v, path),
+        Expr::Binary(b) => {
+            v.visit_expr(&b.left);
+            v.visit_expr(&b.right);
+        },
+        Expr::Unary(u) => v.visit_expr(&u.expr),
+        Expr::Call(c) => {
+            v.visit_expr(&c.func);
+            c.generics.iter().flatten().for_each(|ty| v.visit_type(ty));
+            c.args.iter().for_each(|arg| v.visit_expr(arg));
+        },
+        Expr::MethodCall(m) => {
+            v.visit_expr(&m.receiver);
+            m.args.iter().flatten().for_each(|ty| v.visit_type(ty));
+            m.call_args.iter().for_each(|arg| v.visit_expr(arg));
+        },
+        Expr::Field(f) => v.visit_expr(&f.object),
+        Expr::Index(i) => {
+            v.visit_expr(&i.object);
+            v.visit_expr(&i.index);
+        },
+        Expr::Block(block) => v.visit_block(block),
+        Expr::If(i) => walk_if(v, i),
+        Expr::IfLet(i) => {
+            v.visit_pattern(&i.pattern);
+            v.visit_expr(&i.scrutinee);
+            v.visit_block(&i.then_block);
+            if let Some(else_block) = &i.else_block {
+                v.visit_expr(else_block);
+            }
+        },
+        Expr::Match(m) => {
+            v.visit_expr(&m.scrutinee);
+            for arm in &m.arms {
+                v.visit_pattern(&arm.pattern);
+                if let Some(guard) = &arm.guard {
+                    v.visit_expr(guard);
+                }
+                v.visit_expr(&arm.body);
+            }
+        },
+        Expr::Loop(l) => v.visit_block(&l.body),
+        Expr::Closure(c) => {
+            c.params.iter().for_each(|param| v.visit_type(&param.ty));
+            if let Some(ty) = &c.ret_type {
+                v.visit_type(ty);
+            }
+            v.visit_expr(&c.body);
+        },
+        Expr::Assign(a) => {
+            v.visit_expr(&a.place);
+            v.visit_expr(&a.value);
+        },
+        Expr::CompoundAssign(a) => {
+            v.visit_expr(&a.place);
+            v.visit_expr(&a.value);
+        },
+        Expr::Return(value) | Expr::Break(value, _) => {
+            if let Some(value) = value {
+                v.visit_expr(value);
+            }
+        },
+        Expr::Tuple(elems) | Expr::Array(elems) => elems.iter().for_each(|e| v.visit_expr(e)),
+        Expr::ArrayRepeat { value, count } => {
+            v.visit_expr(value);
+            v.visit_expr(count);
+        },
+        Expr::Range(r) => {
+            if let Some(start) = &r.start {
+                v.visit_expr(start);
+            }
+            if let Some(end) = &r.end {
+                v.visit_expr(end);
+            }
+        },
+        Expr::Cast(e, ty) => {
+            v.visit_expr(e);
+            v.visit_type(ty);
+        },
+        Expr::Async(a) => v.visit_block(&a.body),
+        Expr::Paren(e) | Expr::Await(e) => v.visit_expr(e),
+        Expr::StructLiteral(s) => {
+            walk_path(v, &s.path);
+            s.generics.iter().flatten().for_each(|ty| v.visit_type(ty));
+            s.fields.iter().for_each(|field| v.visit_expr(&field.expr));
+            if let Some(base) = &s.base {
+                v.visit_expr(base);
+            }
+        },
+        Expr::EnumVariant(e) => {
+            walk_path(v, &e.path);
+            e.generics.iter().flatten().for_each(|ty| v.visit_type(ty));
+            match &e.data {
+                EnumVariantData::Unit => {},
+                EnumVariantData::Tuple(args) => args.iter().for_each(|arg| v.visit_expr(arg)),
+                EnumVariantData::Struct(fields) => {
+                    fields.iter().for_each(|field| v.visit_expr(&field.expr))
+                },
+            }
+        },
+    }
+}
+
+pub fn walk_pattern<V: Visitor>(v: &mut V, pattern: &Pattern) {
+    match pattern {
+        Pattern::Wildcard | Pattern::Ident(..) | Pattern::Literal(_) => {},
+        Pattern::Path(path) => walk_path(v, path),
+        Pattern::Struct(path, fields) => {
+            walk_path(v, path);
+            fields
+                .iter()
+                .for_each(|field| v.visit_pattern(&field.pattern));
+        },
+        Pattern::TupleStruct(path, elems) => {
+            walk_path(v, path);
+            elems.iter().for_each(|p| v.visit_pattern(p));
+        },
+        Pattern::Tuple(elems) | Pattern::Slice(elems) => {
+            elems.iter().for_each(|p| v.visit_pattern(p))
+        },
+    }
+}
+
+pub fn walk_type<V: Visitor>(v: &mut V, ty: &Type) {
+    match ty {
+        Type::Unit | Type::Never | Type::Inferred => {},
+        Type::Path(path) => walk_path(v, path),
+        Type::Generic(base, args) => {
+            v.visit_type(base);
+            args.iter().for_each(|arg| v.visit_type(arg));
+        },
+        Type::Reference(inner, _) | Type::Pointer(inner, _) | Type::Slice(inner) => {
+            v.visit_type(inner)
+        },
+        Type::Array(elem, len) => {
+            v.visit_type(elem);
+            if let ArrayLen::Expr(len) = len {
+                v.visit_expr(len);
+            }
+        },
+        Type::Tuple(tys) | Type::TraitObject(tys) | Type::ImplTrait(tys) => {
+            tys.iter().for_each(|ty| v.visit_type(ty))
+        },
+        Type::Fn(params, ret) => {
+            params.iter().for_each(|ty| v.visit_type(ty));
+            v.visit_type(ret);
+        },
+    }
+}
+
+/// Visit the generic arguments written on a path's segments
+fn walk_path<V: Visitor>(v: &mut V, path: &Path) {
+    for segment in &path.segments {
+        segment
+            .args
+            .iter()
+            .flatten()
+            .for_each(|ty| v.visit_type(ty));
+    }
+}
@@ -0,0 +1,82 @@
+//! Allocation count of a parse-heavy input
+//!
+//! The parser inspects every token several times (lookahead, binding power,
+//! recovery checks). Those inspections must borrow the token rather than
+//! clone it, so the number of heap allocations made while parsing tracks the
+//! size of the AST, not the number of token inspections.
+//!
+//! This lives in its own test binary because it installs a counting global
+//! allocator.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use faxc_lex::{Lexer, Token};
+use faxc_par::{Parser, TokenWithSpan};
+use faxc_util::{Handler, Span};
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn lex(source: &str, handler: &mut Handler) -> Vec<TokenWithSpan> {
+    let mut lexer = Lexer::new(source, handler);
+    let mut tokens = Vec::new();
+    loop {
+        let token = lexer.next_token();
+        if token == Token::Eof {
+            break;
+        }
+        tokens.push(TokenWithSpan {
+            token,
+            span: Span::DUMMY,
+        });
+    }
+    tokens
+}
+
+#[test]
+fn test_parse_allocations_stay_below_token_count() {
+    // Each function is 65 tokens. Every `(`...`)` pair is a boxed
+    // `Expr::Paren`, so the AST itself costs about 35 allocations per
+    // function; allocating on token inspections pushes the total past two
+    // allocations for every three tokens.
+    let mut source = String::new();
+    for f in 0..500 {
+        source.push_str(&format!("fn f{}(a: i32) -> i32 {{\n", f));
+        source.push_str("    let x = ((((a)))) + ((((a)))) * ((((a))));\n");
+        source.push_str("    return ((((x)))) - ((((a))));\n");
+        source.push_str("}\n");
+    }
+
+    let mut handler = Handler::new();
+    let tokens = lex(&source, &mut handler);
+    let token_count = tokens.len();
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let ast = Parser::from_tokens(tokens, &mut handler, &source).parse();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+
+    assert!(!handler.has_errors());
+    assert_eq!(ast.len(), 500);
+    assert!(
+        allocations < token_count * 2 / 3,
+        "parsing {} tokens made {} allocations",
+        token_count,
+        allocations
+    );
+}
@@ -0,0 +1,41 @@
+//! Builtin entry points called directly by generated code
+//!
+//! Codegen declares these under their prelude names (`print`, `println`,
+//! `str_concat`) plus the process exit hook used by the emitted `main`.
+
+use crate::gc::{fax_print, fax_println, fax_string_concat};
+use std::io::Write;
+
+#[no_mangle]
+pub extern "C" fn print(value: i64) {
+    print!("{}", value);
+}
+
+#[no_mangle]
+pub extern "C" fn println(value: i64) {
+    println!("{}", value);
+}
+
+#[no_mangle]
+pub extern "C" fn print_str(ptr: *const u8) {
+    fax_print(ptr);
+}
+
+#[no_mangle]
+pub extern "C" fn println_str(ptr: *const u8) {
+    fax_println(ptr);
+}
+
+#[no_mangle]
+pub extern "C" fn str_concat(s1: *const u8, s2: *const u8) -> *mut u8 {
+    fax_string_concat(s1, s2)
+}
+
+/// Flush buffered output and terminate the process
+#[no_mangle]
+// Compiled programs call this to end the process; there is no caller to return to
+#[allow(clippy::disallowed_methods)]
+pub extern "C" fn fax_exit(code: i64) -> ! {
+    let _ = std::io::stdout().flush();
+    std::process::exit(code as i32)
+}
@@ -3,7 +3,10 @@
 //! Provides runtime support for Fax programs including:
 //! - GC allocation functions (via FGC)
 //! - Runtime initialization
+//! - Builtins and process exit for the generated `main`
 
+mod entry;
 mod gc;
 
+pub use entry::*;
 pub use gc::*;
@@ -1,30 +1,94 @@
+use crate::const_eval::{self, ConstValue};
 use crate::hir::*;
-use crate::scope::{RibKind, ScopeTree};
+use crate::modules;
+use crate::prelude;
+use crate::scope::{RibId, RibKind, ScopeTree};
 use crate::types::*;
 use faxc_par as ast;
-use faxc_util::{DefId, DefIdGenerator, Handler, Span};
-
-fn ast_type_to_hir(ty: &ast::Type) -> Type {
-    match ty {
-        ast::Type::Unit => Type::Unit,
-        ast::Type::Never => Type::Never,
-        ast::Type::Path(_) => Type::Int,
-        ast::Type::Generic(_, _) => Type::Int,
-        ast::Type::Reference(ty, _) => Type::Ref(Box::new(ast_type_to_hir(ty)), false),
-        ast::Type::Pointer(_, _) => Type::Int,
-        ast::Type::Slice(ty) => Type::Slice(Box::new(ast_type_to_hir(ty))),
-        ast::Type::Array(ty, size) => Type::Array(Box::new(ast_type_to_hir(ty)), *size),
-        ast::Type::Tuple(tys) => Type::Tuple(tys.iter().map(ast_type_to_hir).collect()),
-        ast::Type::Fn(params, ret) => Type::Fn(
-            params.iter().map(ast_type_to_hir).collect(),
-            Box::new(ast_type_to_hir(ret)),
-        ),
-        ast::Type::TraitObject(_) => Type::String,
-        ast::Type::ImplTrait(_) => Type::Infer(InferId(0)),
-        ast::Type::Inferred => Type::Infer(InferId(0)),
+use faxc_util::{DefId, DefIdGenerator, Handler, Span, Symbol};
+use indexmap::map::Entry;
+use indexmap::{IndexMap, IndexSet};
+
+/// Map a primitive type name to its HIR type
+fn primitive_type(name: &str) -> Option<Type> {
+    match name {
+        "Int" | "i64" | "isize" => Some(Type::Int),
+        "u64" | "usize" => Some(Type::UInt),
+        "i8" => Some(Type::Int8),
+        "u8" => Some(Type::UInt8),
+        "i16" => Some(Type::Int16),
+        "u16" => Some(Type::UInt16),
+        "i32" => Some(Type::Int32),
+        "u32" => Some(Type::UInt32),
+        "Float" | "f64" => Some(Type::Float),
+        "f32" => Some(Type::Float32),
+        "bool" | "Bool" => Some(Type::Bool),
+        "char" | "Char" => Some(Type::Char),
+        "str" | "String" => Some(Type::String),
+        _ => None,
     }
 }
 
+/// Name and declared visibility of an item that can be a module member
+fn item_visibility(item: &ast::Item) -> Option<(Symbol, &ast::Visibility)> {
+    match item {
+        ast::Item::Fn(f) => Some((f.name, &f.visibility)),
+        ast::Item::Struct(s) => Some((s.name, &s.visibility)),
+        ast::Item::Enum(e) => Some((e.name, &e.visibility)),
+        ast::Item::Trait(t) => Some((t.name, &t.visibility)),
+        ast::Item::Const(c) => Some((c.name, &c.visibility)),
+        ast::Item::Static(s) => Some((s.name, &s.visibility)),
+        ast::Item::Mod(m) => Some((m.name, &m.visibility)),
+        ast::Item::Impl(_) | ast::Item::Use(_) => None,
+    }
+}
+
+/// A trait's default method as a function item, if the member has a body
+fn default_method(member: &ast::TraitMember) -> Option<ast::FnItem> {
+    let ast::TraitMember::Method(sig, Some(body)) = member else {
+        return None;
+    };
+    Some(ast::FnItem {
+        name: sig.name,
+        generics: sig.generics.clone(),
+        self_param: sig.self_param,
+        params: sig.params.clone(),
+        ret_type: sig.ret_type.clone(),
+        body: body.clone(),
+        visibility: ast::Visibility::Public,
+        span: body.span,
+        async_kw: false,
+        where_clause: None,
+    })
+}
+
+/// Type of a method's `self` parameter in an impl for `self_ty`
+fn receiver_type(kind: ast::SelfKind, self_ty: Type) -> Type {
+    match kind {
+        ast::SelfKind::Value | ast::SelfKind::MutValue => self_ty,
+        ast::SelfKind::Ref => Type::Ref(Box::new(self_ty), false),
+        ast::SelfKind::RefMut => Type::Ref(Box::new(self_ty), true),
+    }
+}
+
+/// Enum variant information recorded during item collection
+struct VariantInfo {
+    /// Discriminant (declaration order within the enum)
+    index: u32,
+    /// Payload field types
+    fields: Vec<Type>,
+}
+
+/// Items declared directly in an inline module
+struct ModuleInfo {
+    /// Enclosing module, `None` for the crate root
+    parent: Option<DefId>,
+    /// Rib holding the module's item bindings
+    rib: RibId,
+    /// Member items with their declared visibility
+    members: IndexMap<Symbol, (DefId, ast::Visibility)>,
+}
+
 /// Main semantic analyzer
 pub struct SemanticAnalyzer<'a> {
     /// Type context
@@ -39,12 +103,74 @@ pub struct SemanticAnalyzer<'a> {
     /// Current function return type (for return checking)
     pub current_ret_type: Option<Type>,
 
-    /// Loop stack (for break/continue checking)
-    pub loop_stack: Vec<(Option<LabelId>, Type)>,
+    /// Self type of the impl whose methods are being analyzed
+    pub self_ty: Option<Type>,
+
+    /// Generic type parameters in scope, indexed by `ParamId`
+    type_params: Vec<Symbol>,
+
+    /// Loop stack (for break/continue checking), with the type the loop's
+    /// `break`s give it: `!` until the first `break`, `()` for a `break`
+    /// without a value. `while` and `for` loops have no type here, as their
+    /// `break`s cannot carry a value
+    pub loop_stack: Vec<(Option<LabelId>, Option<Type>)>,
+
+    /// Labels of the enclosing labeled loops, innermost last
+    labels: Vec<(Symbol, LabelId)>,
+
+    /// Next `LabelId` to assign to a labeled loop
+    next_label: u32,
+
+    /// Enclosing closures, innermost last: each one's rib, capture mode,
+    /// and the captures found so far
+    closures: Vec<(RibId, CaptureMode, Vec<Capture>)>,
 
     /// Error handler
     pub handler: &'a mut Handler,
 
+    /// Enum variants by DefId
+    variants: IndexMap<DefId, VariantInfo>,
+
+    /// Struct field names and types by DefId, in declaration order
+    structs: IndexMap<DefId, Vec<(Symbol, Type)>>,
+
+    /// Analyzed structs, emitted in source order by `analyze_item`
+    struct_items: IndexMap<DefId, StructItem>,
+
+    /// Trait definitions, which bounds must name
+    traits: IndexSet<DefId>,
+
+    /// Default method bodies of each trait, for impls that don't override them
+    trait_defaults: IndexMap<DefId, Vec<ast::FnItem>>,
+
+    /// Functions of inherent impls by self type and name, for `Type::f` paths
+    assoc_fns: IndexMap<(Type, Symbol), DefId>,
+
+    /// Span of the first `impl` of each trait for each type, for coherence
+    trait_impl_spans: IndexMap<(DefId, Type), Span>,
+
+    /// Enums without variants, which have no values
+    empty_enums: IndexSet<DefId>,
+
+    /// Runtime builtins by DefId
+    builtins: IndexMap<DefId, Symbol>,
+
+    /// Variant standing for `Option::Some` in desugared `for` loops,
+    /// created on first use
+    option_some: Option<DefId>,
+
+    /// Values of evaluated `const` items
+    consts: IndexMap<DefId, ConstValue>,
+
+    /// Analyzed `const` items, emitted in source order by `analyze_item`
+    const_items: IndexMap<DefId, ConstItem>,
+
+    /// Inline modules by DefId
+    modules: IndexMap<DefId, ModuleInfo>,
+
+    /// Module whose items are being collected or analyzed (`None` = crate root)
+    current_module: Option<DefId>,
+
     /// Error count
     error_count: usize,
 }
@@ -61,8 +187,27 @@ impl<'a> SemanticAnalyzer<'a> {
             scope_tree: ScopeTree::new(),
             def_id_gen,
             current_ret_type: None,
+            self_ty: None,
+            type_params: Vec::new(),
             loop_stack: Vec::new(),
+            labels: Vec::new(),
+            next_label: 0,
+            closures: Vec::new(),
             handler,
+            variants: IndexMap::new(),
+            structs: IndexMap::new(),
+            struct_items: IndexMap::new(),
+            traits: IndexSet::new(),
+            trait_defaults: IndexMap::new(),
+            assoc_fns: IndexMap::new(),
+            trait_impl_spans: IndexMap::new(),
+            empty_enums: IndexSet::new(),
+            builtins: IndexMap::new(),
+            option_some: None,
+            consts: IndexMap::new(),
+            const_items: IndexMap::new(),
+            modules: IndexMap::new(),
+            current_module: None,
             error_count: 0,
         }
     }
@@ -73,7 +218,22 @@ impl<'a> SemanticAnalyzer<'a> {
         use faxc_util::diagnostic::DiagnosticBuilder;
         DiagnosticBuilder::error(message)
             .span(span)
-            .emit(&self.handler);
+            .emit(self.handler);
+    }
+
+    /// Report a type error with a `help:` suggestion for fixing it
+    pub fn type_error_with_help(
+        &mut self,
+        message: impl Into<String>,
+        span: Span,
+        help: impl Into<String>,
+    ) {
+        self.error_count += 1;
+        use faxc_util::diagnostic::DiagnosticBuilder;
+        DiagnosticBuilder::error(message)
+            .span(span)
+            .with_help(help)
+            .emit(self.handler);
     }
 
     /// Check if there were any errors
@@ -100,29 +260,40 @@ impl<'a> SemanticAnalyzer<'a> {
             return true;
         }
 
-        self.type_error(
-            format!("type mismatch: expected {:?}, found {:?}", expected, found),
-            span,
+        let message = format!(
+            "type mismatch: expected `{}`, found `{}`",
+            expected.display(self.type_context),
+            found.display(self.type_context)
         );
+        if found.can_cast_to(expected) {
+            let help = format!(
+                "you can convert the value with `as {}`",
+                expected.display(self.type_context)
+            );
+            self.type_error_with_help(message, span, help);
+        } else {
+            self.type_error(message, span);
+        }
         false
     }
 
     /// Analyze AST items and produce HIR
     pub fn analyze_items(&mut self, items: Vec<ast::Item>) -> Vec<Item> {
         println!("Analyzing {} items...", items.len());
+        for cycle in modules::find_import_cycles(&items) {
+            self.type_error(cycle.message(), cycle.span);
+        }
+
         // First pass: collect all item names
         self.collect_items(&items);
+        self.analyze_struct_items(&items);
+        self.collect_assoc_fns(&items);
+        self.eval_const_items(&items);
 
         // Second pass: resolve and type check
         let hir_items: Vec<_> = items
             .into_iter()
-            .filter_map(|item| {
-                let res = self.analyze_item(item);
-                if res.is_none() {
-                    println!("analyze_item returned None");
-                }
-                res
-            })
+            .flat_map(|item| self.analyze_module_item(item))
             .collect();
 
         println!("Generated {} HIR items.", hir_items.len());
@@ -131,28 +302,67 @@ impl<'a> SemanticAnalyzer<'a> {
 
     /// Collect item names (first pass)
     fn collect_items(&mut self, items: &[ast::Item]) {
+        self.collect_prelude();
+
+        let mut uses = Vec::new();
+        self.collect_item_defs(items, &mut uses);
+
+        // Imports are bound once every module in the crate is known
+        let root_rib = self.scope_tree.current_rib;
+        for (rib, module, u) in uses {
+            self.scope_tree.enter_existing(rib);
+            self.current_module = module;
+            self.collect_use(u);
+        }
+        self.scope_tree.enter_existing(root_rib);
+        self.current_module = None;
+    }
+
+    /// Bind the names of `items` in the current rib, queueing their `use`
+    /// items with the rib and module they appear in
+    fn collect_item_defs<'i>(
+        &mut self,
+        items: &'i [ast::Item],
+        uses: &mut Vec<(RibId, Option<DefId>, &'i ast::UseItem)>,
+    ) {
         for item in items {
             match item {
                 ast::Item::Fn(f) => {
                     let def_id = self.def_id_gen.next();
-                    self.scope_tree.add_binding(f.name, def_id);
+                    self.define_item(f.name, def_id, f.span);
                     let infer_id = self.type_context.new_infer_var();
                     self.type_context
                         .set_def_type(def_id, Type::Infer(infer_id));
                 },
                 ast::Item::Struct(s) => {
                     let def_id = self.def_id_gen.next();
-                    self.scope_tree.add_binding(s.name, def_id);
+                    self.define_item(s.name, def_id, s.span);
                     self.type_context.set_def_type(def_id, Type::Adt(def_id));
+                    self.type_context.set_adt_name(def_id, s.name);
+                    self.check_duplicate_fields(&s.fields);
                 },
                 ast::Item::Enum(e) => {
                     let def_id = self.def_id_gen.next();
-                    self.scope_tree.add_binding(e.name, def_id);
+                    self.define_item(e.name, def_id, e.span);
                     self.type_context.set_def_type(def_id, Type::Adt(def_id));
+                    self.type_context.set_adt_name(def_id, e.name);
+                    self.check_duplicate_variants(&e.variants);
+                    self.collect_variants(def_id, &e.variants);
+                    if e.variants.is_empty() {
+                        self.empty_enums.insert(def_id);
+                    }
                 },
                 ast::Item::Trait(t) => {
                     let def_id = self.def_id_gen.next();
-                    self.scope_tree.add_binding(t.name, def_id);
+                    self.define_item(t.name, def_id, Span::DUMMY);
+                    self.traits.insert(def_id);
+                    if t.name.as_str() == "Copy" {
+                        self.type_context.set_copy_trait(def_id);
+                    }
+                    let defaults: Vec<_> = t.items.iter().filter_map(default_method).collect();
+                    if !defaults.is_empty() {
+                        self.trait_defaults.insert(def_id, defaults);
+                    }
                 },
                 ast::Item::Impl(_imp) => {
                     let def_id = self.def_id_gen.next();
@@ -160,28 +370,637 @@ impl<'a> SemanticAnalyzer<'a> {
                 },
                 ast::Item::Const(c) => {
                     let def_id = self.def_id_gen.next();
-                    self.scope_tree.add_binding(c.name, def_id);
+                    self.define_item(c.name, def_id, c.span);
                     let infer_id = self.type_context.new_infer_var();
                     self.type_context
                         .set_def_type(def_id, Type::Infer(infer_id));
                 },
                 ast::Item::Static(s) => {
                     let def_id = self.def_id_gen.next();
-                    self.scope_tree.add_binding(s.name, def_id);
+                    self.define_item(s.name, def_id, s.span);
                     let infer_id = self.type_context.new_infer_var();
                     self.type_context
                         .set_def_type(def_id, Type::Infer(infer_id));
                 },
                 ast::Item::Use(u) => {
-                    let def_id = self.def_id_gen.next();
-                    if let Some(seg) = u.path.segments.first() {
-                        self.scope_tree.add_binding(seg.ident, def_id);
+                    uses.push((self.scope_tree.current_rib, self.current_module, u));
+                },
+                ast::Item::Mod(m) => self.collect_mod(m, uses),
+            }
+        }
+    }
+
+    /// Bind an item name in the current rib, reporting a second item of the
+    /// same name
+    ///
+    /// Prelude builtins and enum variants may be redefined by items; `let`
+    /// bindings shadow through `ScopeTree::add_binding` directly.
+    fn define_item(&mut self, name: Symbol, def_id: DefId, span: Span) {
+        let Some(previous) = self.scope_tree.add_binding(name, def_id) else {
+            return;
+        };
+        if !self.builtins.contains_key(&previous) && !self.variants.contains_key(&previous) {
+            self.type_error(format!("the name {} is defined multiple times", name), span);
+        }
+    }
+
+    /// Report each field whose name an earlier field already uses
+    fn check_duplicate_fields(&mut self, fields: &[ast::Field]) {
+        let mut seen = IndexSet::new();
+        for field in fields {
+            if !seen.insert(field.name) {
+                self.type_error(
+                    format!("field `{}` is already declared", field.name),
+                    field.span,
+                );
+            }
+        }
+    }
+
+    /// Report each variant whose name an earlier variant already uses, and
+    /// duplicate fields within struct variants
+    fn check_duplicate_variants(&mut self, variants: &[ast::Variant]) {
+        let mut seen = IndexSet::new();
+        for variant in variants {
+            if !seen.insert(variant.name) {
+                self.type_error(
+                    format!("variant `{}` defined multiple times", variant.name),
+                    variant.span,
+                );
+            }
+            if let ast::VariantData::Struct(fields) = &variant.data {
+                self.check_duplicate_fields(fields);
+            }
+        }
+    }
+
+    /// Bind a module and collect its items in a rib of their own
+    fn collect_mod<'i>(
+        &mut self,
+        m: &'i ast::ModItem,
+        uses: &mut Vec<(RibId, Option<DefId>, &'i ast::UseItem)>,
+    ) {
+        let def_id = self.def_id_gen.next();
+        self.define_item(m.name, def_id, m.span);
+
+        let items = m.items.as_deref().unwrap_or_default();
+        let rib = self.scope_tree.enter_scope(RibKind::Module);
+        let parent = self.current_module.replace(def_id);
+        self.collect_item_defs(items, uses);
+        self.current_module = parent;
+        self.scope_tree.exit_scope();
+
+        let bindings = &self.scope_tree.ribs[rib].bindings;
+        let members = items
+            .iter()
+            .filter_map(|item| {
+                let (name, vis) = item_visibility(item)?;
+                Some((name, (*bindings.get(&name)?, vis.clone())))
+            })
+            .collect();
+        self.modules.insert(
+            def_id,
+            ModuleInfo {
+                parent,
+                rib,
+                members,
+            },
+        );
+    }
+
+    /// Bind the name brought in by a `use` item
+    ///
+    /// Paths into modules of this crate bind the imported item itself;
+    /// anything else (e.g. `std::...`) binds its first segment.
+    fn collect_use(&mut self, u: &ast::UseItem) {
+        let (Some(first), Some(last)) = (u.path.segments.first(), u.path.segments.last()) else {
+            return;
+        };
+        let into_module = self
+            .scope_tree
+            .resolve(first.ident)
+            .is_some_and(|def_id| self.modules.contains_key(&def_id));
+
+        if into_module && !u.is_glob {
+            if let Some(def_id) = self.resolve_path(&u.path) {
+                self.scope_tree
+                    .add_binding(u.alias.unwrap_or(last.ident), def_id);
+            }
+            return;
+        }
+
+        let def_id = self.def_id_gen.next();
+        self.scope_tree.add_binding(first.ident, def_id);
+    }
+
+    /// Resolve the fields of every struct, including those in inline
+    /// modules, once all type names are bound
+    fn analyze_struct_items(&mut self, items: &[ast::Item]) {
+        for item in items {
+            match item {
+                ast::Item::Struct(s) => {
+                    let Some(item) = self.analyze_struct_item(s) else {
+                        continue;
+                    };
+                    let fields = item.fields.iter().map(|f| (f.name, f.ty.clone())).collect();
+                    self.structs.insert(item.def_id, fields);
+                    self.struct_items.insert(item.def_id, item);
+                },
+                ast::Item::Mod(m) => {
+                    let Some(rib) = self
+                        .scope_tree
+                        .resolve(m.name)
+                        .and_then(|def_id| self.modules.get(&def_id))
+                        .map(|info| info.rib)
+                    else {
+                        continue;
+                    };
+                    self.scope_tree.enter_existing(rib);
+                    self.analyze_struct_items(m.items.as_deref().unwrap_or_default());
+                    self.scope_tree.exit_scope();
+                },
+                _ => {},
+            }
+        }
+    }
+
+    /// Give the functions of inherent impls their DefIds and signature types,
+    /// so `Type::f` paths resolve before the impl itself is analyzed
+    fn collect_assoc_fns(&mut self, items: &[ast::Item]) {
+        for item in items {
+            match item {
+                ast::Item::Impl(imp) if imp.trait_ref.is_none() => {
+                    let self_ty = self.resolve_type(&imp.self_ty);
+                    let outer = self.self_ty.replace(self_ty.clone());
+                    for member in &imp.items {
+                        if let ast::ImplMember::Method(f) = member {
+                            self.collect_assoc_fn(&self_ty, f);
+                        }
                     }
+                    self.self_ty = outer;
+                },
+                ast::Item::Mod(m) => {
+                    let Some(rib) = self
+                        .scope_tree
+                        .resolve(m.name)
+                        .and_then(|def_id| self.modules.get(&def_id))
+                        .map(|info| info.rib)
+                    else {
+                        continue;
+                    };
+                    self.scope_tree.enter_existing(rib);
+                    self.collect_assoc_fns(m.items.as_deref().unwrap_or_default());
+                    self.scope_tree.exit_scope();
                 },
+                _ => {},
             }
         }
     }
 
+    fn collect_assoc_fn(&mut self, self_ty: &Type, f: &ast::FnItem) {
+        let key = (self_ty.clone(), f.name);
+        if self.assoc_fns.contains_key(&key) {
+            self.type_error(
+                format!("duplicate definitions with name `{}`", f.name),
+                f.span,
+            );
+            return;
+        }
+
+        let receiver = f
+            .self_param
+            .map(|kind| receiver_type(kind, self_ty.clone()));
+        let params = receiver
+            .into_iter()
+            .chain(f.params.iter().map(|param| self.resolve_type(&param.ty)))
+            .collect();
+        let ret = match &f.ret_type {
+            Some(ty) => self.resolve_type(ty),
+            None => Type::Unit,
+        };
+
+        let def_id = self.def_id_gen.next();
+        self.type_context
+            .set_def_type(def_id, Type::Fn(params, Box::new(ret)));
+        self.assoc_fns.insert(key, def_id);
+    }
+
+    /// Associated function named by a `Type::f` path, if there is one
+    fn resolve_assoc_fn(&self, path: &ast::Path) -> Option<DefId> {
+        let [.., ty_seg, fn_seg] = path.segments.as_slice() else {
+            return None;
+        };
+        let self_ty = self.resolve_type_name(ty_seg.ident)?;
+        self.assoc_fns.get(&(self_ty, fn_seg.ident)).copied()
+    }
+
+    /// Resolve a struct's field types with its generic parameters in scope
+    fn analyze_struct_item(&mut self, s: &ast::StructItem) -> Option<StructItem> {
+        let def_id = self.scope_tree.resolve(s.name)?;
+
+        let outer = std::mem::take(&mut self.type_params);
+        let generics = self.lower_generics(&s.generics, s.where_clause.as_ref());
+        self.type_params = s.generics.iter().map(|g| g.name).collect();
+        let fields = s
+            .fields
+            .iter()
+            .map(|f| {
+                let ty = match self.unknown_type_name(&f.ty) {
+                    Some(name) => {
+                        self.type_error(
+                            format!("cannot find type `{}` in this scope", name),
+                            s.span,
+                        );
+                        Type::Error
+                    },
+                    None => self.resolve_type(&f.ty),
+                };
+                FieldDef { name: f.name, ty }
+            })
+            .collect();
+        self.type_params = outer;
+
+        Some(StructItem {
+            def_id,
+            name: s.name,
+            generics,
+            fields,
+        })
+    }
+
+    /// Analyze and fold `const` items so their values are available to
+    /// array lengths anywhere in the module, and check `static`
+    /// initializers
+    fn eval_const_items(&mut self, items: &[ast::Item]) {
+        let const_defs: IndexSet<DefId> = items
+            .iter()
+            .filter_map(|item| match item {
+                ast::Item::Const(c) => self.scope_tree.resolve(c.name),
+                _ => None,
+            })
+            .collect();
+
+        for item in items {
+            match item {
+                ast::Item::Const(c) => self.eval_const_item(c, &const_defs),
+                ast::Item::Static(s) => self.check_static_item(s, &const_defs),
+                _ => {},
+            }
+        }
+    }
+
+    fn eval_const_item(&mut self, c: &ast::ConstItem, const_defs: &IndexSet<DefId>) {
+        let Some(def_id) = self.scope_tree.resolve(c.name) else {
+            return;
+        };
+
+        let ty = self.resolve_type(&c.ty);
+        self.type_context.set_def_type(def_id, ty.clone());
+
+        let Some(value) = self.analyze_expr(c.value.clone()) else {
+            return;
+        };
+        self.check_const_init("const", &value, &ty, const_defs);
+
+        let folded = const_eval::eval(&value, &self.consts);
+        if let Some(folded) = folded {
+            if let ConstValue::Int(n) = folded {
+                self.check_int_in_range(n, &ty, c.span);
+            }
+            self.consts.insert(def_id, folded);
+        }
+
+        self.const_items.insert(
+            def_id,
+            ConstItem {
+                def_id,
+                name: c.name,
+                ty,
+                value,
+                folded,
+            },
+        );
+    }
+
+    /// Type a `static` and check its initializer; statics have no HIR item
+    /// yet
+    fn check_static_item(&mut self, s: &ast::StaticItem, const_defs: &IndexSet<DefId>) {
+        let Some(def_id) = self.scope_tree.resolve(s.name) else {
+            return;
+        };
+
+        let ty = self.resolve_type(&s.ty);
+        self.type_context.set_def_type(def_id, ty.clone());
+
+        let Some(value) = self.analyze_expr(s.value.clone()) else {
+            return;
+        };
+        self.check_const_init("static", &value, &ty, const_defs);
+
+        if let Some(ConstValue::Int(n)) = const_eval::eval(&value, &self.consts) {
+            self.check_int_in_range(n, &ty, s.span);
+        }
+    }
+
+    /// Check that a `const` or `static` initializer is a constant expression
+    /// of the declared type
+    ///
+    /// Integer and float literals take the width of the declared type, and a
+    /// string literal may initialize a `&str`. The type of an initializer
+    /// that is not constant is not checked.
+    fn check_const_init(
+        &mut self,
+        kind: &str,
+        value: &Expr,
+        ty: &Type,
+        const_defs: &IndexSet<DefId>,
+    ) {
+        if !const_eval::is_const_expr(value, const_defs) {
+            self.type_error_with_help(
+                format!("`{}` initializer is not a constant expression", kind),
+                value.span(),
+                "only literals, operators, casts and other constants may be used",
+            );
+            return;
+        }
+
+        let found = value.ty();
+        let literal_fits = match (&found, ty) {
+            (_, Type::Error) | (Type::Error, _) => true,
+            (Type::Int, ty) => ty.is_integral(),
+            (Type::Float, ty) => ty.is_float(),
+            (Type::String, Type::Ref(inner, false)) => **inner == Type::String,
+            _ => false,
+        };
+        if !literal_fits {
+            self.unify_types(ty, &found, value.span());
+        }
+    }
+
+    /// Lower generic parameters and their where-clause
+    ///
+    /// The parameters are numbered after those already in scope and are in
+    /// scope while the where-clause is resolved. Inline bounds such as
+    /// `<T: Clone>` are not resolved yet.
+    fn lower_generics(
+        &mut self,
+        generics: &[ast::GenericParam],
+        where_clause: Option<&ast::WhereClause>,
+    ) -> GenericParams {
+        let base = self.type_params.len();
+        let params = generics
+            .iter()
+            .enumerate()
+            .map(|(i, g)| GenericParam {
+                id: ParamId((base + i) as u32),
+                name: g.name,
+                kind: GenericParamKind::Type { bounds: Vec::new() },
+            })
+            .collect();
+
+        self.type_params.extend(generics.iter().map(|g| g.name));
+        let mut predicates = Vec::new();
+        for bound in where_clause.iter().flat_map(|w| &w.bounds) {
+            let ty = self.resolve_type(&bound.ty);
+            let bounds = bound
+                .traits
+                .iter()
+                .filter_map(|path| self.resolve_trait(path))
+                .collect();
+            predicates.push(WherePredicate { ty, bounds });
+        }
+        self.type_params.truncate(base);
+
+        GenericParams {
+            params,
+            where_clause: predicates,
+        }
+    }
+
+    /// Resolve the path of a trait bound to the trait's definition
+    fn resolve_trait(&mut self, path: &ast::Path) -> Option<DefId> {
+        let name = path.segments.last()?.ident;
+        let def_id = match path.segments.as_slice() {
+            [seg] => {
+                let Some(def_id) = self.scope_tree.resolve(seg.ident) else {
+                    self.type_error(
+                        format!("cannot find trait `{}` in this scope", name),
+                        Span::DUMMY,
+                    );
+                    return None;
+                };
+                def_id
+            },
+            _ => self.resolve_path(path)?,
+        };
+        if !self.traits.contains(&def_id) {
+            self.type_error(format!("expected trait, found `{}`", name), Span::DUMMY);
+            return None;
+        }
+        Some(def_id)
+    }
+
+    /// Report an integer constant that does not fit its integer type
+    fn check_int_in_range(&mut self, value: i128, ty: &Type, span: Span) {
+        let Some((min, max)) = ty.int_range() else {
+            return;
+        };
+        if !(min..=max).contains(&value) {
+            self.type_error_with_help(
+                format!("literal out of range for `{}`", ty),
+                span,
+                format!(
+                    "the literal `{}` does not fit into the type `{}` whose range is `{}..={}`",
+                    value, ty, min, max
+                ),
+            );
+        }
+    }
+
+    /// Type named by the suffix of an integer literal such as `255u8`,
+    /// reporting the literal if its value does not fit
+    fn suffixed_int_type(&mut self, value: i64, suffix: Symbol) -> Type {
+        let ty = primitive_type(suffix.as_str()).unwrap_or(Type::Int);
+        self.check_int_in_range(value.into(), &ty, Span::DUMMY);
+        ty
+    }
+
+    /// Convert an AST type to its HIR type
+    fn resolve_type(&mut self, ty: &ast::Type) -> Type {
+        match ty {
+            ast::Type::Unit => Type::Unit,
+            ast::Type::Never => Type::Never,
+            ast::Type::Path(path) => {
+                let Some(name) = path.segments.last().map(|seg| seg.ident) else {
+                    return Type::Int;
+                };
+                self.resolve_type_name(name).unwrap_or(Type::Int)
+            },
+            ast::Type::Generic(_, _) => Type::Int,
+            ast::Type::Reference(ty, _) => Type::Ref(Box::new(self.resolve_type(ty)), false),
+            ast::Type::Pointer(ty, m) => Type::Ptr(
+                Box::new(self.resolve_type(ty)),
+                matches!(m, ast::Mutability::Mutable),
+            ),
+            ast::Type::Slice(ty) => Type::Slice(Box::new(self.resolve_type(ty))),
+            ast::Type::Array(ty, len) => {
+                let elem = self.resolve_type(ty);
+                match self.eval_array_len(len) {
+                    Some(len) => Type::Array(Box::new(elem), len),
+                    None => Type::Error,
+                }
+            },
+            ast::Type::Tuple(tys) => {
+                Type::Tuple(tys.iter().map(|t| self.resolve_type(t)).collect())
+            },
+            ast::Type::Fn(params, ret) => Type::Fn(
+                params.iter().map(|t| self.resolve_type(t)).collect(),
+                Box::new(self.resolve_type(ret)),
+            ),
+            ast::Type::TraitObject(_) => Type::String,
+            ast::Type::ImplTrait(_) => Type::Infer(InferId(0)),
+            ast::Type::Inferred => Type::Infer(InferId(0)),
+        }
+    }
+
+    /// Resolve a type name to a generic parameter in scope, the impl's
+    /// `Self` type, a primitive or a struct or enum
+    fn resolve_type_name(&self, name: Symbol) -> Option<Type> {
+        if name.as_str() == "Self" {
+            return self.self_ty.clone();
+        }
+        if let Some(index) = self.type_params.iter().rposition(|&p| p == name) {
+            return Some(Type::Param(ParamId(index as u32)));
+        }
+        primitive_type(name.as_str()).or_else(|| self.resolve_adt(name))
+    }
+
+    /// First type name in `ty` that does not resolve, if any
+    fn unknown_type_name(&self, ty: &ast::Type) -> Option<Symbol> {
+        match ty {
+            ast::Type::Path(path) => {
+                let name = path.segments.last()?.ident;
+                self.resolve_type_name(name).is_none().then_some(name)
+            },
+            ast::Type::Reference(ty, _)
+            | ast::Type::Pointer(ty, _)
+            | ast::Type::Slice(ty)
+            | ast::Type::Array(ty, _) => self.unknown_type_name(ty),
+            ast::Type::Tuple(tys) => tys.iter().find_map(|t| self.unknown_type_name(t)),
+            _ => None,
+        }
+    }
+
+    /// Evaluate an array length, reporting an error if it is not a constant
+    fn eval_array_len(&mut self, len: &ast::ArrayLen) -> Option<usize> {
+        match len {
+            ast::ArrayLen::Lit(n) => Some(*n),
+            ast::ArrayLen::Expr(expr) => self.eval_const_usize(expr),
+        }
+    }
+
+    /// Evaluate an array size or repeat count, reporting an error if it is
+    /// not a constant
+    fn eval_const_usize(&mut self, expr: &ast::Expr) -> Option<usize> {
+        let span = expr.span().unwrap_or(Span::DUMMY);
+        let len = self
+            .analyze_expr(expr.clone())
+            .and_then(|e| const_eval::eval(&e, &self.consts))
+            .and_then(ConstValue::as_usize);
+        if len.is_none() {
+            self.type_error("array size must be a constant", span);
+        }
+        len
+    }
+
+    /// Inject builtin function signatures into the module scope
+    fn collect_prelude(&mut self) {
+        for builtin in prelude::builtins() {
+            let def_id = self.def_id_gen.next();
+            self.scope_tree.add_binding(builtin.name, def_id);
+            self.type_context.set_def_type(def_id, builtin.ty);
+            self.builtins.insert(def_id, builtin.name);
+        }
+    }
+
+    /// Register enum variants so they resolve in paths and patterns
+    fn collect_variants(&mut self, enum_id: DefId, variants: &[ast::Variant]) {
+        for (index, variant) in variants.iter().enumerate() {
+            let def_id = self.def_id_gen.next();
+            self.scope_tree.add_binding(variant.name, def_id);
+
+            let fields: Vec<Type> = match &variant.data {
+                ast::VariantData::Unit => Vec::new(),
+                ast::VariantData::Tuple(tys) => tys.iter().map(|t| self.resolve_type(t)).collect(),
+                ast::VariantData::Struct(fields) => {
+                    fields.iter().map(|f| self.resolve_type(&f.ty)).collect()
+                },
+            };
+
+            let ty = match variant.data {
+                ast::VariantData::Tuple(_) => {
+                    Type::Fn(fields.clone(), Box::new(Type::Adt(enum_id)))
+                },
+                _ => Type::Adt(enum_id),
+            };
+            self.type_context.set_def_type(def_id, ty);
+
+            self.variants.insert(
+                def_id,
+                VariantInfo {
+                    index: index as u32,
+                    fields,
+                },
+            );
+        }
+    }
+
+    /// Resolve a type name to the struct or enum it names
+    fn resolve_adt(&self, name: Symbol) -> Option<Type> {
+        let def_id = self.scope_tree.resolve(name)?;
+        match self.type_context.type_of_def(def_id) {
+            Some(Type::Adt(adt)) if *adt == def_id => Some(Type::Adt(def_id)),
+            _ => None,
+        }
+    }
+
+    /// Resolve a name to an enum variant, returning its DefId
+    fn resolve_variant(&self, name: Symbol) -> Option<DefId> {
+        let def_id = self.scope_tree.resolve(name)?;
+        self.variants.contains_key(&def_id).then_some(def_id)
+    }
+
+    /// Analyze an item, descending into inline modules
+    fn analyze_module_item(&mut self, item: ast::Item) -> Vec<Item> {
+        if let ast::Item::Mod(m) = item {
+            return self.analyze_mod(m);
+        }
+
+        self.analyze_item(item).into_iter().collect()
+    }
+
+    /// Analyze the items of an inline module inside the module's rib
+    fn analyze_mod(&mut self, m: ast::ModItem) -> Vec<Item> {
+        let Some(def_id) = self.scope_tree.resolve(m.name) else {
+            return Vec::new();
+        };
+        let Some(rib) = self.modules.get(&def_id).map(|info| info.rib) else {
+            return Vec::new();
+        };
+
+        let parent = self.current_module.replace(def_id);
+        self.scope_tree.enter_existing(rib);
+        let items = m
+            .items
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|item| self.analyze_module_item(item))
+            .collect();
+        self.scope_tree.exit_scope();
+        self.current_module = parent;
+        items
+    }
+
     /// Analyze single item
     fn analyze_item(&mut self, item: ast::Item) -> Option<Item> {
         match item {
@@ -189,6 +1008,15 @@ impl<'a> SemanticAnalyzer<'a> {
                 println!("Analyzing function item: {}", fn_item.name.as_str());
                 self.analyze_fn_item(fn_item).map(Item::Function)
             },
+            ast::Item::Const(c) => {
+                let def_id = self.scope_tree.resolve(c.name)?;
+                self.const_items.shift_remove(&def_id).map(Item::Const)
+            },
+            ast::Item::Impl(imp) => self.analyze_impl(imp).map(Item::Impl),
+            ast::Item::Struct(s) => {
+                let def_id = self.scope_tree.resolve(s.name)?;
+                self.struct_items.shift_remove(&def_id).map(Item::Struct)
+            },
             // Implement others as needed
             _ => {
                 println!("Non-function item encountered");
@@ -200,23 +1028,159 @@ impl<'a> SemanticAnalyzer<'a> {
     /// Analyze function item
     fn analyze_fn_item(&mut self, item: ast::FnItem) -> Option<FnItem> {
         let def_id = self.scope_tree.resolve(item.name)?;
+        self.analyze_fn(def_id, item)
+    }
+
+    /// Analyze an impl block, typing method receivers as its self type
+    ///
+    /// A trait impl is recorded in the type context; its methods are
+    /// analyzed like inherent ones. Default methods of the trait that the
+    /// impl doesn't override are analyzed as if written in the impl.
+    fn analyze_impl(&mut self, imp: ast::ImplItem) -> Option<ImplItem> {
+        let impl_id = self.def_id_gen.next();
+        let generics = self.lower_generics(&imp.generics, imp.where_clause.as_ref());
+        let self_ty = self.resolve_type(&imp.self_ty);
+
+        let trait_ref = match &imp.trait_ref {
+            Some(ast::Type::Path(path)) => self.resolve_trait(path).map(|def_id| TraitRef {
+                def_id,
+                args: Vec::new(),
+            }),
+            _ => None,
+        };
+        if let Some(trait_ref) = &trait_ref {
+            self.check_impl_coherence(trait_ref.def_id, &imp, &self_ty);
+            self.type_context
+                .add_impl(trait_ref.def_id, self_ty.clone());
+        }
+
+        let defaults = trait_ref
+            .as_ref()
+            .and_then(|trait_ref| self.trait_defaults.get(&trait_ref.def_id))
+            .cloned()
+            .unwrap_or_default();
+        let overridden: IndexSet<Symbol> = imp
+            .items
+            .iter()
+            .filter_map(|member| match member {
+                ast::ImplMember::Method(method) => Some(method.name),
+                _ => None,
+            })
+            .collect();
+        let inherited = defaults
+            .into_iter()
+            .filter(|method| !overridden.contains(&method.name));
+
+        let outer = self.self_ty.replace(self_ty.clone());
+        let mut items = Vec::new();
+        let methods = imp.items.into_iter().filter_map(|member| match member {
+            ast::ImplMember::Method(method) => Some(method),
+            _ => None,
+        });
+        for method in methods.chain(inherited) {
+            let collected = match &trait_ref {
+                None => self.assoc_fns.get(&(self_ty.clone(), method.name)).copied(),
+                Some(_) => None,
+            };
+            let def_id = collected.unwrap_or_else(|| self.def_id_gen.next());
+            if let Some(f) = self.analyze_fn(def_id, method) {
+                items.push(ImplItemKind::Method(f));
+            }
+        }
+        self.self_ty = outer;
+
+        Some(ImplItem {
+            impl_id,
+            generics,
+            trait_ref,
+            self_ty,
+            items,
+        })
+    }
+
+    /// Report an `impl` of a trait for a type that already has one
+    fn check_impl_coherence(&mut self, trait_def: DefId, imp: &ast::ImplItem, self_ty: &Type) {
+        if *self_ty == Type::Error {
+            return;
+        }
+        let first = match self.trait_impl_spans.entry((trait_def, self_ty.clone())) {
+            Entry::Occupied(entry) => *entry.get(),
+            Entry::Vacant(entry) => {
+                entry.insert(imp.span);
+                return;
+            },
+        };
+
+        let trait_name = match &imp.trait_ref {
+            Some(ast::Type::Path(path)) => path.segments.last().map(|seg| seg.ident),
+            _ => None,
+        };
+        let Some(trait_name) = trait_name else {
+            return;
+        };
+        self.error_count += 1;
+        use faxc_util::diagnostic::DiagnosticBuilder;
+        DiagnosticBuilder::error(format!(
+            "conflicting implementations of trait `{}` for type `{}`",
+            trait_name,
+            self_ty.display(self.type_context)
+        ))
+        .span(imp.span)
+        .with_note(format!(
+            "first implementation here, at line {}, column {}",
+            first.line, first.column
+        ))
+        .emit(self.handler);
+    }
+
+    /// Analyze a function or method body and signature
+    fn analyze_fn(&mut self, def_id: DefId, item: ast::FnItem) -> Option<FnItem> {
+        let generics = self.lower_generics(&item.generics, item.where_clause.as_ref());
 
         // Enter function scope
         self.scope_tree.enter_scope(RibKind::Function);
 
-        // Analyze parameters
+        // Analyze parameters, starting with the receiver
         let mut params = Vec::new();
         let mut param_pats = Vec::new();
+        if let Some(kind) = item.self_param {
+            let self_ty = match &self.self_ty {
+                Some(ty) => ty.clone(),
+                None => {
+                    self.type_error(
+                        "`self` parameter is only allowed in associated functions",
+                        item.span,
+                    );
+                    Type::Error
+                },
+            };
+            let ty = receiver_type(kind, self_ty);
+            let name = Symbol::intern("self");
+            let def_id = self.def_id_gen.next();
+            let pat = Pattern::Binding {
+                name,
+                def_id,
+                ty: ty.clone(),
+                mutability: kind == ast::SelfKind::MutValue,
+            };
+            param_pats.push(pat.clone());
+
+            self.scope_tree.add_binding(name, def_id);
+            self.type_context.set_def_type(def_id, ty.clone());
+
+            params.push(Param { pat, ty });
+        }
         for param in &item.params {
-            let hir_ty = ast_type_to_hir(&param.ty);
+            let hir_ty = self.resolve_type(&param.ty);
+            let def_id = self.def_id_gen.next();
             let pat = Pattern::Binding {
                 name: param.name,
+                def_id,
                 ty: hir_ty.clone(),
                 mutability: param.mutable,
             };
             param_pats.push(pat.clone());
 
-            let def_id = self.def_id_gen.next();
             self.scope_tree.add_binding(param.name, def_id);
             self.type_context.set_def_type(def_id, hir_ty.clone());
 
@@ -229,11 +1193,10 @@ impl<'a> SemanticAnalyzer<'a> {
         self.scope_tree.exit_scope();
 
         // Determine return type
-        let ret_type = item
-            .ret_type
-            .as_ref()
-            .map(ast_type_to_hir)
-            .unwrap_or(Type::Unit);
+        let ret_type = match &item.ret_type {
+            Some(ty) => self.resolve_type(ty),
+            None => Type::Unit,
+        };
 
         // Extract body into proper structure
         let body = Body {
@@ -244,7 +1207,7 @@ impl<'a> SemanticAnalyzer<'a> {
         Some(FnItem {
             def_id,
             name: item.name,
-            generics: GenericParams::default(),
+            generics,
             params,
             ret_type,
             body,
@@ -254,6 +1217,7 @@ impl<'a> SemanticAnalyzer<'a> {
 
     /// Analyze block expression
     fn analyze_block(&mut self, block: ast::Block) -> Option<Expr> {
+        let span = block.span;
         self.scope_tree.enter_scope(RibKind::Block);
 
         let mut stmts = Vec::new();
@@ -275,18 +1239,36 @@ impl<'a> SemanticAnalyzer<'a> {
 
         self.scope_tree.exit_scope();
 
-        Some(Expr::Block { stmts, expr, ty })
+        Some(Expr::Block {
+            stmts,
+            expr,
+            ty,
+            span,
+        })
     }
 
     /// Analyze statement
     fn analyze_stmt(&mut self, stmt: ast::Stmt) -> Option<Stmt> {
         match stmt {
-            ast::Stmt::Let(l) => {
+            ast::Stmt::Let(mut l) => {
+                if let Some(else_block) = l.else_block.take() {
+                    return self.analyze_let_else(l, else_block);
+                }
+
                 let init = if let Some(expr) = l.init {
                     self.analyze_expr(expr)
                 } else {
                     None
                 };
+                let span = init.as_ref().map_or(Span::DUMMY, Expr::span);
+
+                // A constant initializer must fit the annotated integer type
+                if let (Some(ty), Some(init)) = (&l.ty, &init) {
+                    if let Some(ConstValue::Int(n)) = const_eval::eval(init, &self.consts) {
+                        let ty = self.resolve_type(ty);
+                        self.check_int_in_range(n, &ty, span);
+                    }
+                }
 
                 // Placeholder pattern handling
                 let (name, mutability) = match l.pattern {
@@ -301,6 +1283,7 @@ impl<'a> SemanticAnalyzer<'a> {
 
                 let pat = Pattern::Binding {
                     name,
+                    def_id,
                     ty: Type::Int, // Inference placeholder
                     mutability,
                 };
@@ -309,41 +1292,206 @@ impl<'a> SemanticAnalyzer<'a> {
                     pat,
                     ty: Type::Int,
                     init,
+                    else_block: None,
+                    span,
                 })
             },
-            ast::Stmt::If(if_stmt) => {
-                // Convert Stmt::If to Expr::If wrapped in Stmt::Expr
-                let if_expr = self.analyze_if(ast::IfExpr {
-                    cond: Box::new(if_stmt.cond),
-                    then_block: if_stmt.then_block,
-                    else_block: if_stmt.else_clause.map(|c| match *c {
-                        ast::ElseClause::Block(b) => Box::new(ast::Expr::Block(b)),
-                        ast::ElseClause::If(i) => Box::new(ast::Expr::If(ast::IfExpr {
-                            cond: Box::new(i.cond),
-                            then_block: i.then_block,
-                            else_block: i.else_clause.map(|ec| match *ec {
-                                ast::ElseClause::Block(b) => Box::new(ast::Expr::Block(b)),
-                                ast::ElseClause::If(next_i) => {
-                                    Box::new(ast::Expr::If(ast::IfExpr {
-                                        cond: Box::new(next_i.cond),
-                                        then_block: next_i.then_block,
-                                        else_block: None, // Simplified for deep nesting
-                                    }))
-                                },
-                            }),
-                        })),
-                    }),
-                })?;
+            ast::Stmt::If(if_expr) => {
+                let if_expr = self.analyze_if(if_expr)?;
                 Some(Stmt::Expr(if_expr))
             },
             ast::Stmt::Expr(e) => {
                 let expr = self.analyze_expr(e)?;
                 Some(Stmt::Expr(expr))
             },
+            ast::Stmt::Return(value) => {
+                let expr = self.analyze_return(value.map(Box::new))?;
+                Some(Stmt::Expr(expr))
+            },
+            ast::Stmt::Break(label) => {
+                let expr = self.analyze_break(None, label)?;
+                Some(Stmt::Expr(expr))
+            },
+            ast::Stmt::Continue(label) => {
+                let expr = self.analyze_continue(label)?;
+                Some(Stmt::Expr(expr))
+            },
+            ast::Stmt::While(while_stmt) => {
+                // Loops have no HIR form yet; check the condition and body for errors
+                self.analyze_expr(while_stmt.cond);
+                self.enter_loop(while_stmt.label, false);
+                self.analyze_block(while_stmt.body);
+                self.exit_loop();
+                None
+            },
+            ast::Stmt::For(for_stmt) => {
+                let expr = self.desugar_for(for_stmt)?;
+                Some(Stmt::Expr(expr))
+            },
+            ast::Stmt::WhileLet(while_let) => {
+                let scrutinee = self.analyze_expr(while_let.scrutinee)?;
+                self.enter_loop(while_let.label, false);
+                self.analyze_pattern(while_let.pattern, &scrutinee.ty());
+                self.analyze_block(while_let.body);
+                self.exit_loop();
+                None
+            },
             _ => None,
         }
     }
 
+    /// Analyze `let PAT = EXPR else { ... };`
+    ///
+    /// The pattern must be refutable and the else block must diverge, since
+    /// code after the statement relies on the pattern's bindings.
+    fn analyze_let_else(&mut self, l: ast::LetStmt, else_block: ast::Block) -> Option<Stmt> {
+        let init = self.analyze_expr(l.init?)?;
+        let init_ty = init.ty();
+
+        // The bindings are not in scope inside the else block
+        let else_span = else_block.span;
+        let else_expr = self.analyze_block(else_block)?;
+        if !else_expr.diverges() {
+            self.type_error("`else` block of let-else must diverge", else_span);
+        }
+
+        let pat = self.analyze_pattern(l.pattern, &init_ty)?;
+        if !pat.is_refutable() {
+            self.type_error("let-else requires a refutable pattern", else_span);
+        }
+
+        let span = init.span();
+        Some(Stmt::Let {
+            pat,
+            ty: init_ty,
+            init: Some(init),
+            else_block: Some(Box::new(else_expr)),
+            span,
+        })
+    }
+
+    /// Desugar `for PAT in ITER { BODY }` into
+    ///
+    /// ```text
+    /// {
+    ///     let mut iter = ITER;
+    ///     loop {
+    ///         match iter_next(&mut iter) {
+    ///             Some(PAT) => BODY,
+    ///             _ => break,
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// `iter` is not added to any scope, so the loop body cannot name it.
+    /// Arrays, slices and ranges are iterable, yielding their elements.
+    fn desugar_for(&mut self, for_stmt: ast::ForStmt) -> Option<Expr> {
+        let span = for_stmt.body.span;
+        let iter_init = self.analyze_expr(for_stmt.iter)?;
+        let iter_ty = iter_init.ty();
+        let elem_ty = match self.type_context.substitute(&iter_ty) {
+            Type::Array(elem, _) | Type::Slice(elem) => *elem,
+            Type::Ref(inner, _) => match *inner {
+                Type::Array(elem, _) | Type::Slice(elem) => Type::Ref(elem, false),
+                _ => Type::Error,
+            },
+            Type::Error => Type::Error,
+            other => {
+                self.type_error(
+                    format!("`{}` is not an iterator", other.display(self.type_context)),
+                    iter_init.span(),
+                );
+                Type::Error
+            },
+        };
+
+        let iter_id = self.def_id_gen.next();
+        self.type_context.set_def_type(iter_id, iter_ty.clone());
+        let iter_var = Expr::Var {
+            def_id: iter_id,
+            ty: iter_ty.clone(),
+            span,
+        };
+        let next_ty = Type::Option(Box::new(elem_ty.clone()));
+        let next_call = Expr::Call {
+            func: Box::new(Expr::Intrinsic {
+                name: Symbol::intern("iter_next"),
+                ty: Type::Fn(
+                    vec![Type::Ref(Box::new(iter_ty.clone()), true)],
+                    Box::new(next_ty.clone()),
+                ),
+                span,
+            }),
+            args: vec![Expr::Unary {
+                op: UnOp::Ref(true),
+                expr: Box::new(iter_var),
+                ty: Type::Ref(Box::new(iter_ty.clone()), true),
+                span,
+            }],
+            ty: next_ty.clone(),
+            span,
+        };
+
+        let label = self.enter_loop(for_stmt.label, false);
+        let pat = self.analyze_pattern(for_stmt.pattern, &elem_ty);
+        let body = self.analyze_block(for_stmt.body);
+        self.exit_loop();
+        let some = *self
+            .option_some
+            .get_or_insert_with(|| self.def_id_gen.next());
+
+        let arms = vec![
+            Arm {
+                pat: Pattern::Variant {
+                    def_id: some,
+                    index: 1,
+                    fields: vec![pat?],
+                },
+                guard: None,
+                body: body?,
+            },
+            Arm {
+                pat: Pattern::Wildcard,
+                guard: None,
+                body: Expr::Break {
+                    value: None,
+                    label,
+                    span,
+                },
+            },
+        ];
+        let loop_expr = Expr::Loop {
+            body: Box::new(Expr::Match {
+                scrutinee: Box::new(next_call),
+                arms,
+                ty: Type::Unit,
+                span,
+            }),
+            label,
+            ty: Type::Unit,
+            span,
+        };
+
+        Some(Expr::Block {
+            stmts: vec![Stmt::Let {
+                pat: Pattern::Binding {
+                    name: Symbol::intern("iter"),
+                    def_id: iter_id,
+                    ty: iter_ty.clone(),
+                    mutability: true,
+                },
+                ty: iter_ty,
+                init: Some(iter_init),
+                else_block: None,
+                span,
+            }],
+            expr: Some(Box::new(loop_expr)),
+            ty: Type::Unit,
+            span,
+        })
+    }
+
     /// Analyze expression
     fn analyze_expr(&mut self, expr: ast::Expr) -> Option<Expr> {
         match expr {
@@ -352,13 +1500,17 @@ impl<'a> SemanticAnalyzer<'a> {
             ast::Expr::Binary(bin) => self.analyze_binary(bin),
             ast::Expr::Unary(unary) => self.analyze_unary(unary),
             ast::Expr::If(if_expr) => self.analyze_if(if_expr),
+            ast::Expr::IfLet(if_let) => self.analyze_if_let(if_let),
             ast::Expr::Call(call) => self.analyze_call(call),
             ast::Expr::Block(block) => self.analyze_block(block),
+            ast::Expr::Paren(inner) => self.analyze_expr(*inner),
             ast::Expr::Tuple(items) => self.analyze_tuple(items),
             ast::Expr::Array(items) => self.analyze_array(items),
+            ast::Expr::ArrayRepeat { value, count } => self.analyze_array_repeat(*value, &count),
             ast::Expr::Index(index_expr) => self.analyze_index(index_expr),
             ast::Expr::Field(field_expr) => self.analyze_field(field_expr),
             ast::Expr::Match(match_expr) => self.analyze_match(match_expr),
+            ast::Expr::Loop(loop_expr) => self.analyze_loop(loop_expr),
             ast::Expr::Return(ret) => self.analyze_return(ret),
             ast::Expr::Break(value, label) => self.analyze_break(value, label),
             ast::Expr::Continue(label) => self.analyze_continue(label),
@@ -370,6 +1522,7 @@ impl<'a> SemanticAnalyzer<'a> {
             ast::Expr::Cast(cast_expr, target_ty) => self.analyze_cast(cast_expr, target_ty),
             ast::Expr::Async(async_expr) => self.analyze_async(async_expr),
             ast::Expr::Await(await_expr) => self.analyze_await(await_expr),
+            ast::Expr::StructLiteral(lit) => self.analyze_struct_literal(*lit),
             _ => None,
         }
     }
@@ -377,6 +1530,7 @@ impl<'a> SemanticAnalyzer<'a> {
     /// Analyze method call
     fn analyze_method_call(&mut self, expr: ast::MethodCallExpr) -> Option<Expr> {
         let receiver = self.analyze_expr(*expr.receiver)?;
+        let span = receiver.span();
 
         let mut args = Vec::new();
         for arg in expr.call_args {
@@ -388,17 +1542,29 @@ impl<'a> SemanticAnalyzer<'a> {
         Some(Expr::Call {
             func: Box::new(Expr::Field {
                 object: Box::new(receiver),
-                field: DefId(0),
+                index: 0,
                 ty: Type::Fn(vec![], Box::new(Type::Unit)),
+                span,
             }),
             args,
             ty: Type::Unit,
+            span,
         })
     }
 
     /// Analyze closure (lambda)
+    ///
+    /// Variables of the enclosing function used in the body become the
+    /// closure's captures, by value for `move` closures and by reference
+    /// otherwise.
     fn analyze_closure(&mut self, expr: ast::ClosureExpr) -> Option<Expr> {
-        self.scope_tree.enter_scope(RibKind::Block);
+        let rib = self.scope_tree.enter_scope(RibKind::Block);
+        let mode = if expr.move_kw {
+            CaptureMode::ByValue
+        } else {
+            CaptureMode::ByRef
+        };
+        self.closures.push((rib, mode, Vec::new()));
 
         let mut params = Vec::new();
         let mut param_tys = Vec::new();
@@ -406,38 +1572,65 @@ impl<'a> SemanticAnalyzer<'a> {
             let def_id = self.def_id_gen.next();
             self.scope_tree.add_binding(param.name, def_id);
 
-            let param_hir_ty = ast_type_to_hir(&param.ty);
+            let param_hir_ty = self.resolve_type(&param.ty);
             self.type_context.set_def_type(def_id, param_hir_ty.clone());
             param_tys.push(param_hir_ty.clone());
 
             params.push(Pattern::Binding {
                 name: param.name,
+                def_id,
                 ty: param_hir_ty,
                 mutability: false,
             });
         }
 
-        let body = self.analyze_expr(*expr.body)?;
-        let body_ty = body.ty();
+        let body = self.analyze_expr(*expr.body);
 
         self.scope_tree.exit_scope();
+        let (_, _, captures) = self.closures.pop()?;
 
-        let ty = Type::Fn(param_tys, Box::new(body_ty));
+        let body = body?;
+        let ty = Type::Fn(param_tys, Box::new(body.ty()));
+        let span = body.span();
 
-        Some(Expr::Literal {
-            lit: Literal::Unit,
+        Some(Expr::Closure {
+            def_id: self.def_id_gen.next(),
+            params,
+            body: Box::new(body),
+            captures,
             ty,
+            span,
         })
     }
 
+    /// Record `def_id` as a capture of each enclosing closure it is a
+    /// local variable from outside of
+    fn note_capture(&mut self, def_id: DefId, ty: &Type) {
+        for (rib, mode, captures) in &mut self.closures {
+            if captures.iter().any(|c| c.def_id == def_id) {
+                continue;
+            }
+            if let Some(name) = self.scope_tree.enclosing_local(*rib, def_id) {
+                captures.push(Capture {
+                    def_id,
+                    name,
+                    ty: ty.clone(),
+                    mode: *mode,
+                });
+            }
+        }
+    }
+
     /// Analyze assignment
     fn analyze_assign(&mut self, expr: ast::AssignExpr) -> Option<Expr> {
         let place = self.analyze_expr(*expr.place)?;
         let value = self.analyze_expr(*expr.value)?;
+        let span = place.span();
 
         Some(Expr::Assign {
             place: Box::new(place),
             value: Box::new(value),
+            span,
         })
     }
 
@@ -445,6 +1638,7 @@ impl<'a> SemanticAnalyzer<'a> {
     fn analyze_compound_assign(&mut self, expr: ast::CompoundAssignExpr) -> Option<Expr> {
         let place = self.analyze_expr(*expr.place)?;
         let place_ty = place.ty();
+        let span = place.span();
         let rhs = self.analyze_expr(*expr.value)?;
 
         let op = match expr.op {
@@ -461,6 +1655,7 @@ impl<'a> SemanticAnalyzer<'a> {
             left: Box::new(place),
             right: Box::new(rhs),
             ty: place_ty,
+            span,
         })
     }
 
@@ -474,17 +1669,33 @@ impl<'a> SemanticAnalyzer<'a> {
         Some(Expr::Literal {
             lit: Literal::Unit,
             ty,
+            span: Span::DUMMY,
         })
     }
 
     /// Analyze cast expression
     fn analyze_cast(&mut self, expr: Box<ast::Expr>, target_ty: ast::Type) -> Option<Expr> {
+        let span = expr.span().unwrap_or(Span::DUMMY);
         let inner = self.analyze_expr(*expr)?;
-        let ty = ast_type_to_hir(&target_ty);
+        let ty = self.resolve_type(&target_ty);
+
+        let from = self.type_context.substitute(&inner.ty());
+        if !from.can_cast_to(&ty) {
+            self.type_error_with_help(
+                format!(
+                    "non-primitive cast: `{}` as `{}`",
+                    from.display(self.type_context),
+                    ty.display(self.type_context)
+                ),
+                span,
+                "`as` only converts between numeric, `bool`, `char` and pointer types",
+            );
+        }
 
         Some(Expr::Cast {
             expr: Box::new(inner),
             ty,
+            span,
         })
     }
 
@@ -492,16 +1703,19 @@ impl<'a> SemanticAnalyzer<'a> {
     fn analyze_async(&mut self, expr: ast::AsyncExpr) -> Option<Expr> {
         let body = self.analyze_block(expr.body)?;
         let body_ty = body.ty();
+        let span = body.span();
 
         Some(Expr::Async {
             body: Box::new(body),
             ty: Type::Future(Box::new(body_ty)),
+            span,
         })
     }
 
     /// Analyze await expression
     fn analyze_await(&mut self, expr: Box<ast::Expr>) -> Option<Expr> {
         let future = self.analyze_expr(*expr)?;
+        let span = future.span();
 
         let ty = match future.ty() {
             Type::Future(inner_ty) => *inner_ty,
@@ -511,6 +1725,7 @@ impl<'a> SemanticAnalyzer<'a> {
         Some(Expr::Await {
             expr: Box::new(future),
             ty,
+            span,
         })
     }
 
@@ -535,11 +1750,14 @@ impl<'a> SemanticAnalyzer<'a> {
             op,
             expr: Box::new(inner),
             ty,
+            span: expr.span,
         })
     }
 
     /// Analyze function call
     fn analyze_call(&mut self, call: ast::CallExpr) -> Option<Expr> {
+        let assoc_fn =
+            matches!(&*call.func, ast::Expr::Path(path) if self.resolve_assoc_fn(path).is_some());
         let func = self.analyze_expr(*call.func)?;
 
         let mut args = Vec::new();
@@ -550,7 +1768,23 @@ impl<'a> SemanticAnalyzer<'a> {
         }
 
         let ty = match func.ty() {
-            Type::Fn(_, ret_ty) => *ret_ty,
+            Type::Fn(params, ret_ty) => {
+                if params.len() != args.len() {
+                    self.type_error(
+                        format!(
+                            "function takes {} arguments but {} were supplied",
+                            params.len(),
+                            args.len()
+                        ),
+                        call.span,
+                    );
+                } else if assoc_fn {
+                    for (param, arg) in params.iter().zip(&args) {
+                        self.unify_types(param, &arg.ty(), arg.span());
+                    }
+                }
+                *ret_ty
+            },
             Type::Infer(_) => Type::Unit,
             _ => Type::Unit,
         };
@@ -559,6 +1793,7 @@ impl<'a> SemanticAnalyzer<'a> {
             func: Box::new(func),
             args,
             ty,
+            span: call.span,
         })
     }
 
@@ -572,10 +1807,12 @@ impl<'a> SemanticAnalyzer<'a> {
         }
 
         let ty = Type::Tuple(analyzed.iter().map(|e| e.ty()).collect());
+        let span = analyzed.first().map_or(Span::DUMMY, Expr::span);
 
-        Some(Expr::Literal {
-            lit: Literal::Unit,
+        Some(Expr::Tuple {
+            elems: analyzed,
             ty,
+            span,
         })
     }
 
@@ -590,10 +1827,78 @@ impl<'a> SemanticAnalyzer<'a> {
 
         let elem_ty = analyzed.first().map(|e| e.ty()).unwrap_or(Type::Unit);
         let ty = Type::Array(Box::new(elem_ty), analyzed.len());
+        let span = analyzed.first().map_or(Span::DUMMY, Expr::span);
+
+        Some(Expr::Array {
+            elems: analyzed,
+            ty,
+            span,
+        })
+    }
+
+    /// Analyze struct literal, ordering its fields as the struct declares them
+    fn analyze_struct_literal(&mut self, lit: ast::StructLiteralExpr) -> Option<Expr> {
+        let name = lit.path.segments.last()?.ident;
+        let Some(def_id) = self
+            .scope_tree
+            .resolve(name)
+            .filter(|def_id| self.structs.contains_key(def_id))
+        else {
+            self.type_error(format!("cannot find struct `{}`", name), Span::DUMMY);
+            return None;
+        };
+        let decl = self.structs[&def_id].clone();
+        if lit.base.is_some() {
+            self.type_error("struct update syntax is not supported yet", Span::DUMMY);
+        }
+
+        let mut values: Vec<Option<Expr>> = vec![None; decl.len()];
+        for field in lit.fields {
+            let value = self.analyze_expr(field.expr)?;
+            match decl.iter().position(|(n, _)| *n == field.name) {
+                Some(idx) => values[idx] = Some(value),
+                None => self.type_error(
+                    format!("struct `{}` has no field named `{}`", name, field.name),
+                    value.span(),
+                ),
+            }
+        }
+
+        let mut fields = Vec::new();
+        for ((field_name, _), value) in decl.iter().zip(values) {
+            match value {
+                Some(value) => fields.push(value),
+                None if lit.base.is_some() => {},
+                None => self.type_error(
+                    format!(
+                        "missing field `{}` in initializer of `{}`",
+                        field_name, name
+                    ),
+                    Span::DUMMY,
+                ),
+            }
+        }
+
+        let span = fields.first().map_or(Span::DUMMY, Expr::span);
+        Some(Expr::Struct {
+            def_id,
+            fields,
+            ty: Type::Adt(def_id),
+            span,
+        })
+    }
+
+    /// Analyze array repeat expression `[value; count]`
+    fn analyze_array_repeat(&mut self, value: ast::Expr, count: &ast::Expr) -> Option<Expr> {
+        let value = self.analyze_expr(value)?;
+        let count = self.eval_const_usize(count)?;
+
+        let ty = Type::Array(Box::new(value.ty()), count);
 
         Some(Expr::Literal {
             lit: Literal::Unit,
             ty,
+            span: value.span(),
         })
     }
 
@@ -601,6 +1906,7 @@ impl<'a> SemanticAnalyzer<'a> {
     fn analyze_index(&mut self, index_expr: ast::IndexExpr) -> Option<Expr> {
         let object = self.analyze_expr(*index_expr.object)?;
         let index = self.analyze_expr(*index_expr.index)?;
+        let span = object.span();
 
         let ty = match object.ty() {
             Type::Array(elem_ty, _) => *elem_ty,
@@ -619,86 +1925,184 @@ impl<'a> SemanticAnalyzer<'a> {
             _ => Type::Unit,
         };
 
-        Some(Expr::Literal {
-            lit: Literal::Unit,
+        Some(Expr::Index {
+            object: Box::new(object),
+            index: Box::new(index),
             ty,
+            span,
         })
     }
 
-    /// Analyze field access
+    /// Analyze field access, looking through references to the object
     fn analyze_field(&mut self, field_expr: ast::FieldExpr) -> Option<Expr> {
         let object = self.analyze_expr(*field_expr.object)?;
+        let name = field_expr.field;
 
-        // Resolve field name to def_id (placeholder)
-        let field = DefId(0);
+        let mut object_ty = self.type_context.substitute(&object.ty());
+        while let Type::Ref(inner, _) = object_ty {
+            object_ty = *inner;
+        }
 
-        let ty = Type::Unit;
+        let field = match &object_ty {
+            Type::Adt(def_id) => self.structs.get(def_id).and_then(|fields| {
+                fields
+                    .iter()
+                    .position(|(n, _)| *n == name)
+                    .map(|idx| (idx as u32, fields[idx].1.clone()))
+            }),
+            Type::Tuple(tys) => name
+                .as_str()
+                .parse::<usize>()
+                .ok()
+                .and_then(|idx| Some((idx as u32, tys.get(idx)?.clone()))),
+            // Field types of other objects are not known yet
+            _ => Some((0, Type::Unit)),
+        };
+        let (index, ty) = field.unwrap_or_else(|| {
+            self.type_error(
+                format!(
+                    "no field `{}` on type `{}`",
+                    name,
+                    object_ty.display(self.type_context)
+                ),
+                field_expr.span,
+            );
+            (0, Type::Error)
+        });
 
         Some(Expr::Field {
             object: Box::new(object),
-            field,
+            index,
             ty,
+            span: field_expr.span,
         })
     }
 
     /// Analyze match expression
     fn analyze_match(&mut self, match_expr: ast::MatchExpr) -> Option<Expr> {
         let scrutinee = self.analyze_expr(*match_expr.scrutinee)?;
+        let span = scrutinee.span();
+
+        let scrutinee_ty = scrutinee.ty();
+
+        // With no arms, the match only type checks if there is no value to match
+        if match_expr.arms.is_empty()
+            && scrutinee_ty != Type::Error
+            && !self.is_uninhabited(&scrutinee_ty)
+        {
+            self.type_error_with_help(
+                "non-exhaustive patterns: match has no arms",
+                span,
+                format!(
+                    "the matched value has type `{}`; add an arm for each of its values",
+                    scrutinee_ty.display(self.type_context)
+                ),
+            );
+        }
 
         let mut arms = Vec::new();
         for arm in match_expr.arms {
-            let pat = self.analyze_pattern(arm.pattern)?;
+            self.scope_tree.enter_scope(RibKind::Block);
+            let pat = self.analyze_pattern(arm.pattern, &scrutinee_ty);
             let guard = arm.guard.and_then(|g| self.analyze_expr(g));
-            let body = self.analyze_expr(arm.body)?;
+            let body = self.analyze_expr(arm.body);
+            self.scope_tree.exit_scope();
 
-            arms.push(Arm { pat, guard, body });
+            arms.push(Arm {
+                pat: pat?,
+                guard,
+                body: body?,
+            });
         }
 
-        let ty = arms.first().map(|a| a.body.ty()).unwrap_or(Type::Unit);
+        // A match without arms never produces a value
+        let ty = arms.first().map(|a| a.body.ty()).unwrap_or(Type::Never);
 
         Some(Expr::Match {
             scrutinee: Box::new(scrutinee),
             arms,
             ty,
+            span,
         })
     }
 
-    /// Analyze pattern
-    fn analyze_pattern(&mut self, pat: ast::Pattern) -> Option<Pattern> {
+    /// Whether `ty` has no values, so that code receiving one is unreachable
+    fn is_uninhabited(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Never => true,
+            Type::Adt(def_id) => self.empty_enums.contains(def_id),
+            _ => false,
+        }
+    }
+
+    /// Analyze pattern against the type of the value it matches
+    fn analyze_pattern(&mut self, pat: ast::Pattern, expected: &Type) -> Option<Pattern> {
         match pat {
             ast::Pattern::Wildcard => Some(Pattern::Wildcard),
             ast::Pattern::Ident(name, mutability) => {
-                let ty = Type::Infer(InferId(0));
+                // A bare name that resolves to a unit variant matches that variant
+                if let Some(def_id) = self.resolve_variant(name) {
+                    return self.variant_pattern(def_id, Vec::new());
+                }
+
+                let def_id = self.def_id_gen.next();
+                self.scope_tree.add_binding(name, def_id);
+                self.type_context.set_def_type(def_id, expected.clone());
+
                 Some(Pattern::Binding {
                     name,
-                    ty,
+                    def_id,
+                    ty: expected.clone(),
                     mutability: matches!(mutability, ast::Mutability::Mutable),
                 })
             },
             ast::Pattern::Literal(lit) => {
-                let (_lit_kind, ty) = match lit {
+                if let ast::Literal::Int(n) = lit {
+                    self.check_int_in_range(n.into(), expected, Span::DUMMY);
+                }
+                let (lit, ty) = match lit {
                     ast::Literal::Int(n) => (Literal::Int(n), Type::Int),
+                    ast::Literal::TypedInt(n, suffix) => {
+                        (Literal::Int(n), self.suffixed_int_type(n, suffix))
+                    },
                     ast::Literal::Float(f) => (Literal::Float(f), Type::Float),
                     ast::Literal::String(s) => (Literal::String(s), Type::String),
                     ast::Literal::Bool(b) => (Literal::Bool(b), Type::Bool),
                     ast::Literal::Char(c) => (Literal::Char(c), Type::Char),
-                    ast::Literal::Unit => (Literal::Unit, Type::Unit),
+                    // `()` has a single value, so it matches like an empty tuple
+                    ast::Literal::Unit => return Some(Pattern::Tuple { pats: Vec::new() }),
                 };
-                Some(Pattern::Binding {
-                    name: faxc_util::Symbol::intern("_"),
-                    ty,
-                    mutability: false,
-                })
+                Some(Pattern::Literal { lit, ty })
             },
             ast::Pattern::Path(path) => {
-                let name = path.segments.first()?.ident;
+                let name = path.segments.last()?.ident;
+                if let Some(def_id) = self.resolve_variant(name) {
+                    return self.variant_pattern(def_id, Vec::new());
+                }
                 let def_id = self.scope_tree.resolve(name).unwrap_or(DefId(0));
                 Some(Pattern::Path { def_id })
             },
+            ast::Pattern::TupleStruct(path, pats) => {
+                let name = path.segments.last()?.ident;
+                match self.resolve_variant(name) {
+                    Some(def_id) => self.variant_pattern(def_id, pats),
+                    None => {
+                        self.type_error(
+                            format!("cannot find tuple struct or variant `{}`", name),
+                            Span::DUMMY,
+                        );
+                        Some(Pattern::Wildcard)
+                    },
+                }
+            },
             ast::Pattern::Tuple(pats) => {
                 let mut analyzed = Vec::new();
-                for p in pats {
-                    if let Some(ap) = self.analyze_pattern(p) {
+                for (i, p) in pats.into_iter().enumerate() {
+                    let ty = match expected {
+                        Type::Tuple(tys) => tys.get(i).cloned().unwrap_or(Type::Error),
+                        _ => Type::Infer(InferId(0)),
+                    };
+                    if let Some(ap) = self.analyze_pattern(p, &ty) {
                         analyzed.push(ap);
                     }
                 }
@@ -708,10 +2112,44 @@ impl<'a> SemanticAnalyzer<'a> {
         }
     }
 
+    /// Build a variant pattern, typing payload sub-patterns from the variant's fields
+    fn variant_pattern(&mut self, def_id: DefId, pats: Vec<ast::Pattern>) -> Option<Pattern> {
+        let (index, field_tys) = {
+            let info = self.variants.get(&def_id)?;
+            (info.index, info.fields.clone())
+        };
+
+        if pats.len() != field_tys.len() {
+            self.type_error(
+                format!(
+                    "variant pattern has {} fields, but the variant has {}",
+                    pats.len(),
+                    field_tys.len()
+                ),
+                Span::DUMMY,
+            );
+        }
+
+        let mut fields = Vec::new();
+        for (p, ty) in pats.into_iter().zip(field_tys.iter()) {
+            fields.push(self.analyze_pattern(p, ty)?);
+        }
+
+        Some(Pattern::Variant {
+            def_id,
+            index,
+            fields,
+        })
+    }
+
     /// Analyze return expression
     fn analyze_return(&mut self, value: Option<Box<ast::Expr>>) -> Option<Expr> {
         let val = value.and_then(|v| self.analyze_expr(*v));
-        Some(Expr::Return(val.map(Box::new)))
+        let span = val.as_ref().map_or(Span::DUMMY, Expr::span);
+        Some(Expr::Return {
+            value: val.map(Box::new),
+            span,
+        })
     }
 
     /// Analyze break expression
@@ -720,13 +2158,144 @@ impl<'a> SemanticAnalyzer<'a> {
         value: Option<Box<ast::Expr>>,
         label: Option<faxc_util::Symbol>,
     ) -> Option<Expr> {
+        let label = match label {
+            Some(name) => Some(self.resolve_label(name)?),
+            None => None,
+        };
         let val = value.and_then(|v| self.analyze_expr(*v));
-        Some(Expr::Break(val.map(Box::new), label.map(|_| LabelId(0))))
+        let span = val.as_ref().map_or(Span::DUMMY, Expr::span);
+        let ty = val.as_ref().map(Expr::ty);
+        self.check_break_type(label, ty, span);
+        Some(Expr::Break {
+            value: val.map(Box::new),
+            label,
+            span,
+        })
+    }
+
+    /// Analyze loop expression, which has the type its `break`s agree on
+    fn analyze_loop(&mut self, loop_expr: ast::LoopExpr) -> Option<Expr> {
+        let label = self.enter_loop(loop_expr.label, true);
+        let body = self.analyze_block(loop_expr.body);
+        let ty = self.exit_loop();
+        let body = body?;
+        let span = body.span();
+        Some(Expr::Loop {
+            body: Box::new(body),
+            label,
+            ty,
+            span,
+        })
+    }
+
+    /// Check the type of a `break` against the loop it exits
+    ///
+    /// The first `break` gives the loop its type; every later one must
+    /// agree with it. `ty` is `None` for a `break` without a value, which
+    /// is the only kind a `while` or `for` loop accepts.
+    fn check_break_type(&mut self, label: Option<LabelId>, ty: Option<Type>, span: Span) {
+        use faxc_util::diagnostic::DiagnosticBuilder;
+
+        let target = match label {
+            Some(label) => self
+                .loop_stack
+                .iter_mut()
+                .rev()
+                .find(|(id, _)| *id == Some(label)),
+            None => self.loop_stack.last_mut(),
+        };
+        let Some((_, loop_ty)) = target else {
+            return;
+        };
+        let Some(loop_ty) = loop_ty else {
+            if ty.is_some() {
+                self.error_count += 1;
+                DiagnosticBuilder::error("`break` with a value is only allowed in `loop`")
+                    .span(span)
+                    .with_note("`while` and `for` loops always have the type `()`")
+                    .emit(self.handler);
+            }
+            return;
+        };
+        let ty = ty.unwrap_or(Type::Unit);
+        if *loop_ty == Type::Never {
+            *loop_ty = ty;
+            return;
+        }
+        let unknown = |t: &Type| matches!(t, Type::Never | Type::Error | Type::Infer(_));
+        if *loop_ty == ty || unknown(loop_ty) || unknown(&ty) {
+            return;
+        }
+
+        let expected = loop_ty.display(self.type_context).to_string();
+        self.error_count += 1;
+        DiagnosticBuilder::error(format!(
+            "mismatched types: expected `{}`, found `{}`",
+            expected,
+            ty.display(self.type_context)
+        ))
+        .span(span)
+        .with_note(format!(
+            "an earlier `break` gives the loop the type `{}`",
+            expected
+        ))
+        .emit(self.handler);
     }
 
     /// Analyze continue expression
     fn analyze_continue(&mut self, label: Option<faxc_util::Symbol>) -> Option<Expr> {
-        Some(Expr::Continue(label.map(|_| LabelId(0))))
+        let label = match label {
+            Some(name) => Some(self.resolve_label(name)?),
+            None => None,
+        };
+        Some(Expr::Continue {
+            label,
+            span: Span::DUMMY,
+        })
+    }
+
+    /// Enter a loop body, giving the loop a fresh `LabelId` if it is labeled
+    ///
+    /// `valued` is set for `loop`, the only loop a `break` can give a value.
+    fn enter_loop(&mut self, label: Option<Symbol>, valued: bool) -> Option<LabelId> {
+        let id = label.map(|name| {
+            let id = LabelId(self.next_label);
+            self.next_label += 1;
+            self.labels.push((name, id));
+            id
+        });
+        self.loop_stack.push((id, valued.then_some(Type::Never)));
+        self.scope_tree.enter_scope(RibKind::Loop(id));
+        id
+    }
+
+    /// Leave the loop body entered by the matching `enter_loop`, returning
+    /// the loop's type as an expression: `()` for `while` and `for`
+    fn exit_loop(&mut self) -> Type {
+        self.scope_tree.exit_scope();
+        match self.loop_stack.pop() {
+            Some((label, ty)) => {
+                if label.is_some() {
+                    self.labels.pop();
+                }
+                ty.unwrap_or(Type::Unit)
+            },
+            None => Type::Never,
+        }
+    }
+
+    /// Find the innermost enclosing loop labeled `name`
+    fn resolve_label(&mut self, name: Symbol) -> Option<LabelId> {
+        let found = self
+            .labels
+            .iter()
+            .rev()
+            .find(|(label, _)| *label == name)
+            .map(|(_, id)| *id);
+        if found.is_none() {
+            self.type_error(format!("use of undeclared label `'{}`", name), Span::DUMMY);
+        }
+        found
     }
 
     /// Analyze if expression
@@ -737,11 +2306,12 @@ impl<'a> SemanticAnalyzer<'a> {
         if cond.ty() != Type::Bool {
             use faxc_util::diagnostic::DiagnosticBuilder;
             DiagnosticBuilder::error("If condition must be a boolean")
-                .span(faxc_util::Span::DUMMY)
-                .emit(&self.handler);
+                .span(cond.span())
+                .emit(self.handler);
         }
 
         let then_expr = Box::new(self.analyze_block(expr.then_block)?);
+        let span = then_expr.span();
 
         let mut else_expr = None;
         let mut ty = Type::Unit;
@@ -755,16 +2325,16 @@ impl<'a> SemanticAnalyzer<'a> {
             if then_expr.ty() != ty {
                 use faxc_util::diagnostic::DiagnosticBuilder;
                 DiagnosticBuilder::error("If and Else branches must have the same type")
-                    .span(faxc_util::Span::DUMMY)
-                    .emit(&self.handler);
+                    .span(else_expr.as_ref().map_or(span, |e| e.span()))
+                    .emit(self.handler);
             }
         } else {
             // If no else, type must be unit
             if then_expr.ty() != Type::Unit {
                 use faxc_util::diagnostic::DiagnosticBuilder;
                 DiagnosticBuilder::error("If branch without else must return unit")
-                    .span(faxc_util::Span::DUMMY)
-                    .emit(&self.handler);
+                    .span(span)
+                    .emit(self.handler);
             }
         }
 
@@ -773,6 +2343,64 @@ impl<'a> SemanticAnalyzer<'a> {
             then_expr,
             else_expr,
             ty,
+            span,
+        })
+    }
+
+    /// Analyze `if let`, lowering it to a two-armed match
+    fn analyze_if_let(&mut self, expr: ast::IfLetExpr) -> Option<Expr> {
+        let scrutinee = self.analyze_expr(*expr.scrutinee)?;
+        let scrutinee_ty = scrutinee.ty();
+
+        // Pattern bindings are only visible in the then-block
+        self.scope_tree.enter_scope(RibKind::Block);
+        let pat = self.analyze_pattern(expr.pattern, &scrutinee_ty);
+        let then_expr = self.analyze_block(expr.then_block);
+        self.scope_tree.exit_scope();
+        let (pat, then_expr) = (pat?, then_expr?);
+
+        let else_expr = match expr.else_block {
+            Some(e) => {
+                let else_expr = self.analyze_expr(*e)?;
+                if then_expr.ty() != else_expr.ty() {
+                    self.type_error(
+                        "If and Else branches must have the same type",
+                        else_expr.span(),
+                    );
+                }
+                else_expr
+            },
+            None => {
+                if then_expr.ty() != Type::Unit {
+                    self.type_error("If branch without else must return unit", then_expr.span());
+                }
+                Expr::Block {
+                    stmts: Vec::new(),
+                    expr: None,
+                    ty: Type::Unit,
+                    span: Span::DUMMY,
+                }
+            },
+        };
+
+        let ty = then_expr.ty();
+        let span = scrutinee.span();
+        Some(Expr::Match {
+            scrutinee: Box::new(scrutinee),
+            arms: vec![
+                Arm {
+                    pat,
+                    guard: None,
+                    body: then_expr,
+                },
+                Arm {
+                    pat: Pattern::Wildcard,
+                    guard: None,
+                    body: else_expr,
+                },
+            ],
+            ty,
+            span,
         })
     }
 
@@ -780,6 +2408,9 @@ impl<'a> SemanticAnalyzer<'a> {
     fn analyze_literal(&mut self, lit: ast::Literal) -> Option<Expr> {
         let (lit_kind, ty) = match lit {
             ast::Literal::Int(n) => (Literal::Int(n), Type::Int),
+            ast::Literal::TypedInt(n, suffix) => {
+                (Literal::Int(n), self.suffixed_int_type(n, suffix))
+            },
             ast::Literal::Float(f) => (Literal::Float(f), Type::Float),
             ast::Literal::String(s) => (Literal::String(s), Type::String),
             ast::Literal::Bool(b) => (Literal::Bool(b), Type::Bool),
@@ -787,14 +2418,125 @@ impl<'a> SemanticAnalyzer<'a> {
             ast::Literal::Unit => (Literal::Unit, Type::Unit),
         };
 
-        Some(Expr::Literal { lit: lit_kind, ty })
+        Some(Expr::Literal {
+            lit: lit_kind,
+            ty,
+            span: Span::DUMMY,
+        })
+    }
+
+    /// Resolve a path to a definition
+    ///
+    /// The first segment is looked up in scope. While the path continues
+    /// through inline modules, each further segment is looked up among the
+    /// module's members and must be visible from the current module. Other
+    /// multi-segment paths (such as `Enum::Variant`) resolve to their first
+    /// segment.
+    fn resolve_path(&mut self, path: &ast::Path) -> Option<DefId> {
+        let (first, rest) = path.segments.split_first()?;
+        let Some(mut def_id) = self.scope_tree.resolve(first.ident) else {
+            self.unresolved_name(first.ident);
+            return None;
+        };
+
+        for seg in rest {
+            let Some(module) = self.modules.get(&def_id) else {
+                break;
+            };
+            let Some((member, vis)) = module.members.get(&seg.ident).cloned() else {
+                self.type_error(
+                    format!("cannot find `{}` in module `{}`", seg.ident, first.ident),
+                    Span::DUMMY,
+                );
+                return None;
+            };
+            if !self.is_accessible(def_id, &vis) {
+                self.type_error_with_help(
+                    format!("item {} is private", seg.ident),
+                    Span::DUMMY,
+                    format!("consider declaring `{}` with `pub`", seg.ident),
+                );
+            }
+            def_id = member;
+        }
+
+        Some(def_id)
+    }
+
+    /// Report a name that is not bound in any enclosing scope, suggesting
+    /// a similarly spelled binding if there is one
+    fn unresolved_name(&mut self, name: Symbol) {
+        let message = format!("cannot find value `{}` in this scope", name);
+        match self.scope_tree.similar_name(name) {
+            Some(similar) => self.type_error_with_help(
+                message,
+                Span::DUMMY,
+                format!("did you mean `{}`?", similar),
+            ),
+            None => self.type_error(message, Span::DUMMY),
+        }
+    }
+
+    /// Check whether an item of module `owner` with visibility `vis` can be
+    /// used from the current module
+    fn is_accessible(&self, owner: DefId, vis: &ast::Visibility) -> bool {
+        // Module that the item is visible within, `None` for the whole crate
+        let scope = match vis {
+            ast::Visibility::Public | ast::Visibility::Crate => return true,
+            ast::Visibility::Private => Some(owner),
+            ast::Visibility::Super => self.modules[&owner].parent,
+            ast::Visibility::Restricted(path) => self.restriction_scope(owner, path),
+        };
+        let Some(scope) = scope else {
+            return true;
+        };
+
+        let mut current = self.current_module;
+        while let Some(module) = current {
+            if module == scope {
+                return true;
+            }
+            current = self.modules[&module].parent;
+        }
+        false
+    }
+
+    /// Module named by a `pub(in path)` restriction on an item of `owner`
+    ///
+    /// The path is relative to `owner` unless it starts with `crate`. A path
+    /// that doesn't name a module restricts the item to `owner`.
+    fn restriction_scope(&self, owner: DefId, path: &ast::Path) -> Option<DefId> {
+        let mut scope = Some(owner);
+        for seg in &path.segments {
+            scope = match seg.ident.as_str() {
+                "crate" => None,
+                "self" => Some(owner),
+                "super" => scope.and_then(|m| self.modules[&m].parent),
+                _ => {
+                    let def_id = match scope {
+                        Some(m) => self.modules[&m].members.get(&seg.ident).map(|(id, _)| *id),
+                        None => self.scope_tree.ribs[RibId(0)]
+                            .bindings
+                            .get(&seg.ident)
+                            .copied(),
+                    };
+                    match def_id.filter(|id| self.modules.contains_key(id)) {
+                        Some(id) => Some(id),
+                        None => return Some(owner),
+                    }
+                },
+            };
+        }
+        scope
     }
 
     /// Analyze path expression
     fn analyze_path(&mut self, path: ast::Path) -> Option<Expr> {
-        // Resolve path to definition
-        let name = path.segments.first()?;
-        let def_id = self.scope_tree.resolve(name.ident)?;
+        // Resolve path to definition, trying associated functions first
+        let def_id = match self.resolve_assoc_fn(&path) {
+            Some(def_id) => def_id,
+            None => self.resolve_path(&path)?,
+        };
 
         // Get type of definition (Mocked for MVP if not in context)
         let ty = self
@@ -803,7 +2545,20 @@ impl<'a> SemanticAnalyzer<'a> {
             .cloned()
             .unwrap_or(Type::Int);
 
-        Some(Expr::Var { def_id, ty })
+        if let Some(&name) = self.builtins.get(&def_id) {
+            return Some(Expr::Intrinsic {
+                name,
+                ty,
+                span: Span::DUMMY,
+            });
+        }
+        self.note_capture(def_id, &ty);
+
+        Some(Expr::Var {
+            def_id,
+            ty,
+            span: Span::DUMMY,
+        })
     }
 
     /// Analyze binary expression
@@ -812,18 +2567,44 @@ impl<'a> SemanticAnalyzer<'a> {
         let right = self.analyze_expr(*expr.right)?;
 
         let op = self.convert_binop(expr.op, expr.span)?;
+        let (left_ty, right_ty) = (left.ty(), right.ty());
 
         // Determine result type
         let ty = match op {
             BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Gt | BinOp::Le | BinOp::Ge => Type::Bool,
-            _ => left.ty(),
+            // String concatenation, lowered to a runtime call
+            BinOp::Add if left_ty == Type::String && right_ty == Type::String => Type::String,
+            _ if left_ty == Type::String || right_ty == Type::String => {
+                self.type_error(
+                    format!(
+                        "cannot apply `{:?}` to `{}` and `{}`",
+                        op,
+                        left_ty.display(self.type_context),
+                        right_ty.display(self.type_context)
+                    ),
+                    expr.span,
+                );
+                Type::Error
+            },
+            _ => left_ty,
         };
 
+        // The division is still emitted, so the program traps when it runs
+        if matches!(op, BinOp::Div | BinOp::Mod)
+            && const_eval::eval(&right, &self.consts) == Some(ConstValue::Int(0))
+        {
+            self.type_error(
+                "this operation will panic at runtime: attempt to divide by zero",
+                expr.span,
+            );
+        }
+
         Some(Expr::Binary {
             op,
             left: Box::new(left),
             right: Box::new(right),
             ty,
+            span: expr.span,
         })
     }
 
@@ -849,7 +2630,7 @@ impl<'a> SemanticAnalyzer<'a> {
                 use faxc_util::diagnostic::DiagnosticBuilder;
                 DiagnosticBuilder::error("Bitwise XOR operator is not yet supported")
                     .span(span)
-                    .emit(&self.handler);
+                    .emit(self.handler);
                 Some(BinOp::And) // Fallback to prevent compilation failure
             },
             ast::BinOp::Shl => {
@@ -857,7 +2638,7 @@ impl<'a> SemanticAnalyzer<'a> {
                 use faxc_util::diagnostic::DiagnosticBuilder;
                 DiagnosticBuilder::error("Shift left operator is not yet supported")
                     .span(span)
-                    .emit(&self.handler);
+                    .emit(self.handler);
                 Some(BinOp::Add) // Fallback to prevent compilation failure
             },
             ast::BinOp::Shr => {
@@ -865,9 +2646,169 @@ impl<'a> SemanticAnalyzer<'a> {
                 use faxc_util::diagnostic::DiagnosticBuilder;
                 DiagnosticBuilder::error("Shift right operator is not yet supported")
                     .span(span)
-                    .emit(&self.handler);
+                    .emit(self.handler);
                 Some(BinOp::Add) // Fallback to prevent compilation failure
             },
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_labeled_break_resolves_to_its_loop() {
+        let mut type_ctx = TypeContext::default();
+        let def_id_gen = DefIdGenerator::new();
+        let mut handler = Handler::new();
+        let mut analyzer = SemanticAnalyzer::new(&mut type_ctx, &def_id_gen, &mut handler);
+
+        let outer = Symbol::intern("outer");
+        let inner = Symbol::intern("inner");
+        let outer_id = analyzer.enter_loop(Some(outer), true).unwrap();
+        let inner_id = analyzer.enter_loop(Some(inner), true).unwrap();
+        assert_ne!(outer_id, inner_id);
+
+        match analyzer.analyze_stmt(ast::Stmt::Break(Some(outer))) {
+            Some(Stmt::Expr(Expr::Break { label, .. })) => assert_eq!(label, Some(outer_id)),
+            other => panic!("expected break, got {:?}", other),
+        }
+        match analyzer.analyze_stmt(ast::Stmt::Continue(Some(inner))) {
+            Some(Stmt::Expr(Expr::Continue { label, .. })) => assert_eq!(label, Some(inner_id)),
+            other => panic!("expected continue, got {:?}", other),
+        }
+
+        analyzer.exit_loop();
+        analyzer.exit_loop();
+        assert!(analyzer.labels.is_empty());
+        assert!(analyzer.loop_stack.is_empty());
+        assert!(!handler.has_errors());
+    }
+
+    /// `break value;` as an expression
+    fn break_with(lit: ast::Literal) -> ast::Expr {
+        ast::Expr::Break(Some(Box::new(ast::Expr::Literal(lit))), None)
+    }
+
+    #[test]
+    fn test_agreeing_breaks_give_loop_type() {
+        let mut type_ctx = TypeContext::default();
+        let def_id_gen = DefIdGenerator::new();
+        let mut handler = Handler::new();
+        let mut analyzer = SemanticAnalyzer::new(&mut type_ctx, &def_id_gen, &mut handler);
+
+        analyzer.enter_loop(None, true);
+        analyzer.analyze_expr(break_with(ast::Literal::Int(1)));
+        analyzer.analyze_expr(break_with(ast::Literal::Int(2)));
+        assert_eq!(analyzer.exit_loop(), Type::Int);
+
+        // A loop without `break` never completes
+        analyzer.enter_loop(None, true);
+        assert_eq!(analyzer.exit_loop(), Type::Never);
+        assert!(!handler.has_errors());
+    }
+
+    #[test]
+    fn test_disagreeing_breaks_are_an_error() {
+        let mut type_ctx = TypeContext::default();
+        let def_id_gen = DefIdGenerator::new();
+        let mut handler = Handler::new();
+        let mut analyzer = SemanticAnalyzer::new(&mut type_ctx, &def_id_gen, &mut handler);
+
+        analyzer.enter_loop(None, true);
+        analyzer.analyze_expr(break_with(ast::Literal::Int(1)));
+        analyzer.analyze_expr(break_with(ast::Literal::Bool(true)));
+        // `break;` gives the loop the type `()`
+        analyzer.analyze_stmt(ast::Stmt::Break(None));
+        analyzer.exit_loop();
+
+        let messages: Vec<_> = handler
+            .diagnostics()
+            .into_iter()
+            .map(|d| d.message)
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "mismatched types: expected `int`, found `bool`",
+                "mismatched types: expected `int`, found `()`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_loop_expr_takes_type_of_breaks() {
+        let mut type_ctx = TypeContext::default();
+        let def_id_gen = DefIdGenerator::new();
+        let mut handler = Handler::new();
+        let mut analyzer = SemanticAnalyzer::new(&mut type_ctx, &def_id_gen, &mut handler);
+
+        // loop { break 1; }
+        let expr = analyzer.analyze_expr(ast::Expr::Loop(ast::LoopExpr {
+            body: ast::Block {
+                stmts: vec![ast::Stmt::Expr(break_with(ast::Literal::Int(1)))],
+                trailing: None,
+                span: Span::DUMMY,
+            },
+            label: None,
+        }));
+        assert!(matches!(expr, Some(Expr::Loop { ty: Type::Int, .. })));
+        assert!(analyzer.loop_stack.is_empty());
+        assert!(!handler.has_errors());
+    }
+
+    #[test]
+    fn test_break_value_in_while_is_an_error() {
+        let mut type_ctx = TypeContext::default();
+        let def_id_gen = DefIdGenerator::new();
+        let mut handler = Handler::new();
+        let mut analyzer = SemanticAnalyzer::new(&mut type_ctx, &def_id_gen, &mut handler);
+
+        // while true { break 1; break; }
+        analyzer.analyze_stmt(ast::Stmt::While(ast::WhileStmt {
+            cond: ast::Expr::Literal(ast::Literal::Bool(true)),
+            body: ast::Block {
+                stmts: vec![
+                    ast::Stmt::Expr(break_with(ast::Literal::Int(1))),
+                    ast::Stmt::Break(None),
+                ],
+                trailing: None,
+                span: Span::DUMMY,
+            },
+            label: None,
+        }));
+
+        let messages: Vec<_> = handler
+            .diagnostics()
+            .into_iter()
+            .map(|d| d.message)
+            .collect();
+        assert_eq!(messages, ["`break` with a value is only allowed in `loop`"]);
+    }
+
+    #[test]
+    fn test_undeclared_label_is_an_error() {
+        let mut type_ctx = TypeContext::default();
+        let def_id_gen = DefIdGenerator::new();
+        let mut handler = Handler::new();
+        let mut analyzer = SemanticAnalyzer::new(&mut type_ctx, &def_id_gen, &mut handler);
+
+        // 'outer: while true { break 'missing; }
+        analyzer.analyze_stmt(ast::Stmt::While(ast::WhileStmt {
+            cond: ast::Expr::Literal(ast::Literal::Bool(true)),
+            body: ast::Block {
+                stmts: vec![ast::Stmt::Break(Some(Symbol::intern("missing")))],
+                trailing: None,
+                span: Span::DUMMY,
+            },
+            label: Some(Symbol::intern("outer")),
+        }));
+        assert!(analyzer.loop_stack.is_empty());
+
+        assert!(handler
+            .diagnostics()
+            .iter()
+            .any(|d| d.message == "use of undeclared label `'missing`"));
+    }
+}
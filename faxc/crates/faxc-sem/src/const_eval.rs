@@ -0,0 +1,118 @@
+//! Constant evaluation
+//!
+//! Folds HIR expressions built from literals, operators, casts and
+//! references to other constants into concrete values. Used for array
+//! lengths and `const` item initializers.
+
+use crate::hir::{BinOp, Expr, Literal, UnOp};
+use faxc_util::DefId;
+use indexmap::{IndexMap, IndexSet};
+
+/// Value of an evaluated constant expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstValue {
+    Int(i128),
+    Bool(bool),
+}
+
+impl ConstValue {
+    /// The value as an array length, if it is a non-negative integer
+    pub fn as_usize(self) -> Option<usize> {
+        match self {
+            ConstValue::Int(n) => usize::try_from(n).ok(),
+            ConstValue::Bool(_) => None,
+        }
+    }
+}
+
+/// Evaluate `expr`, resolving constant references through `consts`
+///
+/// Returns `None` if the expression is not a constant expression or its
+/// evaluation fails (overflow, division by zero).
+pub fn eval(expr: &Expr, consts: &IndexMap<DefId, ConstValue>) -> Option<ConstValue> {
+    match expr {
+        Expr::Literal { lit, .. } => match lit {
+            Literal::Int(n) => Some(ConstValue::Int(*n as i128)),
+            Literal::Bool(b) => Some(ConstValue::Bool(*b)),
+            Literal::Char(c) => Some(ConstValue::Int(*c as i128)),
+            _ => None,
+        },
+        Expr::Var { def_id, .. } => consts.get(def_id).copied(),
+        Expr::Unary { op, expr, .. } => match (op, eval(expr, consts)?) {
+            (UnOp::Neg, ConstValue::Int(n)) => n.checked_neg().map(ConstValue::Int),
+            (UnOp::Not, ConstValue::Int(n)) => Some(ConstValue::Int(!n)),
+            (UnOp::Not, ConstValue::Bool(b)) => Some(ConstValue::Bool(!b)),
+            _ => None,
+        },
+        Expr::Binary {
+            op, left, right, ..
+        } => eval_binary(*op, eval(left, consts)?, eval(right, consts)?),
+        Expr::Cast { expr, .. } => match eval(expr, consts)? {
+            ConstValue::Bool(b) => Some(ConstValue::Int(b as i128)),
+            value => Some(value),
+        },
+        Expr::Block {
+            stmts,
+            expr: Some(expr),
+            ..
+        } if stmts.is_empty() => eval(expr, consts),
+        _ => None,
+    }
+}
+
+/// Check whether `expr` may initialize a `const` or `static` item
+///
+/// Literals, arithmetic, casts, aggregates of constant expressions and
+/// references to the items in `consts` qualify. Calls, dereferences and
+/// other runtime operations do not.
+pub fn is_const_expr(expr: &Expr, consts: &IndexSet<DefId>) -> bool {
+    match expr {
+        Expr::Literal { .. } => true,
+        Expr::Var { def_id, .. } => consts.contains(def_id),
+        Expr::Unary { op, expr, .. } => !matches!(op, UnOp::Deref) && is_const_expr(expr, consts),
+        Expr::Binary { left, right, .. } => {
+            is_const_expr(left, consts) && is_const_expr(right, consts)
+        },
+        Expr::Cast { expr, .. } => is_const_expr(expr, consts),
+        Expr::Tuple { elems, .. }
+        | Expr::Array { elems, .. }
+        | Expr::Struct { fields: elems, .. } => {
+            elems.iter().all(|elem| is_const_expr(elem, consts))
+        },
+        Expr::Block {
+            stmts,
+            expr: Some(expr),
+            ..
+        } if stmts.is_empty() => is_const_expr(expr, consts),
+        _ => false,
+    }
+}
+
+fn eval_binary(op: BinOp, left: ConstValue, right: ConstValue) -> Option<ConstValue> {
+    use ConstValue::{Bool, Int};
+
+    match (left, right) {
+        (Int(a), Int(b)) => match op {
+            BinOp::Add => a.checked_add(b).map(Int),
+            BinOp::Sub => a.checked_sub(b).map(Int),
+            BinOp::Mul => a.checked_mul(b).map(Int),
+            BinOp::Div => a.checked_div(b).map(Int),
+            BinOp::Mod => a.checked_rem(b).map(Int),
+            BinOp::Eq => Some(Bool(a == b)),
+            BinOp::Ne => Some(Bool(a != b)),
+            BinOp::Lt => Some(Bool(a < b)),
+            BinOp::Gt => Some(Bool(a > b)),
+            BinOp::Le => Some(Bool(a <= b)),
+            BinOp::Ge => Some(Bool(a >= b)),
+            BinOp::And | BinOp::Or => None,
+        },
+        (Bool(a), Bool(b)) => match op {
+            BinOp::And => Some(Bool(a && b)),
+            BinOp::Or => Some(Bool(a || b)),
+            BinOp::Eq => Some(Bool(a == b)),
+            BinOp::Ne => Some(Bool(a != b)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -3,7 +3,72 @@
 #[cfg(test)]
 mod tests {
     use crate::{Type, TypeContext, ScopeTree, RibKind, SemanticAnalyzer};
-    use faxc_util::{Handler, Symbol, DefIdGenerator};
+    use crate::const_eval::ConstValue;
+    use crate::hir::{CaptureMode, Expr, ImplItem, ImplItemKind, Item, Pattern, Stmt};
+    use faxc_lex::{Lexer, Token};
+    use faxc_par::{Parser, TokenWithSpan};
+    use faxc_util::{Handler, Symbol, DefIdGenerator, Span};
+
+    /// Helper to lex, parse and analyze source, returning HIR items
+    fn analyze_source(source: &str) -> (Vec<Item>, Handler) {
+        let mut handler = Handler::new();
+        let mut lexer = Lexer::new(source, &mut handler);
+
+        let mut tokens = Vec::new();
+        loop {
+            let token = lexer.next_token();
+            if token == Token::Eof {
+                break;
+            }
+            tokens.push(token);
+        }
+
+        let ast = Parser::new(tokens, &mut handler).parse();
+
+        let mut type_ctx = TypeContext::default();
+        let def_id_gen = DefIdGenerator::new();
+        let items =
+            SemanticAnalyzer::new(&mut type_ctx, &def_id_gen, &mut handler).analyze_items(ast);
+
+        (items, handler)
+    }
+
+    /// Helper like `analyze_source`, but keeping the lexer's token spans so
+    /// diagnostics carry real source offsets
+    fn analyze_source_with_spans(source: &str) -> (Vec<Item>, Handler) {
+        let mut handler = Handler::new();
+        let mut lexer = Lexer::new(source, &mut handler);
+
+        let mut tokens = Vec::new();
+        loop {
+            let token = lexer.next_token();
+            if token == Token::Eof {
+                break;
+            }
+            let span = Span::new(lexer.token_start, lexer.position(), lexer.line(), lexer.column());
+            tokens.push(TokenWithSpan { token, span });
+        }
+
+        let ast = Parser::from_tokens(tokens, &mut handler, source).parse();
+
+        let mut type_ctx = TypeContext::default();
+        let def_id_gen = DefIdGenerator::new();
+        let items =
+            SemanticAnalyzer::new(&mut type_ctx, &def_id_gen, &mut handler).analyze_items(ast);
+
+        (items, handler)
+    }
+
+    /// Helper to get the trailing expression of the first function's body
+    fn trailing_expr(items: &[Item]) -> &Expr {
+        match &items[0] {
+            Item::Function(f) => match &f.body.value {
+                Expr::Block { expr: Some(e), .. } => e,
+                other => panic!("expected block with trailing expression, got {:?}", other),
+            },
+            other => panic!("expected function, got {:?}", other),
+        }
+    }
 
     // ==================== SCOPE TREE TESTS ====================
 
@@ -112,6 +177,41 @@ mod tests {
         tree.exit_scope();
     }
 
+    /// EDGE CASE: Scope dump after analysis shows nested ribs and shadowing
+    #[test]
+    fn test_edge_scope_dump_shows_shadowing() {
+        let source = "fn main() -> i64 { let x = 1; if true { let x = 2; } x }";
+        let mut handler = Handler::new();
+        let mut lexer = Lexer::new(source, &mut handler);
+        let tokens: Vec<_> = std::iter::from_fn(|| Some(lexer.next_token()))
+            .take_while(|t| *t != Token::Eof)
+            .collect();
+        let ast = Parser::new(tokens, &mut handler).parse();
+
+        let mut type_ctx = TypeContext::default();
+        let def_id_gen = DefIdGenerator::new();
+        let mut analyzer = SemanticAnalyzer::new(&mut type_ctx, &def_id_gen, &mut handler);
+        analyzer.analyze_items(ast);
+
+        // module, function, fn body block, if block
+        let tree = &analyzer.scope_tree;
+        assert_eq!(tree.iter_ribs().count(), 4);
+
+        let dump = tree.dump();
+        assert!(dump.starts_with("rib 0 (module)\n"), "{}", dump);
+        assert!(dump.contains("\n  rib 1 (function)\n"), "{}", dump);
+        assert!(dump.contains("\n      rib 3 (block)\n"), "{}", dump);
+
+        let x_lines: Vec<_> = dump
+            .lines()
+            .filter(|l| l.trim_start().starts_with("x ->"))
+            .collect();
+        assert_eq!(x_lines.len(), 2, "{}", dump);
+        assert!(!x_lines[0].contains("shadows"), "{}", dump);
+        assert!(x_lines[1].starts_with("        x -> DefId("), "{}", dump);
+        assert!(x_lines[1].contains("(shadows DefId("), "{}", dump);
+    }
+
     // ==================== TYPE CONTEXT TESTS ====================
 
     /// EDGE CASE: New type context
@@ -311,6 +411,41 @@ mod tests {
         let _ = ctx.substitute(&current);
     }
 
+    /// EDGE CASE: `let` shadowing in the same and nested blocks is allowed
+    #[test]
+    fn test_edge_let_shadowing_allowed() {
+        let (_items, handler) = analyze_source(
+            "fn main() -> i64 { let x = 1; let x = x + 1; if true { let x = 3; } x }",
+        );
+
+        assert!(!handler
+            .diagnostics()
+            .iter()
+            .any(|d| d.message.contains("defined multiple times")));
+    }
+
+    /// ERROR CASE: Two functions with the same name at module scope
+    #[test]
+    fn test_err_duplicate_fn_definition() {
+        let (_items, handler) = analyze_source("fn foo() { } fn foo() { } fn main() { }");
+
+        let dups: Vec<_> = handler
+            .diagnostics()
+            .into_iter()
+            .filter(|d| d.message.contains("defined multiple times"))
+            .collect();
+        assert_eq!(dups.len(), 1);
+        assert_eq!(dups[0].message, "the name foo is defined multiple times");
+    }
+
+    /// EDGE CASE: Items may reuse prelude builtin names
+    #[test]
+    fn test_edge_item_shadows_prelude() {
+        let (_items, handler) = analyze_source("fn print(x: i64) { } fn main() { }");
+
+        assert!(!handler.has_errors());
+    }
+
     // ==================== SEMANTIC ANALYZER TESTS ====================
 
     /// EDGE CASE: New analyzer
@@ -354,4 +489,944 @@ mod tests {
         
         assert!(analyzer.scope_tree.resolve(Symbol::intern("x")).is_some());
     }
-}
\ No newline at end of file
+
+    // ==================== CAST TESTS ====================
+
+    /// EDGE CASE: Integer to float cast
+    #[test]
+    fn test_edge_cast_int_to_float() {
+        let (items, handler) = analyze_source("fn f(x: i64) -> f64 { x as f64 }");
+
+        assert!(!handler.has_errors());
+        assert!(matches!(trailing_expr(&items), Expr::Cast { ty: Type::Float, .. }));
+    }
+
+    /// EDGE CASE: Pointer to pointer cast
+    #[test]
+    fn test_edge_cast_ptr_to_ptr() {
+        let (items, handler) = analyze_source("fn f(p: *mut i32) -> *const u8 { p as *const u8 }");
+
+        assert!(!handler.has_errors());
+        match trailing_expr(&items) {
+            Expr::Cast { expr, ty, .. } => {
+                assert_eq!(expr.ty(), Type::Ptr(Box::new(Type::Int32), true));
+                assert_eq!(*ty, Type::Ptr(Box::new(Type::UInt8), false));
+            },
+            other => panic!("expected cast, got {:?}", other),
+        }
+    }
+
+    /// ERROR CASE: A mismatch between convertible types suggests a cast
+    #[test]
+    fn test_err_type_mismatch_has_help() {
+        let mut type_ctx = TypeContext::default();
+        let def_id_gen = DefIdGenerator::new();
+        let mut handler = Handler::new();
+
+        let mut analyzer = SemanticAnalyzer::new(&mut type_ctx, &def_id_gen, &mut handler);
+        assert!(!analyzer.unify_types(&Type::Int32, &Type::Int, Span::DUMMY));
+
+        let diags = handler.diagnostics();
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].message, "type mismatch: expected `i32`, found `int`");
+        assert_eq!(diags[0].helps, vec!["you can convert the value with `as i32`"]);
+    }
+
+    /// ERROR CASE: Mismatched if/else branches are reported at the else branch
+    #[test]
+    fn test_err_if_else_mismatch_points_at_else_branch() {
+        let source = "fn f(b: bool) -> i64 { if b { 1 } else { \"no\" } }";
+        let (_items, handler) = analyze_source_with_spans(source);
+
+        let diag = handler
+            .diagnostics()
+            .into_iter()
+            .find(|d| d.message == "If and Else branches must have the same type")
+            .expect("branch mismatch should be reported");
+        assert_ne!(diag.span, Span::DUMMY);
+        assert_eq!(diag.span.start, source.find("{ \"no\"").unwrap());
+    }
+
+    /// ERROR CASE: Bool to str cast is rejected
+    #[test]
+    fn test_err_cast_bool_to_str() {
+        let (_items, handler) = analyze_source("fn f(b: bool) -> str { b as str }");
+
+        assert!(handler.has_errors());
+    }
+
+    // ==================== MATCH TESTS ====================
+
+    /// EDGE CASE: Match arms over enum variants resolve to variant patterns
+    #[test]
+    fn test_edge_match_enum_variants() {
+        let source = "enum Shape { Circle(f64), Empty }
+            fn area(s: Shape) -> f64 { match s { Shape::Circle(r) => r, Empty => 0.0 } }";
+        let (items, handler) = analyze_source(source);
+
+        assert!(!handler.has_errors());
+        let arms = match trailing_expr(&items) {
+            Expr::Match { arms, .. } => arms,
+            other => panic!("expected match, got {:?}", other),
+        };
+        match &arms[0].pat {
+            Pattern::Variant { index: 0, fields, .. } => {
+                assert!(matches!(&fields[..], [Pattern::Binding { ty: Type::Float, .. }]));
+            },
+            other => panic!("expected variant pattern, got {:?}", other),
+        }
+        assert!(matches!(arms[1].pat, Pattern::Variant { index: 1, .. }));
+        assert_eq!(arms[0].body.ty(), Type::Float);
+    }
+
+    /// EDGE CASE: `if let` lowers to a match binding the payload in the then-block
+    #[test]
+    fn test_edge_if_let_binds_payload() {
+        let source = "enum Opt { Some(i64), None }
+            fn f(o: Opt) -> i64 { if let Some(x) = o { x } else { 0 } }";
+        let (items, handler) = analyze_source(source);
+
+        assert!(!handler.has_errors());
+        match trailing_expr(&items) {
+            Expr::Match { arms, ty, .. } => {
+                assert_eq!(arms.len(), 2);
+                assert!(matches!(&arms[0].pat, Pattern::Variant { index: 0, fields, .. }
+                    if matches!(&fields[0], Pattern::Binding { ty: Type::Int, .. })));
+                assert!(matches!(arms[1].pat, Pattern::Wildcard));
+                assert_eq!(*ty, Type::Int);
+            },
+            other => panic!("expected match, got {:?}", other),
+        }
+    }
+
+    /// ERROR CASE: `if let` bindings are not visible in the else branch
+    #[test]
+    fn test_err_if_let_binding_out_of_scope() {
+        let source = "enum Opt { Some(i64), None }
+            fn f(o: Opt) -> i64 { if let Some(x) = o { x } else { x } }";
+        let (_items, handler) = analyze_source(source);
+
+        assert!(handler.has_errors());
+    }
+
+    /// EDGE CASE: let-else binds the payload for the rest of the block
+    #[test]
+    fn test_edge_let_else() {
+        let source = "enum Opt { Some(i64), None }
+            fn f(o: Opt) -> i64 { let Some(x) = o else { return 0; }; x }";
+        let (items, handler) = analyze_source(source);
+
+        assert!(!handler.has_errors());
+        match &items[0] {
+            Item::Function(f) => match &f.body.value {
+                Expr::Block { stmts, .. } => {
+                    assert!(matches!(&stmts[0], Stmt::Let { else_block: Some(_), .. }));
+                },
+                other => panic!("expected block, got {:?}", other),
+            },
+            other => panic!("expected function, got {:?}", other),
+        }
+    }
+
+    /// ERROR CASE: let-else whose else block does not diverge
+    #[test]
+    fn test_err_let_else_must_diverge() {
+        let source = "enum Opt { Some(i64), None }
+            fn f(o: Opt) -> i64 { let Some(x) = o else { 1 }; x }";
+        let (_items, handler) = analyze_source(source);
+
+        assert!(handler
+            .diagnostics()
+            .iter()
+            .any(|d| d.message.contains("must diverge")));
+    }
+
+    /// ERROR CASE: let-else with an irrefutable pattern
+    #[test]
+    fn test_err_let_else_irrefutable() {
+        let source = "fn f(o: i64) -> i64 { let x = o else { return 0; }; x }";
+        let (_items, handler) = analyze_source(source);
+
+        assert!(handler
+            .diagnostics()
+            .iter()
+            .any(|d| d.message.contains("refutable pattern")));
+    }
+
+    // ==================== BINARY OPERATOR TESTS ====================
+
+    /// EDGE CASE: String concatenation with `+`
+    #[test]
+    fn test_edge_string_concat() {
+        let (items, handler) = analyze_source("fn f() -> str { \"a\" + \"b\" }");
+
+        assert!(!handler.has_errors());
+        assert_eq!(trailing_expr(&items).ty(), Type::String);
+    }
+
+    /// ERROR CASE: Adding a string and an integer
+    #[test]
+    fn test_err_string_plus_int() {
+        let (_items, handler) = analyze_source("fn f() -> str { \"a\" + 1 }");
+
+        assert!(handler.has_errors());
+    }
+
+    // ==================== PRELUDE TESTS ====================
+
+    /// EDGE CASE: Builtin `println` resolves against its prelude signature
+    #[test]
+    fn test_edge_prelude_println() {
+        let (items, handler) = analyze_source("fn main() { println(\"hi\") }");
+
+        assert!(!handler.has_errors());
+        match trailing_expr(&items) {
+            Expr::Call { func, ty, .. } => {
+                assert!(matches!(
+                    func.as_ref(),
+                    Expr::Intrinsic { name, .. } if name.as_str() == "println"
+                ));
+                assert_eq!(func.ty(), Type::Fn(vec![Type::String], Box::new(Type::Unit)));
+                assert_eq!(*ty, Type::Unit);
+            },
+            other => panic!("expected call, got {:?}", other),
+        }
+    }
+
+    /// ERROR CASE: Builtin called with the wrong number of arguments
+    #[test]
+    fn test_err_prelude_arity() {
+        let (_items, handler) = analyze_source("fn main() { println(\"a\", \"b\") }");
+
+        assert!(handler.has_errors());
+    }
+
+    /// ERROR CASE: A misspelled name suggests the closest binding in scope
+    #[test]
+    fn test_err_unresolved_name_suggests_similar() {
+        let (_items, handler) = analyze_source("fn main() { pritnln(\"hi\") }");
+
+        let diags = handler.diagnostics();
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].message, "cannot find value `pritnln` in this scope");
+        assert_eq!(diags[0].helps, vec!["did you mean `println`?"]);
+    }
+
+    /// ERROR CASE: A name unlike anything in scope gets no suggestion
+    #[test]
+    fn test_err_unresolved_name_without_suggestion() {
+        let (_items, handler) = analyze_source("fn main() { zzqxv(\"hi\") }");
+
+        let diags = handler.diagnostics();
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].message, "cannot find value `zzqxv` in this scope");
+        assert!(diags[0].helps.is_empty());
+    }
+
+    /// EDGE CASE: User definitions shadow prelude builtins
+    #[test]
+    fn test_edge_prelude_shadowed() {
+        let (items, handler) = analyze_source("fn print() {} fn main() { print() }");
+
+        assert!(!handler.has_errors());
+        // The second item is `main`
+        match trailing_expr(&items[1..]) {
+            Expr::Call { func, .. } => assert!(matches!(func.as_ref(), Expr::Var { .. })),
+            other => panic!("expected call, got {:?}", other),
+        }
+    }
+
+    // ==================== CONST EVAL TESTS ====================
+
+    /// EDGE CASE: Const initializer is folded to a concrete value
+    #[test]
+    fn test_edge_const_item_folded() {
+        let (items, handler) = analyze_source("const N: usize = 2 + 2;");
+
+        assert!(!handler.has_errors());
+        match &items[0] {
+            Item::Const(c) => {
+                assert_eq!(c.ty, Type::UInt);
+                assert_eq!(c.folded, Some(ConstValue::Int(4)));
+            },
+            other => panic!("expected const, got {:?}", other),
+        }
+    }
+
+    /// EDGE CASE: Literal initializers take the declared width
+    #[test]
+    fn test_edge_const_and_static_init_typed() {
+        let (_items, handler) = analyze_source(
+            "const N: i32 = 5; const M: i32 = N * 2; const S: &str = \"hi\"; \
+             static mut COUNT: u8 = 0; static RATIO: f32 = 0.5;",
+        );
+        assert!(!handler.has_errors());
+    }
+
+    /// ERROR CASE: Const initializer of the wrong type
+    #[test]
+    fn test_err_const_init_type_mismatch() {
+        let (_items, handler) = analyze_source("const N: i32 = \"x\";");
+
+        let messages: Vec<_> = handler.diagnostics().into_iter().map(|d| d.message).collect();
+        assert_eq!(messages, ["type mismatch: expected `i32`, found `str`"]);
+    }
+
+    /// ERROR CASE: Const and static initializers must be constant
+    #[test]
+    fn test_err_const_init_not_constant() {
+        let (_items, handler) = analyze_source(
+            "fn some_fn() -> i32 { 5 } const N: i32 = some_fn(); static S: i32 = some_fn();",
+        );
+
+        let messages: Vec<_> = handler.diagnostics().into_iter().map(|d| d.message).collect();
+        assert_eq!(
+            messages,
+            [
+                "`const` initializer is not a constant expression",
+                "`static` initializer is not a constant expression",
+            ]
+        );
+    }
+
+    /// EDGE CASE: Array length picks up the evaluated const
+    #[test]
+    fn test_edge_array_len_from_const() {
+        let (items, handler) =
+            analyze_source("const N: usize = 2 + 2; fn f(a: [i32; N]) -> i32 { 0 }");
+
+        assert!(!handler.has_errors());
+        match &items[1] {
+            Item::Function(f) => {
+                assert_eq!(f.params[0].ty, Type::Array(Box::new(Type::Int32), 4));
+            },
+            other => panic!("expected function, got {:?}", other),
+        }
+    }
+
+    /// ERROR CASE: Array length that is not a constant expression
+    #[test]
+    fn test_err_array_len_not_constant() {
+        let (_items, handler) = analyze_source("fn f(n: usize, a: [i32; n]) -> i32 { 0 }");
+
+        assert!(handler.has_errors());
+        assert!(handler
+            .diagnostics()
+            .iter()
+            .any(|d| d.message.contains("array size must be a constant")));
+    }
+
+    /// EDGE CASE: Repeat count is const-evaluated into the array type
+    #[test]
+    fn test_edge_array_repeat_type() {
+        let (items, handler) = analyze_source("const N: usize = 3; fn f() { [0; N] }");
+
+        assert!(!handler.has_errors());
+        assert_eq!(
+            trailing_expr(&items[1..]).ty(),
+            Type::Array(Box::new(Type::Int), 3)
+        );
+    }
+
+    /// ERROR CASE: Repeat count that is not a constant expression
+    #[test]
+    fn test_err_array_repeat_count_not_constant() {
+        let (_items, handler) = analyze_source("fn f(n: usize) { [0; n] }");
+
+        assert!(handler.has_errors());
+    }
+
+    /// ERROR CASE: A match without arms over a type that has values
+    #[test]
+    fn test_err_empty_match_on_inhabited_type() {
+        let (_items, handler) = analyze_source("fn f(x: i32) { match x { } }");
+
+        let messages: Vec<_> = handler.diagnostics().into_iter().map(|d| d.message).collect();
+        assert!(messages.contains(&"non-exhaustive patterns: match has no arms".to_string()));
+    }
+
+    /// EDGE CASE: A match without arms is fine when there is no value to match
+    #[test]
+    fn test_edge_empty_match_on_uninhabited_type() {
+        let (_items, handler) = analyze_source(
+            "enum Void { } fn f(x: !) { match x { } } fn g(v: Void) { match v { } }",
+        );
+        assert!(!handler.has_errors());
+    }
+
+    /// ERROR CASE: Division and remainder by a constant zero
+    #[test]
+    fn test_err_divide_by_constant_zero() {
+        for source in ["fn f() -> i64 { 1 / 0 }", "fn f() -> i64 { 1 % 0 }"] {
+            let (_items, handler) = analyze_source(source);
+
+            assert!(
+                handler.diagnostics().iter().any(|d| d.message
+                    == "this operation will panic at runtime: attempt to divide by zero"),
+                "{}",
+                source
+            );
+        }
+
+        let (_items, handler) = analyze_source("const Z: i64 = 0; fn f(n: i64) -> i64 { n / Z }");
+        assert!(handler.has_errors());
+
+        let (_items, handler) = analyze_source("fn f(n: i64) -> i64 { n / n + n % 2 }");
+        assert!(!handler.has_errors());
+    }
+
+    // ==================== MODULE TESTS ====================
+
+    /// ERROR CASE: Two modules importing from each other
+    #[test]
+    fn test_err_circular_module_imports() {
+        let (_items, handler) = analyze_source(
+            "mod a { use b::g; fn f() { } } mod b { use a::f; fn g() { } } fn main() { }",
+        );
+
+        let cycles: Vec<_> = handler
+            .diagnostics()
+            .into_iter()
+            .filter(|d| d.message.contains("circular dependency"))
+            .collect();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].message, "circular dependency between modules a and b");
+    }
+
+    /// EDGE CASE: One-way imports between modules are not a cycle
+    #[test]
+    fn test_edge_module_import_without_cycle() {
+        let (_items, handler) =
+            analyze_source("mod a { fn f() { } } mod b { use a::f; } fn main() { }");
+
+        assert!(!handler
+            .diagnostics()
+            .iter()
+            .any(|d| d.message.contains("circular dependency")));
+    }
+
+    /// EDGE CASE: Public module items are reachable by path; private ones
+    /// from inside their own module
+    #[test]
+    fn test_edge_pub_item_accessible_across_modules() {
+        let (items, handler) = analyze_source(
+            "mod a { fn hidden() { } pub fn visible() { hidden(); } } fn main() { a::visible(); }",
+        );
+
+        assert!(!handler.has_errors());
+        assert_eq!(items.len(), 3);
+    }
+
+    /// ERROR CASE: Private item used from another module
+    #[test]
+    fn test_err_private_item_across_modules() {
+        let (_items, handler) =
+            analyze_source("mod a { fn hidden() { } } fn main() { a::hidden(); }");
+
+        assert!(handler
+            .diagnostics()
+            .iter()
+            .any(|d| d.message == "item hidden is private"));
+    }
+
+    /// ERROR CASE: Private item imported with `use`
+    #[test]
+    fn test_err_private_item_imported() {
+        let (_items, handler) =
+            analyze_source("mod a { pub(super) fn f() { } fn g() { } } use a::f; use a::g;");
+
+        let private: Vec<_> = handler
+            .diagnostics()
+            .into_iter()
+            .filter(|d| d.message.ends_with("is private"))
+            .collect();
+        assert_eq!(private.len(), 1);
+        assert_eq!(private[0].message, "item g is private");
+    }
+
+    // ==================== AGGREGATE TESTS ====================
+
+    /// EDGE CASE: Struct literal fields are put in declaration order
+    #[test]
+    fn test_edge_struct_literal_field_order() {
+        let (items, handler) = analyze_source(
+            "struct Pair { a: i64, b: bool }
+             fn f() -> Pair { Pair { b: true, a: 1 } }",
+        );
+        assert!(!handler.has_errors());
+
+        let f = items
+            .iter()
+            .find_map(|item| match item {
+                Item::Function(f) => Some(f),
+                _ => None,
+            })
+            .expect("expected function");
+        match &f.body.value {
+            Expr::Block { expr: Some(e), .. } => match e.as_ref() {
+                Expr::Struct { fields, ty, .. } => {
+                    assert!(matches!(ty, Type::Adt(_)));
+                    assert_eq!(fields[0].ty(), Type::Int);
+                    assert_eq!(fields[1].ty(), Type::Bool);
+                },
+                other => panic!("expected struct literal, got {:?}", other),
+            },
+            other => panic!("expected block with trailing expression, got {:?}", other),
+        }
+    }
+
+    /// ERROR CASE: Struct literal with a missing and an unknown field
+    #[test]
+    fn test_err_struct_literal_fields() {
+        let (_items, handler) = analyze_source(
+            "struct Pair { a: i64, b: bool }
+             fn f() -> Pair { Pair { a: 1, c: 2 } }",
+        );
+
+        let messages: Vec<_> = handler.diagnostics().into_iter().map(|d| d.message).collect();
+        assert!(messages.contains(&"struct `Pair` has no field named `c`".to_string()));
+        assert!(messages.contains(&"missing field `b` in initializer of `Pair`".to_string()));
+    }
+
+    /// EDGE CASE: A struct's generic parameter resolves in its field types
+    #[test]
+    fn test_edge_struct_generic_field_type() {
+        let (items, handler) = analyze_source("struct Wrapper<T> { value: T, count: i64 }");
+        assert!(!handler.has_errors());
+
+        match items.as_slice() {
+            [Item::Struct(s)] => {
+                assert_eq!(s.generics.params.len(), 1);
+                assert_eq!(s.fields[0].ty, Type::Param(s.generics.params[0].id));
+                assert_eq!(s.fields[1].ty, Type::Int);
+            },
+            other => panic!("expected a single struct, got {:?}", other),
+        }
+    }
+
+    /// EDGE CASE: The most negative value of a type is in range
+    #[test]
+    fn test_edge_negative_literal_at_type_minimum() {
+        let (_items, handler) = analyze_source(
+            "const MIN: i8 = -128; fn main() { let y: i8 = -128; }",
+        );
+        assert!(!handler.has_errors());
+    }
+
+    /// ERROR CASE: Integer constants that do not fit their annotated type
+    #[test]
+    fn test_err_literal_out_of_range() {
+        let (_items, handler) =
+            analyze_source("const LOW: i8 = -129; fn main() { let y: i8 = 128; }");
+
+        let errors = handler
+            .diagnostics()
+            .into_iter()
+            .filter(|d| d.message == "literal out of range for `i8`")
+            .count();
+        assert_eq!(errors, 2);
+    }
+
+    /// ERROR CASE: A literal's type suffix bounds its value
+    #[test]
+    fn test_err_suffixed_literal_out_of_range() {
+        for (source, fits) in [
+            ("fn main() { let x = 300u8; }", false),
+            ("fn main() { let x = 255u8; }", true),
+            ("fn main() { let x = -1u8; }", false),
+        ] {
+            let (_items, handler) = analyze_source(source);
+            let messages: Vec<_> = handler.diagnostics().into_iter().map(|d| d.message).collect();
+            if fits {
+                assert!(messages.is_empty(), "{}: {:?}", source, messages);
+            } else {
+                assert_eq!(messages, ["literal out of range for `u8`"], "{}", source);
+            }
+        }
+    }
+
+    /// EDGE CASE: Raw identifiers name bindings and modules after keywords
+    #[test]
+    fn test_edge_raw_identifiers() {
+        let source = "mod r#type { pub fn get() -> i64 { 1 } }
+            fn f() -> i64 { let r#match = r#type::get(); r#match }";
+        let (items, handler) = analyze_source(source);
+        assert!(!handler.has_errors());
+
+        let f = items
+            .iter()
+            .find_map(|item| match item {
+                Item::Function(f) if f.name.as_str() == "f" => Some(f),
+                _ => None,
+            })
+            .expect("expected function `f`");
+        let Expr::Block { stmts, expr: Some(tail), .. } = &f.body.value else {
+            panic!("expected block, got {:?}", f.body.value);
+        };
+        let Stmt::Let { pat: Pattern::Binding { name, .. }, .. } = &stmts[0] else {
+            panic!("expected binding, got {:?}", stmts[0]);
+        };
+        assert_eq!(name.as_str(), "match");
+        assert!(matches!(tail.as_ref(), Expr::Var { .. }));
+    }
+
+    /// EDGE CASE: A where-clause binds a generic parameter to a trait
+    #[test]
+    fn test_edge_fn_where_clause_binds_trait() {
+        let (items, handler) = analyze_source(
+            "trait Clone { } fn f<T>() where T: Clone { } fn g<U>() where U: Clone { }",
+        );
+        assert!(!handler.has_errors());
+
+        match items.as_slice() {
+            [Item::Function(f), Item::Function(g)] => {
+                assert_eq!(f.generics.params[0].name, Symbol::intern("T"));
+                let predicate = &f.generics.where_clause[0];
+                assert_eq!(predicate.ty, Type::Param(f.generics.params[0].id));
+                assert_eq!(predicate.bounds.len(), 1);
+                // Both bounds name the same `Clone` trait definition
+                assert_eq!(predicate.bounds, g.generics.where_clause[0].bounds);
+            },
+            other => panic!("expected two functions, got {:?}", other),
+        }
+    }
+
+    /// ERROR CASE: A where-clause bound that is not a trait
+    #[test]
+    fn test_err_where_clause_bound_not_a_trait() {
+        let (_items, handler) =
+            analyze_source("struct Point { x: i64 } fn f<T>() where T: Point + Missing { }");
+
+        let messages: Vec<_> = handler.diagnostics().into_iter().map(|d| d.message).collect();
+        assert!(messages.contains(&"expected trait, found `Point`".to_string()));
+        assert!(messages.contains(&"cannot find trait `Missing` in this scope".to_string()));
+    }
+
+    /// ERROR CASE: A struct declaring the same field twice
+    #[test]
+    fn test_err_duplicate_struct_field() {
+        let source = "struct S { x: i32, y: i32, x: i32 }";
+        let (_items, handler) = analyze_source_with_spans(source);
+
+        let diagnostics = handler.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "field `x` is already declared");
+        assert_eq!(diagnostics[0].span.start, source.rfind('x').unwrap());
+    }
+
+    /// ERROR CASE: An enum declaring the same variant twice, and a struct
+    /// variant repeating a field
+    #[test]
+    fn test_err_duplicate_enum_variant() {
+        let source = "enum E { A, B(i64), A, C { v: i64, v: bool } }";
+        let (_items, handler) = analyze_source_with_spans(source);
+
+        let diagnostics = handler.diagnostics();
+        let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            ["variant `A` defined multiple times", "field `v` is already declared"]
+        );
+        assert_eq!(diagnostics[0].span.start, source.find("A, C").unwrap());
+    }
+
+    /// EDGE CASE: Distinct field and variant names are accepted, and
+    /// different items may reuse each other's member names
+    #[test]
+    fn test_edge_distinct_fields_and_variants() {
+        let (_items, handler) = analyze_source(
+            "struct S { x: i32, y: i32 } struct T { x: i32 } \
+             enum E { A, B(i64), C { x: i64 } }",
+        );
+        assert!(!handler.has_errors());
+    }
+
+    /// ERROR CASE: Unknown type name in a struct field
+    #[test]
+    fn test_err_struct_field_unknown_type() {
+        let (_items, handler) = analyze_source("struct Wrapper<T> { value: U }");
+
+        let messages: Vec<_> = handler.diagnostics().into_iter().map(|d| d.message).collect();
+        assert!(messages.contains(&"cannot find type `U` in this scope".to_string()));
+    }
+
+    /// EDGE CASE: Field access resolves the field's position and type
+    #[test]
+    fn test_edge_field_access_index() {
+        let (items, handler) =
+            analyze_source("struct P { x: i64, y: bool } fn f(p: P) -> bool { p.y }");
+        assert!(!handler.has_errors());
+
+        let f = items
+            .iter()
+            .find(|item| matches!(item, Item::Function(_)))
+            .expect("expected function");
+        match trailing_expr(std::slice::from_ref(f)) {
+            Expr::Field { index, ty, .. } => {
+                assert_eq!(*index, 1);
+                assert_eq!(*ty, Type::Bool);
+            },
+            other => panic!("expected field access, got {:?}", other),
+        }
+    }
+
+    /// ERROR CASE: Access to a field the struct does not have
+    #[test]
+    fn test_err_unknown_field() {
+        let (_items, handler) = analyze_source("struct P { x: i64 } fn f(p: P) -> i64 { p.z }");
+
+        assert!(handler
+            .diagnostics()
+            .iter()
+            .any(|d| d.message.starts_with("no field `z` on type")));
+    }
+
+    // ==================== METHOD TESTS ====================
+
+    /// EDGE CASE: Method receivers are typed from the impl's self type
+    #[test]
+    fn test_edge_trait_impl_recorded() {
+        let source = "trait Copy { } struct Point { x: i64 } impl Copy for Point { }";
+        let mut handler = Handler::new();
+        let mut lexer = Lexer::new(source, &mut handler);
+        let mut tokens = Vec::new();
+        loop {
+            let token = lexer.next_token();
+            if token == Token::Eof {
+                break;
+            }
+            tokens.push(token);
+        }
+        let ast = Parser::new(tokens, &mut handler).parse();
+
+        let mut type_ctx = TypeContext::default();
+        let def_id_gen = DefIdGenerator::new();
+        let items =
+            SemanticAnalyzer::new(&mut type_ctx, &def_id_gen, &mut handler).analyze_items(ast);
+        assert!(!handler.has_errors());
+
+        let Some(Item::Impl(imp)) = items.iter().find(|i| matches!(i, Item::Impl(_))) else {
+            panic!("expected impl item");
+        };
+        let trait_ref = imp.trait_ref.as_ref().expect("trait impl should resolve its trait");
+        assert!(type_ctx.implements(&imp.self_ty, trait_ref.def_id));
+        assert!(type_ctx.is_copy(&Type::Tuple(vec![imp.self_ty.clone(), Type::Int])));
+    }
+
+    /// ERROR CASE: Two impls of the same trait for the same type
+    #[test]
+    fn test_err_conflicting_trait_impls() {
+        let source = "trait Foo { } impl Foo for i32 { } impl Foo for i32 { }";
+        let (_items, handler) = analyze_source_with_spans(source);
+
+        let diagnostics = handler.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "conflicting implementations of trait `Foo` for type `i32`"
+        );
+        assert_eq!(diagnostics[0].span.start, source.rfind("impl").unwrap());
+        assert!(diagnostics[0].notes[0].starts_with("first implementation here, at line 1"));
+    }
+
+    /// EDGE CASE: One trait implemented for different types is coherent
+    #[test]
+    fn test_edge_trait_impls_for_different_types() {
+        let (_items, handler) = analyze_source(
+            "trait Foo { } struct S { } impl Foo for i32 { } impl Foo for i64 { } \
+             impl Foo for S { }",
+        );
+        assert!(!handler.has_errors());
+    }
+
+    /// EDGE CASE: An impl omitting a default method gets the trait's body,
+    /// while one overriding it keeps its own
+    #[test]
+    fn test_edge_default_trait_method() {
+        let (items, handler) = analyze_source(
+            "trait Describe { fn id(&self) -> i64; fn describe(&self) -> i64 { 7 } }
+             struct A { } struct B { }
+             impl Describe for A { fn id(&self) -> i64 { 1 } }
+             impl Describe for B { fn id(&self) -> i64 { 2 } fn describe(&self) -> i64 { 8 } }",
+        );
+        assert!(!handler.has_errors(), "{:?}", handler.diagnostics());
+
+        let impls: Vec<_> = items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Impl(imp) => Some(imp),
+                _ => None,
+            })
+            .collect();
+        let method_names = |imp: &ImplItem| -> Vec<String> {
+            imp.items
+                .iter()
+                .filter_map(|member| match member {
+                    ImplItemKind::Method(f) => Some(f.name.as_str().to_string()),
+                    _ => None,
+                })
+                .collect()
+        };
+        assert_eq!(method_names(impls[0]), ["id", "describe"]);
+        assert_eq!(method_names(impls[1]), ["id", "describe"]);
+
+        let ImplItemKind::Method(describe) = &impls[0].items[1] else {
+            panic!("expected method");
+        };
+        assert_eq!(describe.params[0].ty, Type::Ref(Box::new(impls[0].self_ty.clone()), false));
+    }
+
+    /// EDGE CASE: `Type::f(args)` calls the associated function of an impl
+    /// of `Type`, even one declared after the call
+    #[test]
+    fn test_edge_assoc_fn_call() {
+        let (items, handler) = analyze_source(
+            "fn main() -> Point { Point::origin() }
+             struct Point { x: i64 }
+             impl Point {
+                 fn origin() -> Point { Point::at(0) }
+                 fn at(x: i64) -> Self { Point { x: x } }
+             }",
+        );
+        assert!(!handler.has_errors(), "{:?}", handler.diagnostics());
+
+        let Some(Item::Impl(imp)) = items.iter().find(|i| matches!(i, Item::Impl(_))) else {
+            panic!("expected impl item");
+        };
+        let ImplItemKind::Method(origin) = &imp.items[0] else {
+            panic!("expected method");
+        };
+        match trailing_expr(&items) {
+            Expr::Call { func, ty, .. } => {
+                assert_eq!(*ty, imp.self_ty);
+                assert!(
+                    matches!(**func, Expr::Var { def_id, .. } if def_id == origin.def_id),
+                    "{:?}",
+                    func
+                );
+            },
+            other => panic!("expected call, got {:?}", other),
+        }
+    }
+
+    /// ERROR CASE: Arguments to an associated function are type checked
+    #[test]
+    fn test_err_assoc_fn_call_args() {
+        let (_items, handler) = analyze_source(
+            "struct Point { x: i64 }
+             impl Point { fn at(x: i64) -> Point { Point { x: x } } }
+             fn main() { let p = Point::at(true); let q = Point::at(1, 2); }",
+        );
+
+        let messages: Vec<_> = handler.diagnostics().into_iter().map(|d| d.message).collect();
+        assert_eq!(
+            messages,
+            [
+                "type mismatch: expected `int`, found `bool`",
+                "function takes 1 arguments but 2 were supplied",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_edge_method_receiver_types() {
+        let (items, handler) = analyze_source(
+            "struct Point { x: i64 }
+             impl Point {
+                 fn get(&self, n: i64) -> i64 { n }
+                 fn take(self) -> Point { self }
+                 fn set(&mut self) { }
+             }",
+        );
+        assert!(!handler.has_errors());
+
+        let Some(Item::Impl(imp)) = items.iter().find(|i| matches!(i, Item::Impl(_))) else {
+            panic!("expected impl item");
+        };
+        let point = imp.self_ty.clone();
+        assert!(matches!(point, Type::Adt(_)));
+
+        let methods: Vec<_> = imp
+            .items
+            .iter()
+            .map(|item| match item {
+                ImplItemKind::Method(f) => f,
+                other => panic!("expected method, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(methods[0].params.len(), 2);
+        assert_eq!(methods[0].params[0].ty, Type::Ref(Box::new(point.clone()), false));
+        assert_eq!(methods[1].params[0].ty, point);
+        assert_eq!(methods[2].params[0].ty, Type::Ref(Box::new(point.clone()), true));
+
+        // `self` resolves to the receiver inside the body
+        match &methods[1].body.value {
+            Expr::Block { expr: Some(e), .. } => assert_eq!(e.ty(), point),
+            other => panic!("expected block with trailing expression, got {:?}", other),
+        }
+    }
+
+    /// ERROR CASE: `self` receiver on a free function
+    #[test]
+    fn test_err_self_outside_impl() {
+        let (_items, handler) = analyze_source("fn f(&self) { }");
+
+        assert!(handler
+            .diagnostics()
+            .iter()
+            .any(|d| d.message == "`self` parameter is only allowed in associated functions"));
+    }
+
+    // ==================== CLOSURE TESTS ====================
+
+    /// Captures of the closure bound by the last `let` of the first function
+    fn closure_captures(items: &[Item]) -> Vec<(String, CaptureMode)> {
+        let stmts = match &items[0] {
+            Item::Function(f) => match &f.body.value {
+                Expr::Block { stmts, .. } => stmts,
+                other => panic!("expected block, got {:?}", other),
+            },
+            other => panic!("expected function, got {:?}", other),
+        };
+        match stmts.last() {
+            Some(Stmt::Let {
+                init: Some(Expr::Closure { captures, .. }),
+                ..
+            }) => captures
+                .iter()
+                .map(|c| (c.name.as_str().to_string(), c.mode))
+                .collect(),
+            other => panic!("expected closure binding, got {:?}", other),
+        }
+    }
+
+    /// EDGE CASE: A closure captures the outer variables it uses by reference
+    #[test]
+    fn test_edge_closure_captures_by_ref() {
+        let (items, handler) =
+            analyze_source("fn f(x: i64) { let y = 2; let add = || x + y + x; }");
+
+        assert!(!handler.has_errors());
+        assert_eq!(
+            closure_captures(&items),
+            [
+                ("x".to_string(), CaptureMode::ByRef),
+                ("y".to_string(), CaptureMode::ByRef)
+            ]
+        );
+    }
+
+    /// EDGE CASE: A `move` closure captures by value; its own parameters and
+    /// locals are not captures
+    #[test]
+    fn test_edge_move_closure_captures_by_value() {
+        let (items, handler) =
+            analyze_source("fn f(x: i64) { let c = move |a: i64| { let b = a; b + x }; }");
+
+        assert!(!handler.has_errors());
+        assert_eq!(
+            closure_captures(&items),
+            [("x".to_string(), CaptureMode::ByValue)]
+        );
+    }
+}
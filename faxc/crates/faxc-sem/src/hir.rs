@@ -1,5 +1,6 @@
+use crate::const_eval::ConstValue;
 use crate::types::*;
-use faxc_util::{DefId, Symbol};
+use faxc_util::{DefId, Span, Symbol};
 
 /// HIR Item
 #[derive(Debug, Clone)]
@@ -9,6 +10,7 @@ pub enum Item {
     Enum(EnumItem),
     Trait(TraitItem),
     Impl(ImplItem),
+    Const(ConstItem),
 }
 
 /// HIR Function
@@ -23,6 +25,17 @@ pub struct FnItem {
     pub async_kw: bool,
 }
 
+/// HIR Const
+#[derive(Debug, Clone)]
+pub struct ConstItem {
+    pub def_id: DefId,
+    pub name: Symbol,
+    pub ty: Type,
+    pub value: Expr,
+    /// Folded value, if the initializer is a constant expression
+    pub folded: Option<ConstValue>,
+}
+
 /// Generic parameters
 #[derive(Debug, Clone, Default)]
 pub struct GenericParams {
@@ -46,11 +59,12 @@ pub enum GenericParamKind {
     Const { ty: Type },
 }
 
-/// Where clause predicate
+/// Where clause predicate (e.g., `T: Clone + Debug`)
 #[derive(Debug, Clone)]
 pub struct WherePredicate {
     pub ty: Type,
-    pub bounds: Vec<Type>,
+    /// Traits that `ty` must implement
+    pub bounds: Vec<DefId>,
 }
 
 /// Function parameter
@@ -161,104 +175,249 @@ pub struct FnSig {
 }
 
 /// HIR Expression
+///
+/// Every expression records the span of the source it was lowered from, so
+/// later checks can report errors at the offending sub-expression. Nodes
+/// synthesized during analysis carry `Span::DUMMY`.
 #[derive(Debug, Clone)]
 pub enum Expr {
     Literal {
         lit: Literal,
         ty: Type,
+        span: Span,
     },
     Var {
         def_id: DefId,
         ty: Type,
+        span: Span,
+    },
+    /// Builtin function provided by the runtime, referenced by symbol name
+    Intrinsic {
+        name: Symbol,
+        ty: Type,
+        span: Span,
     },
     Binary {
         op: BinOp,
         left: Box<Expr>,
         right: Box<Expr>,
         ty: Type,
+        span: Span,
     },
     Unary {
         op: UnOp,
         expr: Box<Expr>,
         ty: Type,
+        span: Span,
     },
     Call {
         func: Box<Expr>,
         args: Vec<Expr>,
         ty: Type,
+        span: Span,
     },
     MethodCall {
         receiver: Box<Expr>,
         method: DefId,
         args: Vec<Expr>,
         ty: Type,
+        span: Span,
     },
+    /// Field access; `index` is the field's position in its struct or tuple
     Field {
         object: Box<Expr>,
-        field: DefId,
+        index: u32,
+        ty: Type,
+        span: Span,
+    },
+    Index {
+        object: Box<Expr>,
+        index: Box<Expr>,
         ty: Type,
+        span: Span,
     },
     Block {
         stmts: Vec<Stmt>,
         expr: Option<Box<Expr>>,
         ty: Type,
+        span: Span,
     },
     If {
         cond: Box<Expr>,
         then_expr: Box<Expr>,
         else_expr: Option<Box<Expr>>,
         ty: Type,
+        span: Span,
     },
     Match {
         scrutinee: Box<Expr>,
         arms: Vec<Arm>,
         ty: Type,
+        span: Span,
+    },
+    /// Loop run until a `break` targets it; `for` loops are desugared into
+    /// one during semantic analysis
+    Loop {
+        body: Box<Expr>,
+        label: Option<LabelId>,
+        ty: Type,
+        span: Span,
     },
     Assign {
         place: Box<Expr>,
         value: Box<Expr>,
+        span: Span,
+    },
+    Return {
+        value: Option<Box<Expr>>,
+        span: Span,
+    },
+    Break {
+        value: Option<Box<Expr>>,
+        label: Option<LabelId>,
+        span: Span,
+    },
+    Continue {
+        label: Option<LabelId>,
+        span: Span,
+    },
+    /// Closure; `captures` lists the enclosing function's variables it uses,
+    /// from which MIR builds the closure's environment. `def_id` names the
+    /// function MIR generates for the body.
+    Closure {
+        def_id: DefId,
+        params: Vec<Pattern>,
+        body: Box<Expr>,
+        captures: Vec<Capture>,
+        ty: Type,
+        span: Span,
     },
-    Return(Option<Box<Expr>>),
-    Break(Option<Box<Expr>>, Option<LabelId>),
-    Continue(Option<LabelId>),
     Async {
         body: Box<Expr>,
         ty: Type,
+        span: Span,
     },
     Await {
         expr: Box<Expr>,
         ty: Type,
+        span: Span,
     },
     Cast {
         expr: Box<Expr>,
         ty: Type,
+        span: Span,
+    },
+    Tuple {
+        elems: Vec<Expr>,
+        ty: Type,
+        span: Span,
+    },
+    Array {
+        elems: Vec<Expr>,
+        ty: Type,
+        span: Span,
+    },
+    /// Struct literal; `fields` are in the struct's declaration order
+    Struct {
+        def_id: DefId,
+        fields: Vec<Expr>,
+        ty: Type,
+        span: Span,
     },
 }
 
 impl Expr {
+    /// Whether evaluating this expression never completes normally
+    pub fn diverges(&self) -> bool {
+        match self {
+            Expr::Block { stmts, expr, .. } => {
+                stmts
+                    .iter()
+                    .any(|stmt| matches!(stmt, Stmt::Expr(e) if e.diverges()))
+                    || expr.as_ref().is_some_and(|e| e.diverges())
+            },
+            _ => self.ty() == Type::Never,
+        }
+    }
+
     pub fn ty(&self) -> Type {
         match self {
             Expr::Literal { ty, .. } => ty.clone(),
             Expr::Var { ty, .. } => ty.clone(),
+            Expr::Intrinsic { ty, .. } => ty.clone(),
             Expr::Binary { ty, .. } => ty.clone(),
             Expr::Unary { ty, .. } => ty.clone(),
             Expr::Call { ty, .. } => ty.clone(),
             Expr::MethodCall { ty, .. } => ty.clone(),
             Expr::Field { ty, .. } => ty.clone(),
+            Expr::Index { ty, .. } => ty.clone(),
             Expr::Block { ty, .. } => ty.clone(),
             Expr::If { ty, .. } => ty.clone(),
             Expr::Match { ty, .. } => ty.clone(),
+            Expr::Loop { ty, .. } => ty.clone(),
             Expr::Assign { .. } => Type::Unit,
-            Expr::Return(_) => Type::Never,
-            Expr::Break(_, _) => Type::Never,
-            Expr::Continue(_) => Type::Never,
+            Expr::Return { .. } => Type::Never,
+            Expr::Break { .. } => Type::Never,
+            Expr::Continue { .. } => Type::Never,
+            Expr::Closure { ty, .. } => ty.clone(),
             Expr::Async { ty, .. } => ty.clone(),
             Expr::Await { ty, .. } => ty.clone(),
             Expr::Cast { ty, .. } => ty.clone(),
+            Expr::Tuple { ty, .. } => ty.clone(),
+            Expr::Array { ty, .. } => ty.clone(),
+            Expr::Struct { ty, .. } => ty.clone(),
+        }
+    }
+
+    /// Source span of the expression
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::Literal { span, .. }
+            | Expr::Var { span, .. }
+            | Expr::Intrinsic { span, .. }
+            | Expr::Binary { span, .. }
+            | Expr::Unary { span, .. }
+            | Expr::Call { span, .. }
+            | Expr::MethodCall { span, .. }
+            | Expr::Field { span, .. }
+            | Expr::Index { span, .. }
+            | Expr::Block { span, .. }
+            | Expr::If { span, .. }
+            | Expr::Match { span, .. }
+            | Expr::Loop { span, .. }
+            | Expr::Assign { span, .. }
+            | Expr::Return { span, .. }
+            | Expr::Break { span, .. }
+            | Expr::Continue { span, .. }
+            | Expr::Closure { span, .. }
+            | Expr::Async { span, .. }
+            | Expr::Await { span, .. }
+            | Expr::Cast { span, .. }
+            | Expr::Tuple { span, .. }
+            | Expr::Array { span, .. }
+            | Expr::Struct { span, .. } => *span,
         }
     }
 }
 
+/// How a closure captures a variable of its enclosing function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureMode {
+    /// The environment holds a reference to the variable
+    ByRef,
+    /// The variable is moved into the environment (`move` closures)
+    ByValue,
+}
+
+/// A variable of the enclosing function used inside a closure
+#[derive(Debug, Clone)]
+pub struct Capture {
+    pub def_id: DefId,
+    pub name: Symbol,
+    pub ty: Type,
+    pub mode: CaptureMode,
+}
+
 /// Literal
 #[derive(Debug, Clone)]
 pub enum Literal {
@@ -304,19 +463,39 @@ pub enum Stmt {
         pat: Pattern,
         ty: Type,
         init: Option<Expr>,
+        /// Diverging fallback when `pat` does not match (let-else)
+        else_block: Option<Box<Expr>>,
+        span: Span,
     },
     Expr(Expr),
 }
 
+impl Stmt {
+    /// Source span of the statement
+    pub fn span(&self) -> Span {
+        match self {
+            Stmt::Let { span, .. } => *span,
+            Stmt::Expr(expr) => expr.span(),
+        }
+    }
+}
+
 /// Pattern
 #[derive(Debug, Clone)]
 pub enum Pattern {
     Wildcard,
     Binding {
         name: Symbol,
+        /// Definition `Expr::Var` uses to refer to this binding
+        def_id: DefId,
         ty: Type,
         mutability: bool,
     },
+    /// Matches values equal to `lit`
+    Literal {
+        lit: Literal,
+        ty: Type,
+    },
     Path {
         def_id: DefId,
     },
@@ -324,6 +503,12 @@ pub enum Pattern {
         def_id: DefId,
         fields: Vec<FieldPattern>,
     },
+    /// Enum variant pattern; `index` is the variant's discriminant
+    Variant {
+        def_id: DefId,
+        index: u32,
+        fields: Vec<Pattern>,
+    },
     Tuple {
         pats: Vec<Pattern>,
     },
@@ -349,6 +534,22 @@ pub struct Arm {
     pub body: Expr,
 }
 
+impl Pattern {
+    /// Whether the pattern can fail to match a value of its type
+    pub fn is_refutable(&self) -> bool {
+        match self {
+            Pattern::Wildcard | Pattern::Binding { .. } => false,
+            Pattern::Tuple { pats } => pats.iter().any(Pattern::is_refutable),
+            Pattern::Struct { fields, .. } => fields.iter().any(|f| f.pat.is_refutable()),
+            Pattern::Ref { pat, .. } => pat.is_refutable(),
+            Pattern::Literal { .. }
+            | Pattern::Path { .. }
+            | Pattern::Variant { .. }
+            | Pattern::Or(_) => true,
+        }
+    }
+}
+
 /// Label ID for loops
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct LabelId(pub u32);
@@ -2,6 +2,10 @@ pub mod types;
 pub mod hir;
 pub mod scope;
 pub mod analysis;
+pub mod const_eval;
+pub mod lint;
+pub mod modules;
+pub mod prelude;
 #[cfg(test)]
 mod edge_cases;
 
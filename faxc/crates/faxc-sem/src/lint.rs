@@ -0,0 +1,212 @@
+//! AST lints
+//!
+//! A [`Lint`] looks at one item at a time after parsing and reports
+//! warnings about code that is valid but likely to be a mistake or hard to
+//! maintain. Lints are collected in a [`LintRegistry`], which hands every
+//! item, including the items of inline modules, to each registered lint.
+
+use faxc_par as ast;
+use faxc_util::diagnostic::DiagnosticBuilder;
+use faxc_util::Handler;
+
+/// A check run over each item of a parsed file
+pub trait Lint {
+    /// Name of the lint, as used in its diagnostics
+    fn name(&self) -> &'static str;
+
+    /// Report any warnings for `item`
+    fn check(&self, item: &ast::Item, handler: &mut Handler);
+}
+
+/// The lints run after parsing
+#[derive(Default)]
+pub struct LintRegistry {
+    lints: Vec<Box<dyn Lint>>,
+}
+
+impl LintRegistry {
+    /// An empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry with the lints that ship with the compiler
+    pub fn with_builtin_lints() -> Self {
+        let mut registry = Self::new();
+        registry.register(TooManyParams::default());
+        registry
+    }
+
+    /// Add `lint` to the lints that are run
+    pub fn register(&mut self, lint: impl Lint + 'static) {
+        self.lints.push(Box::new(lint));
+    }
+
+    /// Names of the registered lints, in registration order
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.lints.iter().map(|lint| lint.name())
+    }
+
+    /// Run every lint over `items` and the items of their inline modules
+    pub fn run(&self, items: &[ast::Item], handler: &mut Handler) {
+        for item in items {
+            for lint in &self.lints {
+                lint.check(item, handler);
+            }
+            if let ast::Item::Mod(m) = item {
+                if let Some(items) = &m.items {
+                    self.run(items, handler);
+                }
+            }
+        }
+    }
+}
+
+/// Warns about functions and methods taking more than `max` parameters
+pub struct TooManyParams {
+    /// Most parameters, counting `self`, a function may take unreported
+    pub max: usize,
+}
+
+impl Default for TooManyParams {
+    fn default() -> Self {
+        Self { max: 7 }
+    }
+}
+
+impl TooManyParams {
+    fn check_fn(&self, f: &ast::FnItem, handler: &mut Handler) {
+        let count = f.params.len() + usize::from(f.self_param.is_some());
+        if count > self.max {
+            DiagnosticBuilder::warning(format!(
+                "function `{}` has too many parameters ({}/{})",
+                f.name, count, self.max
+            ))
+            .span(f.span)
+            .with_help("consider grouping related parameters into a struct")
+            .emit(handler);
+        }
+    }
+}
+
+impl Lint for TooManyParams {
+    fn name(&self) -> &'static str {
+        "too-many-params"
+    }
+
+    fn check(&self, item: &ast::Item, handler: &mut Handler) {
+        match item {
+            ast::Item::Fn(f) => self.check_fn(f, handler),
+            ast::Item::Impl(imp) => {
+                for member in &imp.items {
+                    if let ast::ImplMember::Method(f) = member {
+                        self.check_fn(f, handler);
+                    }
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use faxc_lex::Lexer;
+    use faxc_par::{Parser, TokenWithSpan};
+    use faxc_util::diagnostic::Level;
+    use faxc_util::Span;
+
+    fn parse(source: &str) -> Vec<ast::Item> {
+        let mut handler = Handler::new();
+        let mut lexer = Lexer::new(source, &mut handler);
+        let mut tokens = Vec::new();
+        loop {
+            let token = lexer.next_token();
+            if token == faxc_lex::Token::Eof {
+                break;
+            }
+            tokens.push(TokenWithSpan {
+                token,
+                span: Span::DUMMY,
+            });
+        }
+        let items = Parser::from_tokens(tokens, &mut handler, source).parse();
+        assert!(!handler.has_errors());
+        items
+    }
+
+    /// Flags functions whose name starts with `todo_`
+    struct TodoFunctions;
+
+    impl Lint for TodoFunctions {
+        fn name(&self) -> &'static str {
+            "todo-functions"
+        }
+
+        fn check(&self, item: &ast::Item, handler: &mut Handler) {
+            if let ast::Item::Fn(f) = item {
+                if f.name.as_str().starts_with("todo_") {
+                    DiagnosticBuilder::warning(format!("unfinished function `{}`", f.name))
+                        .emit(handler);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_lint_fires_on_matching_function() {
+        let items = parse("fn todo_parse() { } fn done() { } mod m { fn todo_emit() { } }");
+        let mut registry = LintRegistry::new();
+        registry.register(TodoFunctions);
+
+        let mut handler = Handler::new();
+        registry.run(&items, &mut handler);
+
+        let messages: Vec<_> = handler
+            .diagnostics()
+            .into_iter()
+            .map(|d| d.message)
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "unfinished function `todo_parse`",
+                "unfinished function `todo_emit`"
+            ]
+        );
+        assert!(!handler.has_errors());
+    }
+
+    #[test]
+    fn test_too_many_params() {
+        let items = parse(
+            "fn few(a: i64, b: i64) { } \
+             fn many(a: i64, b: i64, c: i64, d: i64) { } \
+             struct S { } \
+             impl S { fn method(&self, a: i64, b: i64, c: i64) { } }",
+        );
+        let mut registry = LintRegistry::new();
+        registry.register(TooManyParams { max: 3 });
+
+        let mut handler = Handler::new();
+        registry.run(&items, &mut handler);
+
+        let diagnostics = handler.diagnostics();
+        let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "function `many` has too many parameters (4/3)",
+                "function `method` has too many parameters (4/3)",
+            ]
+        );
+        assert!(diagnostics.iter().all(|d| d.level == Level::Warning));
+    }
+
+    #[test]
+    fn test_builtin_lints() {
+        let registry = LintRegistry::with_builtin_lints();
+        assert_eq!(registry.names().collect::<Vec<_>>(), ["too-many-params"]);
+    }
+}
@@ -0,0 +1,178 @@
+//! Module dependency graph
+//!
+//! Builds a graph with an edge from each module to every module its `use`
+//! items import from, and finds cycles in it. Resolution follows imports
+//! module by module, so a circular chain has to be reported up front
+//! instead of being walked.
+
+use faxc_par as ast;
+use faxc_util::{Span, Symbol};
+use indexmap::IndexMap;
+
+/// A cycle in the import graph
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportCycle {
+    /// Module where the cycle was entered
+    pub first: String,
+    /// Module whose import closes the cycle
+    pub second: String,
+    /// Span of the module declaration closing the cycle
+    pub span: Span,
+}
+
+impl ImportCycle {
+    /// Diagnostic message for this cycle
+    pub fn message(&self) -> String {
+        format!(
+            "circular dependency between modules {} and {}",
+            self.first, self.second
+        )
+    }
+}
+
+/// A module node; index 0 is the crate root
+struct Module {
+    path: String,
+    parent: Option<usize>,
+    children: IndexMap<Symbol, usize>,
+    imports: Vec<Vec<Symbol>>,
+    span: Span,
+}
+
+/// Find all import cycles between the modules declared in `items`
+pub fn find_import_cycles(items: &[ast::Item]) -> Vec<ImportCycle> {
+    let mut modules = vec![Module {
+        path: "crate".to_string(),
+        parent: None,
+        children: IndexMap::new(),
+        imports: Vec::new(),
+        span: Span::DUMMY,
+    }];
+    collect_modules(&mut modules, 0, items);
+
+    let edges: Vec<Vec<usize>> = (0..modules.len())
+        .map(|from| {
+            modules[from]
+                .imports
+                .iter()
+                .filter_map(|path| resolve_import(&modules, from, path))
+                .filter(|&to| to != from)
+                .collect()
+        })
+        .collect();
+
+    find_cycles(&modules, &edges)
+}
+
+fn collect_modules(modules: &mut Vec<Module>, current: usize, items: &[ast::Item]) {
+    for item in items {
+        match item {
+            ast::Item::Use(u) => {
+                let path = u.path.segments.iter().map(|seg| seg.ident).collect();
+                modules[current].imports.push(path);
+            },
+            ast::Item::Mod(m) => {
+                let idx = modules.len();
+                modules.push(Module {
+                    path: match current {
+                        0 => m.name.as_str().to_string(),
+                        _ => format!("{}::{}", modules[current].path, m.name),
+                    },
+                    parent: Some(current),
+                    children: IndexMap::new(),
+                    imports: Vec::new(),
+                    span: m.span,
+                });
+                modules[current].children.insert(m.name, idx);
+                if let Some(inner) = &m.items {
+                    collect_modules(modules, idx, inner);
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+/// Find the innermost known module an import path reaches into
+///
+/// Paths starting with `crate`, `self` or `super` are anchored there;
+/// other paths are looked up in the importing module first, then at the
+/// crate root. Paths into unknown (external) modules produce no edge.
+fn resolve_import(modules: &[Module], from: usize, path: &[Symbol]) -> Option<usize> {
+    let (first, rest) = path.split_first()?;
+    let (mut current, rest) = match first.as_str() {
+        "crate" => (0, rest),
+        "self" => (from, rest),
+        "super" => {
+            let mut current = modules[from].parent?;
+            let mut rest = rest;
+            while let Some((seg, tail)) = rest.split_first() {
+                if !seg.is("super") {
+                    break;
+                }
+                current = modules[current].parent?;
+                rest = tail;
+            }
+            (current, rest)
+        },
+        _ if modules[from].children.contains_key(first) => (from, path),
+        _ if modules[0].children.contains_key(first) => (0, path),
+        _ => return None,
+    };
+
+    for seg in rest {
+        match modules[current].children.get(seg) {
+            Some(&child) => current = child,
+            None => break,
+        }
+    }
+    Some(current)
+}
+
+/// Depth-first search reporting every back edge as a cycle
+fn find_cycles(modules: &[Module], edges: &[Vec<usize>]) -> Vec<ImportCycle> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Unvisited,
+        InProgress,
+        Done,
+    }
+
+    let mut state = vec![State::Unvisited; modules.len()];
+    let mut cycles = Vec::new();
+
+    for root in 0..modules.len() {
+        if state[root] != State::Unvisited {
+            continue;
+        }
+
+        // Stack of (module, index of next edge to follow)
+        let mut stack = vec![(root, 0)];
+        state[root] = State::InProgress;
+        while let Some(&(node, next)) = stack.last() {
+            let Some(&target) = edges[node].get(next) else {
+                state[node] = State::Done;
+                stack.pop();
+                continue;
+            };
+            if let Some(top) = stack.last_mut() {
+                top.1 += 1;
+            }
+
+            match state[target] {
+                State::Unvisited => {
+                    state[target] = State::InProgress;
+                    stack.push((target, 0));
+                },
+                State::InProgress => cycles.push(ImportCycle {
+                    first: modules[target].path.clone(),
+                    second: modules[node].path.clone(),
+                    span: modules[node].span,
+                }),
+                State::Done => {},
+            }
+        }
+    }
+
+    cycles
+}
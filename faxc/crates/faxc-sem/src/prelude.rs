@@ -0,0 +1,30 @@
+//! Builtin functions provided by the runtime
+//!
+//! These are injected into the module scope before user items are collected,
+//! so user definitions with the same name shadow them.
+
+use crate::types::Type;
+use faxc_util::Symbol;
+
+/// A builtin function signature
+#[derive(Debug, Clone)]
+pub struct Builtin {
+    /// Name in source and runtime symbol name
+    pub name: Symbol,
+    /// Function type
+    pub ty: Type,
+}
+
+/// Signatures of all builtin functions
+pub fn builtins() -> Vec<Builtin> {
+    let builtin = |name: &str, params: Vec<Type>, ret: Type| Builtin {
+        name: Symbol::intern(name),
+        ty: Type::Fn(params, Box::new(ret)),
+    };
+
+    vec![
+        builtin("println", vec![Type::String], Type::Unit),
+        builtin("print", vec![Type::String], Type::Unit),
+        builtin("str_concat", vec![Type::String, Type::String], Type::String),
+    ]
+}
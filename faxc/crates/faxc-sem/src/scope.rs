@@ -36,6 +36,18 @@ pub enum RibKind {
     Loop(Option<LabelId>),
 }
 
+impl RibKind {
+    /// Short lowercase name used in scope dumps
+    pub fn name(&self) -> &'static str {
+        match self {
+            RibKind::Module => "module",
+            RibKind::Function => "function",
+            RibKind::Block => "block",
+            RibKind::Loop(_) => "loop",
+        }
+    }
+}
+
 /// Scope tree for name resolution
 pub struct ScopeTree {
     /// All ribs (scopes)
@@ -71,6 +83,12 @@ impl ScopeTree {
         new_rib
     }
 
+    /// Re-enter a rib created earlier, e.g. a module's rib when its items
+    /// are analyzed after collection
+    pub fn enter_existing(&mut self, rib: RibId) {
+        self.current_rib = rib;
+    }
+
     /// Exit current scope
     pub fn exit_scope(&mut self) {
         if let Some(parent) = self.ribs[self.current_rib].parent {
@@ -79,25 +97,157 @@ impl ScopeTree {
     }
 
     /// Add binding to current scope
-    pub fn add_binding(&mut self, name: Symbol, def_id: DefId) {
-        self.ribs[self.current_rib].bindings.insert(name, def_id);
+    ///
+    /// A binding of the same name in the current rib is replaced, and its
+    /// DefId returned so callers can tell shadowing from redefinition.
+    pub fn add_binding(&mut self, name: Symbol, def_id: DefId) -> Option<DefId> {
+        self.ribs[self.current_rib].bindings.insert(name, def_id)
     }
 
     /// Resolve name to definition
     pub fn resolve(&self, name: Symbol) -> Option<DefId> {
-        let mut rib_id = self.current_rib;
+        self.resolve_from(self.current_rib, name)
+    }
+
+    /// Name bound to `def_id` in a rib enclosing `rib`, not looking past the
+    /// nearest module rib
+    ///
+    /// This finds the local variables an inner scope, such as a closure,
+    /// uses from the function around it.
+    pub fn enclosing_local(&self, rib: RibId, def_id: DefId) -> Option<Symbol> {
+        let mut rib_id = self.ribs[rib].parent;
+        while let Some(id) = rib_id {
+            let rib = &self.ribs[id];
+            if matches!(rib.kind, RibKind::Module) {
+                return None;
+            }
+            if let Some((&name, _)) = rib.bindings.iter().find(|(_, &d)| d == def_id) {
+                return Some(name);
+            }
+            rib_id = rib.parent;
+        }
+        None
+    }
+
+    /// Find the visible name closest to `name`, for "did you mean" hints
+    ///
+    /// Candidates are the bindings reachable from the current rib. A match
+    /// must be within an edit distance of a third of the name's length
+    /// (at least one); ties go to the alphabetically first name.
+    pub fn similar_name(&self, name: Symbol) -> Option<Symbol> {
+        let target = name.as_str();
+        let max_distance = (target.chars().count() / 3).max(1);
+
+        let mut best: Option<(usize, Symbol)> = None;
+        let mut rib_id = Some(self.current_rib);
+        while let Some(id) = rib_id {
+            let rib = &self.ribs[id];
+            for &candidate in rib.bindings.keys() {
+                if candidate == name {
+                    continue;
+                }
+                let distance = edit_distance(target, candidate.as_str());
+                if distance > max_distance {
+                    continue;
+                }
+                let better = match best {
+                    None => true,
+                    Some((d, b)) => (distance, candidate.as_str()) < (d, b.as_str()),
+                };
+                if better {
+                    best = Some((distance, candidate));
+                }
+            }
+            rib_id = rib.parent;
+        }
+        best.map(|(_, candidate)| candidate)
+    }
 
+    /// Iterate over all ribs in creation order
+    ///
+    /// Ribs are never removed when a scope is exited, so after analysis
+    /// this covers every scope that was entered. A rib's parent always
+    /// comes before it.
+    pub fn iter_ribs(&self) -> impl Iterator<Item = (RibId, &Rib)> {
+        self.ribs
+            .as_slice()
+            .iter()
+            .enumerate()
+            .map(|(idx, rib)| (RibId::from_usize(idx), rib))
+    }
+
+    /// Number of ancestors of a rib (the root has depth 0)
+    pub fn depth(&self, rib_id: RibId) -> usize {
+        let mut depth = 0;
+        let mut current = self.ribs[rib_id].parent;
+        while let Some(parent) = current {
+            depth += 1;
+            current = self.ribs[parent].parent;
+        }
+        depth
+    }
+
+    /// Render the rib hierarchy with each rib's bindings
+    ///
+    /// Bindings that hide a binding of the same name in an enclosing rib
+    /// are marked with the definition they shadow.
+    pub fn dump(&self) -> String {
+        let mut children: Vec<Vec<RibId>> = vec![Vec::new(); self.ribs.len()];
+        let mut roots = Vec::new();
+        for (id, rib) in self.iter_ribs() {
+            match rib.parent {
+                Some(parent) => children[parent.index()].push(id),
+                None => roots.push(id),
+            }
+        }
+
+        let mut out = String::new();
+        let mut stack: Vec<RibId> = roots.into_iter().rev().collect();
+        while let Some(id) = stack.pop() {
+            let rib = &self.ribs[id];
+            let indent = "  ".repeat(self.depth(id));
+            out.push_str(&format!("{}rib {} ({})\n", indent, id.0, rib.kind.name()));
+
+            let mut bindings: Vec<_> = rib.bindings.iter().collect();
+            bindings.sort_by_key(|(name, _)| name.as_str());
+            for (name, def_id) in bindings {
+                out.push_str(&format!("{}  {} -> DefId({})", indent, name, def_id.0));
+                if let Some(shadowed) = rib.parent.and_then(|p| self.resolve_from(p, *name)) {
+                    out.push_str(&format!(" (shadows DefId({}))", shadowed.0));
+                }
+                out.push('\n');
+            }
+
+            stack.extend(children[id.index()].iter().rev());
+        }
+        out
+    }
+
+    /// Resolve a name starting at a given rib instead of the current one
+    fn resolve_from(&self, mut rib_id: RibId, name: Symbol) -> Option<DefId> {
         loop {
             let rib = &self.ribs[rib_id];
-
             if let Some(&def_id) = rib.bindings.get(&name) {
                 return Some(def_id);
             }
+            rib_id = rib.parent?;
+        }
+    }
+}
 
-            match rib.parent {
-                Some(parent) => rib_id = parent,
-                None => return None,
-            }
+/// Levenshtein distance between two strings, counted in chars
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitute = prev[j] + usize::from(ca != cb);
+            curr[j + 1] = substitute.min(prev[j + 1] + 1).min(curr[j] + 1);
         }
+        std::mem::swap(&mut prev, &mut curr);
     }
+    prev[b.len()]
 }
@@ -1,8 +1,10 @@
-use faxc_util::{DefId, Idx, IndexVec};
-use std::collections::HashMap;
+use faxc_util::{DefId, Idx, IndexVec, Symbol};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
 /// A type in the type system
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Type {
     /// Error type (for recovery)
     Error,
@@ -42,6 +44,8 @@ pub enum Type {
     Param(ParamId),
     /// Reference type
     Ref(Box<Type>, bool),
+    /// Raw pointer type (*const T / *mut T)
+    Ptr(Box<Type>, bool),
     /// Tuple type
     Tuple(Vec<Type>),
     /// Array type [T; N]
@@ -60,6 +64,199 @@ pub enum Type {
     Infer(InferId),
 }
 
+impl Type {
+    /// Check if this is a signed or unsigned integer type
+    pub fn is_integral(&self) -> bool {
+        matches!(
+            self,
+            Type::Int
+                | Type::UInt
+                | Type::Int8
+                | Type::UInt8
+                | Type::Int16
+                | Type::UInt16
+                | Type::Int32
+                | Type::UInt32
+        )
+    }
+
+    /// Smallest and largest value of an integer type
+    pub fn int_range(&self) -> Option<(i128, i128)> {
+        Some(match self {
+            Type::Int => (i64::MIN.into(), i64::MAX.into()),
+            Type::UInt => (0, u64::MAX.into()),
+            Type::Int8 => (i8::MIN.into(), i8::MAX.into()),
+            Type::UInt8 => (0, u8::MAX.into()),
+            Type::Int16 => (i16::MIN.into(), i16::MAX.into()),
+            Type::UInt16 => (0, u16::MAX.into()),
+            Type::Int32 => (i32::MIN.into(), i32::MAX.into()),
+            Type::UInt32 => (0, u32::MAX.into()),
+            _ => return None,
+        })
+    }
+
+    /// Check if this is a floating point type
+    pub fn is_float(&self) -> bool {
+        matches!(self, Type::Float | Type::Float32)
+    }
+
+    /// Check if this is an integer or floating point type
+    pub fn is_numeric(&self) -> bool {
+        self.is_integral() || self.is_float()
+    }
+
+    /// Check if this is a raw pointer type
+    pub fn is_ptr(&self) -> bool {
+        matches!(self, Type::Ptr(_, _))
+    }
+
+    /// Check if a value of this type is copied rather than moved on use
+    ///
+    /// Scalars, shared references, raw pointers and function values are
+    /// `Copy`, as are tuples, arrays and options of `Copy` types. Strings,
+    /// named types and mutable references are moved.
+    pub fn is_copy(&self) -> bool {
+        match self {
+            Type::Error
+            | Type::Unit
+            | Type::Never
+            | Type::Bool
+            | Type::Char
+            | Type::Ref(_, false)
+            | Type::Ptr(_, _)
+            | Type::Fn(_, _) => true,
+            ty if ty.is_numeric() => true,
+            Type::Tuple(elems) => elems.iter().all(Type::is_copy),
+            Type::Array(elem, _) | Type::Option(elem) => elem.is_copy(),
+            Type::Result(ok, err) => ok.is_copy() && err.is_copy(),
+            _ => false,
+        }
+    }
+
+    /// Check if a value of this type may be converted to `target` with `as`
+    ///
+    /// Mirrors the primitive cast rules: numeric to numeric, `bool`/`char`
+    /// to integer, `u8` to `char`, and pointer/integer conversions.
+    /// Error and inference types are accepted to avoid cascading errors.
+    pub fn can_cast_to(&self, target: &Type) -> bool {
+        if self == target {
+            return true;
+        }
+
+        match (self, target) {
+            (Type::Error | Type::Infer(_), _) | (_, Type::Error | Type::Infer(_)) => true,
+            (from, to) if from.is_numeric() && to.is_numeric() => true,
+            (Type::Bool | Type::Char, to) if to.is_integral() => true,
+            (Type::UInt8, Type::Char) => true,
+            (Type::Ptr(_, _) | Type::Ref(_, _), Type::Ptr(_, _)) => true,
+            (Type::Ptr(_, _), to) if to.is_integral() => true,
+            (from, Type::Ptr(_, _)) if from.is_integral() => true,
+            _ => false,
+        }
+    }
+
+    /// Display this type with ADTs named as recorded in `ctx`
+    pub fn display<'a>(&'a self, ctx: &'a TypeContext) -> TypeDisplay<'a> {
+        TypeDisplay {
+            ty: self,
+            ctx: Some(ctx),
+        }
+    }
+}
+
+/// Renders types the way users write them; ADTs without a name in reach
+/// print as `{adt#N}`
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        TypeDisplay {
+            ty: self,
+            ctx: None,
+        }
+        .fmt(f)
+    }
+}
+
+/// A type paired with the context used to name its ADTs, see [`Type::display`]
+pub struct TypeDisplay<'a> {
+    ty: &'a Type,
+    ctx: Option<&'a TypeContext>,
+}
+
+impl<'a> TypeDisplay<'a> {
+    fn inner(&self, ty: &'a Type) -> TypeDisplay<'a> {
+        TypeDisplay { ty, ctx: self.ctx }
+    }
+
+    fn list(&self, f: &mut fmt::Formatter<'_>, tys: &'a [Type]) -> fmt::Result {
+        for (i, ty) in tys.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", self.inner(ty))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TypeDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.ty {
+            Type::Error => write!(f, "{{error}}"),
+            Type::Unit => write!(f, "()"),
+            Type::Never => write!(f, "!"),
+            Type::Int => write!(f, "int"),
+            Type::UInt => write!(f, "uint"),
+            Type::Float => write!(f, "float"),
+            Type::Bool => write!(f, "bool"),
+            Type::Char => write!(f, "char"),
+            Type::String => write!(f, "str"),
+            Type::Int8 => write!(f, "i8"),
+            Type::UInt8 => write!(f, "u8"),
+            Type::Int16 => write!(f, "i16"),
+            Type::UInt16 => write!(f, "u16"),
+            Type::Int32 => write!(f, "i32"),
+            Type::UInt32 => write!(f, "u32"),
+            Type::Float32 => write!(f, "f32"),
+            Type::Adt(def_id) => match self.ctx.and_then(|ctx| ctx.adt_name(*def_id)) {
+                Some(name) => write!(f, "{}", name.as_str()),
+                None => write!(f, "{{adt#{}}}", def_id.0),
+            },
+            Type::Param(id) => write!(f, "{{param#{}}}", id.0),
+            Type::Ref(ty, mutable) => {
+                let prefix = if *mutable { "&mut " } else { "&" };
+                write!(f, "{}{}", prefix, self.inner(ty))
+            },
+            Type::Ptr(ty, mutable) => {
+                let prefix = if *mutable { "*mut " } else { "*const " };
+                write!(f, "{}{}", prefix, self.inner(ty))
+            },
+            Type::Tuple(tys) => {
+                write!(f, "(")?;
+                self.list(f, tys)?;
+                if tys.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            },
+            Type::Array(ty, len) => write!(f, "[{}; {}]", self.inner(ty), len),
+            Type::Slice(ty) => write!(f, "[{}]", self.inner(ty)),
+            Type::Fn(params, ret) => {
+                write!(f, "fn(")?;
+                self.list(f, params)?;
+                write!(f, ")")?;
+                if **ret != Type::Unit {
+                    write!(f, " -> {}", self.inner(ret))?;
+                }
+                Ok(())
+            },
+            Type::Future(ty) => write!(f, "Future<{}>", self.inner(ty)),
+            Type::Option(ty) => write!(f, "Option<{}>", self.inner(ty)),
+            Type::Result(ok, err) => write!(f, "Result<{}, {}>", self.inner(ok), self.inner(err)),
+            Type::Infer(_) => write!(f, "_"),
+        }
+    }
+}
+
 /// Type parameter ID
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ParamId(pub u32);
@@ -86,6 +283,22 @@ impl Idx for InferId {
     }
 }
 
+/// Handle to a type interned in a [`TypeContext`]
+///
+/// Structurally equal types intern to the same id, so comparing ids is
+/// equivalent to comparing the types without walking them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TyId(pub u32);
+
+impl Idx for TyId {
+    fn from_usize(idx: usize) -> Self {
+        TyId(idx as u32)
+    }
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
 /// Type context - stores all type information
 #[derive(Default)]
 pub struct TypeContext {
@@ -93,10 +306,24 @@ pub struct TypeContext {
     pub def_types: HashMap<DefId, Type>,
     /// Type of each expression
     pub expr_types: HashMap<ExprId, Type>,
+    /// Source names of structs and enums, for displaying `Type::Adt`
+    pub adt_names: HashMap<DefId, Symbol>,
     /// Inference variable substitutions
     pub substitutions: IndexVec<InferId, Option<Type>>,
     /// Constraints to solve
     pub constraints: Vec<Constraint>,
+    /// Interned types by id
+    interned: IndexVec<TyId, Type>,
+    /// Id of each interned type
+    interned_ids: HashMap<Type, TyId>,
+    /// Types with an `impl` of each trait
+    trait_impls: HashMap<DefId, HashSet<Type>>,
+    /// The `Copy` trait, if one is declared
+    copy_trait: Option<DefId>,
+    /// Memoized `is_copy` answers
+    copy_cache: RefCell<HashMap<Type, bool>>,
+    /// Memoized `implements` answers for traits other than `Copy`
+    impl_cache: RefCell<HashMap<(Type, DefId), bool>>,
 }
 
 /// Expression ID (placeholder, should match HIR)
@@ -119,6 +346,87 @@ impl TypeContext {
         self.def_types.insert(def_id, ty);
     }
 
+    pub fn set_adt_name(&mut self, def_id: DefId, name: Symbol) {
+        self.adt_names.insert(def_id, name);
+    }
+
+    pub fn adt_name(&self, def_id: DefId) -> Option<Symbol> {
+        self.adt_names.get(&def_id).copied()
+    }
+
+    /// Intern `ty`, returning the id shared by every type equal to it
+    pub fn intern_type(&mut self, ty: Type) -> TyId {
+        if let Some(&id) = self.interned_ids.get(&ty) {
+            return id;
+        }
+        let id = self.interned.push(ty.clone());
+        self.interned_ids.insert(ty, id);
+        id
+    }
+
+    /// The type behind an interned id
+    pub fn interned_type(&self, id: TyId) -> &Type {
+        &self.interned[id]
+    }
+
+    /// Record an `impl trait_def for ty`
+    pub fn add_impl(&mut self, trait_def: DefId, ty: Type) {
+        self.trait_impls.entry(trait_def).or_default().insert(ty);
+        self.clear_trait_caches();
+    }
+
+    /// Treat `def_id` as the `Copy` trait
+    pub fn set_copy_trait(&mut self, def_id: DefId) {
+        self.copy_trait = Some(def_id);
+        self.clear_trait_caches();
+    }
+
+    fn clear_trait_caches(&mut self) {
+        self.copy_cache.get_mut().clear();
+        self.impl_cache.get_mut().clear();
+    }
+
+    /// Check if `ty` implements `trait_def`, through an impl or, for
+    /// `Copy`, structurally
+    pub fn implements(&self, ty: &Type, trait_def: DefId) -> bool {
+        if self.copy_trait == Some(trait_def) {
+            return self.is_copy(ty);
+        }
+
+        let key = (ty.clone(), trait_def);
+        if let Some(&cached) = self.impl_cache.borrow().get(&key) {
+            return cached;
+        }
+        let found = self.has_impl(ty, trait_def);
+        self.impl_cache.borrow_mut().insert(key, found);
+        found
+    }
+
+    /// Check if values of `ty` are copied rather than moved
+    ///
+    /// Extends `Type::is_copy` to named types with an `impl Copy`, and to
+    /// tuples, arrays, options and results of such types.
+    pub fn is_copy(&self, ty: &Type) -> bool {
+        if let Some(&cached) = self.copy_cache.borrow().get(ty) {
+            return cached;
+        }
+        let copy = match ty {
+            Type::Tuple(elems) => elems.iter().all(|elem| self.is_copy(elem)),
+            Type::Array(elem, _) | Type::Option(elem) => self.is_copy(elem),
+            Type::Result(ok, err) => self.is_copy(ok) && self.is_copy(err),
+            Type::Adt(_) => self.copy_trait.is_some_and(|copy| self.has_impl(ty, copy)),
+            _ => ty.is_copy(),
+        };
+        self.copy_cache.borrow_mut().insert(ty.clone(), copy);
+        copy
+    }
+
+    fn has_impl(&self, ty: &Type, trait_def: DefId) -> bool {
+        self.trait_impls
+            .get(&trait_def)
+            .is_some_and(|types| types.contains(ty))
+    }
+
     pub fn add_eq_constraint(&mut self, t1: Type, t2: Type) {
         self.constraints.push(Constraint::Eq(t1, t2));
     }
@@ -135,6 +443,7 @@ impl TypeContext {
             },
             Type::Tuple(tys) => Type::Tuple(tys.iter().map(|t| self.substitute(t)).collect()),
             Type::Ref(t, m) => Type::Ref(Box::new(self.substitute(t)), *m),
+            Type::Ptr(t, m) => Type::Ptr(Box::new(self.substitute(t)), *m),
             Type::Array(t, n) => Type::Array(Box::new(self.substitute(t)), *n),
             Type::Fn(params, ret) => Type::Fn(
                 params.iter().map(|p| self.substitute(p)).collect(),
@@ -284,6 +593,29 @@ mod tests {
         assert!(debug_str.contains("Int"));
     }
 
+    #[test]
+    fn test_type_display_composed() {
+        let mut ctx = TypeContext::default();
+        ctx.set_adt_name(DefId(3), Symbol::intern("Point"));
+
+        let ty = Type::Fn(
+            vec![
+                Type::Ref(Box::new(Type::Adt(DefId(3))), true),
+                Type::Tuple(vec![Type::Int, Type::Bool]),
+            ],
+            Box::new(Type::Ref(Box::new(Type::String), false)),
+        );
+        assert_eq!(
+            ty.display(&ctx).to_string(),
+            "fn(&mut Point, (int, bool)) -> &str"
+        );
+        assert_eq!(ty.to_string(), "fn(&mut {adt#3}, (int, bool)) -> &str");
+
+        let ty = Type::Fn(vec![Type::Float], Box::new(Type::Unit));
+        assert_eq!(ty.to_string(), "fn(float)");
+        assert_eq!(Type::Tuple(vec![Type::Char]).to_string(), "(char,)");
+    }
+
     // ========================================================================
     // ParamId Tests
     // ========================================================================
@@ -368,6 +700,59 @@ mod tests {
         assert_eq!(set.len(), 2);
     }
 
+    // ========================================================================
+    // Cast Rule Tests
+    // ========================================================================
+
+    #[test]
+    fn test_can_cast_numeric() {
+        assert!(Type::Int.can_cast_to(&Type::Float));
+        assert!(Type::Float32.can_cast_to(&Type::UInt8));
+        assert!(Type::Bool.can_cast_to(&Type::Int));
+        assert!(Type::UInt8.can_cast_to(&Type::Char));
+    }
+
+    #[test]
+    fn test_can_cast_pointer() {
+        let ptr = Type::Ptr(Box::new(Type::Int32), true);
+        let byte_ptr = Type::Ptr(Box::new(Type::UInt8), false);
+
+        assert!(ptr.can_cast_to(&byte_ptr));
+        assert!(ptr.can_cast_to(&Type::UInt));
+        assert!(Type::UInt.can_cast_to(&ptr));
+    }
+
+    #[test]
+    fn test_cannot_cast_non_primitive() {
+        assert!(!Type::Bool.can_cast_to(&Type::String));
+        assert!(!Type::String.can_cast_to(&Type::Int));
+        assert!(!Type::Float.can_cast_to(&Type::Ptr(Box::new(Type::Int), false)));
+        assert!(!Type::Int.can_cast_to(&Type::Bool));
+    }
+
+    // ========================================================================
+    // Type Interning Tests
+    // ========================================================================
+
+    #[test]
+    fn test_intern_structurally_equal_types_share_id() {
+        let mut ctx = TypeContext::default();
+        let nested = || {
+            Type::Fn(
+                vec![Type::Tuple(vec![Type::Adt(DefId(1)), Type::Int])],
+                Box::new(Type::Ref(Box::new(Type::Adt(DefId(1))), true)),
+            )
+        };
+
+        let a = ctx.intern_type(nested());
+        let b = ctx.intern_type(nested());
+        let c = ctx.intern_type(Type::Tuple(vec![Type::Adt(DefId(1)), Type::Int]));
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(ctx.interned_type(a), &nested());
+    }
+
     // ========================================================================
     // ExprId Tests
     // ========================================================================
@@ -588,6 +973,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_type_context_is_copy_tuples() {
+        let ctx = TypeContext::default();
+
+        assert!(ctx.is_copy(&Type::Tuple(vec![Type::Int, Type::Int32])));
+        assert!(!ctx.is_copy(&Type::Tuple(vec![Type::Int, Type::String])));
+        assert!(!ctx.is_copy(&Type::Adt(DefId(1))));
+    }
+
+    #[test]
+    fn test_type_context_is_copy_cached() {
+        let mut ctx = TypeContext::default();
+        let copy = DefId(1);
+        let point = Type::Adt(DefId(2));
+        let pair = Type::Tuple(vec![point.clone(), Type::Bool]);
+        ctx.set_copy_trait(copy);
+
+        assert!(!ctx.is_copy(&pair));
+        assert!(!ctx.is_copy(&pair));
+        assert_eq!(ctx.copy_cache.borrow().get(&pair), Some(&false));
+
+        // A new impl invalidates earlier answers
+        ctx.add_impl(copy, point.clone());
+        assert!(ctx.is_copy(&pair));
+        assert!(ctx.is_copy(&pair));
+        assert!(ctx.implements(&point, copy));
+    }
+
+    #[test]
+    fn test_type_context_implements() {
+        let mut ctx = TypeContext::default();
+        let display = DefId(3);
+        ctx.add_impl(display, Type::Adt(DefId(4)));
+
+        assert!(ctx.implements(&Type::Adt(DefId(4)), display));
+        assert!(ctx.implements(&Type::Adt(DefId(4)), display));
+        assert!(!ctx.implements(&Type::Adt(DefId(5)), display));
+        assert!(!ctx.implements(&Type::Int, display));
+    }
+
     #[test]
     fn test_type_context_substitute_primitive() {
         let ctx = TypeContext::default();
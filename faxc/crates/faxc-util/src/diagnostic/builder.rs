@@ -3,7 +3,7 @@
 //! This module provides the [`DiagnosticBuilder`] type for constructing
 //! diagnostics with a fluent API, including source code snippets.
 
-use super::{Diagnostic, DiagnosticCode, Level, Span};
+use super::{Diagnostic, DiagnosticCode, Level, Span, Suggestion};
 
 /// A source code snippet for display in diagnostics
 ///
@@ -140,6 +140,11 @@ impl SourceSnippet {
     /// let formatted = snippet.format();
     /// ```
     pub fn format(&self) -> String {
+        self.format_styled(|carets| carets.to_string())
+    }
+
+    /// Format the snippet, passing the caret underline through `style`
+    pub(super) fn format_styled(&self, style: impl Fn(&str) -> String) -> String {
         let line_num_width = self.line_number.to_string().len().max(3);
         let mut result = String::new();
 
@@ -164,9 +169,7 @@ impl SourceSnippet {
         }
 
         // Add carets
-        for _ in 0..underline_len {
-            result.push('^');
-        }
+        result.push_str(&style(&"^".repeat(underline_len)));
 
         // Add label if present
         if let Some(ref label) = self.label {
@@ -198,6 +201,7 @@ pub struct DiagnosticBuilder {
     notes: Vec<String>,
     helps: Vec<String>,
     snippets: Vec<SourceSnippet>,
+    suggestions: Vec<Suggestion>,
 }
 
 impl DiagnosticBuilder {
@@ -224,6 +228,7 @@ impl DiagnosticBuilder {
             notes: Vec::new(),
             helps: Vec::new(),
             snippets: Vec::new(),
+            suggestions: Vec::new(),
         }
     }
 
@@ -380,6 +385,33 @@ impl DiagnosticBuilder {
         self
     }
 
+    /// Add a suggested edit replacing `span` with `replacement`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use faxc_util::diagnostic::DiagnosticBuilder;
+    /// use faxc_util::Span;
+    ///
+    /// let diag = DiagnosticBuilder::error("missing `mut`")
+    ///     .with_suggestion(Span::new(4, 5, 1, 5), "make the binding mutable", "mut x")
+    ///     .build();
+    /// assert_eq!(diag.suggestions.len(), 1);
+    /// ```
+    pub fn with_suggestion(
+        mut self,
+        span: Span,
+        message: impl Into<String>,
+        replacement: impl Into<String>,
+    ) -> Self {
+        self.suggestions.push(Suggestion {
+            message: message.into(),
+            span,
+            replacement: replacement.into(),
+        });
+        self
+    }
+
     /// Build the diagnostic
     ///
     /// # Examples
@@ -400,6 +432,7 @@ impl DiagnosticBuilder {
             notes: self.notes,
             helps: self.helps,
             snippets: self.snippets,
+            suggestions: self.suggestions,
         }
     }
 
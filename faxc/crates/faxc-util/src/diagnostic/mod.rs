@@ -47,6 +47,7 @@ pub use codes::{
 };
 
 use crate::Span;
+use rustc_hash::FxHashMap;
 use std::cell::RefCell;
 use std::fmt;
 
@@ -60,7 +61,7 @@ use std::fmt;
 /// assert_eq!(format!("{}", Level::Error), "error");
 /// assert_eq!(format!("{}", Level::Warning), "warning");
 /// ```
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Level {
     /// An error that prevents compilation
     Error,
@@ -72,6 +73,18 @@ pub enum Level {
     Help,
 }
 
+impl Level {
+    /// ANSI SGR parameters used when rendering this level in color
+    pub const fn color_code(&self) -> &'static str {
+        match self {
+            Level::Error => "1;31",
+            Level::Warning => "1;33",
+            Level::Note => "1;34",
+            Level::Help => "1;36",
+        }
+    }
+}
+
 impl fmt::Display for Level {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -109,6 +122,30 @@ pub struct Diagnostic {
     pub helps: Vec<String>,
     /// Source code snippets for display
     pub snippets: Vec<SourceSnippet>,
+    /// Suggested source edits
+    pub suggestions: Vec<Suggestion>,
+}
+
+/// A suggested edit that would fix a diagnostic
+///
+/// # Examples
+///
+/// ```
+/// use faxc_util::diagnostic::{Diagnostic, Suggestion};
+/// use faxc_util::Span;
+///
+/// let diag = Diagnostic::error("expected `;`", Span::new(9, 9, 1, 10))
+///     .with_suggestion(Span::new(9, 9, 1, 10), "add a semicolon", ";");
+/// assert_eq!(diag.suggestions[0].replacement, ";");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Suggestion {
+    /// Description of the edit
+    pub message: String,
+    /// Source range the edit replaces
+    pub span: Span,
+    /// Text to put in place of the range
+    pub replacement: String,
 }
 
 impl Diagnostic {
@@ -130,6 +167,7 @@ impl Diagnostic {
             notes: Vec::new(),
             helps: Vec::new(),
             snippets: Vec::new(),
+            suggestions: Vec::new(),
         }
     }
 
@@ -220,6 +258,128 @@ impl Diagnostic {
         self.snippets.push(snippet);
         self
     }
+
+    /// Add a suggested edit replacing `span` with `replacement`
+    pub fn with_suggestion(
+        mut self,
+        span: Span,
+        message: impl Into<String>,
+        replacement: impl Into<String>,
+    ) -> Self {
+        self.suggestions.push(Suggestion {
+            message: message.into(),
+            span,
+            replacement: replacement.into(),
+        });
+        self
+    }
+
+    /// Render the diagnostic like its `Display` output, naming `file_name`
+    /// in the location line
+    ///
+    /// With `color`, level labels and snippet carets are wrapped in ANSI
+    /// escapes in the level's color.
+    ///
+    /// ```text
+    /// error: file not found for module helper
+    ///  --> src/main.fax:3:1
+    /// ```
+    pub fn render(&self, file_name: Option<&str>, color: bool) -> String {
+        self.render_with_source(file_name, None, color)
+    }
+
+    /// Render like [`Diagnostic::render`], quoting the line of `source` the
+    /// span points at when the diagnostic carries no snippet of its own
+    ///
+    /// `source` must be the text of the file the span's `file_id` names.
+    pub fn render_with_source(
+        &self,
+        file_name: Option<&str>,
+        source: Option<&str>,
+        color: bool,
+    ) -> String {
+        let mut out = String::new();
+        let _ = self.write_to(&mut out, file_name, source, color);
+        out
+    }
+
+    /// Snippet of the source line the span starts on, underlining the span
+    /// up to the end of that line
+    fn source_snippet(&self, source: &str) -> Option<SourceSnippet> {
+        let line = source.lines().nth((self.span.line as usize).checked_sub(1)?)?;
+        let start = self.span.column as usize;
+        let end = (start + self.span.len()).min(line.chars().count() + 1).max(start);
+        Some(SourceSnippet::new(line, self.span.line as usize, start, end, None::<&str>))
+    }
+
+    fn write_to(
+        &self,
+        f: &mut impl fmt::Write,
+        file_name: Option<&str>,
+        source: Option<&str>,
+        color: bool,
+    ) -> fmt::Result {
+        let paint = |level: Level, text: &str| {
+            if color {
+                format!("\x1b[{}m{}\x1b[0m", level.color_code(), text)
+            } else {
+                text.to_string()
+            }
+        };
+
+        let header = match self.code {
+            Some(code) => format!("{}[{}]", self.level, code),
+            None => self.level.to_string(),
+        };
+        write!(f, "{}: {}", paint(self.level, &header), self.message)?;
+        // A dummy span points at nothing, so there is no location or source
+        // line to show
+        if !self.span.is_dummy() {
+            match file_name {
+                Some(name) => {
+                    write!(f, "\n --> {}:{}:{}", name, self.span.line, self.span.column)?
+                },
+                None => write!(f, "\n --> {}:{}", self.span.line, self.span.column)?,
+            }
+            let quoted = match source {
+                Some(source) if self.snippets.is_empty() => self.source_snippet(source),
+                _ => None,
+            };
+            for snippet in self.snippets.iter().chain(&quoted) {
+                let styled = snippet.format_styled(|carets| paint(self.level, carets));
+                write!(f, "\n{}", styled)?;
+            }
+        }
+        let note = paint(Level::Note, &Level::Note.to_string());
+        for text in &self.notes {
+            write!(f, "\n  = {}: {}", note, text)?;
+        }
+        let help = paint(Level::Help, &Level::Help.to_string());
+        for text in &self.helps {
+            write!(f, "\n  = {}: {}", help, text)?;
+        }
+        for suggestion in &self.suggestions {
+            write!(
+                f,
+                "\n  = {}: {}: `{}`",
+                help, suggestion.message, suggestion.replacement
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    /// Render the diagnostic with its location, snippets and secondary messages
+    ///
+    /// ```text
+    /// error[E0002]: type mismatch: expected `i32`, found `int`
+    ///  --> 3:14
+    ///   = help: you can convert the value with `as i32`
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_to(f, None, None, false)
+    }
 }
 
 /// Handler for collecting and reporting diagnostics
@@ -244,6 +404,14 @@ pub struct Handler {
     diagnostics: RefCell<Vec<Diagnostic>>,
     /// Whether to panic on errors (for testing)
     panic_on_error: RefCell<bool>,
+    /// Maximum number of errors to record, if limited
+    error_limit: RefCell<Option<usize>>,
+    /// Index of each stored diagnostic with a real span, by level, span
+    /// and message, so duplicates are found without scanning
+    seen: RefCell<FxHashMap<(Level, Span, String), usize>>,
+    /// Duplicates dropped per stored diagnostic index, with the index of
+    /// the "repeated" note added to it
+    repeats: RefCell<FxHashMap<usize, (usize, usize)>>,
 }
 
 impl Handler {
@@ -260,6 +428,9 @@ impl Handler {
         Self {
             diagnostics: RefCell::new(Vec::new()),
             panic_on_error: RefCell::new(false),
+            error_limit: RefCell::new(None),
+            seen: RefCell::new(FxHashMap::default()),
+            repeats: RefCell::new(FxHashMap::default()),
         }
     }
 
@@ -276,6 +447,9 @@ impl Handler {
         Self {
             diagnostics: RefCell::new(Vec::new()),
             panic_on_error: RefCell::new(true),
+            error_limit: RefCell::new(None),
+            seen: RefCell::new(FxHashMap::default()),
+            repeats: RefCell::new(FxHashMap::default()),
         }
     }
 
@@ -320,7 +494,89 @@ impl Handler {
         if *self.panic_on_error.borrow() && diagnostic.level == Level::Error {
             panic!("Diagnostic error: {}", diagnostic.message);
         }
+        if self.merge_duplicate(&diagnostic) {
+            return;
+        }
+        let is_error = diagnostic.level == Level::Error;
+        if is_error && self.error_limit_reached() {
+            return;
+        }
+        if !diagnostic.span.is_dummy() {
+            let key = (diagnostic.level, diagnostic.span, diagnostic.message.clone());
+            let idx = self.diagnostics.borrow().len();
+            self.seen.borrow_mut().insert(key, idx);
+        }
         self.diagnostics.borrow_mut().push(diagnostic);
+
+        if is_error && self.error_limit_reached() {
+            let count = self.error_count();
+            self.diagnostics.borrow_mut().push(Diagnostic::new(
+                Level::Note,
+                format!("aborting due to {} errors; too many errors emitted", count),
+                Span::DUMMY,
+            ));
+        }
+    }
+
+    /// Fold a diagnostic into an identical one already stored
+    ///
+    /// Diagnostics match on level, message and span. Dummy spans carry no
+    /// location, so diagnostics without one are never merged. Returns
+    /// `true` if the diagnostic was a duplicate.
+    fn merge_duplicate(&self, diagnostic: &Diagnostic) -> bool {
+        if diagnostic.span.is_dummy() {
+            return false;
+        }
+        let key = (diagnostic.level, diagnostic.span, diagnostic.message.clone());
+        let Some(&idx) = self.seen.borrow().get(&key) else {
+            return false;
+        };
+
+        let mut diagnostics = self.diagnostics.borrow_mut();
+        let notes = &mut diagnostics[idx].notes;
+        let mut repeats = self.repeats.borrow_mut();
+        let (note_idx, count) = repeats.entry(idx).or_insert_with(|| {
+            notes.push(String::new());
+            (notes.len() - 1, 0)
+        });
+        *count += 1;
+        notes[*note_idx] = format!("(repeated {} times)", *count + 1);
+        true
+    }
+
+    /// Stop recording errors once `limit` of them have been reported
+    ///
+    /// The error that reaches the limit is followed by a note saying
+    /// compilation is aborting; later errors are dropped. Warnings are
+    /// unaffected. A limit of 0 removes the limit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use faxc_util::diagnostic::{Diagnostic, Handler};
+    /// use faxc_util::Span;
+    ///
+    /// let handler = Handler::new();
+    /// handler.set_error_limit(2);
+    /// for _ in 0..5 {
+    ///     handler.emit_diagnostic(Diagnostic::error("bad", Span::DUMMY));
+    /// }
+    /// assert_eq!(handler.error_count(), 2);
+    /// assert!(handler.error_limit_reached());
+    /// ```
+    pub fn set_error_limit(&self, limit: usize) {
+        *self.error_limit.borrow_mut() = (limit > 0).then_some(limit);
+    }
+
+    /// Check whether the error limit has been reached
+    ///
+    /// Error recovery loops use this to bail out instead of producing
+    /// diagnostics that would be dropped anyway.
+    pub fn error_limit_reached(&self) -> bool {
+        match *self.error_limit.borrow() {
+            Some(limit) => self.error_count() >= limit,
+            None => false,
+        }
     }
 
     /// Emit a pre-built diagnostic
@@ -443,6 +699,36 @@ impl Handler {
         self.diagnostics.borrow().clone()
     }
 
+    /// Get all diagnostics in source order
+    ///
+    /// Diagnostics are ordered by file and then by start offset, so they
+    /// read top to bottom however the passes that found them were ordered.
+    /// Diagnostics without a location keep their relative order after the
+    /// located ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use faxc_util::diagnostic::{Diagnostic, Handler};
+    /// use faxc_util::Span;
+    ///
+    /// let handler = Handler::new();
+    /// handler.emit_diagnostic(Diagnostic::error("second", Span::new(10, 11, 2, 1)));
+    /// handler.emit_diagnostic(Diagnostic::error("first", Span::new(0, 1, 1, 1)));
+    /// assert_eq!(handler.sorted_diagnostics()[0].message, "first");
+    /// ```
+    pub fn sorted_diagnostics(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = self.diagnostics();
+        diagnostics.sort_by_key(|d| (d.span.is_dummy(), d.span.file_id, d.span.start));
+        diagnostics
+    }
+
+    /// Consume the handler, returning its diagnostics in the order they
+    /// were reported
+    pub fn into_diagnostics(self) -> Vec<Diagnostic> {
+        self.diagnostics.into_inner()
+    }
+
     /// Clear all diagnostics
     ///
     /// # Examples
@@ -455,6 +741,8 @@ impl Handler {
     /// ```
     pub fn clear(&self) {
         self.diagnostics.borrow_mut().clear();
+        self.seen.borrow_mut().clear();
+        self.repeats.borrow_mut().clear();
     }
 }
 
@@ -467,7 +755,7 @@ impl Default for Handler {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::span::Span;
+    use crate::span::{FileId, Span};
 
     #[test]
     fn test_level_display() {
@@ -519,6 +807,86 @@ mod tests {
         assert_eq!(diag.helps, vec!["help 1", "help 2"]);
     }
 
+    #[test]
+    fn test_diagnostic_display() {
+        let span = Span::new(20, 23, 3, 14);
+        let diag = Diagnostic::error("type mismatch", span)
+            .with_code(DiagnosticCode::E0002)
+            .with_note("expected `i32`")
+            .with_help("try a cast")
+            .with_suggestion(span, "convert the value", "x as i32");
+
+        assert_eq!(
+            diag.to_string(),
+            "error[E0002]: type mismatch\n --> 3:14\n  = note: expected `i32`\n  \
+             = help: try a cast\n  = help: convert the value: `x as i32`"
+        );
+        assert_eq!(Diagnostic::warning("unused", Span::DUMMY).to_string(), "warning: unused");
+    }
+
+    #[test]
+    fn test_diagnostic_render_with_file_name() {
+        let diag = Diagnostic::error("unknown module", Span::new(20, 23, 3, 14));
+        assert_eq!(
+            diag.render(Some("src/main.fax"), false),
+            "error: unknown module\n --> src/main.fax:3:14"
+        );
+        assert_eq!(diag.render(None, false), diag.to_string());
+    }
+
+    #[test]
+    fn test_diagnostic_render_with_source_quotes_line() {
+        let source = "fn main() {\n    let x = y;\n}";
+        let diag = Diagnostic::error("cannot find value `y`", Span::new(24, 25, 2, 13));
+
+        assert_eq!(
+            diag.render_with_source(Some("main.fax"), Some(source), false),
+            concat!(
+                "error: cannot find value `y`\n",
+                " --> main.fax:2:13\n",
+                "  2 |     let x = y;\n",
+                "    |             ^",
+            )
+        );
+
+        // An attached snippet takes the place of the quoted line
+        let snippet = SourceSnippet::new("custom", 2, 1, 2, None::<&str>);
+        let rendered = diag
+            .clone()
+            .with_snippet(snippet)
+            .render_with_source(None, Some(source), false);
+        assert!(rendered.contains("custom"));
+        assert!(!rendered.contains("let x"));
+    }
+
+    #[test]
+    fn test_diagnostic_render_dummy_span_has_no_caret() {
+        let snippet = || SourceSnippet::new("let x = 1;", 1, 5, 6, None::<&str>);
+
+        let dummy = Diagnostic::error("bad binding", Span::DUMMY).with_snippet(snippet());
+        assert_eq!(dummy.render(None, false), "error: bad binding");
+
+        let real = Diagnostic::error("bad binding", Span::new(4, 5, 1, 5)).with_snippet(snippet());
+        let rendered = real.render(None, false);
+        assert!(rendered.contains(" --> 1:5"));
+        assert!(rendered.contains("    ^"));
+    }
+
+    #[test]
+    fn test_diagnostic_render_color() {
+        let diag = Diagnostic::warning("unused variable", Span::new(4, 5, 1, 5))
+            .with_snippet(SourceSnippet::new("let x = 1;", 1, 5, 6, None::<&str>))
+            .with_note("prefix it with an underscore");
+
+        let plain = diag.render(None, false);
+        assert!(!plain.contains('\x1b'));
+
+        let colored = diag.render(None, true);
+        assert!(colored.starts_with("\x1b[1;33mwarning\x1b[0m: unused variable"));
+        assert!(colored.contains("\x1b[1;33m^\x1b[0m"));
+        assert!(colored.contains("= \x1b[1;34mnote\x1b[0m: prefix it"));
+    }
+
     #[test]
     fn test_handler_new() {
         let handler = Handler::new();
@@ -572,6 +940,94 @@ mod tests {
         assert_eq!(diags.len(), 2);
     }
 
+    #[test]
+    fn test_handler_sorted_diagnostics() {
+        let handler = Handler::new();
+        let at = |file, start, line| Span::with_file(start, start + 1, FileId(file), line, 1);
+        handler.emit_diagnostic(Diagnostic::error("b: line 3", at(1, 20, 3)));
+        handler.emit_diagnostic(Diagnostic::error("no location", Span::DUMMY));
+        handler.emit_diagnostic(Diagnostic::error("a: line 5", at(0, 40, 5)));
+        handler.emit_diagnostic(Diagnostic::warning("b: line 1", at(1, 0, 1)));
+        handler.emit_diagnostic(Diagnostic::error("a: line 2", at(0, 12, 2)));
+
+        let sorted: Vec<_> = handler
+            .sorted_diagnostics()
+            .into_iter()
+            .map(|d| d.message)
+            .collect();
+        assert_eq!(
+            sorted,
+            ["a: line 2", "a: line 5", "b: line 1", "b: line 3", "no location"]
+        );
+
+        // Discovery order is kept by the handler itself
+        let reported: Vec<_> = handler.into_diagnostics().into_iter().map(|d| d.message).collect();
+        assert_eq!(reported[0], "b: line 3");
+        assert_eq!(reported.len(), 5);
+    }
+
+    #[test]
+    fn test_handler_error_limit() {
+        let handler = Handler::new();
+        handler.set_error_limit(3);
+        for i in 0..10 {
+            handler.emit_diagnostic(Diagnostic::error(format!("error {}", i), Span::DUMMY));
+        }
+        handler.emit_diagnostic(Diagnostic::warning("still recorded", Span::DUMMY));
+
+        assert_eq!(handler.error_count(), 3);
+        assert_eq!(handler.warning_count(), 1);
+        assert!(handler.error_limit_reached());
+
+        let diags = handler.diagnostics();
+        let notes: Vec<_> = diags.iter().filter(|d| d.level == Level::Note).collect();
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].message, "aborting due to 3 errors; too many errors emitted");
+    }
+
+    #[test]
+    fn test_handler_error_limit_zero_is_unlimited() {
+        let handler = Handler::new();
+        handler.set_error_limit(0);
+        for i in 0..10 {
+            handler.emit_diagnostic(Diagnostic::error(format!("error {}", i), Span::DUMMY));
+        }
+
+        assert_eq!(handler.error_count(), 10);
+        assert!(!handler.error_limit_reached());
+    }
+
+    #[test]
+    fn test_handler_dedups_identical_diagnostics() {
+        let handler = Handler::new();
+        let span = Span::new(10, 11, 2, 5);
+        handler.emit_diagnostic(Diagnostic::error("expected ';'", span));
+        handler.emit_diagnostic(Diagnostic::error("expected ';'", span));
+
+        let diags = handler.diagnostics();
+        assert_eq!(diags.len(), 1);
+        assert_eq!(handler.error_count(), 1);
+        assert_eq!(diags[0].notes, vec!["(repeated 2 times)"]);
+
+        handler.emit_diagnostic(Diagnostic::error("expected ';'", span));
+        assert_eq!(handler.diagnostics()[0].notes, vec!["(repeated 3 times)"]);
+    }
+
+    #[test]
+    fn test_handler_keeps_distinct_diagnostics() {
+        let handler = Handler::new();
+        let span = Span::new(10, 11, 2, 5);
+        handler.emit_diagnostic(Diagnostic::error("expected ';'", span));
+        handler.emit_diagnostic(Diagnostic::error("expected ';'", Span::new(20, 21, 3, 5)));
+        handler.emit_diagnostic(Diagnostic::error("expected ')'", span));
+        handler.emit_diagnostic(Diagnostic::warning("expected ';'", span));
+        // Without a location, repeats may come from different places
+        handler.emit_diagnostic(Diagnostic::error("expected ';'", Span::DUMMY));
+        handler.emit_diagnostic(Diagnostic::error("expected ';'", Span::DUMMY));
+
+        assert_eq!(handler.diagnostics().len(), 6);
+    }
+
     #[test]
     fn test_handler_panicking() {
         let handler = Handler::new_panicking();
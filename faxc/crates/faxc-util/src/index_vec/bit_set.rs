@@ -0,0 +1,204 @@
+//! Dense bit sets over typed indices.
+
+use std::marker::PhantomData;
+
+use super::Idx;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A fixed-size set of indices of type `I`, stored one bit per index
+///
+/// The set covers the indices `0..domain_size`. It is meant for dataflow
+/// analyses (liveness, dominators, reachability) over `LocalId`s or
+/// `BlockId`s, where sets are dense and combined with union/intersection.
+///
+/// # Example
+///
+/// ```
+/// use faxc_util::index_vec::{Idx, IdxBitSet};
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// struct BlockId(u32);
+///
+/// impl Idx for BlockId {
+///     fn from_usize(idx: usize) -> Self { BlockId(idx as u32) }
+///     fn index(self) -> usize { self.0 as usize }
+/// }
+///
+/// let mut live: IdxBitSet<BlockId> = IdxBitSet::new_empty(100);
+/// live.insert(BlockId(3));
+/// live.insert(BlockId(70));
+///
+/// assert!(live.contains(BlockId(70)));
+/// assert_eq!(live.iter().collect::<Vec<_>>(), vec![BlockId(3), BlockId(70)]);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct IdxBitSet<I: Idx> {
+    domain_size: usize,
+    words: Vec<u64>,
+    _marker: PhantomData<fn(I)>,
+}
+
+impl<I: Idx> IdxBitSet<I> {
+    /// Create an empty set over the indices `0..domain_size`
+    pub fn new_empty(domain_size: usize) -> Self {
+        Self {
+            domain_size,
+            words: vec![0; domain_size.div_ceil(WORD_BITS)],
+            _marker: PhantomData,
+        }
+    }
+
+    /// Number of indices the set can hold
+    #[inline]
+    pub fn domain_size(&self) -> usize {
+        self.domain_size
+    }
+
+    /// Add an index, returning `true` if it was not already present
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is outside the domain.
+    #[inline]
+    pub fn insert(&mut self, elem: I) -> bool {
+        let (word, mask) = self.word_and_mask(elem);
+        let old = self.words[word];
+        self.words[word] |= mask;
+        old != self.words[word]
+    }
+
+    /// Remove an index, returning `true` if it was present
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is outside the domain.
+    #[inline]
+    pub fn remove(&mut self, elem: I) -> bool {
+        let (word, mask) = self.word_and_mask(elem);
+        let old = self.words[word];
+        self.words[word] &= !mask;
+        old != self.words[word]
+    }
+
+    /// Check whether an index is in the set
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is outside the domain.
+    #[inline]
+    pub fn contains(&self, elem: I) -> bool {
+        let (word, mask) = self.word_and_mask(elem);
+        self.words[word] & mask != 0
+    }
+
+    /// Add every index of `other`, returning `true` if the set changed
+    ///
+    /// # Panics
+    ///
+    /// Panics if the two sets have different domain sizes.
+    pub fn union_with(&mut self, other: &Self) -> bool {
+        self.combine_with(other, |a, b| a | b)
+    }
+
+    /// Keep only indices also in `other`, returning `true` if the set changed
+    ///
+    /// # Panics
+    ///
+    /// Panics if the two sets have different domain sizes.
+    pub fn intersect_with(&mut self, other: &Self) -> bool {
+        self.combine_with(other, |a, b| a & b)
+    }
+
+    /// Check whether the set has no indices
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// Number of indices in the set
+    pub fn count(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Remove every index
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// Iterate over the indices in the set in ascending order
+    pub fn iter(&self) -> BitIter<'_, I> {
+        BitIter {
+            words: &self.words,
+            word_idx: 0,
+            current: self.words.first().copied().unwrap_or(0),
+            _marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn word_and_mask(&self, elem: I) -> (usize, u64) {
+        let idx = elem.index();
+        assert!(
+            idx < self.domain_size,
+            "index {} out of bit set domain {}",
+            idx,
+            self.domain_size
+        );
+        (idx / WORD_BITS, 1 << (idx % WORD_BITS))
+    }
+
+    fn combine_with(&mut self, other: &Self, op: impl Fn(u64, u64) -> u64) -> bool {
+        assert_eq!(
+            self.domain_size, other.domain_size,
+            "bit set domain sizes differ"
+        );
+        let mut changed = false;
+        for (word, &other_word) in self.words.iter_mut().zip(&other.words) {
+            let new = op(*word, other_word);
+            changed |= new != *word;
+            *word = new;
+        }
+        changed
+    }
+}
+
+impl<I: Idx + std::fmt::Debug> std::fmt::Debug for IdxBitSet<I> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, I: Idx> IntoIterator for &'a IdxBitSet<I> {
+    type Item = I;
+    type IntoIter = BitIter<'a, I>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the indices of an [`IdxBitSet`], in ascending order
+pub struct BitIter<'a, I: Idx> {
+    words: &'a [u64],
+    word_idx: usize,
+    /// Bits of `words[word_idx]` not yet yielded
+    current: u64,
+    _marker: PhantomData<fn() -> I>,
+}
+
+impl<I: Idx> Iterator for BitIter<'_, I> {
+    type Item = I;
+
+    fn next(&mut self) -> Option<I> {
+        while self.current == 0 {
+            self.word_idx += 1;
+            self.current = *self.words.get(self.word_idx)?;
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        self.current &= self.current - 1;
+        Some(I::from_usize(self.word_idx * WORD_BITS + bit))
+    }
+}
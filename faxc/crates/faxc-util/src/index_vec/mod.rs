@@ -26,6 +26,10 @@
 use std::marker::PhantomData;
 use std::ops::{Index, IndexMut};
 
+mod bit_set;
+
+pub use bit_set::{BitIter, IdxBitSet};
+
 /// Trait for types that can be used as indices
 ///
 /// This trait must be implemented by any type that wants to be used as an
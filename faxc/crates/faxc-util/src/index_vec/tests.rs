@@ -490,3 +490,82 @@ fn test_clone() {
     assert_eq!(vec1[TestId(0)], 100);
     assert_eq!(vec2[TestId(0)], 10);
 }
+
+// ============================================================================
+// BIT SET
+// ============================================================================
+
+#[test]
+fn test_bit_set_insert_remove_contains() {
+    let mut set: IdxBitSet<TestId> = IdxBitSet::new_empty(130);
+    assert!(set.is_empty());
+    assert_eq!(set.domain_size(), 130);
+
+    assert!(set.insert(TestId(0)));
+    assert!(set.insert(TestId(64)));
+    assert!(set.insert(TestId(129)));
+    assert!(!set.insert(TestId(64)));
+
+    assert!(set.contains(TestId(0)));
+    assert!(set.contains(TestId(129)));
+    assert!(!set.contains(TestId(1)));
+    assert_eq!(set.count(), 3);
+
+    assert!(set.remove(TestId(64)));
+    assert!(!set.remove(TestId(64)));
+    assert!(!set.contains(TestId(64)));
+    assert!(!set.is_empty());
+
+    set.clear();
+    assert!(set.is_empty());
+}
+
+#[test]
+fn test_bit_set_union_and_intersection() {
+    let mut a: IdxBitSet<TestId> = IdxBitSet::new_empty(100);
+    let mut b: IdxBitSet<TestId> = IdxBitSet::new_empty(100);
+    for i in [1, 5, 70] {
+        a.insert(TestId(i));
+    }
+    for i in [5, 70, 99] {
+        b.insert(TestId(i));
+    }
+
+    let mut union = a.clone();
+    assert!(union.union_with(&b));
+    assert!(!union.union_with(&b));
+    assert_eq!(
+        union.iter().collect::<Vec<_>>(),
+        vec![TestId(1), TestId(5), TestId(70), TestId(99)]
+    );
+
+    let mut intersection = a.clone();
+    assert!(intersection.intersect_with(&b));
+    assert!(!intersection.intersect_with(&b));
+    assert_eq!(
+        intersection.iter().collect::<Vec<_>>(),
+        vec![TestId(5), TestId(70)]
+    );
+}
+
+#[test]
+fn test_bit_set_iteration_order() {
+    let mut set: IdxBitSet<TestId> = IdxBitSet::new_empty(300);
+    for i in [299, 0, 128, 63, 64, 200] {
+        set.insert(TestId(i));
+    }
+
+    let items: Vec<u32> = set.iter().map(|id| id.0).collect();
+    assert_eq!(items, vec![0, 63, 64, 128, 200, 299]);
+    assert_eq!((&set).into_iter().count(), 6);
+
+    let empty: IdxBitSet<TestId> = IdxBitSet::new_empty(0);
+    assert_eq!(empty.iter().count(), 0);
+}
+
+#[test]
+#[should_panic(expected = "out of bit set domain")]
+fn test_bit_set_out_of_domain() {
+    let mut set: IdxBitSet<TestId> = IdxBitSet::new_empty(10);
+    set.insert(TestId(10));
+}
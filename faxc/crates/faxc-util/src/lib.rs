@@ -10,6 +10,7 @@
 //! - [`diagnostic`] - Error and warning reporting infrastructure
 //! - [`span`] - Source location tracking
 //! - [`error`] - Core error types
+//! - [`worklist`] - Worklist and fixpoint driver for dataflow passes
 //!
 //! # Example
 //!
@@ -50,13 +51,15 @@ pub mod diagnostic;
 pub mod span;
 pub mod def_id;
 pub mod error;
+pub mod worklist;
 
 // Re-export commonly used types at crate root for convenience
 pub use symbol::{Symbol, InternerStats, KW_FN, KW_LET, KW_CONST, KW_MUT, KW_IF, KW_ELSE, KW_WHILE, KW_FOR, KW_LOOP, KW_RETURN, KW_BREAK, KW_CONTINUE, KW_STRUCT, KW_ENUM, KW_IMPL, KW_TRAIT, KW_TYPE, KW_MOD, KW_USE, KW_PUB, KW_TRUE, KW_FALSE, KW_SELF, KW_SELF_UPPER, KW_AS, KW_MATCH, KW_UNSAFE, KW_EXTERN, KW_CRATE, KW_SUPER};
-pub use index_vec::{Idx, IndexVec};
+pub use index_vec::{Idx, IdxBitSet, IndexVec};
 pub use def_id::{DefId, DefIdGenerator};
+pub use worklist::{fixpoint, Worklist};
 pub use diagnostic::{
-    Handler, Diagnostic, Level, DiagnosticCode, DiagnosticBuilder, SourceSnippet,
+    Handler, Diagnostic, Level, DiagnosticCode, DiagnosticBuilder, SourceSnippet, Suggestion,
     // Predefined diagnostic codes
     E0001, E0002, E0003, E0004, E0005,
     E_LEXER_UNEXPECTED_CHAR, E_LEXER_UNTERMINATED_STRING, E_LEXER_INVALID_NUMBER, E_LEXER_UNKNOWN_TOKEN,
@@ -97,7 +97,7 @@ impl Default for FileId {
 /// // Create a span associated with a specific file
 /// let span = Span::with_file(10, 20, Default::default(), 1, 5);
 /// ```
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Span {
     /// Start byte offset in source
     pub start: usize,
@@ -130,6 +130,24 @@ impl Span {
         file_id: FileId::DUMMY,
     };
 
+    /// Check whether this span carries no source location
+    ///
+    /// Lines are 1-based, so a span on line 0 is [`Span::DUMMY`] or was
+    /// built from it, and points at nothing in the source.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use faxc_util::span::Span;
+    ///
+    /// assert!(Span::DUMMY.is_dummy());
+    /// assert!(!Span::point(1, 1).is_dummy());
+    /// ```
+    #[inline]
+    pub const fn is_dummy(&self) -> bool {
+        self.line == 0
+    }
+
     /// Create a new span
     ///
     /// # Arguments
@@ -445,6 +463,13 @@ mod tests {
         assert_eq!(span.column, 5);
     }
 
+    #[test]
+    fn test_span_is_dummy() {
+        assert!(Span::DUMMY.is_dummy());
+        assert!(Span::DUMMY.merge(Span::DUMMY).is_dummy());
+        assert!(!Span::new(0, 1, 1, 1).is_dummy());
+    }
+
     #[test]
     fn test_span_with_file() {
         let file_id = FileId(0);
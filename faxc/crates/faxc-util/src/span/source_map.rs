@@ -6,6 +6,7 @@
 use std::sync::Arc;
 
 use super::{FileId, Span};
+use crate::diagnostic::Diagnostic;
 use crate::error::{SourceMapError, SourceMapResult};
 
 /// A source file with its content and metadata
@@ -349,6 +350,29 @@ impl SourceMap {
         file_id
     }
 
+    /// Render `diagnostic` against the file its span's `file_id` names,
+    /// quoting the source line it points at
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use faxc_util::span::{SourceMap, Span};
+    /// use faxc_util::Diagnostic;
+    ///
+    /// let mut map = SourceMap::new();
+    /// let file_id = map.add_file("main.fax", "let x = 1;");
+    /// let diag = Diagnostic::error("unused", Span::with_file(4, 5, file_id, 1, 5));
+    /// assert!(map.render_diagnostic(&diag, false).contains("main.fax:1:5"));
+    /// ```
+    pub fn render_diagnostic(&self, diagnostic: &Diagnostic, color: bool) -> String {
+        let file = self.get(diagnostic.span.file_id);
+        diagnostic.render_with_source(
+            file.as_ref().map(|file| file.name()),
+            file.as_ref().map(|file| file.content()),
+            color,
+        )
+    }
+
     /// Get a source file by its ID
     ///
     /// # Examples
@@ -605,6 +629,24 @@ mod tests {
         assert!(col >= 1);
     }
 
+    #[test]
+    fn test_render_diagnostic_uses_span_file() {
+        let mut map = SourceMap::new();
+        let a = map.add_file("a.fax", "fn a() {}\nfn shared() {}");
+        let b = map.add_file("b.fax", "fn b() {}\nfn broken( {}");
+        assert_ne!(a, b);
+
+        let span = Span::with_file(20, 21, b, 2, 11);
+        let rendered = map.render_diagnostic(&Diagnostic::error("expected `)`", span), false);
+        assert!(rendered.contains(" --> b.fax:2:11"));
+        assert!(rendered.contains("fn broken( {}"));
+        assert!(!rendered.contains("fn shared"));
+
+        let unknown = Span::with_file(0, 1, FileId(7), 1, 1);
+        let rendered = map.render_diagnostic(&Diagnostic::error("lost", unknown), false);
+        assert_eq!(rendered, "error: lost\n --> 1:1");
+    }
+
     #[test]
     fn test_empty_file() {
         let file = SourceFile::new(0, "empty.rs", "");
@@ -0,0 +1,141 @@
+//! Thread-local cache in front of the global string table.
+//!
+//! The lexer and name resolution intern the same hot identifiers over and
+//! over. Each thread keeps a small direct-mapped cache of recently interned
+//! strings so repeated lookups skip the shard locks of the shared
+//! [`STRING_TABLE`]. Hits are counted per thread and added to the table's
+//! hit counter in batches, so a hit touches no shared state at all.
+//!
+//! The cache is keyed by the same hash the table uses, and a slot only
+//! counts as a hit if its string compares equal, so a collision in the slot
+//! index simply falls through to the table.
+
+use std::cell::{Cell, RefCell};
+
+use super::interner::{StringTable, STRING_TABLE};
+use super::Symbol;
+
+/// Number of slots in each thread's cache (must be a power of two)
+const CACHE_SLOTS: usize = 256;
+
+/// Cache hits a thread collects before adding them to the table's counter
+const HIT_BATCH: usize = 1024;
+
+#[derive(Clone, Copy)]
+struct CacheEntry {
+    hash: u64,
+    string: &'static str,
+    symbol: Symbol,
+}
+
+/// Cache hits on this thread that the table's counter has not seen yet
+struct PendingHits(Cell<usize>);
+
+impl Drop for PendingHits {
+    fn drop(&mut self) {
+        // Hand over the last batch when the thread exits
+        let hits = self.0.get();
+        if hits > 0 {
+            STRING_TABLE.record_hits(hits);
+        }
+    }
+}
+
+thread_local! {
+    static CACHE: RefCell<[Option<CacheEntry>; CACHE_SLOTS]> =
+        const { RefCell::new([None; CACHE_SLOTS]) };
+    static PENDING_HITS: PendingHits = const { PendingHits(Cell::new(0)) };
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Lookups on this thread answered without the string table
+    static HITS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Intern a string, consulting this thread's cache first
+pub(super) fn intern(string: &str) -> Symbol {
+    let hash = StringTable::hash_string(string);
+    let slot = hash as usize & (CACHE_SLOTS - 1);
+
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if let Some(entry) = cache[slot] {
+            if entry.hash == hash && entry.string == string {
+                #[cfg(test)]
+                HITS.with(|hits| hits.set(hits.get() + 1));
+                PENDING_HITS.with(|pending| {
+                    let hits = pending.0.get() + 1;
+                    if hits == HIT_BATCH {
+                        STRING_TABLE.record_hits(hits);
+                        pending.0.set(0);
+                    } else {
+                        pending.0.set(hits);
+                    }
+                });
+                return entry.symbol;
+            }
+        }
+
+        let (symbol, interned) = STRING_TABLE.intern_hashed(string, hash);
+        cache[slot] = Some(CacheEntry {
+            hash,
+            string: interned,
+            symbol,
+        });
+        symbol
+    })
+}
+
+/// Add the current thread's pending cache hits to the table's counter
+pub(super) fn flush_hits() {
+    PENDING_HITS.with(|pending| {
+        let hits = pending.0.take();
+        if hits > 0 {
+            STRING_TABLE.record_hits(hits);
+        }
+    });
+}
+
+/// Number of lookups on the current thread served by the cache
+#[cfg(test)]
+fn hits() -> usize {
+    HITS.with(Cell::get)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_repeated_intern_served_from_cache() {
+        // Run on a fresh thread so the counters start from zero
+        thread::spawn(|| {
+            let first = intern("cache_hot_identifier");
+            assert_eq!(hits(), 0);
+
+            for _ in 0..1000 {
+                assert_eq!(intern("cache_hot_identifier"), first);
+            }
+            assert_eq!(hits(), 1000);
+            assert_eq!(first.as_str(), "cache_hot_identifier");
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_cache_miss_falls_back_to_table() {
+        thread::spawn(|| {
+            let a = intern("cache_miss_a");
+            let b = intern("cache_miss_b");
+            assert_ne!(a, b);
+            assert_eq!(STRING_TABLE.intern("cache_miss_a"), a);
+            assert_eq!(STRING_TABLE.intern("cache_miss_b"), b);
+            assert_eq!(hits(), 0);
+        })
+        .join()
+        .unwrap();
+    }
+}
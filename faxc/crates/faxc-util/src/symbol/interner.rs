@@ -21,11 +21,13 @@
 
 use ahash::AHasher;
 use dashmap::DashMap;
-use std::hash::{Hash, Hasher};
+use indexmap::IndexSet;
+use rustc_hash::FxHasher;
+use std::hash::{BuildHasherDefault, Hash, Hasher};
 use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
 use std::sync::LazyLock;
 
-use super::{InternerStats, Symbol};
+use super::{InternerStats, Symbol, KNOWN_SYMBOLS, KNOWN_SYMBOL_MAP};
 
 /// Global string table instance using DashMap for concurrent access
 ///
@@ -106,57 +108,9 @@ impl StringTable {
     ///
     /// This must be called exactly once during initialization.
     fn initialize_known_symbols(&self) {
-        // List of all known symbols to pre-intern
-        // These must match the order in mod.rs known symbol constants
-        let known_symbols = [
-            // Control flow keywords
-            "fn", "let", "const", "mut", "if", "else", "while", "for", "loop",
-            "return", "break", "continue", "match",
-            // Type declarations
-            "struct", "enum", "impl", "trait", "type", "mod", "use",
-            // Visibility and modules
-            "pub", "crate", "super", "self", "Self", "as",
-            // Literals and values
-            "true", "false",
-            // Control flow extended
-            "unsafe", "extern",
-            // Type keywords - integers
-            "i8", "i16", "i32", "i64", "isize",
-            "u8", "u16", "u32", "u64", "usize",
-            // Type keywords - floats
-            "f32", "f64",
-            // Type keywords - other
-            "bool", "char", "str",
-            // Type system
-            "dyn", "where",
-            // Container types
-            "array", "tuple",
-            // Operators as symbols
-            "add", "sub", "mul", "div", "rem", "neg", "not",
-            "bit_and", "bit_or", "bit_xor", "bit_not",
-            "shl", "shr",
-            "eq", "ne", "lt", "le", "gt", "ge",
-            "and", "or",
-            // Common identifiers
-            "main", "new", "init", "drop",
-            "ok", "err", "some", "none",
-            "len", "size", "capacity",
-            "push", "pop", "insert", "remove",
-            "get", "set", "first", "last",
-            "iter", "next", "done",
-            "clone", "copy", "eq", "ord",
-            "hash", "hasher",
-            "from", "into", "try_from", "try_into",
-            "default", "display", "debug",
-        ];
-
-        for (idx, symbol) in known_symbols.iter().enumerate() {
-            let actual_idx = idx as u32;
-            if actual_idx < RESERVED_SYMBOLS_END {
-                let interned: &'static str = Box::leak(symbol.to_string().into_boxed_str());
-                let hash = Self::hash_string(symbol);
-                self.map.insert(hash, (interned, actual_idx));
-            }
+        for (idx, &symbol) in KNOWN_SYMBOLS.iter().enumerate() {
+            let hash = Self::hash_string(symbol);
+            self.map.insert(hash, (symbol, idx as u32));
         }
     }
 
@@ -181,13 +135,20 @@ impl StringTable {
     /// Each call updates hit/miss counters for profiling. Use
     /// [`StringTable::stats()`] to retrieve statistics.
     pub fn intern(&self, string: &str) -> Symbol {
-        let hash = Self::hash_string(string);
+        self.intern_hashed(string, Self::hash_string(string)).0
+    }
 
+    /// Intern a string whose hash the caller already computed
+    ///
+    /// Also returns the interned string so callers can cache it without a
+    /// second (linear) lookup by index.
+    pub(super) fn intern_hashed(&self, string: &str, hash: u64) -> (Symbol, &'static str) {
         // Fast path: check if string is already interned
         if let Some(entry) = self.map.get(&hash) {
             if entry.value().0 == string {
                 self.hits.fetch_add(1, Ordering::Relaxed);
-                return Symbol { index: entry.value().1 };
+                let (interned, index) = *entry.value();
+                return (Symbol { index }, interned);
             }
         }
 
@@ -198,9 +159,11 @@ impl StringTable {
             dashmap::mapref::entry::Entry::Occupied(entry) => {
                 // Hash collision - check if string matches
                 if entry.get().0 == string {
-                    return Symbol { index: entry.get().1 };
+                    let (interned, index) = *entry.get();
+                    return (Symbol { index }, interned);
                 }
                 // Different string with same hash - handle collision
+                drop(entry);
                 self.handle_collision(string, hash)
             }
             dashmap::mapref::entry::Entry::Vacant(entry) => {
@@ -208,7 +171,7 @@ impl StringTable {
                 let interned: &'static str = Box::leak(string.to_string().into_boxed_str());
                 let idx = self.next_index.fetch_add(1, Ordering::Relaxed);
                 entry.insert((interned, idx));
-                Symbol { index: idx }
+                (Symbol { index: idx }, interned)
             }
         }
     }
@@ -225,8 +188,9 @@ impl StringTable {
     ///
     /// # Returns
     ///
-    /// The symbol for the string (either existing or newly created)
-    fn handle_collision(&self, string: &str, original_hash: u64) -> Symbol {
+    /// The symbol for the string (either existing or newly created) and the
+    /// interned string
+    fn handle_collision(&self, string: &str, original_hash: u64) -> (Symbol, &'static str) {
         // Linear probing with prime-based offset for better distribution
         const MAX_PROBES: u64 = 32;
         const PROBE_PRIME: u64 = 0x9e3779b97f4a7c15; // Golden ratio constant
@@ -236,7 +200,8 @@ impl StringTable {
 
             if let Some(entry) = self.map.get(&probe_hash) {
                 if entry.value().0 == string {
-                    return Symbol { index: entry.value().1 };
+                    let (interned, index) = *entry.value();
+                    return (Symbol { index }, interned);
                 }
             } else {
                 // Found empty slot
@@ -244,7 +209,7 @@ impl StringTable {
                 let interned: &'static str = Box::leak(string.to_string().into_boxed_str());
                 let idx = self.next_index.fetch_add(1, Ordering::Relaxed);
                 self.map.insert(probe_hash, (interned, idx));
-                return Symbol { index: idx };
+                return (Symbol { index: idx }, interned);
             }
         }
 
@@ -254,7 +219,7 @@ impl StringTable {
         let interned: &'static str = Box::leak(string.to_string().into_boxed_str());
         let idx = self.next_index.fetch_add(1, Ordering::Relaxed);
         self.map.insert(original_hash, (interned, idx));
-        Symbol { index: idx }
+        (Symbol { index: idx }, interned)
     }
 
     /// Get string by symbol
@@ -270,6 +235,10 @@ impl StringTable {
     /// Returns `None` if the symbol is invalid (e.g., created manually with
     /// an out-of-bounds index that doesn't exist in the table).
     pub fn get(&self, symbol: Symbol) -> Option<&'static str> {
+        if let Some(&known) = KNOWN_SYMBOLS.get(symbol.index as usize) {
+            return Some(known);
+        }
+
         // Linear search by index - O(n) but should be rare
         self.map
             .iter()
@@ -277,12 +246,18 @@ impl StringTable {
             .map(|entry| entry.value().0)
     }
 
+    /// Count hits answered by a thread-local cache in front of the table
+    #[inline]
+    pub(super) fn record_hits(&self, hits: usize) {
+        self.hits.fetch_add(hits, Ordering::Relaxed);
+    }
+
     /// Compute hash of string using AHasher
     ///
     /// AHasher is a non-cryptographic hash function optimized for speed
     /// while maintaining good distribution properties.
     #[inline]
-    fn hash_string(string: &str) -> u64 {
+    pub(super) fn hash_string(string: &str) -> u64 {
         let mut hasher = AHasher::default();
         string.hash(&mut hasher);
         hasher.finish()
@@ -326,6 +301,92 @@ impl StringTable {
     }
 }
 
+/// Tag bit set in the index of every symbol from an owned [`Interner`]
+///
+/// The global table counts up from `RESERVED_SYMBOLS_END` and never gets
+/// near this bit, so the two index spaces stay disjoint.
+const OWNED_SYMBOL_TAG: u32 = 1 << 31;
+
+/// Owned string interner whose strings are freed when it is dropped
+///
+/// The global [`STRING_TABLE`] leaks every string to hand out `&'static str`,
+/// which is fine for a single compilation but grows without bound in
+/// long-running tools such as a language server. An `Interner` owns its
+/// strings instead, and [`Interner::resolve`] borrows from it.
+///
+/// Known symbols (keywords, primitive types, prelude identifiers) keep their
+/// reserved indices, so constants like [`KW_FN`](super::KW_FN) resolve in
+/// every interner. Other strings get indices tagged with
+/// `OWNED_SYMBOL_TAG`, local to this interner, so they never collide with
+/// symbols from the global table.
+///
+/// # Examples
+///
+/// ```
+/// use faxc_util::symbol::{Interner, KW_FN};
+///
+/// let mut interner = Interner::new();
+/// let sym = interner.intern("session_local");
+///
+/// assert_eq!(interner.resolve(sym), "session_local");
+/// assert_eq!(interner.resolve(KW_FN), "fn");
+/// ```
+#[derive(Debug, Default)]
+pub struct Interner {
+    strings: IndexSet<Box<str>, BuildHasherDefault<FxHasher>>,
+}
+
+impl Interner {
+    /// Create an empty interner
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Intern a string, returning its symbol in this interner
+    pub fn intern(&mut self, string: &str) -> Symbol {
+        if let Some(&symbol) = KNOWN_SYMBOL_MAP.get(string) {
+            return symbol;
+        }
+
+        let idx = match self.strings.get_index_of(string) {
+            Some(idx) => idx,
+            None => self.strings.insert_full(string.into()).0,
+        };
+        Symbol {
+            index: OWNED_SYMBOL_TAG | idx as u32,
+        }
+    }
+
+    /// Get the string for a symbol interned here, borrowed from the interner
+    ///
+    /// Known symbols resolve in every interner. Symbols from the global
+    /// table resolve to an empty string, as owned symbols do through
+    /// [`Symbol::as_str`]. Symbols from another `Interner` share this
+    /// interner's index space and must not be resolved here.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        if let Some(&known) = KNOWN_SYMBOLS.get(symbol.index as usize) {
+            return known;
+        }
+        if symbol.index & OWNED_SYMBOL_TAG == 0 {
+            return "";
+        }
+
+        self.strings
+            .get_index((symbol.index & !OWNED_SYMBOL_TAG) as usize)
+            .map_or("", |string| string)
+    }
+
+    /// Number of strings interned here, not counting known symbols
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Whether no strings other than known symbols have been interned
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -498,4 +559,46 @@ mod tests {
         fn assert_send_sync<T: Send + Sync>() {}
         assert_send_sync::<StringTable>();
     }
+
+    #[test]
+    fn test_owned_interner_resolve() {
+        let mut interner = Interner::new();
+        let a = interner.intern("alpha");
+        let b = interner.intern("beta");
+
+        assert_eq!(interner.intern("alpha"), a);
+        assert_ne!(a, b);
+        assert!(!a.is_known());
+        assert_eq!(interner.resolve(a), "alpha");
+        assert_eq!(interner.resolve(b), "beta");
+        assert_eq!(interner.len(), 2);
+
+        // Known symbols keep their reserved indices and aren't stored
+        assert_eq!(interner.intern("fn"), crate::symbol::KW_FN);
+        assert_eq!(interner.len(), 2);
+
+        // Symbols from elsewhere don't resolve
+        assert_eq!(Interner::new().resolve(a), "");
+    }
+
+    #[test]
+    fn test_owned_and_global_symbols_never_alias() {
+        // Intern into both with the global table at its lowest free indices
+        let mut interner = Interner::new();
+        let globals: Vec<_> = (0..8)
+            .map(|i| STRING_TABLE.intern(&format!("global_alias_{}", i)))
+            .collect();
+        let owned: Vec<_> = (0..8)
+            .map(|i| interner.intern(&format!("owned_alias_{}", i)))
+            .collect();
+
+        for &sym in &globals {
+            assert!(!owned.contains(&sym));
+            assert_eq!(interner.resolve(sym), "");
+        }
+        for &sym in &owned {
+            assert_eq!(sym.as_str(), "");
+            assert_eq!(STRING_TABLE.get(sym), None);
+        }
+    }
 }
@@ -37,6 +37,10 @@
 //! 2. The total memory usage is bounded by source code size
 //! 3. This avoids complex lifetime tracking
 //!
+//! Long-running tools that intern input from many sessions should use an
+//! owned [`Interner`] instead: its strings are freed when it is dropped and
+//! [`Interner::resolve`] borrows from it rather than returning `'static`.
+//!
 //! # Examples
 //!
 //! Basic usage:
@@ -98,9 +102,13 @@
 //! println!("Hits: {}, Misses: {}", stats.hits, stats.misses);
 //! ```
 
+mod cache;
 mod interner;
 
-pub use interner::STRING_TABLE;
+pub use interner::{Interner, STRING_TABLE};
+
+use rustc_hash::FxHashMap;
+use std::sync::LazyLock;
 
 /// Statistics about the string interner for profiling
 ///
@@ -585,6 +593,65 @@ pub const ID_DISPLAY: Symbol = Symbol { index: 101 };
 /// Known symbol for `debug`
 pub const ID_DEBUG: Symbol = Symbol { index: 102 };
 
+/// Strings of all known symbols, indexed by their reserved symbol index
+///
+/// The position of each entry must match the index of the corresponding
+/// constant above. The string table pre-registers these at startup.
+#[rustfmt::skip]
+pub(crate) static KNOWN_SYMBOLS: &[&str] = &[
+    // Control flow keywords
+    "fn", "let", "const", "mut", "if", "else", "while", "for", "loop",
+    "return", "break", "continue", "match",
+    // Type declarations
+    "struct", "enum", "impl", "trait", "type", "mod", "use",
+    // Visibility and modules
+    "pub", "crate", "super", "self", "Self", "as",
+    // Literals and values
+    "true", "false",
+    // Control flow extended
+    "unsafe", "extern",
+    // Type keywords - integers
+    "i8", "i16", "i32", "i64", "isize",
+    "u8", "u16", "u32", "u64", "usize",
+    // Type keywords - floats
+    "f32", "f64",
+    // Type keywords - other
+    "bool", "char", "str",
+    // Type system
+    "dyn", "where",
+    // Container types
+    "array", "tuple",
+    // Operators as symbols
+    "add", "sub", "mul", "div", "rem", "neg", "not",
+    "bit_and", "bit_or", "bit_xor", "bit_not",
+    "shl", "shr",
+    "eq", "ne", "lt", "le", "gt", "ge",
+    "and", "or",
+    // Common identifiers
+    "main", "new", "init", "drop",
+    "ok", "err", "some", "none",
+    "len", "size", "capacity",
+    "push", "pop", "insert", "remove",
+    "get", "set", "first", "last",
+    "iter", "next", "done",
+    "clone", "copy",
+    "hash", "hasher",
+    "from", "into", "try_from", "try_into",
+    "default", "display", "debug",
+];
+
+/// Read-only lookup from known symbol strings to their reserved symbols
+///
+/// Built once from [`KNOWN_SYMBOLS`] and never mutated afterwards, so
+/// lookups don't touch the shared string table.
+static KNOWN_SYMBOL_MAP: LazyLock<FxHashMap<&'static str, Symbol>> = LazyLock::new(|| {
+    KNOWN_SYMBOLS
+        .iter()
+        .enumerate()
+        .map(|(index, &name)| (name, Symbol { index: index as u32 }))
+        .collect()
+});
+
 impl Symbol {
     /// The maximum index value for a symbol
     pub const MAX_INDEX: u32 = u32::MAX;
@@ -592,8 +659,9 @@ impl Symbol {
     /// Intern a string, returning its symbol
     ///
     /// This function will:
-    /// 1. Hash the string to check for existing entry
-    /// 2. If found, return existing symbol (cache hit)
+    /// 1. Hash the string and check the calling thread's recent-symbol cache
+    /// 2. Otherwise look it up in the global table; if found, return existing
+    ///    symbol (cache hit)
     /// 3. If not found, allocate new slot and return new symbol (cache miss)
     ///
     /// # Thread Safety
@@ -619,7 +687,30 @@ impl Symbol {
     /// ```
     #[inline]
     pub fn intern(string: &str) -> Self {
-        STRING_TABLE.intern(string)
+        cache::intern(string)
+    }
+
+    /// Intern a string with static lifetime, skipping the shared table for
+    /// known symbols
+    ///
+    /// Keywords, primitive type names and prelude identifiers resolve to
+    /// their reserved symbol through a read-only map that is built once at
+    /// startup. Any other string falls back to [`Symbol::intern`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use faxc_util::symbol::{Symbol, KW_FN};
+    ///
+    /// assert_eq!(Symbol::intern_static("fn"), KW_FN);
+    /// assert_eq!(Symbol::intern_static("hello"), Symbol::intern("hello"));
+    /// ```
+    #[inline]
+    pub fn intern_static(string: &'static str) -> Self {
+        match KNOWN_SYMBOL_MAP.get(string) {
+            Some(&symbol) => symbol,
+            None => Self::intern(string),
+        }
     }
 
     /// Get the string value associated with this symbol
@@ -753,6 +844,31 @@ impl Symbol {
         self.as_str() == other
     }
 
+    /// Check whether this symbol is the interned form of `string`
+    ///
+    /// Unlike [`Symbol::eq_str()`], this never reads the string table:
+    /// `string` is interned (a known-symbol map or cache lookup for strings
+    /// seen before) and the two indices are compared. Prefer it for keyword
+    /// checks such as `sym.is("in")` on hot paths.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use faxc_util::symbol::Symbol;
+    ///
+    /// let sym = Symbol::intern("in");
+    /// assert!(sym.is("in"));
+    /// assert!(!sym.is("if"));
+    /// ```
+    #[inline]
+    pub fn is(&self, string: &str) -> bool {
+        let other = match KNOWN_SYMBOL_MAP.get(string) {
+            Some(&symbol) => symbol,
+            None => Self::intern(string),
+        };
+        *self == other
+    }
+
     /// Returns true if this is a "known" symbol (predefined keywords)
     ///
     /// Known symbols are interned at compiler startup and have indices
@@ -815,7 +931,8 @@ impl Symbol {
     ///
     /// Returns an `InternerStats` struct with detailed information about
     /// the interner's state, including count, capacity, collisions, hits,
-    /// and misses.
+    /// and misses. Hits include this thread's thread-local cache hits, and
+    /// other threads' up to their last batch.
     ///
     /// # Examples
     ///
@@ -828,6 +945,7 @@ impl Symbol {
     /// ```
     #[inline]
     pub fn stats_struct() -> InternerStats {
+        cache::flush_hits();
         STRING_TABLE.stats()
     }
 
@@ -1232,6 +1350,15 @@ mod tests {
         assert_eq!(OP_EQ.as_str(), "eq");
     }
 
+    #[test]
+    fn test_is() {
+        assert!(Symbol::intern("in").is("in"));
+        assert!(!Symbol::intern("in").is("if"));
+        assert!(KW_SUPER.is("super"));
+        assert!(!KW_SUPER.is("self"));
+        assert!(Symbol::intern("my_variable").is("my_variable"));
+    }
+
     #[test]
     fn test_intern_known() {
         assert_eq!(Symbol::intern_known("fn"), KW_FN);
@@ -1243,6 +1370,21 @@ mod tests {
         assert!(!unknown.is_known());
     }
 
+    #[test]
+    fn test_intern_static_known_symbols() {
+        for (index, &name) in KNOWN_SYMBOLS.iter().enumerate() {
+            let sym = Symbol::intern_static(name);
+            assert_eq!(sym.as_u32(), index as u32, "wrong index for {:?}", name);
+            assert!(sym.is_known(), "{:?} should be known", name);
+            assert_eq!(sym.as_str(), name);
+            assert_eq!(Symbol::intern(name), sym);
+        }
+
+        let unknown = Symbol::intern_static("not_a_known_symbol");
+        assert!(!unknown.is_known());
+        assert_eq!(unknown.as_str(), "not_a_known_symbol");
+    }
+
     #[test]
     fn test_intern_type() {
         assert_eq!(Symbol::intern_type("i32"), TY_I32);
@@ -1448,7 +1590,7 @@ mod tests {
         for s in &test_strings {
             let sym1 = Symbol::intern(s);
             let sym2 = Symbol::intern(s);
-            let sym3 = Symbol::intern(&sym1.as_str());
+            let sym3 = Symbol::intern(sym1.as_str());
 
             assert_eq!(sym1, sym2);
             assert_eq!(sym1, sym3);
@@ -0,0 +1,181 @@
+//! Worklist - Shared iteration machinery for fixpoint dataflow passes.
+//!
+//! Liveness, constant propagation and dominator computations all repeat
+//! the same loop: process a node, and if its result changed, revisit the
+//! nodes that depend on it until nothing changes. [`Worklist`] holds the
+//! pending nodes (each at most once) and [`fixpoint`] drives the loop.
+//!
+//! # Example
+//!
+//! ```
+//! use faxc_util::worklist::fixpoint;
+//!
+//! // Propagate "reachable" along edges of a small graph
+//! let succs = [vec![1], vec![2], vec![0], vec![]];
+//! let mut reachable = vec![false; succs.len()];
+//! reachable[0] = true;
+//!
+//! fixpoint([0], |node, worklist| {
+//!     for &succ in &succs[node] {
+//!         if !reachable[succ] {
+//!             reachable[succ] = true;
+//!             worklist.push(succ);
+//!         }
+//!     }
+//! });
+//!
+//! assert_eq!(reachable, vec![true, true, true, false]);
+//! ```
+
+use rustc_hash::FxHashSet;
+use std::collections::VecDeque;
+use std::hash::Hash;
+
+/// FIFO queue of nodes awaiting processing, holding each node at most once
+///
+/// Pushing a node that is already queued is a no-op; once a node has been
+/// popped it can be queued again.
+#[derive(Debug, Clone)]
+pub struct Worklist<N> {
+    queue: VecDeque<N>,
+    queued: FxHashSet<N>,
+}
+
+impl<N: Hash + Eq + Clone> Worklist<N> {
+    /// Create an empty worklist
+    pub fn new() -> Self {
+        Self {
+            queue: VecDeque::new(),
+            queued: FxHashSet::default(),
+        }
+    }
+
+    /// Queue a node, returning `false` if it was already queued
+    pub fn push(&mut self, node: N) -> bool {
+        if !self.queued.insert(node.clone()) {
+            return false;
+        }
+        self.queue.push_back(node);
+        true
+    }
+
+    /// Queue every node not already queued
+    pub fn push_all(&mut self, nodes: impl IntoIterator<Item = N>) {
+        for node in nodes {
+            self.push(node);
+        }
+    }
+
+    /// Take the node queued earliest
+    pub fn pop(&mut self) -> Option<N> {
+        let node = self.queue.pop_front()?;
+        self.queued.remove(&node);
+        Some(node)
+    }
+
+    /// Number of queued nodes
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Check whether no nodes are queued
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+impl<N: Hash + Eq + Clone> Default for Worklist<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Hash + Eq + Clone> FromIterator<N> for Worklist<N> {
+    fn from_iter<T: IntoIterator<Item = N>>(iter: T) -> Self {
+        let mut worklist = Self::new();
+        worklist.push_all(iter);
+        worklist
+    }
+}
+
+/// Run `step` on queued nodes until the worklist is empty
+///
+/// The worklist starts with `nodes`. `step` receives each popped node and
+/// should push the nodes affected by any change it made, so the loop ends
+/// once no step changes anything. Returns the number of steps taken.
+pub fn fixpoint<N, F>(nodes: impl IntoIterator<Item = N>, mut step: F) -> usize
+where
+    N: Hash + Eq + Clone,
+    F: FnMut(N, &mut Worklist<N>),
+{
+    let mut worklist: Worklist<N> = nodes.into_iter().collect();
+    let mut steps = 0;
+    while let Some(node) = worklist.pop() {
+        step(node, &mut worklist);
+        steps += 1;
+    }
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worklist_dedups_queued_nodes() {
+        let mut worklist = Worklist::new();
+        assert!(worklist.push(1));
+        assert!(worklist.push(2));
+        assert!(!worklist.push(1));
+        assert_eq!(worklist.len(), 2);
+
+        assert_eq!(worklist.pop(), Some(1));
+        // Popped nodes can be queued again
+        assert!(worklist.push(1));
+        assert_eq!(worklist.pop(), Some(2));
+        assert_eq!(worklist.pop(), Some(1));
+        assert_eq!(worklist.pop(), None);
+        assert!(worklist.is_empty());
+    }
+
+    #[test]
+    fn test_fixpoint_reachability_converges() {
+        // 0 -> 1 -> 2 -> 0 (cycle), 2 -> 3, 4 -> 0 (4 unreachable from 0)
+        let succs: [&[usize]; 5] = [&[1], &[2], &[0, 3], &[], &[0]];
+        let mut reachable = [false; 5];
+        reachable[0] = true;
+
+        let steps = fixpoint([0], |node, worklist| {
+            for &succ in succs[node] {
+                if !reachable[succ] {
+                    reachable[succ] = true;
+                    worklist.push(succ);
+                }
+            }
+        });
+
+        assert_eq!(reachable, [true, true, true, true, false]);
+        // Each reachable node is processed exactly once
+        assert_eq!(steps, 4);
+    }
+
+    #[test]
+    fn test_fixpoint_revisits_changed_nodes() {
+        // Longest-path style relaxation over a DAG seeded with all nodes:
+        // nodes are revisited when a predecessor's value grows.
+        let succs: [&[usize]; 4] = [&[1, 2], &[3], &[3], &[]];
+        let mut depth = [0usize; 4];
+
+        let steps = fixpoint(0..4, |node, worklist| {
+            for &succ in succs[node] {
+                if depth[succ] < depth[node] + 1 {
+                    depth[succ] = depth[node] + 1;
+                    worklist.push(succ);
+                }
+            }
+        });
+
+        assert_eq!(depth, [0, 1, 1, 2]);
+        assert!(steps >= 4);
+    }
+}
@@ -0,0 +1,55 @@
+//! Checks that an owned `Interner` releases its strings when dropped
+//!
+//! Lives in its own test binary because it installs a counting global
+//! allocator, which would pick up allocations from unrelated tests.
+
+use faxc_util::symbol::{Interner, Symbol};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicIsize, Ordering};
+
+struct CountingAlloc;
+
+static LIVE_BYTES: AtomicIsize = AtomicIsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LIVE_BYTES.fetch_add(layout.size() as isize, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE_BYTES.fetch_sub(layout.size() as isize, Ordering::SeqCst);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAlloc = CountingAlloc;
+
+#[test]
+fn test_dropping_interner_frees_strings() {
+    // Build the shared known-symbol map up front so it isn't counted
+    let _ = Symbol::intern_static("fn");
+
+    let before = LIVE_BYTES.load(Ordering::SeqCst);
+    {
+        let mut interner = Interner::new();
+        let mut name = String::new();
+        for i in 0..10_000 {
+            name.clear();
+            name.push_str("unique_identifier_");
+            name.push_str(&i.to_string());
+            let sym = interner.intern(&name);
+            assert_eq!(interner.resolve(sym), name);
+        }
+        assert_eq!(interner.len(), 10_000);
+        assert!(LIVE_BYTES.load(Ordering::SeqCst) - before > 10_000 * 18);
+    }
+    let after = LIVE_BYTES.load(Ordering::SeqCst);
+
+    assert!(
+        after - before <= 64,
+        "{} bytes still live after dropping the interner",
+        after - before
+    );
+}
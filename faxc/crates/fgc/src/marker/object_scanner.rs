@@ -365,9 +365,9 @@ where
     #[cfg(target_arch = "x86_64")]
     {
         if data_size >= 64 {
-            core::arch::x86_64::_mm_prefetch(data_start as *const _, 3);
+            unsafe { core::arch::x86_64::_mm_prefetch(data_start as *const _, 3) };
             if data_size >= 128 {
-                core::arch::x86_64::_mm_prefetch((data_start + 64) as *const _, 2);
+                unsafe { core::arch::x86_64::_mm_prefetch((data_start + 64) as *const _, 2) };
             }
         }
     }
@@ -383,7 +383,7 @@ where
                 if next_offset < data_size {
                     #[cfg(target_arch = "x86_64")]
                     {
-                        core::arch::x86_64::_mm_prefetch((data_start + next_offset) as *const _, 1);
+                        unsafe { core::arch::x86_64::_mm_prefetch((data_start + next_offset) as *const _, 1) };
                     }
                 }
             }
@@ -462,9 +462,9 @@ where
     #[cfg(target_arch = "x86_64")]
     {
         if data_size >= 64 {
-            core::arch::x86_64::_mm_prefetch(data_start as *const _, 3);
+            unsafe { core::arch::x86_64::_mm_prefetch(data_start as *const _, 3) };
             if data_size >= 128 {
-                core::arch::x86_64::_mm_prefetch((data_start + 64) as *const _, 2);
+                unsafe { core::arch::x86_64::_mm_prefetch((data_start + 64) as *const _, 2) };
             }
         }
     }
@@ -481,7 +481,7 @@ where
                 if next_offset < data_size {
                     #[cfg(target_arch = "x86_64")]
                     {
-                        core::arch::x86_64::_mm_prefetch((data_start + next_offset) as *const _, 1);
+                        unsafe { core::arch::x86_64::_mm_prefetch((data_start + next_offset) as *const _, 1) };
                     }
                 }
             }
@@ -547,9 +547,9 @@ where
     #[cfg(target_arch = "x86_64")]
     {
         if data_size >= 64 {
-            core::arch::x86_64::_mm_prefetch(data_start as *const _, 3);
+            unsafe { core::arch::x86_64::_mm_prefetch(data_start as *const _, 3) };
             if data_size >= 128 {
-                core::arch::x86_64::_mm_prefetch((data_start + 64) as *const _, 2);
+                unsafe { core::arch::x86_64::_mm_prefetch((data_start + 64) as *const _, 2) };
             }
         }
     }
@@ -565,7 +565,7 @@ where
             if next_addr < data_start + data_size {
                 #[cfg(target_arch = "x86_64")]
                 {
-                    core::arch::x86_64::_mm_prefetch(next_addr as *const _, 1);
+                    unsafe { core::arch::x86_64::_mm_prefetch(next_addr as *const _, 1) };
                 }
             }
         }